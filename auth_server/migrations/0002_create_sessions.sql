@@ -0,0 +1,14 @@
+-- Session storage for auth instances that share state through Postgres.
+-- Tokens are kept only as SHA-256 hashes, so a leaked table cannot be replayed.
+CREATE TABLE IF NOT EXISTS sessions (
+    token_hash BYTEA PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    -- Guest ids are u64; values above i64::MAX wrap to negative.
+    guest_id BIGINT NOT NULL,
+    display_name TEXT NOT NULL,
+    metadata TEXT,
+    expires_at BIGINT NOT NULL
+);
+
+-- Serves the expired-session sweeper.
+CREATE INDEX IF NOT EXISTS sessions_expires_at_idx ON sessions (expires_at);
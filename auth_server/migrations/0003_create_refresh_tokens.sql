@@ -0,0 +1,20 @@
+-- Refresh tokens, stored hashed like session tokens. Spent tokens stay until they
+-- expire so a replay can be recognised and its whole family revoked.
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    token_hash BYTEA PRIMARY KEY,
+    family_id TEXT NOT NULL,
+    guest_id BIGINT NOT NULL,
+    display_name TEXT NOT NULL,
+    metadata TEXT,
+    session_id TEXT NOT NULL,
+    session_expires_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    used BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS refresh_tokens_family_id_idx ON refresh_tokens (family_id);
+CREATE INDEX IF NOT EXISTS refresh_tokens_session_id_idx ON refresh_tokens (session_id);
+CREATE INDEX IF NOT EXISTS refresh_tokens_expires_at_idx ON refresh_tokens (expires_at);
+
+-- Revoking a family ends its sessions by id.
+CREATE INDEX IF NOT EXISTS sessions_session_id_idx ON sessions (session_id);
@@ -0,0 +1,4 @@
+-- Proof of guest ownership: SHA-256 of the secret returned once by guest init.
+ALTER TABLE guest_profiles
+    ADD COLUMN IF NOT EXISTS secret_hash BYTEA,
+    ADD COLUMN IF NOT EXISTS failed_login_attempts BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Registered accounts. `user_id` is the canonical id sessions carry, so an
+-- upgraded guest keeps its guest id; usernames are stored lowercase.
+CREATE TABLE IF NOT EXISTS accounts (
+    user_id BIGINT PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,10 @@
+-- Users signed in through an external OpenID Connect provider. The provider's
+-- subject is mapped to the canonical `user_id` sessions carry; the first login
+-- links it to a freshly allocated id.
+CREATE TABLE IF NOT EXISTS external_identities (
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    user_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, subject)
+);
@@ -0,0 +1,4 @@
+-- Guest profiles hold every user's profile, keyed by the canonical id. The
+-- last rename is kept so display name changes can be rate limited.
+ALTER TABLE guest_profiles
+    ADD COLUMN IF NOT EXISTS display_name_changed_at BIGINT;
@@ -0,0 +1,3 @@
+-- Serves listing a user's sessions, capping them and logging out everywhere.
+CREATE INDEX IF NOT EXISTS sessions_guest_id_idx ON sessions (guest_id);
+CREATE INDEX IF NOT EXISTS refresh_tokens_guest_id_idx ON refresh_tokens (guest_id);
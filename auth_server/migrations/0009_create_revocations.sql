@@ -0,0 +1,12 @@
+-- Sessions revoked before their expiry, shared by auth instances on the Postgres
+-- session backend. `seq` is the cursor game servers poll with.
+CREATE TABLE IF NOT EXISTS revocations (
+    seq BIGSERIAL PRIMARY KEY,
+    session_id TEXT NOT NULL UNIQUE,
+    user_id BIGINT NOT NULL,
+    revoked_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+
+-- Serves the expired-session sweeper.
+CREATE INDEX IF NOT EXISTS revocations_expires_at_idx ON revocations (expires_at);
@@ -0,0 +1,15 @@
+-- External logins waiting for the provider's callback, shared by auth instances
+-- on the Postgres session backend. Only hashes of the `state` and of the
+-- client's binding are kept; the PKCE verifier must be sent to the provider.
+CREATE TABLE IF NOT EXISTS pending_external_logins (
+    state_hash BYTEA PRIMARY KEY,
+    provider TEXT NOT NULL,
+    code_verifier TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    binding_hash BYTEA NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+
+-- Serves the expired-session sweeper.
+CREATE INDEX IF NOT EXISTS pending_external_logins_expires_at_idx
+    ON pending_external_logins (expires_at);
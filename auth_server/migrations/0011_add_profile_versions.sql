@@ -0,0 +1,4 @@
+-- Every profile write bumps the version, so an update only lands if nothing
+-- else changed the profile since it was read.
+ALTER TABLE guest_profiles
+    ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Hard cutover for guests created before 0004_add_guest_secrets: their
+-- secret_hash stays NULL and guest login refuses them for good. They cannot
+-- claim a secret on their next login, because the guest id those clients hold
+-- was never a credential; they start over through guest init.
+COMMENT ON COLUMN guest_profiles.secret_hash IS
+    'SHA-256 of the guest secret. NULL for users without one, including guests created before secrets, who can no longer log in as guests.';
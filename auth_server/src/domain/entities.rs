@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 // Guest session record stored in memory.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Session {
     pub guest_id: u64,
     pub display_name: String,
@@ -10,3 +10,125 @@ pub struct Session {
     pub session_id: String,
     pub expires_at: u64,
 }
+
+// Proof that a caller owns a guest identity, kept with the guest profile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuestCredential {
+    // SHA-256 of the guest secret; the secret itself is never stored.
+    pub secret_hash: Vec<u8>,
+    // Logins rejected for a wrong secret since the last successful one.
+    pub failed_attempts: u64,
+}
+
+// What a refresh token renews: the identity of its session and the family it rotates within.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RefreshGrant {
+    // Shared by every refresh token rotated from the same login.
+    pub family_id: String,
+    pub guest_id: u64,
+    pub display_name: String,
+    pub metadata: Option<Value>,
+    // Session issued together with this refresh token.
+    pub session_id: String,
+    pub session_expires_at: u64,
+    pub expires_at: u64,
+}
+
+// A user's public identity, keyed by the canonical user id. Sessions and token
+// verification take the display name from here rather than from the login.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub user_id: u64,
+    pub display_name: String,
+    // Client-defined data, replaced whenever a login or an update sends some.
+    pub metadata: Value,
+    // Unix seconds of the last rename; `None` until the first one.
+    pub display_name_changed_at: Option<u64>,
+    // Bumped by every save, so concurrent updates cannot overwrite each other.
+    pub version: u64,
+}
+
+// Registered account; `user_id` is the same canonical id sessions carry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub user_id: u64,
+    // Lowercase, so usernames are unique regardless of case.
+    pub username: String,
+    // Argon2 PHC string; the password itself is never stored.
+    pub password_hash: String,
+    pub display_name: String,
+}
+
+// Outcome of registering an account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountCreation {
+    Created,
+    UsernameTaken,
+    UserAlreadyRegistered,
+}
+
+// A user as asserted by an external identity provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalIdentity {
+    // Stable id of the user at the provider; never reassigned.
+    pub subject: String,
+    // The provider's name for the user, if it shared one.
+    pub display_name: Option<String>,
+}
+
+// External login between the redirect to the provider and its callback.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingExternalLogin {
+    pub provider: String,
+    // PKCE secret; only its hash was sent with the redirect.
+    pub code_verifier: String,
+    // Must come back inside the ID token, tying it to this login.
+    pub nonce: String,
+    // Hash of the secret the starting client keeps and echoes on the callback.
+    pub binding_hash: Vec<u8>,
+    pub expires_at: u64,
+}
+
+// Outcome of presenting a refresh token.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RefreshRedemption {
+    Unknown,
+    // First use; the token is now spent.
+    Fresh(RefreshGrant),
+    // The token was spent before, so it has leaked or been replayed.
+    Reused(RefreshGrant),
+}
+
+// Record of a session revoked before its expiry, published to game servers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revocation {
+    pub session_id: String,
+    pub user_id: u64,
+    pub revoked_at: u64,
+    // Retained until the session would have expired anyway.
+    pub expires_at: u64,
+}
+
+// Revocations after a poll cursor plus the cursor to resume from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevocationPage {
+    pub revocations: Vec<Revocation>,
+    pub cursor: u64,
+}
+
+// Identity carried inside a signed session token.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub user_id: u64,
+    pub display_name: String,
+    pub session_id: String,
+    // Unix seconds after which the token is no longer accepted.
+    pub exp: u64,
+}
+
+// Public half of a token signing key, published so other services can verify tokens locally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationKey {
+    pub kid: String,
+    pub public_key: [u8; 32],
+}
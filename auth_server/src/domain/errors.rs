@@ -2,8 +2,25 @@
 #[derive(Debug)]
 pub enum AuthError {
     InvalidGuestId,
+    InvalidGuestSecret,
     InvalidDisplayName,
     InvalidToken,
     SessionExpired,
     StorageFailure,
+    RefreshTokenReused,
+    InvalidUsername,
+    InvalidPassword,
+    UsernameTaken,
+    // The user id already belongs to an account, e.g. a guest upgraded twice.
+    AlreadyRegistered,
+    // Unknown username or wrong password; the two are not told apart.
+    InvalidCredentials,
+    // Unknown or expired login state, or a code or ID token the provider did not vouch for.
+    InvalidExternalLogin,
+    // The identity provider could not be reached or answered unexpectedly.
+    IdentityProviderUnavailable,
+    // The display name was changed too recently to change it again.
+    DisplayNameCooldown,
+    // No live session with that id belongs to the caller.
+    SessionNotFound,
 }
@@ -1,13 +1,144 @@
 use async_trait::async_trait;
 
-use crate::domain::entities::Session;
+use crate::domain::entities::{
+    Account, AccountCreation, ExternalIdentity, GuestCredential, PendingExternalLogin, Profile,
+    RefreshGrant, RefreshRedemption, Revocation, RevocationPage, Session, TokenClaims,
+    VerificationKey,
+};
 
 // Port for session storage used by auth use cases.
 #[async_trait]
 pub trait SessionStore: Send + Sync {
-    async fn insert(&self, token: String, session: Session) -> Result<(), String>;
+    // Returns the sessions evicted to stay within a store-wide cap; the caller revokes them.
+    async fn insert(&self, token: String, session: Session) -> Result<Vec<Session>, String>;
     async fn get(&self, token: &str) -> Result<Option<Session>, String>;
     async fn remove(&self, token: &str) -> Result<bool, String>;
+    async fn remove_by_session_id(&self, session_id: &str) -> Result<bool, String>;
+    // Deletes sessions that expired at or before `now`, returning how many were removed.
+    async fn remove_expired(&self, now: u64) -> Result<u64, String>;
+    // The user's stored sessions, expired ones not yet swept included, soonest to expire first.
+    async fn list_for_user(&self, user_id: u64) -> Result<Vec<Session>, String>;
+}
+
+// Port for the secrets that prove guest ownership.
+#[async_trait]
+pub trait GuestCredentialStore: Send + Sync {
+    // Registers a new guest; fails if `guest_id` is already taken.
+    async fn create(
+        &self,
+        guest_id: u64,
+        display_name: &str,
+        secret_hash: Vec<u8>,
+    ) -> Result<(), String>;
+    // `None` for unknown guests and for guests created before secrets existed.
+    async fn get(&self, guest_id: u64) -> Result<Option<GuestCredential>, String>;
+    // Returns the guest's failed attempts including this one.
+    async fn record_failed_attempt(&self, guest_id: u64) -> Result<u64, String>;
+    async fn reset_failed_attempts(&self, guest_id: u64) -> Result<(), String>;
+}
+
+// Port for user profiles, the authoritative display name and metadata of each user id.
+#[async_trait]
+pub trait ProfileStore: Send + Sync {
+    async fn get(&self, user_id: u64) -> Result<Option<Profile>, String>;
+    // Creates the profile on a user's first login; later logins only replace its metadata, if sent.
+    async fn record_login(
+        &self,
+        user_id: u64,
+        display_name: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<Profile, String>;
+    // Saves `profile` and bumps its version, unless the stored one is no longer at
+    // `profile.version`; returns whether it was saved.
+    async fn update(&self, profile: &Profile) -> Result<bool, String>;
+}
+
+// Port for registered accounts.
+#[async_trait]
+pub trait AccountStore: Send + Sync {
+    async fn create(&self, account: Account) -> Result<AccountCreation, String>;
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, String>;
+}
+
+// Port for password hashing, which is deliberately slow.
+#[async_trait]
+pub trait PasswordHasher: Send + Sync {
+    async fn hash(&self, password: &str) -> Result<String, String>;
+    async fn verify(&self, password: &str, password_hash: &str) -> Result<bool, String>;
+    // A hash no password matches, as costly to verify as a real one. Logins for unknown
+    // users are checked against it so they take as long as a wrong password.
+    fn dummy_hash(&self) -> &str;
+}
+
+// Port for an external identity provider using the authorization code flow with PKCE.
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    // URL the user is sent to for signing in at the provider.
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, String>;
+    // `None` when the provider rejects the code or its ID token does not check out.
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<Option<ExternalIdentity>, String>;
+}
+
+// Port for external logins waiting for the provider's callback, keyed by their `state`.
+#[async_trait]
+pub trait PendingExternalLoginStore: Send + Sync {
+    async fn insert(&self, state: String, login: PendingExternalLogin) -> Result<(), String>;
+    // Removes the login, so each `state` completes at most once.
+    async fn take(&self, state: &str) -> Result<Option<PendingExternalLogin>, String>;
+    async fn remove_expired(&self, now: u64) -> Result<u64, String>;
+}
+
+// Port mapping external identities to canonical user ids.
+#[async_trait]
+pub trait ExternalIdentityStore: Send + Sync {
+    // Returns the user id linked to the subject, linking `new_user_id` first if there is none.
+    async fn link(&self, provider: &str, subject: &str, new_user_id: u64) -> Result<u64, String>;
+}
+
+// Port for single-use refresh tokens grouped into rotation families.
+#[async_trait]
+pub trait RefreshTokenStore: Send + Sync {
+    async fn insert(&self, token: String, grant: RefreshGrant) -> Result<(), String>;
+    // Spends `token`; spent tokens are kept until they expire so a replay is detected.
+    async fn redeem(&self, token: &str) -> Result<RefreshRedemption, String>;
+    // Deletes every token in the family and returns their grants.
+    async fn revoke_family(&self, family_id: &str) -> Result<Vec<RefreshGrant>, String>;
+    // Revokes the family that issued `session_id`, if any.
+    async fn revoke_family_of_session(&self, session_id: &str)
+        -> Result<Vec<RefreshGrant>, String>;
+    // Deletes every token issued to the user and returns their grants.
+    async fn revoke_user(&self, user_id: u64) -> Result<Vec<RefreshGrant>, String>;
+    async fn remove_expired(&self, now: u64) -> Result<u64, String>;
+}
+
+// Port for the append-only log of revoked sessions that game servers poll.
+#[async_trait]
+pub trait RevocationStore: Send + Sync {
+    async fn record(&self, revocation: Revocation) -> Result<(), String>;
+    // Returns revocations recorded after `cursor` that have not expired by `now`.
+    async fn list_since(&self, cursor: u64, now: u64) -> Result<RevocationPage, String>;
+    async fn is_revoked(&self, session_id: &str) -> Result<bool, String>;
+    // Drops revocations of sessions that have expired by `now`; returns how many went.
+    async fn remove_expired(&self, now: u64) -> Result<u64, String>;
+}
+
+// Port for minting session tokens and reading back the claims of signed ones.
+pub trait TokenIssuer: Send + Sync {
+    fn issue(&self, claims: &TokenClaims) -> Result<String, String>;
+    // Claims of a token with a valid signature, expiry unchecked; `None` for opaque or forged tokens.
+    fn decode(&self, token: &str) -> Option<TokenClaims>;
+    // Keys that currently verify issued tokens; empty when tokens are opaque.
+    fn verification_keys(&self) -> Vec<VerificationKey>;
 }
 
 // Port for retrieving the current time.
@@ -1,21 +1,87 @@
+use crate::interface_adapters::oidc::{is_secure_url, OidcProviderConfig};
+use crate::interface_adapters::rate_limit::{Budget, LimitKey, LimitedRoute, RateLimits};
+use crate::interface_adapters::state::SessionBackend;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ipnet::IpNet;
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
 use std::path::PathBuf;
 
+const SIGNING_KEYS_ENV_VAR: &str = "AUTH_TOKEN_SIGNING_KEYS";
+const SESSION_STORE_ENV_VAR: &str = "AUTH_SESSION_STORE";
+const MAX_SESSIONS_ENV_VAR: &str = "AUTH_MAX_SESSIONS";
+const MAX_SESSIONS_PER_USER_ENV_VAR: &str = "AUTH_MAX_SESSIONS_PER_USER";
+const OIDC_PROVIDERS_ENV_VAR: &str = "AUTH_OIDC_PROVIDERS";
+const TRUSTED_PROXIES_ENV_VAR: &str = "AUTH_TRUSTED_PROXIES";
+const ADMIN_TOKEN_ENV_VAR: &str = "AUTH_ADMIN_TOKEN";
+const DEFAULT_MAX_SESSIONS: usize = 100_000;
+const DEFAULT_MAX_SESSIONS_PER_USER: usize = 10;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AuthServerConfig {
     pub database_url: String,
     pub bind_host: String,
     pub port: u16,
+    // Token signing keys, active key first; empty keeps tokens opaque.
+    pub signing_keys: Vec<SigningKeySeed>,
+    pub session_backend: SessionBackend,
+    // Cap on in-memory sessions; the oldest are evicted beyond it.
+    pub max_sessions: usize,
+    // Live sessions one user may hold; their oldest are ended beyond it.
+    pub max_sessions_per_user: usize,
+    // External OpenID Connect providers by name; empty disables external login.
+    pub oidc_providers: BTreeMap<String, OidcProviderConfig>,
+    // Request budgets for unauthenticated routes, and the proxies allowed to name clients.
+    pub rate_limits: RateLimits,
+    // Shared with head and game servers to sign calls to internal routes.
+    pub internal_api_secret: String,
+    // Bearer token for operator routes such as `/metrics`; unset disables them.
+    pub admin_token: Option<String>,
+}
+
+// Ed25519 private key seed with its key id.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SigningKeySeed {
+    pub kid: String,
+    pub seed: [u8; 32],
+}
+
+// Keep seeds out of logs.
+impl fmt::Debug for SigningKeySeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKeySeed")
+            .field("kid", &self.kid)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthServerConfigError {
     MissingEnvVar(&'static str),
-    InvalidEnvVar { key: &'static str, value: String },
+    InvalidEnvVar {
+        key: &'static str,
+        value: String,
+    },
     ReadPortsConfig(PathBuf),
     ParsePortsConfig(PathBuf),
     MissingPortsConfigKey(&'static str),
-    InvalidPortsConfigValue { key: &'static str, value: u16 },
+    InvalidPortsConfigValue {
+        key: &'static str,
+        value: u16,
+    },
+    // The value is secret, so only the reason is kept.
+    InvalidSigningKeys(&'static str),
+    InvalidOidcProvider {
+        provider: String,
+        reason: &'static str,
+    },
+    InvalidRateLimit {
+        key: String,
+        value: String,
+    },
 }
 
 pub trait EnvSource {
@@ -47,9 +113,244 @@ pub fn load_auth_server_config(
         database_url: required_env_var(env, "DATABASE_URL")?,
         bind_host: required_env_var(env, "AUTH_SERVER_BIND_HOST")?,
         port: resolve_auth_server_port(env)?,
+        signing_keys: parse_signing_keys(env)?,
+        session_backend: parse_session_backend(env)?,
+        max_sessions: parse_session_cap(env, MAX_SESSIONS_ENV_VAR, DEFAULT_MAX_SESSIONS)?,
+        max_sessions_per_user: parse_session_cap(
+            env,
+            MAX_SESSIONS_PER_USER_ENV_VAR,
+            DEFAULT_MAX_SESSIONS_PER_USER,
+        )?,
+        oidc_providers: parse_oidc_providers(env)?,
+        rate_limits: parse_rate_limits(env)?,
+        internal_api_secret: required_env_var(env, "INTERNAL_API_SECRET")?,
+        admin_token: env
+            .get_var(ADMIN_TOKEN_ENV_VAR)
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty()),
     })
 }
 
+// Reads `AUTH_RATE_LIMIT_<ROUTE>_IP` and, for routes with identities,
+// `AUTH_RATE_LIMIT_<ROUTE>_IDENTITY` as `<requests>/<seconds>` or `off`, plus
+// `AUTH_TRUSTED_PROXIES=cidr,...`, where a bare address is a single host. Unset budgets
+// keep their defaults.
+fn parse_rate_limits(env: &impl EnvSource) -> Result<RateLimits, AuthServerConfigError> {
+    let mut limits = RateLimits::default();
+    for route in LimitedRoute::ALL {
+        for key in LimitKey::ALL {
+            if key == LimitKey::Identity && !route.has_identity() {
+                continue;
+            }
+            let name = format!(
+                "AUTH_RATE_LIMIT_{}_{}",
+                route.name().to_ascii_uppercase(),
+                key.name().to_ascii_uppercase()
+            );
+            let Some(value) = env.get_var(&name).filter(|value| !value.trim().is_empty()) else {
+                continue;
+            };
+            let budget = parse_budget(value.trim())
+                .ok_or(AuthServerConfigError::InvalidRateLimit { key: name, value })?;
+            let limits = limits.route_mut(route);
+            match key {
+                LimitKey::Ip => limits.ip = budget,
+                LimitKey::Identity => limits.identity = budget,
+            }
+        }
+    }
+
+    if let Some(value) = env
+        .get_var(TRUSTED_PROXIES_ENV_VAR)
+        .filter(|value| !value.trim().is_empty())
+    {
+        limits.trusted_proxies = value
+            .split(',')
+            .map(|proxy| parse_trusted_proxy(proxy.trim()))
+            .collect::<Result<_, _>>()
+            .map_err(|_| AuthServerConfigError::InvalidEnvVar {
+                key: TRUSTED_PROXIES_ENV_VAR,
+                value,
+            })?;
+    }
+    Ok(limits)
+}
+
+fn parse_trusted_proxy(value: &str) -> Result<IpNet, ()> {
+    value
+        .parse::<IpNet>()
+        .or_else(|_| value.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| ())
+}
+
+// `off` disables the budget; otherwise both parts must be positive.
+fn parse_budget(value: &str) -> Option<Option<Budget>> {
+    if value == "off" {
+        return Some(None);
+    }
+    let (requests, window_seconds) = value.split_once('/')?;
+    let requests = requests.trim().parse::<u32>().ok().filter(|n| *n > 0)?;
+    let window_seconds = window_seconds
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)?;
+    Some(Some(Budget {
+        requests,
+        window_seconds,
+    }))
+}
+
+// Reads provider names from `AUTH_OIDC_PROVIDERS=name,...` and, for each name, its
+// `AUTH_OIDC_<NAME>_ISSUER`, `_CLIENT_ID`, `_REDIRECT_URI` and optional `_CLIENT_SECRET`
+// and `_ALLOW_INSECURE_LOOPBACK`.
+fn parse_oidc_providers(
+    env: &impl EnvSource,
+) -> Result<BTreeMap<String, OidcProviderConfig>, AuthServerConfigError> {
+    let Some(value) = env
+        .get_var(OIDC_PROVIDERS_ENV_VAR)
+        .filter(|value| !value.trim().is_empty())
+    else {
+        return Ok(BTreeMap::new());
+    };
+
+    let mut providers = BTreeMap::new();
+    for name in value.split(',').map(str::trim) {
+        let invalid = |reason| AuthServerConfigError::InvalidOidcProvider {
+            provider: name.to_string(),
+            reason,
+        };
+        // Names appear in routes and env var names, so keep them to one safe spelling.
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(invalid(
+                "provider names must be non-empty and use only [a-z0-9_]",
+            ));
+        }
+        if providers.contains_key(name) {
+            return Err(invalid("provider names must be unique"));
+        }
+
+        let prefix = format!("AUTH_OIDC_{}_", name.to_ascii_uppercase());
+        let setting = |suffix: &str| {
+            env.get_var(&format!("{prefix}{suffix}"))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let issuer = setting("ISSUER").ok_or_else(|| invalid("issuer is missing"))?;
+        let allow_insecure_loopback = match setting("ALLOW_INSECURE_LOOPBACK").as_deref() {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(_) => return Err(invalid("allow insecure loopback must be true or false")),
+        };
+        if !is_secure_url(&issuer, allow_insecure_loopback) {
+            return Err(invalid(
+                "issuer must use https unless insecure loopback is allowed and it is on localhost",
+            ));
+        }
+        let client_id = setting("CLIENT_ID").ok_or_else(|| invalid("client id is missing"))?;
+        let redirect_uri =
+            setting("REDIRECT_URI").ok_or_else(|| invalid("redirect uri is missing"))?;
+        providers.insert(
+            name.to_string(),
+            OidcProviderConfig {
+                issuer,
+                client_id,
+                client_secret: setting("CLIENT_SECRET"),
+                redirect_uri,
+                allow_insecure_loopback,
+            },
+        );
+    }
+    Ok(providers)
+}
+
+// Reads a positive session count, falling back to `default` when unset.
+fn parse_session_cap(
+    env: &impl EnvSource,
+    key: &'static str,
+    default: usize,
+) -> Result<usize, AuthServerConfigError> {
+    let Some(value) = env.get_var(key).filter(|value| !value.trim().is_empty()) else {
+        return Ok(default);
+    };
+
+    value
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|max| *max > 0)
+        .ok_or(AuthServerConfigError::InvalidEnvVar { key, value })
+}
+
+// Reads `memory` (the default) or `postgres`.
+fn parse_session_backend(env: &impl EnvSource) -> Result<SessionBackend, AuthServerConfigError> {
+    let Some(value) = env
+        .get_var(SESSION_STORE_ENV_VAR)
+        .filter(|value| !value.trim().is_empty())
+    else {
+        return Ok(SessionBackend::Memory);
+    };
+
+    match value.trim() {
+        "memory" => Ok(SessionBackend::Memory),
+        "postgres" => Ok(SessionBackend::Postgres),
+        _ => Err(AuthServerConfigError::InvalidEnvVar {
+            key: SESSION_STORE_ENV_VAR,
+            value,
+        }),
+    }
+}
+
+// Parses `kid:seed` pairs separated by commas, where each seed is 32 bytes of base64url.
+fn parse_signing_keys(env: &impl EnvSource) -> Result<Vec<SigningKeySeed>, AuthServerConfigError> {
+    let Some(value) = env
+        .get_var(SIGNING_KEYS_ENV_VAR)
+        .filter(|value| !value.trim().is_empty())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut keys: Vec<SigningKeySeed> = Vec::new();
+    for entry in value.split(',').map(str::trim) {
+        let (kid, seed) =
+            entry
+                .split_once(':')
+                .ok_or(AuthServerConfigError::InvalidSigningKeys(
+                    "expected comma-separated kid:seed entries",
+                ))?;
+        if kid.is_empty()
+            || !kid
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(AuthServerConfigError::InvalidSigningKeys(
+                "key ids must be non-empty and use only [A-Za-z0-9._-]",
+            ));
+        }
+        if keys.iter().any(|key| key.kid == kid) {
+            return Err(AuthServerConfigError::InvalidSigningKeys(
+                "key ids must be unique",
+            ));
+        }
+        let seed = URL_SAFE_NO_PAD
+            .decode(seed.trim_end_matches('='))
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or(AuthServerConfigError::InvalidSigningKeys(
+                "seeds must be 32 bytes encoded as base64url",
+            ))?;
+        keys.push(SigningKeySeed {
+            kid: kid.to_string(),
+            seed,
+        });
+    }
+    Ok(keys)
+}
+
 fn resolve_auth_server_port(env: &impl EnvSource) -> Result<u16, AuthServerConfigError> {
     if let Some(value) = env.get_var("AUTH_SERVER_PORT") {
         if value.is_empty() {
@@ -217,6 +518,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn load_auth_server_config_requires_internal_api_secret() {
+        for secret in [None, Some(""), Some("  ")] {
+            let mut pairs = vec![
+                ("DATABASE_URL", "postgres://db"),
+                ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+                ("AUTH_SERVER_PORT", "4310"),
+            ];
+            pairs.extend(secret.map(|secret| ("INTERNAL_API_SECRET", secret)));
+
+            assert!(matches!(
+                load_auth_server_config(&TestEnv::from_pairs(&pairs)),
+                Err(AuthServerConfigError::MissingEnvVar("INTERNAL_API_SECRET"))
+            ));
+        }
+    }
+
     #[test]
     fn load_auth_server_config_reads_port_from_shared_catalog() {
         let config_file = TempConfigFile::new(
@@ -230,6 +548,7 @@ auth_server = 4102
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             (
                 "BACKEND_PORTS_CONFIG_PATH",
                 config_file.path().to_string_lossy().as_ref(),
@@ -245,6 +564,7 @@ auth_server = 4102
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("AUTH_SERVER_PORT", "4310"),
         ]))
         .expect("config should load");
@@ -265,6 +585,7 @@ auth_server = 4202
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("AUTH_SERVER_PORT", ""),
             (
                 "BACKEND_PORTS_CONFIG_PATH",
@@ -281,6 +602,7 @@ auth_server = 4202
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("AUTH_SERVER_PORT", "not-a-number"),
         ]));
 
@@ -298,6 +620,7 @@ auth_server = 4202
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("AUTH_SERVER_PORT", "0"),
         ]));
 
@@ -315,6 +638,7 @@ auth_server = 4202
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
         ]))
         .expect("config should load from default backend ports path");
 
@@ -334,6 +658,7 @@ head_server = 3000
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             (
                 "BACKEND_PORTS_CONFIG_PATH",
                 config_file.path().to_string_lossy().as_ref(),
@@ -361,6 +686,7 @@ auth_server = 0
         let config = load_auth_server_config(&TestEnv::from_pairs(&[
             ("DATABASE_URL", "postgres://db"),
             ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             (
                 "BACKEND_PORTS_CONFIG_PATH",
                 config_file.path().to_string_lossy().as_ref(),
@@ -375,4 +701,356 @@ auth_server = 0
             })
         ));
     }
+
+    #[test]
+    fn load_auth_server_config_selects_session_backend() {
+        let default = load_auth_server_config(&TestEnv::from_pairs(&[
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+        ]))
+        .expect("config should load");
+        let postgres = load_auth_server_config(&TestEnv::from_pairs(&[
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+            ("AUTH_SESSION_STORE", "postgres"),
+        ]))
+        .expect("config should load");
+        let invalid = load_auth_server_config(&TestEnv::from_pairs(&[
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+            ("AUTH_SESSION_STORE", "redis"),
+        ]));
+
+        assert_eq!(default.session_backend, SessionBackend::Memory);
+        assert_eq!(postgres.session_backend, SessionBackend::Postgres);
+        assert!(matches!(
+            invalid,
+            Err(AuthServerConfigError::InvalidEnvVar {
+                key: "AUTH_SESSION_STORE",
+                value,
+            }) if value == "redis"
+        ));
+    }
+
+    #[test]
+    fn load_auth_server_config_reads_session_cap() {
+        let base = [
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+        ];
+        let with_cap = |value: &'static str| {
+            let mut pairs = base.to_vec();
+            pairs.push(("AUTH_MAX_SESSIONS", value));
+            load_auth_server_config(&TestEnv::from_pairs(&pairs))
+        };
+
+        let default =
+            load_auth_server_config(&TestEnv::from_pairs(&base)).expect("config should load");
+        assert_eq!(default.max_sessions, 100_000);
+        assert_eq!(
+            with_cap("250").expect("config should load").max_sessions,
+            250
+        );
+        for invalid in ["0", "-1", "lots"] {
+            assert!(matches!(
+                with_cap(invalid),
+                Err(AuthServerConfigError::InvalidEnvVar {
+                    key: "AUTH_MAX_SESSIONS",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn load_auth_server_config_reads_per_user_session_cap() {
+        let base = [
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+        ];
+        let with_cap = |value: &'static str| {
+            let mut pairs = base.to_vec();
+            pairs.push(("AUTH_MAX_SESSIONS_PER_USER", value));
+            load_auth_server_config(&TestEnv::from_pairs(&pairs))
+        };
+
+        let default =
+            load_auth_server_config(&TestEnv::from_pairs(&base)).expect("config should load");
+        assert_eq!(default.max_sessions_per_user, 10);
+        assert_eq!(
+            with_cap("3")
+                .expect("config should load")
+                .max_sessions_per_user,
+            3
+        );
+        for invalid in ["0", "many"] {
+            assert!(matches!(
+                with_cap(invalid),
+                Err(AuthServerConfigError::InvalidEnvVar {
+                    key: "AUTH_MAX_SESSIONS_PER_USER",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn load_auth_server_config_reads_rate_limits() {
+        let base = [
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+        ];
+        let with = |key: &'static str, value: &'static str| {
+            let mut pairs = base.to_vec();
+            pairs.push((key, value));
+            load_auth_server_config(&TestEnv::from_pairs(&pairs))
+        };
+
+        let default =
+            load_auth_server_config(&TestEnv::from_pairs(&base)).expect("config should load");
+        assert_eq!(default.rate_limits, RateLimits::default());
+
+        let config =
+            with("AUTH_RATE_LIMIT_GUEST_LOGIN_IDENTITY", "5/300").expect("config should load");
+        assert_eq!(
+            config.rate_limits.guest_login.identity,
+            Some(Budget {
+                requests: 5,
+                window_seconds: 300,
+            })
+        );
+        assert_eq!(
+            config.rate_limits.guest_login.ip,
+            RateLimits::default().guest_login.ip
+        );
+        let config = with("AUTH_RATE_LIMIT_VERIFY_TOKEN_IP", "off").expect("config should load");
+        assert_eq!(config.rate_limits.verify_token.ip, None);
+        assert!(RateLimits::default().trusted_proxies.is_empty());
+        let config = with("AUTH_TRUSTED_PROXIES", "10.0.0.2, 172.16.0.0/12, ::1")
+            .expect("config should load");
+        assert_eq!(
+            config.rate_limits.trusted_proxies,
+            vec![
+                "10.0.0.2/32".parse::<IpNet>().unwrap(),
+                "172.16.0.0/12".parse::<IpNet>().unwrap(),
+                "::1/128".parse::<IpNet>().unwrap()
+            ]
+        );
+
+        for invalid in ["10", "0/60", "10/0", "ten/60"] {
+            assert!(matches!(
+                with("AUTH_RATE_LIMIT_GUEST_INIT_IP", invalid),
+                Err(AuthServerConfigError::InvalidRateLimit { key, .. })
+                    if key == "AUTH_RATE_LIMIT_GUEST_INIT_IP"
+            ));
+        }
+        assert!(matches!(
+            with("AUTH_TRUSTED_PROXIES", "head.internal"),
+            Err(AuthServerConfigError::InvalidEnvVar {
+                key: "AUTH_TRUSTED_PROXIES",
+                ..
+            })
+        ));
+        assert!(matches!(
+            with("AUTH_TRUSTED_PROXIES", "10.0.0.0/33"),
+            Err(AuthServerConfigError::InvalidEnvVar {
+                key: "AUTH_TRUSTED_PROXIES",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn load_auth_server_config_reads_optional_admin_token() {
+        let load = |token: Option<&'static str>| {
+            let mut pairs = vec![
+                ("DATABASE_URL", "postgres://db"),
+                ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+                ("INTERNAL_API_SECRET", "shared-secret"),
+                ("AUTH_SERVER_PORT", "4310"),
+            ];
+            pairs.extend(token.map(|token| ("AUTH_ADMIN_TOKEN", token)));
+            load_auth_server_config(&TestEnv::from_pairs(&pairs))
+                .expect("config should load")
+                .admin_token
+        };
+
+        assert_eq!(load(None), None);
+        assert_eq!(load(Some("  ")), None);
+        assert_eq!(load(Some("s3cret")), Some("s3cret".to_string()));
+    }
+
+    #[test]
+    fn load_auth_server_config_keeps_tokens_opaque_without_signing_keys() {
+        let config = load_auth_server_config(&TestEnv::from_pairs(&[
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+        ]))
+        .expect("config should load");
+
+        assert!(config.signing_keys.is_empty());
+    }
+
+    #[test]
+    fn load_auth_server_config_reads_signing_keys_in_order() {
+        let seed_2 = URL_SAFE_NO_PAD.encode([2u8; 32]);
+        let seed_1 = format!("{}=", URL_SAFE_NO_PAD.encode([1u8; 32]));
+        let keys = format!("key-2:{seed_2}, key-1:{seed_1}");
+
+        let config = load_auth_server_config(&TestEnv::from_pairs(&[
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+            ("AUTH_TOKEN_SIGNING_KEYS", &keys),
+        ]))
+        .expect("config should load");
+
+        assert_eq!(
+            config.signing_keys,
+            vec![
+                SigningKeySeed {
+                    kid: "key-2".to_string(),
+                    seed: [2; 32],
+                },
+                SigningKeySeed {
+                    kid: "key-1".to_string(),
+                    seed: [1; 32],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn load_auth_server_config_rejects_malformed_signing_keys() {
+        let seed = URL_SAFE_NO_PAD.encode([1u8; 32]);
+        let short_seed = URL_SAFE_NO_PAD.encode([1u8; 16]);
+        let duplicate = format!("key-1:{seed},key-1:{seed}");
+        let short = format!("key-1:{short_seed}");
+
+        for keys in [seed.as_str(), duplicate.as_str(), short.as_str()] {
+            let config = load_auth_server_config(&TestEnv::from_pairs(&[
+                ("DATABASE_URL", "postgres://db"),
+                ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+                ("INTERNAL_API_SECRET", "shared-secret"),
+                ("AUTH_SERVER_PORT", "4310"),
+                ("AUTH_TOKEN_SIGNING_KEYS", keys),
+            ]));
+
+            assert!(matches!(
+                config,
+                Err(AuthServerConfigError::InvalidSigningKeys(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn load_auth_server_config_reads_oidc_providers() {
+        let config = load_auth_server_config(&TestEnv::from_pairs(&[
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+            ("AUTH_OIDC_PROVIDERS", "google, dev_idp"),
+            ("AUTH_OIDC_GOOGLE_ISSUER", "https://accounts.google.com"),
+            ("AUTH_OIDC_GOOGLE_CLIENT_ID", "client-1"),
+            ("AUTH_OIDC_GOOGLE_CLIENT_SECRET", "secret-1"),
+            ("AUTH_OIDC_GOOGLE_REDIRECT_URI", "https://game.example/oidc"),
+            (
+                "AUTH_OIDC_DEV_IDP_ISSUER",
+                "http://localhost:8080/realms/dev",
+            ),
+            ("AUTH_OIDC_DEV_IDP_CLIENT_ID", "client-2"),
+            ("AUTH_OIDC_DEV_IDP_ALLOW_INSECURE_LOOPBACK", "true"),
+            (
+                "AUTH_OIDC_DEV_IDP_REDIRECT_URI",
+                "http://localhost:3000/oidc",
+            ),
+        ]))
+        .expect("config should load");
+
+        assert_eq!(
+            config.oidc_providers["google"],
+            OidcProviderConfig {
+                issuer: "https://accounts.google.com".to_string(),
+                client_id: "client-1".to_string(),
+                client_secret: Some("secret-1".to_string()),
+                redirect_uri: "https://game.example/oidc".to_string(),
+                allow_insecure_loopback: false,
+            }
+        );
+        assert_eq!(config.oidc_providers["dev_idp"].client_secret, None);
+        assert!(config.oidc_providers["dev_idp"].allow_insecure_loopback);
+        assert_eq!(config.oidc_providers.len(), 2);
+    }
+
+    #[test]
+    fn load_auth_server_config_rejects_incomplete_or_insecure_oidc_providers() {
+        let base = [
+            ("DATABASE_URL", "postgres://db"),
+            ("AUTH_SERVER_BIND_HOST", "0.0.0.0"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("AUTH_SERVER_PORT", "4310"),
+            ("AUTH_OIDC_CORP_CLIENT_ID", "client"),
+            ("AUTH_OIDC_CORP_REDIRECT_URI", "https://game.example/oidc"),
+        ];
+        let with = |extra: &[(&'static str, &'static str)]| {
+            let mut pairs = base.to_vec();
+            pairs.extend_from_slice(extra);
+            load_auth_server_config(&TestEnv::from_pairs(&pairs))
+        };
+
+        for extra in [
+            &[("AUTH_OIDC_PROVIDERS", "corp")][..],
+            &[
+                ("AUTH_OIDC_PROVIDERS", "corp"),
+                ("AUTH_OIDC_CORP_ISSUER", "http://idp.example"),
+            ],
+            &[
+                ("AUTH_OIDC_PROVIDERS", "corp"),
+                ("AUTH_OIDC_CORP_ISSUER", "http://localhost:8080"),
+            ],
+            &[
+                ("AUTH_OIDC_PROVIDERS", "corp"),
+                ("AUTH_OIDC_CORP_ISSUER", "http://idp.example"),
+                ("AUTH_OIDC_CORP_ALLOW_INSECURE_LOOPBACK", "true"),
+            ],
+            &[
+                ("AUTH_OIDC_PROVIDERS", "corp"),
+                ("AUTH_OIDC_CORP_ISSUER", "https://idp.example"),
+                ("AUTH_OIDC_CORP_ALLOW_INSECURE_LOOPBACK", "yes"),
+            ],
+            &[
+                ("AUTH_OIDC_PROVIDERS", "Corp"),
+                ("AUTH_OIDC_CORP_ISSUER", "https://idp.example"),
+            ],
+            &[
+                ("AUTH_OIDC_PROVIDERS", "corp,corp"),
+                ("AUTH_OIDC_CORP_ISSUER", "https://idp.example"),
+            ],
+        ] {
+            assert!(matches!(
+                with(extra),
+                Err(AuthServerConfigError::InvalidOidcProvider { .. })
+            ));
+        }
+        assert!(with(&[])
+            .expect("config should load")
+            .oidc_providers
+            .is_empty());
+    }
 }
@@ -1,14 +1,28 @@
 use crate::frameworks::config::{load_auth_server_config, AuthServerConfigError, ProcessEnv};
 use crate::frameworks::db;
+use crate::interface_adapters::external_identities::PendingExternalLoginMap;
+use crate::interface_adapters::metrics::SessionMetrics;
+use crate::interface_adapters::oidc::OidcIdentityProvider;
+use crate::interface_adapters::passwords::Argon2Passwords;
+use crate::interface_adapters::rate_limit::RateLimiter;
+use crate::interface_adapters::refresh_tokens::RefreshTokenMap;
 use crate::interface_adapters::routes::app;
-use crate::interface_adapters::state::AppState;
+use crate::interface_adapters::state::{
+    AppState, RevocationLog, SessionBackend, SessionMap, SystemClock,
+};
+use crate::interface_adapters::tokens::SigningKeyring;
+use crate::use_cases::sweep_expired_sessions::SweepExpiredSessionsUseCase;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 
+// How often expired sessions are deleted from the session store.
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum StartupFailure {
     MissingRequiredConfig,
@@ -71,7 +85,7 @@ pub async fn run() -> Result<(), StartupFailure> {
             tracing::error!(
                 env_var = key,
                 value = %value,
-                "environment variable has invalid numeric value"
+                "environment variable has invalid value"
             );
             StartupFailure::InvalidConfiguration
         }
@@ -104,14 +118,91 @@ pub async fn run() -> Result<(), StartupFailure> {
             );
             StartupFailure::InvalidConfiguration
         }
+        AuthServerConfigError::InvalidSigningKeys(reason) => {
+            tracing::error!(
+                env_var = "AUTH_TOKEN_SIGNING_KEYS",
+                reason,
+                "token signing keys are invalid"
+            );
+            StartupFailure::InvalidConfiguration
+        }
+        AuthServerConfigError::InvalidOidcProvider { provider, reason } => {
+            tracing::error!(
+                provider = %provider,
+                reason,
+                "identity provider configuration is invalid"
+            );
+            StartupFailure::InvalidConfiguration
+        }
+        AuthServerConfigError::InvalidRateLimit { key, value } => {
+            tracing::error!(
+                env_var = %key,
+                value = %value,
+                "rate limit must be <requests>/<seconds> or off"
+            );
+            StartupFailure::InvalidConfiguration
+        }
     })?;
     let db = initialize_database(&config.database_url).await?;
 
-    // Shared, in-memory store for guest sessions.
+    let signing_keys = SigningKeyring::new(
+        config
+            .signing_keys
+            .into_iter()
+            .map(|key| (key.kid, key.seed))
+            .collect(),
+    )
+    .map(Arc::new);
+    match &signing_keys {
+        Some(_) => tracing::info!("issuing signed session tokens"),
+        None => tracing::info!("issuing opaque session tokens"),
+    }
+
+    match config.session_backend {
+        SessionBackend::Memory => tracing::info!(
+            max_sessions = config.max_sessions,
+            "storing sessions in memory"
+        ),
+        SessionBackend::Postgres => tracing::info!("storing sessions in postgres"),
+    }
+
+    let mut identity_providers = HashMap::new();
+    for (name, provider) in config.oidc_providers {
+        let provider = OidcIdentityProvider::new(provider).map_err(|error| {
+            tracing::error!(provider = %name, error = %error, "failed to build identity provider");
+            StartupFailure::InvalidConfiguration
+        })?;
+        tracing::info!(provider = %name, "external login enabled");
+        identity_providers.insert(name, provider);
+    }
+
+    tracing::info!(
+        limits = ?config.rate_limits,
+        "rate limiting guest and token verification routes"
+    );
+
+    // The in-memory session map is only used by the memory backend.
     let state = AppState {
-        sessions: Arc::new(Mutex::new(HashMap::new())),
+        sessions: Arc::new(Mutex::new(SessionMap::new(config.max_sessions))),
+        revocations: Arc::new(Mutex::new(RevocationLog::default())),
+        signing_keys,
+        session_backend: config.session_backend,
         db,
+        session_metrics: Arc::new(SessionMetrics::default()),
+        refresh_tokens: Arc::new(Mutex::new(RefreshTokenMap::default())),
+        guest_credentials: None,
+        profiles: None,
+        accounts: None,
+        passwords: Argon2Passwords::default(),
+        identity_providers: Arc::new(identity_providers),
+        external_identities: None,
+        pending_external_logins: Arc::new(Mutex::new(PendingExternalLoginMap::new())),
+        rate_limiter: Arc::new(RateLimiter::new(config.rate_limits)),
+        max_sessions_per_user: config.max_sessions_per_user,
+        internal_api_secret: Arc::from(config.internal_api_secret),
+        admin_token: config.admin_token.map(Arc::from),
     };
+    spawn_session_sweeper(state.clone());
 
     // Wire routes for the guest-only auth flow.
     let app = app(state);
@@ -130,7 +221,13 @@ pub async fn run() -> Result<(), StartupFailure> {
     let listener = bind_listener(addr).await?;
     tracing::info!(%addr, "listening");
 
-    axum::serve(listener, app).await.map_err(|error| {
+    // Peer addresses are needed to rate limit by client.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .map_err(|error| {
         tracing::error!(error = %error, "server error");
         StartupFailure::Serve
     })
@@ -150,6 +247,43 @@ async fn initialize_database(database_url: &str) -> Result<PgPool, StartupFailur
     Ok(pool)
 }
 
+// Periodically deletes expired sessions, refresh tokens and revocations, including ones nobody
+// presents again.
+fn spawn_session_sweeper(state: AppState) {
+    tokio::spawn(async move {
+        let use_case = SweepExpiredSessionsUseCase {
+            clock: SystemClock,
+            store: state.session_store(),
+            refresh_tokens: state.refresh_token_store(),
+            pending_logins: state.pending_external_login_store(),
+            revocations: state.revocation_store(),
+        };
+        let mut ticker = tokio::time::interval(SESSION_SWEEP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match use_case.execute().await {
+                Ok(outcome) => {
+                    state.session_metrics.record_expired(outcome.sessions);
+                    if outcome.sessions > 0
+                        || outcome.refresh_tokens > 0
+                        || outcome.pending_logins > 0
+                        || outcome.revocations > 0
+                    {
+                        tracing::debug!(
+                            sessions = outcome.sessions,
+                            refresh_tokens = outcome.refresh_tokens,
+                            pending_logins = outcome.pending_logins,
+                            revocations = outcome.revocations,
+                            "swept expired sessions"
+                        );
+                    }
+                }
+                Err(error) => tracing::warn!(?error, "failed to sweep expired sessions"),
+            }
+        }
+    });
+}
+
 async fn bind_listener(addr: SocketAddr) -> Result<TcpListener, StartupFailure> {
     TcpListener::bind(addr).await.map_err(|error| {
         tracing::error!("Failed to bind to address {}: {}", addr, error);
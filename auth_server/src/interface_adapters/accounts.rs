@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::domain::entities::{Account, AccountCreation};
+use crate::domain::ports::AccountStore;
+
+// Accounts keyed by lowercase username.
+pub type AccountMap = HashMap<String, Account>;
+
+// In-memory account store; accounts do not survive a restart.
+#[derive(Clone)]
+pub struct InMemoryAccountStore {
+    pub accounts: Arc<Mutex<AccountMap>>,
+}
+
+// PostgreSQL-backed account store on the `accounts` table.
+#[derive(Clone)]
+pub struct PostgresAccountStore {
+    pub db: PgPool,
+}
+
+// Account store for whichever backend the state was built with.
+#[derive(Clone)]
+pub enum ConfiguredAccountStore {
+    Memory(InMemoryAccountStore),
+    Postgres(PostgresAccountStore),
+}
+
+#[async_trait]
+impl AccountStore for InMemoryAccountStore {
+    async fn create(&self, account: Account) -> Result<AccountCreation, String> {
+        let mut accounts = self.accounts.lock().await;
+        if accounts.contains_key(&account.username) {
+            return Ok(AccountCreation::UsernameTaken);
+        }
+        if accounts
+            .values()
+            .any(|existing| existing.user_id == account.user_id)
+        {
+            return Ok(AccountCreation::UserAlreadyRegistered);
+        }
+        accounts.insert(account.username.clone(), account);
+        Ok(AccountCreation::Created)
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, String> {
+        Ok(self.accounts.lock().await.get(username).cloned())
+    }
+}
+
+#[async_trait]
+impl AccountStore for PostgresAccountStore {
+    async fn create(&self, account: Account) -> Result<AccountCreation, String> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO accounts (user_id, username, password_hash, display_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(account.user_id as i64)
+        .bind(&account.username)
+        .bind(&account.password_hash)
+        .bind(&account.display_name)
+        .execute(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        if result.rows_affected() == 1 {
+            return Ok(AccountCreation::Created);
+        }
+
+        // Either unique column may have collided; report the user id first.
+        let registered: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM accounts WHERE user_id = $1)")
+                .bind(account.user_id as i64)
+                .fetch_one(&self.db)
+                .await
+                .map_err(|err| err.to_string())?;
+        Ok(if registered {
+            AccountCreation::UserAlreadyRegistered
+        } else {
+            AccountCreation::UsernameTaken
+        })
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, String> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, username, password_hash, display_name FROM accounts
+            WHERE username = $1
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        row.map(|row| {
+            Ok(Account {
+                user_id: row
+                    .try_get::<i64, _>("user_id")
+                    .map_err(|err| err.to_string())? as u64,
+                username: row.try_get("username").map_err(|err| err.to_string())?,
+                password_hash: row
+                    .try_get("password_hash")
+                    .map_err(|err| err.to_string())?,
+                display_name: row.try_get("display_name").map_err(|err| err.to_string())?,
+            })
+        })
+        .transpose()
+    }
+}
+
+#[async_trait]
+impl AccountStore for ConfiguredAccountStore {
+    async fn create(&self, account: Account) -> Result<AccountCreation, String> {
+        match self {
+            ConfiguredAccountStore::Memory(store) => store.create(account).await,
+            ConfiguredAccountStore::Postgres(store) => store.create(account).await,
+        }
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, String> {
+        match self {
+            ConfiguredAccountStore::Memory(store) => store.find_by_username(username).await,
+            ConfiguredAccountStore::Postgres(store) => store.find_by_username(username).await,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::domain::entities::PendingExternalLogin;
+use crate::domain::ports::{ExternalIdentityStore, PendingExternalLoginStore};
+use crate::interface_adapters::state::hash_token;
+
+// Linked user ids keyed by provider and subject.
+pub type ExternalIdentityMap = HashMap<(String, String), u64>;
+
+// Pending external logins keyed by their `state`.
+pub type PendingExternalLoginMap = HashMap<String, PendingExternalLogin>;
+
+// In-memory external identity store; links do not survive a restart.
+#[derive(Clone)]
+pub struct InMemoryExternalIdentityStore {
+    pub identities: Arc<Mutex<ExternalIdentityMap>>,
+}
+
+// PostgreSQL-backed external identity store on the `external_identities` table.
+#[derive(Clone)]
+pub struct PostgresExternalIdentityStore {
+    pub db: PgPool,
+}
+
+// External identity store for whichever backend the state was built with.
+#[derive(Clone)]
+pub enum ConfiguredExternalIdentityStore {
+    Memory(InMemoryExternalIdentityStore),
+    Postgres(PostgresExternalIdentityStore),
+}
+
+// In-memory store for logins waiting on the provider; the callback must reach
+// the instance that started the login.
+#[derive(Clone)]
+pub struct InMemoryPendingExternalLoginStore {
+    pub logins: Arc<Mutex<PendingExternalLoginMap>>,
+}
+
+// PostgreSQL-backed store on `pending_external_logins`, so any instance can
+// complete a login another one started.
+#[derive(Clone)]
+pub struct PostgresPendingExternalLoginStore {
+    pub db: PgPool,
+}
+
+// Pending login store for the configured session backend.
+#[derive(Clone)]
+pub enum ConfiguredPendingExternalLoginStore {
+    Memory(InMemoryPendingExternalLoginStore),
+    Postgres(PostgresPendingExternalLoginStore),
+}
+
+#[async_trait]
+impl ExternalIdentityStore for InMemoryExternalIdentityStore {
+    async fn link(&self, provider: &str, subject: &str, new_user_id: u64) -> Result<u64, String> {
+        let mut identities = self.identities.lock().await;
+        Ok(*identities
+            .entry((provider.to_string(), subject.to_string()))
+            .or_insert(new_user_id))
+    }
+}
+
+#[async_trait]
+impl ExternalIdentityStore for PostgresExternalIdentityStore {
+    async fn link(&self, provider: &str, subject: &str, new_user_id: u64) -> Result<u64, String> {
+        // The no-op update makes RETURNING yield the existing link on conflict.
+        let user_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO external_identities (provider, subject, user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (provider, subject) DO UPDATE SET provider = EXCLUDED.provider
+            RETURNING user_id
+            "#,
+        )
+        .bind(provider)
+        .bind(subject)
+        .bind(new_user_id as i64)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        Ok(user_id as u64)
+    }
+}
+
+#[async_trait]
+impl ExternalIdentityStore for ConfiguredExternalIdentityStore {
+    async fn link(&self, provider: &str, subject: &str, new_user_id: u64) -> Result<u64, String> {
+        match self {
+            ConfiguredExternalIdentityStore::Memory(store) => {
+                store.link(provider, subject, new_user_id).await
+            }
+            ConfiguredExternalIdentityStore::Postgres(store) => {
+                store.link(provider, subject, new_user_id).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PendingExternalLoginStore for InMemoryPendingExternalLoginStore {
+    async fn insert(&self, state: String, login: PendingExternalLogin) -> Result<(), String> {
+        self.logins.lock().await.insert(state, login);
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingExternalLogin>, String> {
+        Ok(self.logins.lock().await.remove(state))
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let mut logins = self.logins.lock().await;
+        let before = logins.len();
+        logins.retain(|_, login| login.expires_at > now);
+        Ok((before - logins.len()) as u64)
+    }
+}
+
+#[async_trait]
+impl PendingExternalLoginStore for PostgresPendingExternalLoginStore {
+    async fn insert(&self, state: String, login: PendingExternalLogin) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO pending_external_logins
+                (state_hash, provider, code_verifier, nonce, binding_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(hash_token(&state))
+        .bind(&login.provider)
+        .bind(&login.code_verifier)
+        .bind(&login.nonce)
+        .bind(&login.binding_hash)
+        .bind(login.expires_at as i64)
+        .execute(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingExternalLogin>, String> {
+        // Deleting and returning in one statement lets only one callback have it.
+        let row = sqlx::query(
+            r#"
+            DELETE FROM pending_external_logins
+            WHERE state_hash = $1
+            RETURNING provider, code_verifier, nonce, binding_hash, expires_at
+            "#,
+        )
+        .bind(hash_token(state))
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        row.map(|row| {
+            Ok(PendingExternalLogin {
+                provider: row.try_get("provider").map_err(|err| err.to_string())?,
+                code_verifier: row
+                    .try_get("code_verifier")
+                    .map_err(|err| err.to_string())?,
+                nonce: row.try_get("nonce").map_err(|err| err.to_string())?,
+                binding_hash: row.try_get("binding_hash").map_err(|err| err.to_string())?,
+                expires_at: row
+                    .try_get::<i64, _>("expires_at")
+                    .map_err(|err| err.to_string())? as u64,
+            })
+        })
+        .transpose()
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let result = sqlx::query("DELETE FROM pending_external_logins WHERE expires_at <= $1")
+            .bind(now as i64)
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl PendingExternalLoginStore for ConfiguredPendingExternalLoginStore {
+    async fn insert(&self, state: String, login: PendingExternalLogin) -> Result<(), String> {
+        match self {
+            ConfiguredPendingExternalLoginStore::Memory(store) => store.insert(state, login).await,
+            ConfiguredPendingExternalLoginStore::Postgres(store) => {
+                store.insert(state, login).await
+            }
+        }
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingExternalLogin>, String> {
+        match self {
+            ConfiguredPendingExternalLoginStore::Memory(store) => store.take(state).await,
+            ConfiguredPendingExternalLoginStore::Postgres(store) => store.take(state).await,
+        }
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        match self {
+            ConfiguredPendingExternalLoginStore::Memory(store) => store.remove_expired(now).await,
+            ConfiguredPendingExternalLoginStore::Postgres(store) => store.remove_expired(now).await,
+        }
+    }
+}
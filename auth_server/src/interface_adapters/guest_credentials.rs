@@ -0,0 +1,225 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::domain::entities::GuestCredential;
+use crate::domain::ports::GuestCredentialStore;
+
+// Guest credentials keyed by guest id.
+pub type GuestCredentialMap = HashMap<u64, GuestCredential>;
+
+// In-memory guest credential store; guests do not survive a restart.
+#[derive(Clone)]
+pub struct InMemoryGuestCredentialStore {
+    pub guests: Arc<Mutex<GuestCredentialMap>>,
+}
+
+// PostgreSQL-backed guest credential store on the `guest_profiles` table.
+#[derive(Clone)]
+pub struct PostgresGuestCredentialStore {
+    pub db: PgPool,
+}
+
+// Guest credential store for whichever backend the state was built with.
+#[derive(Clone)]
+pub enum ConfiguredGuestCredentialStore {
+    Memory(InMemoryGuestCredentialStore),
+    Postgres(PostgresGuestCredentialStore),
+}
+
+#[async_trait]
+impl GuestCredentialStore for InMemoryGuestCredentialStore {
+    async fn create(
+        &self,
+        guest_id: u64,
+        _display_name: &str,
+        secret_hash: Vec<u8>,
+    ) -> Result<(), String> {
+        let mut guests = self.guests.lock().await;
+        if guests.contains_key(&guest_id) {
+            return Err("guest already exists".to_string());
+        }
+        guests.insert(
+            guest_id,
+            GuestCredential {
+                secret_hash,
+                failed_attempts: 0,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get(&self, guest_id: u64) -> Result<Option<GuestCredential>, String> {
+        Ok(self.guests.lock().await.get(&guest_id).cloned())
+    }
+
+    async fn record_failed_attempt(&self, guest_id: u64) -> Result<u64, String> {
+        let mut guests = self.guests.lock().await;
+        Ok(guests.get_mut(&guest_id).map_or(0, |credential| {
+            credential.failed_attempts += 1;
+            credential.failed_attempts
+        }))
+    }
+
+    async fn reset_failed_attempts(&self, guest_id: u64) -> Result<(), String> {
+        if let Some(credential) = self.guests.lock().await.get_mut(&guest_id) {
+            credential.failed_attempts = 0;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GuestCredentialStore for PostgresGuestCredentialStore {
+    async fn create(
+        &self,
+        guest_id: u64,
+        display_name: &str,
+        secret_hash: Vec<u8>,
+    ) -> Result<(), String> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO guest_profiles (guest_id, display_name, secret_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (guest_id) DO NOTHING
+            "#,
+        )
+        .bind(guest_id.to_string())
+        .bind(display_name)
+        .bind(secret_hash)
+        .execute(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        if result.rows_affected() == 0 {
+            return Err("guest already exists".to_string());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, guest_id: u64) -> Result<Option<GuestCredential>, String> {
+        let row = sqlx::query(
+            r#"
+            SELECT secret_hash, failed_login_attempts FROM guest_profiles
+            WHERE guest_id = $1 AND secret_hash IS NOT NULL
+            "#,
+        )
+        .bind(guest_id.to_string())
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        row.map(|row| {
+            Ok(GuestCredential {
+                secret_hash: row.try_get("secret_hash").map_err(|err| err.to_string())?,
+                failed_attempts: row
+                    .try_get::<i64, _>("failed_login_attempts")
+                    .map_err(|err| err.to_string())? as u64,
+            })
+        })
+        .transpose()
+    }
+
+    async fn record_failed_attempt(&self, guest_id: u64) -> Result<u64, String> {
+        let attempts: Option<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE guest_profiles SET failed_login_attempts = failed_login_attempts + 1
+            WHERE guest_id = $1
+            RETURNING failed_login_attempts
+            "#,
+        )
+        .bind(guest_id.to_string())
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        Ok(attempts.unwrap_or(0) as u64)
+    }
+
+    async fn reset_failed_attempts(&self, guest_id: u64) -> Result<(), String> {
+        sqlx::query("UPDATE guest_profiles SET failed_login_attempts = 0 WHERE guest_id = $1")
+            .bind(guest_id.to_string())
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GuestCredentialStore for ConfiguredGuestCredentialStore {
+    async fn create(
+        &self,
+        guest_id: u64,
+        display_name: &str,
+        secret_hash: Vec<u8>,
+    ) -> Result<(), String> {
+        match self {
+            ConfiguredGuestCredentialStore::Memory(store) => {
+                store.create(guest_id, display_name, secret_hash).await
+            }
+            ConfiguredGuestCredentialStore::Postgres(store) => {
+                store.create(guest_id, display_name, secret_hash).await
+            }
+        }
+    }
+
+    async fn get(&self, guest_id: u64) -> Result<Option<GuestCredential>, String> {
+        match self {
+            ConfiguredGuestCredentialStore::Memory(store) => store.get(guest_id).await,
+            ConfiguredGuestCredentialStore::Postgres(store) => store.get(guest_id).await,
+        }
+    }
+
+    async fn record_failed_attempt(&self, guest_id: u64) -> Result<u64, String> {
+        match self {
+            ConfiguredGuestCredentialStore::Memory(store) => {
+                store.record_failed_attempt(guest_id).await
+            }
+            ConfiguredGuestCredentialStore::Postgres(store) => {
+                store.record_failed_attempt(guest_id).await
+            }
+        }
+    }
+
+    async fn reset_failed_attempts(&self, guest_id: u64) -> Result<(), String> {
+        match self {
+            ConfiguredGuestCredentialStore::Memory(store) => {
+                store.reset_failed_attempts(guest_id).await
+            }
+            ConfiguredGuestCredentialStore::Postgres(store) => {
+                store.reset_failed_attempts(guest_id).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::errors::AuthError;
+    use crate::domain::ports::ProfileStore;
+    use crate::interface_adapters::profiles::PostgresProfileStore;
+    use crate::use_cases::guest_login::verify_guest_secret;
+    use crate::use_cases::test_support::{test_database, TEST_DATABASE_URL_ENV_VAR};
+
+    #[tokio::test]
+    async fn when_guest_predates_secrets_then_login_is_refused_and_no_secret_is_claimed() {
+        let Ok(url) = std::env::var(TEST_DATABASE_URL_ENV_VAR) else {
+            return;
+        };
+        let db = test_database(&url).await;
+        let credentials = PostgresGuestCredentialStore { db: db.clone() };
+        // A guest from before 0004 has a profile row but a NULL secret hash.
+        PostgresProfileStore { db }
+            .record_login(42, "Pilot", None)
+            .await
+            .unwrap();
+
+        for secret in ["", "any-secret"] {
+            let result = verify_guest_secret(&credentials, 42, secret).await;
+            assert!(matches!(result, Err(AuthError::InvalidGuestSecret)));
+        }
+        assert!(credentials.get(42).await.unwrap().is_none());
+    }
+}
@@ -1,21 +1,60 @@
 use crate::domain::errors::AuthError;
+use crate::interface_adapters::profiles::ConfiguredProfileStore;
 use crate::interface_adapters::protocol::{
-    ErrorResponse, GuestInitRequest, GuestInitResponse, GuestLoginRequest, GuestLoginResponse,
-    HealthResponse, LogoutRequest, LogoutResponse, VerifyTokenRequest, VerifyTokenResponse,
+    AccountLoginRequest, AccountSessionResponse, ErrorCode, ErrorResponse,
+    ExternalLoginCallbackRequest, ExternalLoginStartResponse, GuestInitRequest, GuestInitResponse,
+    GuestLoginRequest, GuestLoginResponse, HealthResponse, JsonWebKey, KeySetResponse,
+    LogoutRequest, LogoutResponse, ProfileResponse, RefreshRequest, RefreshResponse,
+    RegisterRequest, RevocationsQuery, RevocationsResponse, RevokeSessionsResponse, RevokedSession,
+    SessionListResponse, SessionSummary, UpdateProfileRequest, VerifyTokenRequest,
+    VerifyTokenResponse,
 };
 use crate::interface_adapters::state::{
-    AppState, InMemorySessionStore, PostgresGuestProfileStore, SystemClock,
+    AppState, ConfiguredRevocationStore, ConfiguredSessionStore, SystemClock,
 };
+use crate::interface_adapters::tokens::SessionTokens;
+use crate::use_cases::account_login::{AccountLoginInput, AccountLoginUseCase};
+use crate::use_cases::external_login::{
+    CompleteExternalLoginInput, CompleteExternalLoginUseCase, StartExternalLoginUseCase,
+};
+use crate::use_cases::guest_init::{GuestInitInput, GuestInitOutput, GuestInitUseCase};
 use crate::use_cases::guest_login::{GuestLoginInput, GuestLoginUseCase};
+use crate::use_cases::list_revocations::ListRevocationsUseCase;
+use crate::use_cases::list_verification_keys::ListVerificationKeysUseCase;
 use crate::use_cases::logout::LogoutUseCase;
+use crate::use_cases::profile::{
+    GetProfileUseCase, ProfileUpdate, ProfileView, UpdateProfileUseCase,
+};
+use crate::use_cases::refresh_session::RefreshSessionUseCase;
+use crate::use_cases::register_account::{
+    GuestProof, RegisterAccountInput, RegisterAccountUseCase,
+};
+use crate::use_cases::sessions::{
+    ListSessionsUseCase, RevokeAllSessionsUseCase, RevokeSessionUseCase,
+};
 use crate::use_cases::verify_token::VerifyTokenUseCase;
-use axum::{extract::State, http::StatusCode, Json};
-use tracing::warn;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    Json,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 // Basic session lifetime for guest tokens (in seconds).
 const GUEST_SESSION_TTL_SECONDS: u64 = 60 * 60;
 
+// Refresh tokens keep a client signed in for this long after its last refresh (in seconds).
+const GUEST_REFRESH_TTL_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+// Time a user waits between display name changes (in seconds).
+const DISPLAY_NAME_COOLDOWN_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+// Time a user has to sign in at an external provider before the login is dropped (in seconds).
+const EXTERNAL_LOGIN_TTL_SECONDS: u64 = 10 * 60;
+
 // Lightweight liveness endpoint for container and runtime smoke checks.
 pub async fn health() -> Json<HealthResponse> {
     Json(HealthResponse { status: "ok" })
@@ -28,46 +67,42 @@ pub async fn guest_init(
 ) -> Result<Json<GuestInitResponse>, (StatusCode, Json<ErrorResponse>)> {
     // Generate a numeric guest id that stays stable for future sessions.
     let guest_id = generate_guest_id();
-    let display_name = payload.display_name;
-    let metadata = payload.metadata;
-    let metadata_json = metadata
-        .as_ref()
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| "{}".to_string());
 
-    let store = InMemorySessionStore {
-        sessions: state.sessions.clone(),
-    };
-    let use_case = GuestLoginUseCase {
+    let store = state.session_store();
+    let use_case = GuestInitUseCase {
         clock: SystemClock,
         store,
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
+        credentials: state.guest_credential_store(),
+        profiles: state.profile_store(),
         ttl_seconds: GUEST_SESSION_TTL_SECONDS,
+        refresh_ttl_seconds: GUEST_REFRESH_TTL_SECONDS,
+        max_sessions_per_user: state.max_sessions_per_user,
     };
 
-    let result = use_case
-        .execute(GuestLoginInput {
+    let GuestInitOutput {
+        guest_secret,
+        session: result,
+    } = use_case
+        .execute(GuestInitInput {
             guest_id,
-            display_name: display_name.clone(),
-            metadata,
+            display_name: payload.display_name,
+            metadata: payload.metadata,
         })
         .await
         .map_err(|err| map_auth_error(err, AuthErrorContext::GuestInit))?;
 
-    // Best-effort persistence of the guest profile for downstream services.
-    let profile_store = PostgresGuestProfileStore {
-        db: state.db.clone(),
-    };
-    if let Err(err) = profile_store
-        .upsert_guest_profile(&guest_id.to_string(), &result.display_name, &metadata_json)
-        .await
-    {
-        warn!(error = %err, "failed to upsert guest profile");
-    }
-
     Ok(Json(GuestInitResponse {
         guest_id,
+        guest_secret,
         token: result.token,
         expires_at: result.expires_at,
+        refresh_token: result.refresh_token,
+        refresh_expires_at: result.refresh_expires_at,
     }))
 }
 
@@ -78,48 +113,300 @@ pub async fn guest_login(
 ) -> Result<Json<GuestLoginResponse>, (StatusCode, Json<ErrorResponse>)> {
     let GuestLoginRequest {
         guest_id,
+        guest_secret,
         display_name,
         metadata,
     } = payload;
 
-    // Capture guest identity fields before moving the payload into the use case.
-    let metadata_json = metadata
-        .as_ref()
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| "{}".to_string());
-
-    let store = InMemorySessionStore {
-        sessions: state.sessions.clone(),
-    };
+    let store = state.session_store();
     let use_case = GuestLoginUseCase {
         clock: SystemClock,
         store,
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
+        credentials: state.guest_credential_store(),
+        profiles: state.profile_store(),
         ttl_seconds: GUEST_SESSION_TTL_SECONDS,
+        refresh_ttl_seconds: GUEST_REFRESH_TTL_SECONDS,
+        max_sessions_per_user: state.max_sessions_per_user,
     };
 
     let result = use_case
         .execute(GuestLoginInput {
             guest_id,
+            guest_secret,
             display_name,
             metadata,
         })
         .await
-        .map_err(|err| map_auth_error(err, AuthErrorContext::GuestLogin))?;
+        .map_err(|err| {
+            if matches!(err, AuthError::InvalidGuestSecret) {
+                warn!(guest_id, "guest login rejected: invalid guest secret");
+            }
+            map_auth_error(err, AuthErrorContext::GuestLogin)
+        })?;
+
+    Ok(Json(GuestLoginResponse {
+        token: result.token,
+        expires_at: result.expires_at,
+        refresh_token: result.refresh_token,
+        refresh_expires_at: result.refresh_expires_at,
+    }))
+}
+
+// Handler for registering an account, optionally upgrading the caller's guest identity.
+pub async fn register(
+    State(state): State<AppState>,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<Json<AccountSessionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let RegisterRequest {
+        username,
+        password,
+        display_name,
+        guest_id,
+        guest_secret,
+    } = payload;
+
+    let use_case = RegisterAccountUseCase {
+        clock: SystemClock,
+        store: state.session_store(),
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
+        credentials: state.guest_credential_store(),
+        accounts: state.account_store(),
+        passwords: state.passwords.clone(),
+        profiles: state.profile_store(),
+        ttl_seconds: GUEST_SESSION_TTL_SECONDS,
+        refresh_ttl_seconds: GUEST_REFRESH_TTL_SECONDS,
+        max_sessions_per_user: state.max_sessions_per_user,
+    };
+
+    let registered = use_case
+        .execute(RegisterAccountInput {
+            new_user_id: generate_guest_id(),
+            username,
+            password,
+            display_name,
+            guest: guest_id.map(|guest_id| GuestProof {
+                guest_id,
+                guest_secret: guest_secret.unwrap_or_default(),
+            }),
+        })
+        .await
+        .map_err(|err| {
+            if matches!(err, AuthError::InvalidGuestSecret) {
+                warn!(guest_id, "guest upgrade rejected: invalid guest secret");
+            }
+            map_auth_error(err, AuthErrorContext::Register)
+        })?;
+
+    Ok(Json(AccountSessionResponse {
+        user_id: registered.user_id,
+        display_name: registered.session.display_name,
+        token: registered.session.token,
+        expires_at: registered.session.expires_at,
+        refresh_token: registered.session.refresh_token,
+        refresh_expires_at: registered.session.refresh_expires_at,
+    }))
+}
+
+// Handler for username/password login.
+pub async fn account_login(
+    State(state): State<AppState>,
+    Json(payload): Json<AccountLoginRequest>,
+) -> Result<Json<AccountSessionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let use_case = AccountLoginUseCase {
+        clock: SystemClock,
+        store: state.session_store(),
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
+        accounts: state.account_store(),
+        passwords: state.passwords.clone(),
+        profiles: state.profile_store(),
+        ttl_seconds: GUEST_SESSION_TTL_SECONDS,
+        refresh_ttl_seconds: GUEST_REFRESH_TTL_SECONDS,
+        max_sessions_per_user: state.max_sessions_per_user,
+    };
+
+    let login = use_case
+        .execute(AccountLoginInput {
+            username: payload.username,
+            password: payload.password,
+        })
+        .await
+        .map_err(|err| {
+            if matches!(err, AuthError::InvalidCredentials) {
+                warn!("account login rejected: invalid credentials");
+            }
+            map_auth_error(err, AuthErrorContext::AccountLogin)
+        })?;
+
+    Ok(Json(AccountSessionResponse {
+        user_id: login.user_id,
+        display_name: login.session.display_name,
+        token: login.session.token,
+        expires_at: login.session.expires_at,
+        refresh_token: login.session.refresh_token,
+        refresh_expires_at: login.session.refresh_expires_at,
+    }))
+}
+
+// Handler for starting a login with an external identity provider.
+pub async fn external_login_start(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+) -> Result<Json<ExternalLoginStartResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let identity_provider = state
+        .identity_providers
+        .get(&provider)
+        .cloned()
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_FOUND,
+                ErrorCode::UnknownIdentityProvider,
+                "unknown identity provider",
+            )
+        })?;
+
+    let use_case = StartExternalLoginUseCase {
+        clock: SystemClock,
+        provider: identity_provider,
+        pending: state.pending_external_login_store(),
+        provider_name: provider.clone(),
+        ttl_seconds: EXTERNAL_LOGIN_TTL_SECONDS,
+    };
+
+    let started = use_case.execute().await.map_err(|err| {
+        if matches!(err, AuthError::IdentityProviderUnavailable) {
+            warn!(provider, "identity provider unavailable");
+        }
+        map_auth_error(err, AuthErrorContext::ExternalLogin)
+    })?;
+
+    Ok(Json(ExternalLoginStartResponse {
+        authorization_url: started.authorization_url,
+        state: started.state,
+        binding: started.binding,
+    }))
+}
 
-    // Best-effort persistence of the guest profile for downstream services.
-    let profile_store = PostgresGuestProfileStore {
-        db: state.db.clone(),
+// Handler for the provider's callback: trades its code for a session.
+pub async fn external_login_callback(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Json(payload): Json<ExternalLoginCallbackRequest>,
+) -> Result<Json<AccountSessionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let identity_provider = state
+        .identity_providers
+        .get(&provider)
+        .cloned()
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_FOUND,
+                ErrorCode::UnknownIdentityProvider,
+                "unknown identity provider",
+            )
+        })?;
+
+    let use_case = CompleteExternalLoginUseCase {
+        clock: SystemClock,
+        store: state.session_store(),
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
+        provider: identity_provider,
+        pending: state.pending_external_login_store(),
+        identities: state.external_identity_store(),
+        profiles: state.profile_store(),
+        provider_name: provider.clone(),
+        ttl_seconds: GUEST_SESSION_TTL_SECONDS,
+        refresh_ttl_seconds: GUEST_REFRESH_TTL_SECONDS,
+        max_sessions_per_user: state.max_sessions_per_user,
     };
-    if let Err(err) = profile_store
-        .upsert_guest_profile(&guest_id.to_string(), &result.display_name, &metadata_json)
+
+    let login = use_case
+        .execute(CompleteExternalLoginInput {
+            state: payload.state,
+            code: payload.code,
+            binding: payload.binding,
+            new_user_id: generate_guest_id(),
+        })
         .await
-    {
-        warn!(error = %err, "failed to upsert guest profile");
+        .map_err(|err| {
+            match err {
+                AuthError::InvalidExternalLogin => {
+                    warn!(provider, "external login rejected");
+                }
+                AuthError::IdentityProviderUnavailable => {
+                    warn!(provider, "identity provider unavailable");
+                }
+                _ => {}
+            }
+            map_auth_error(err, AuthErrorContext::ExternalLogin)
+        })?;
+    if login.first_login {
+        info!(
+            provider,
+            user_id = login.user_id,
+            "external identity linked to a new user"
+        );
     }
 
-    Ok(Json(GuestLoginResponse {
+    Ok(Json(AccountSessionResponse {
+        user_id: login.user_id,
+        display_name: login.session.display_name,
+        token: login.session.token,
+        expires_at: login.session.expires_at,
+        refresh_token: login.session.refresh_token,
+        refresh_expires_at: login.session.refresh_expires_at,
+    }))
+}
+
+// Handler for rotating a refresh token into a new session.
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<Json<RefreshResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let use_case = RefreshSessionUseCase {
+        clock: SystemClock,
+        store: state.session_store(),
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
+        profiles: state.profile_store(),
+        ttl_seconds: GUEST_SESSION_TTL_SECONDS,
+        refresh_ttl_seconds: GUEST_REFRESH_TTL_SECONDS,
+        max_sessions_per_user: state.max_sessions_per_user,
+    };
+
+    let result = use_case
+        .execute(payload.refresh_token)
+        .await
+        .map_err(|err| {
+            if matches!(err, AuthError::RefreshTokenReused) {
+                warn!("refresh token reused; revoked its family");
+            }
+            map_auth_error(err, AuthErrorContext::Refresh)
+        })?;
+
+    Ok(Json(RefreshResponse {
         token: result.token,
         expires_at: result.expires_at,
+        refresh_token: result.refresh_token,
+        refresh_expires_at: result.refresh_expires_at,
     }))
 }
 
@@ -128,12 +415,15 @@ pub async fn verify_token(
     State(state): State<AppState>,
     Json(payload): Json<VerifyTokenRequest>,
 ) -> Result<Json<VerifyTokenResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let store = InMemorySessionStore {
-        sessions: state.sessions.clone(),
-    };
+    let store = state.session_store();
     let use_case = VerifyTokenUseCase {
         clock: SystemClock,
         store,
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        revocations: state.revocation_store(),
+        profiles: state.profile_store(),
     };
 
     let result = use_case
@@ -150,15 +440,181 @@ pub async fn verify_token(
     }))
 }
 
+// Handler for reading the caller's profile.
+pub async fn get_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ProfileResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = bearer_token(&headers)?;
+    let use_case = GetProfileUseCase {
+        verify: session_verifier(&state),
+        rename_cooldown_seconds: DISPLAY_NAME_COOLDOWN_SECONDS,
+    };
+
+    let view = use_case
+        .execute(token)
+        .await
+        .map_err(|err| map_auth_error(err, AuthErrorContext::Profile))?;
+
+    Ok(Json(profile_response(view)))
+}
+
+// Handler for changing the caller's display name or metadata.
+pub async fn update_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateProfileRequest>,
+) -> Result<Json<ProfileResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = bearer_token(&headers)?;
+    let use_case = UpdateProfileUseCase {
+        verify: session_verifier(&state),
+        rename_cooldown_seconds: DISPLAY_NAME_COOLDOWN_SECONDS,
+    };
+
+    let view = use_case
+        .execute(
+            token,
+            ProfileUpdate {
+                display_name: payload.display_name,
+                metadata: payload.metadata,
+            },
+        )
+        .await
+        .map_err(|err| map_auth_error(err, AuthErrorContext::Profile))?;
+
+    Ok(Json(profile_response(view)))
+}
+
+// Handler for listing the caller's live sessions.
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SessionListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = bearer_token(&headers)?;
+    let use_case = ListSessionsUseCase {
+        verify: session_verifier(&state),
+    };
+
+    let views = use_case
+        .execute(token)
+        .await
+        .map_err(|err| map_auth_error(err, AuthErrorContext::Sessions))?;
+
+    Ok(Json(SessionListResponse {
+        sessions: views
+            .into_iter()
+            .map(|view| SessionSummary {
+                session_id: view.session.session_id,
+                expires_at: view.session.expires_at,
+                metadata: view.session.metadata,
+                current: view.current,
+            })
+            .collect(),
+    }))
+}
+
+// Handler for ending one of the caller's sessions.
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<RevokeSessionsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = bearer_token(&headers)?;
+    let use_case = RevokeSessionUseCase {
+        verify: session_verifier(&state),
+        refresh_tokens: state.refresh_token_store(),
+    };
+
+    use_case
+        .execute(token, &session_id)
+        .await
+        .map_err(|err| map_auth_error(err, AuthErrorContext::Sessions))?;
+
+    Ok(Json(RevokeSessionsResponse { revoked: 1 }))
+}
+
+// Handler for logging the caller out everywhere.
+pub async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<RevokeSessionsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = bearer_token(&headers)?;
+    let use_case = RevokeAllSessionsUseCase {
+        verify: session_verifier(&state),
+        refresh_tokens: state.refresh_token_store(),
+    };
+
+    let revoked = use_case
+        .execute(token)
+        .await
+        .map_err(|err| map_auth_error(err, AuthErrorContext::Sessions))?;
+
+    Ok(Json(RevokeSessionsResponse { revoked }))
+}
+
+// Token verification the bearer-authenticated endpoints use.
+fn session_verifier(
+    state: &AppState,
+) -> VerifyTokenUseCase<
+    SystemClock,
+    ConfiguredSessionStore,
+    SessionTokens,
+    ConfiguredRevocationStore,
+    ConfiguredProfileStore,
+> {
+    VerifyTokenUseCase {
+        clock: SystemClock,
+        store: state.session_store(),
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        revocations: state.revocation_store(),
+        profiles: state.profile_store(),
+    }
+}
+
+fn profile_response(view: ProfileView) -> ProfileResponse {
+    ProfileResponse {
+        user_id: view.profile.user_id,
+        display_name: view.profile.display_name,
+        metadata: view.profile.metadata,
+        display_name_changeable_at: view.display_name_changeable_at,
+    }
+}
+
+// Session token from an `Authorization: Bearer` header.
+fn bearer_token(headers: &HeaderMap) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidToken,
+                "missing session token",
+            )
+        })
+}
+
 // Handler for revoking a session token.
 pub async fn logout(
     State(state): State<AppState>,
     Json(payload): Json<LogoutRequest>,
 ) -> Result<Json<LogoutResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let store = InMemorySessionStore {
-        sessions: state.sessions.clone(),
+    let store = state.session_store();
+    let use_case = LogoutUseCase {
+        clock: SystemClock,
+        store,
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+        refresh_tokens: state.refresh_token_store(),
+        revocations: state.revocation_store(),
     };
-    let use_case = LogoutUseCase { store };
 
     let result = use_case
         .execute(payload.token)
@@ -170,12 +626,73 @@ pub async fn logout(
     }))
 }
 
+// Handler for polling sessions revoked since the caller's cursor.
+pub async fn list_revocations(
+    State(state): State<AppState>,
+    Query(query): Query<RevocationsQuery>,
+) -> Result<Json<RevocationsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let use_case = ListRevocationsUseCase {
+        clock: SystemClock,
+        revocations: state.revocation_store(),
+    };
+
+    let page = use_case
+        .execute(query.since)
+        .await
+        .map_err(|err| map_auth_error(err, AuthErrorContext::Revocations))?;
+
+    Ok(Json(RevocationsResponse {
+        revocations: page
+            .revocations
+            .into_iter()
+            .map(|revocation| RevokedSession {
+                session_id: revocation.session_id,
+                user_id: revocation.user_id,
+                revoked_at: revocation.revoked_at,
+                expires_at: revocation.expires_at,
+            })
+            .collect(),
+        cursor: page.cursor,
+    }))
+}
+
+// Handler for the key set that verifies signed session tokens; empty while tokens are opaque.
+pub async fn verification_keys(State(state): State<AppState>) -> Json<KeySetResponse> {
+    let use_case = ListVerificationKeysUseCase {
+        tokens: SessionTokens {
+            keyring: state.signing_keys.clone(),
+        },
+    };
+
+    Json(KeySetResponse {
+        keys: use_case
+            .execute()
+            .into_iter()
+            .map(|key| JsonWebKey {
+                kty: "OKP",
+                crv: "Ed25519",
+                alg: "EdDSA",
+                key_use: "sig",
+                kid: key.kid,
+                x: URL_SAFE_NO_PAD.encode(key.public_key),
+            })
+            .collect(),
+    })
+}
+
 // Helper to build a JSON error response.
-fn error_response(status: StatusCode, message: &str) -> (StatusCode, Json<ErrorResponse>) {
+fn error_response(
+    status: StatusCode,
+    code: ErrorCode,
+    message: &str,
+) -> (StatusCode, Json<ErrorResponse>) {
     (
         status,
         Json(ErrorResponse {
+            code,
             message: message.to_string(),
+            retryable: code.retryable(),
+            details: None,
         }),
     )
 }
@@ -185,51 +702,359 @@ enum AuthErrorContext {
     GuestInit,
     GuestLogin,
     VerifyToken,
+    Refresh,
     Logout,
+    Revocations,
+    Register,
+    AccountLogin,
+    ExternalLogin,
+    Profile,
+    Sessions,
 }
 
 fn map_auth_error(err: AuthError, context: AuthErrorContext) -> (StatusCode, Json<ErrorResponse>) {
     match context {
         AuthErrorContext::GuestInit => match err {
-            AuthError::InvalidDisplayName => {
-                error_response(StatusCode::BAD_REQUEST, "invalid display_name")
-            }
-            AuthError::InvalidGuestId => {
-                error_response(StatusCode::BAD_GATEWAY, "failed to allocate guest_id")
-            }
-            AuthError::StorageFailure | AuthError::InvalidToken | AuthError::SessionExpired => {
-                error_response(StatusCode::BAD_GATEWAY, "storage error")
-            }
+            AuthError::InvalidDisplayName => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidDisplayName,
+                "invalid display_name",
+            ),
+            AuthError::InvalidGuestId => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "failed to allocate guest_id",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidToken
+            | AuthError::SessionExpired
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
         },
         AuthErrorContext::GuestLogin => match err {
-            AuthError::InvalidGuestId => {
-                error_response(StatusCode::BAD_REQUEST, "guest_id is required")
-            }
-            AuthError::InvalidDisplayName => {
-                error_response(StatusCode::BAD_REQUEST, "invalid display_name")
-            }
-            AuthError::StorageFailure | AuthError::InvalidToken | AuthError::SessionExpired => {
-                error_response(StatusCode::BAD_GATEWAY, "storage error")
-            }
+            AuthError::InvalidGuestId => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidGuestId,
+                "guest_id is required",
+            ),
+            AuthError::InvalidDisplayName => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidDisplayName,
+                "invalid display_name",
+            ),
+            AuthError::InvalidGuestSecret => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidGuestCredentials,
+                "invalid guest credentials",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidToken
+            | AuthError::SessionExpired
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
         },
         AuthErrorContext::VerifyToken => match err {
-            AuthError::InvalidToken => {
-                error_response(StatusCode::UNAUTHORIZED, "invalid session token")
-            }
-            AuthError::SessionExpired => {
-                error_response(StatusCode::UNAUTHORIZED, "session expired")
-            }
-            AuthError::StorageFailure => error_response(StatusCode::BAD_GATEWAY, "storage error"),
-            AuthError::InvalidGuestId | AuthError::InvalidDisplayName => {
-                error_response(StatusCode::BAD_REQUEST, "invalid session data")
-            }
+            AuthError::InvalidToken | AuthError::RefreshTokenReused => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidToken,
+                "invalid session token",
+            ),
+            AuthError::SessionExpired => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::SessionExpired,
+                "session expired",
+            ),
+            AuthError::StorageFailure => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
+            AuthError::InvalidGuestId
+            | AuthError::InvalidDisplayName
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidRequest,
+                "invalid session data",
+            ),
+        },
+        AuthErrorContext::Refresh => match err {
+            AuthError::InvalidToken => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidRefreshToken,
+                "invalid refresh token",
+            ),
+            AuthError::SessionExpired => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::RefreshTokenExpired,
+                "refresh token expired",
+            ),
+            AuthError::RefreshTokenReused => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::RefreshTokenReused,
+                "refresh token reused",
+            ),
+            AuthError::StorageFailure => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
+            AuthError::InvalidGuestId
+            | AuthError::InvalidDisplayName
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidRequest,
+                "invalid session data",
+            ),
         },
         AuthErrorContext::Logout => match err {
-            AuthError::StorageFailure => error_response(StatusCode::BAD_GATEWAY, "storage error"),
+            AuthError::StorageFailure => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
             AuthError::InvalidGuestId
             | AuthError::InvalidDisplayName
             | AuthError::InvalidToken
-            | AuthError::SessionExpired => error_response(StatusCode::BAD_REQUEST, "invalid token"),
+            | AuthError::SessionExpired
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidToken,
+                "invalid token",
+            ),
+        },
+        AuthErrorContext::Revocations => error_response(
+            StatusCode::BAD_GATEWAY,
+            ErrorCode::StorageUnavailable,
+            "storage error",
+        ),
+        AuthErrorContext::Register => match err {
+            AuthError::InvalidUsername => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidUsername,
+                "invalid username",
+            ),
+            AuthError::InvalidPassword => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidPassword,
+                "invalid password",
+            ),
+            AuthError::InvalidDisplayName => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidDisplayName,
+                "invalid display_name",
+            ),
+            AuthError::InvalidGuestId => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidGuestId,
+                "invalid guest_id",
+            ),
+            AuthError::InvalidGuestSecret => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidGuestCredentials,
+                "invalid guest credentials",
+            ),
+            AuthError::UsernameTaken => error_response(
+                StatusCode::CONFLICT,
+                ErrorCode::UsernameTaken,
+                "username taken",
+            ),
+            AuthError::AlreadyRegistered => error_response(
+                StatusCode::CONFLICT,
+                ErrorCode::AlreadyRegistered,
+                "guest already registered",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidToken
+            | AuthError::SessionExpired
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
+        },
+        AuthErrorContext::AccountLogin => match err {
+            AuthError::InvalidCredentials => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidCredentials,
+                "invalid username or password",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidGuestId
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidDisplayName
+            | AuthError::InvalidToken
+            | AuthError::SessionExpired
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
+        },
+        AuthErrorContext::ExternalLogin => match err {
+            AuthError::InvalidExternalLogin => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidExternalLogin,
+                "invalid external login",
+            ),
+            AuthError::IdentityProviderUnavailable => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::IdentityProviderUnavailable,
+                "identity provider unavailable",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidGuestId
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidDisplayName
+            | AuthError::InvalidToken
+            | AuthError::SessionExpired
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::DisplayNameCooldown
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
+        },
+        AuthErrorContext::Profile => match err {
+            AuthError::InvalidToken | AuthError::RefreshTokenReused => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidToken,
+                "invalid session token",
+            ),
+            AuthError::SessionExpired => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::SessionExpired,
+                "session expired",
+            ),
+            AuthError::InvalidDisplayName => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidDisplayName,
+                "invalid display_name",
+            ),
+            AuthError::DisplayNameCooldown => error_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorCode::DisplayNameCooldown,
+                "display_name changed too recently",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidGuestId
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::SessionNotFound => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
+        },
+        AuthErrorContext::Sessions => match err {
+            AuthError::InvalidToken | AuthError::RefreshTokenReused => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidToken,
+                "invalid session token",
+            ),
+            AuthError::SessionExpired => error_response(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::SessionExpired,
+                "session expired",
+            ),
+            AuthError::SessionNotFound => error_response(
+                StatusCode::NOT_FOUND,
+                ErrorCode::SessionNotFound,
+                "session not found",
+            ),
+            AuthError::StorageFailure
+            | AuthError::InvalidDisplayName
+            | AuthError::InvalidGuestId
+            | AuthError::InvalidGuestSecret
+            | AuthError::InvalidUsername
+            | AuthError::InvalidPassword
+            | AuthError::UsernameTaken
+            | AuthError::AlreadyRegistered
+            | AuthError::InvalidCredentials
+            | AuthError::InvalidExternalLogin
+            | AuthError::IdentityProviderUnavailable
+            | AuthError::DisplayNameCooldown => error_response(
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::StorageUnavailable,
+                "storage error",
+            ),
         },
     }
 }
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use subtle::ConstantTimeEq;
+
+use crate::interface_adapters::protocol::{ErrorCode, ErrorResponse};
+use crate::interface_adapters::rate_limit::{LimitKey, LimitedRoute};
+use crate::interface_adapters::state::AppState;
+
+// Process-wide session counters, exposed in Prometheus text format.
+#[derive(Debug, Default)]
+pub struct SessionMetrics {
+    expired: AtomicU64,
+    evicted: AtomicU64,
+}
+
+impl SessionMetrics {
+    pub fn record_expired(&self, count: u64) {
+        self.expired.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_evicted(&self, count: u64) {
+        self.evicted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn expired(&self) -> u64 {
+        self.expired.load(Ordering::Relaxed)
+    }
+
+    pub fn evicted(&self) -> u64 {
+        self.evicted.load(Ordering::Relaxed)
+    }
+
+    // `sessions` is left out when the store could not be counted.
+    fn render(&self, sessions: Option<u64>) -> String {
+        let mut out = String::new();
+        if let Some(sessions) = sessions {
+            let _ = write!(
+                out,
+                "# HELP auth_server_sessions Sessions currently stored.\n\
+                 # TYPE auth_server_sessions gauge\n\
+                 auth_server_sessions {sessions}\n"
+            );
+        }
+        let _ = write!(
+            out,
+            "# HELP auth_server_sessions_expired_total Expired sessions removed by the sweeper.\n\
+             # TYPE auth_server_sessions_expired_total counter\n\
+             auth_server_sessions_expired_total {}\n\
+             # HELP auth_server_sessions_evicted_total Live sessions evicted to stay within the session cap.\n\
+             # TYPE auth_server_sessions_evicted_total counter\n\
+             auth_server_sessions_evicted_total {}\n",
+            self.expired(),
+            self.evicted()
+        );
+        out
+    }
+}
+
+// Rate limiter decisions per route, and per exhausted key for throttled requests.
+#[derive(Debug, Default)]
+pub struct RateLimitMetrics {
+    allowed: [AtomicU64; LimitedRoute::ALL.len()],
+    throttled: [[AtomicU64; LimitKey::ALL.len()]; LimitedRoute::ALL.len()],
+}
+
+impl RateLimitMetrics {
+    pub fn record_allowed(&self, route: LimitedRoute) {
+        self.allowed[route as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_throttled(&self, route: LimitedRoute, key: LimitKey) {
+        self.throttled[route as usize][key as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn allowed(&self, route: LimitedRoute) -> u64 {
+        self.allowed[route as usize].load(Ordering::Relaxed)
+    }
+
+    pub fn throttled(&self, route: LimitedRoute, key: LimitKey) -> u64 {
+        self.throttled[route as usize][key as usize].load(Ordering::Relaxed)
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::from(
+            "# HELP auth_server_rate_limit_allowed_total Requests admitted by the rate limiter.\n\
+             # TYPE auth_server_rate_limit_allowed_total counter\n",
+        );
+        for route in LimitedRoute::ALL {
+            let _ = writeln!(
+                out,
+                "auth_server_rate_limit_allowed_total{{route=\"{}\"}} {}",
+                route.name(),
+                self.allowed(route)
+            );
+        }
+        out.push_str(
+            "# HELP auth_server_rate_limit_throttled_total Requests rejected by the rate limiter, by the key whose budget ran out.\n\
+             # TYPE auth_server_rate_limit_throttled_total counter\n",
+        );
+        for route in LimitedRoute::ALL {
+            for key in LimitKey::ALL {
+                let _ = writeln!(
+                    out,
+                    "auth_server_rate_limit_throttled_total{{route=\"{}\",key=\"{}\"}} {}",
+                    route.name(),
+                    key.name(),
+                    self.throttled(route, key)
+                );
+            }
+        }
+        out
+    }
+}
+
+// Handler for the Prometheus scrape endpoint.
+// Metrics share the public listener, so only callers with the admin token may read them.
+pub async fn require_admin_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    let authorized = match (state.admin_token.as_deref(), provided) {
+        (Some(expected), Some(provided)) => {
+            bool::from(expected.as_bytes().ct_eq(provided.as_bytes()))
+        }
+        _ => false,
+    };
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                code: ErrorCode::InvalidAdminToken,
+                message: "admin token required".to_string(),
+                retryable: ErrorCode::InvalidAdminToken.retryable(),
+                details: None,
+            }),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let sessions = match state.session_store().count().await {
+        Ok(count) => Some(count),
+        Err(error) => {
+            tracing::warn!(error = %error, "failed to count sessions for metrics");
+            None
+        }
+    };
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.session_metrics.render(sessions) + &state.rate_limiter.metrics.render(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_metrics_are_rendered_then_counts_and_counters_are_reported() {
+        let metrics = SessionMetrics::default();
+        metrics.record_expired(3);
+        metrics.record_evicted(2);
+        metrics.record_evicted(1);
+
+        let text = metrics.render(Some(7));
+
+        assert!(text.contains("auth_server_sessions 7\n"));
+        assert!(text.contains("auth_server_sessions_expired_total 3\n"));
+        assert!(text.contains("auth_server_sessions_evicted_total 3\n"));
+        assert!(!metrics.render(None).contains("auth_server_sessions "));
+    }
+
+    #[test]
+    fn when_rate_limit_metrics_are_rendered_then_every_route_and_key_is_reported() {
+        let metrics = RateLimitMetrics::default();
+        metrics.record_allowed(LimitedRoute::GuestInit);
+        metrics.record_allowed(LimitedRoute::GuestInit);
+        metrics.record_throttled(LimitedRoute::VerifyToken, LimitKey::Identity);
+
+        let text = metrics.render();
+
+        assert!(text.contains("auth_server_rate_limit_allowed_total{route=\"guest_init\"} 2\n"));
+        assert!(text.contains("auth_server_rate_limit_allowed_total{route=\"guest_login\"} 0\n"));
+        assert!(text.contains(
+            "auth_server_rate_limit_throttled_total{route=\"verify_token\",key=\"identity\"} 1\n"
+        ));
+        assert!(text.contains(
+            "auth_server_rate_limit_throttled_total{route=\"verify_token\",key=\"ip\"} 0\n"
+        ));
+    }
+}
@@ -1,4 +1,17 @@
+pub mod accounts;
+pub mod external_identities;
+pub mod guest_credentials;
 pub mod handlers;
+pub mod metrics;
+pub mod oidc;
+pub mod passwords;
+pub mod profiles;
 pub mod protocol;
+pub mod rate_limit;
+pub mod refresh_tokens;
+pub mod request_signing;
 pub mod routes;
 pub mod state;
+#[cfg(test)]
+pub(crate) mod test_oidc_issuer;
+pub mod tokens;
@@ -0,0 +1,412 @@
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use crate::domain::entities::ExternalIdentity;
+use crate::domain::ports::{Clock, IdentityProvider};
+use crate::interface_adapters::state::SystemClock;
+
+// Upper bound on each call to the provider, so a stalled issuer cannot hold a login open.
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+// `profile` carries the name new users start with.
+const SCOPES: &str = "openid profile";
+
+// Client registration with one OpenID Connect provider.
+#[derive(Clone, PartialEq, Eq)]
+pub struct OidcProviderConfig {
+    pub issuer: String,
+    pub client_id: String,
+    // Sent with the code exchange when set; public clients rely on PKCE alone.
+    pub client_secret: Option<String>,
+    // Where the provider sends the user back with `code` and `state`.
+    pub redirect_uri: String,
+    // Lets the issuer and its endpoints use plain http on localhost, for local
+    // development and tests. Everything else must be https.
+    pub allow_insecure_loopback: bool,
+}
+
+// Keep the client secret out of logs.
+impl fmt::Debug for OidcProviderConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OidcProviderConfig")
+            .field("issuer", &self.issuer)
+            .field("client_id", &self.client_id)
+            .field("redirect_uri", &self.redirect_uri)
+            .field("allow_insecure_loopback", &self.allow_insecure_loopback)
+            .finish_non_exhaustive()
+    }
+}
+
+// OpenID Connect identity provider using the authorization code flow with PKCE.
+// Discovery runs on first use, so an issuer that is down does not block startup.
+#[derive(Clone)]
+pub struct OidcIdentityProvider {
+    config: Arc<OidcProviderConfig>,
+    http: Client,
+    metadata: Arc<OnceCell<ProviderMetadata>>,
+}
+
+// The parts of the discovery document the flow needs.
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    exp: u64,
+    nonce: Option<String>,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+// `aud` is a single client id or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Audience::One(audience) => audience == client_id,
+            Audience::Many(audiences) => audiences.iter().any(|audience| audience == client_id),
+        }
+    }
+}
+
+// ID tokens are trusted on the strength of the TLS connection they arrive over, so every
+// URL the flow talks to must be https unless the provider allows insecure loopback.
+pub(crate) fn is_secure_url(url: &str, allow_insecure_loopback: bool) -> bool {
+    if url.starts_with("https://") {
+        return true;
+    }
+    allow_insecure_loopback
+        && ["http://localhost", "http://127.0.0.1"].iter().any(|host| {
+            url.strip_prefix(host)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/']))
+        })
+}
+
+impl OidcIdentityProvider {
+    pub fn new(config: OidcProviderConfig) -> Result<Self, String> {
+        if !is_secure_url(&config.issuer, config.allow_insecure_loopback) {
+            return Err(format!("issuer {} does not use https", config.issuer));
+        }
+        let http = Client::builder()
+            .timeout(PROVIDER_TIMEOUT)
+            .build()
+            .map_err(|err| err.to_string())?;
+        Ok(Self {
+            config: Arc::new(config),
+            http,
+            metadata: Arc::new(OnceCell::new()),
+        })
+    }
+
+    // Cached discovery document; a failed fetch is retried on the next login.
+    async fn metadata(&self) -> Result<&ProviderMetadata, String> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let response = self
+                    .http
+                    .get(url)
+                    .send()
+                    .await
+                    .map_err(|err| err.to_string())?;
+                if !response.status().is_success() {
+                    return Err(format!("discovery returned {}", response.status()));
+                }
+                let metadata: ProviderMetadata =
+                    response.json().await.map_err(|err| err.to_string())?;
+                // OpenID Connect Discovery 1.0 §4.3: the document must name the issuer we asked.
+                if metadata.issuer != self.config.issuer {
+                    return Err(format!("discovery named issuer {}", metadata.issuer));
+                }
+                let insecure = [&metadata.authorization_endpoint, &metadata.token_endpoint]
+                    .into_iter()
+                    .find(|url| !is_secure_url(url, self.config.allow_insecure_loopback));
+                if let Some(url) = insecure {
+                    return Err(format!(
+                        "discovery named endpoint {url}, which does not use https"
+                    ));
+                }
+                Ok(metadata)
+            })
+            .await
+    }
+
+    // The ID token came straight from the token endpoint, which `metadata` only accepts
+    // over https, so per OpenID Connect Core §3.1.3.7 the TLS connection stands in for
+    // checking its signature.
+    fn identity_from_id_token(
+        &self,
+        id_token: &str,
+        issuer: &str,
+        nonce: &str,
+        now: u64,
+    ) -> Option<ExternalIdentity> {
+        let payload = id_token.split('.').nth(1)?;
+        let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+        let claims: IdTokenClaims = serde_json::from_slice(&payload).ok()?;
+
+        let valid = claims.iss == issuer
+            && claims.aud.contains(&self.config.client_id)
+            && claims.exp > now
+            && claims.nonce.as_deref() == Some(nonce)
+            && !claims.sub.is_empty();
+        valid.then(|| ExternalIdentity {
+            subject: claims.sub,
+            display_name: claims.name.or(claims.preferred_username),
+        })
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for OidcIdentityProvider {
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, String> {
+        let metadata = self.metadata().await?;
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_uri.as_str()),
+                ("scope", SCOPES),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        Ok(url.into())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<Option<ExternalIdentity>, String> {
+        let metadata = self.metadata().await?;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        // A bad, spent or mismatched code is a 400 (RFC 6749 §5.2); anything else is on our side.
+        if response.status() == StatusCode::BAD_REQUEST {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("token endpoint returned {}", response.status()));
+        }
+        let tokens: TokenResponse = response.json().await.map_err(|err| err.to_string())?;
+
+        Ok(self.identity_from_id_token(
+            &tokens.id_token,
+            &metadata.issuer,
+            nonce,
+            SystemClock.now_epoch_seconds(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface_adapters::test_oidc_issuer::TestIssuer;
+    use crate::use_cases::external_login::pkce_challenge;
+    use std::collections::HashMap;
+
+    const VERIFIER: &str = "test-code-verifier-that-is-long-enough-for-pkce-rules";
+
+    async fn authorize(provider: &OidcIdentityProvider, nonce: &str) -> String {
+        provider
+            .authorization_url("state-1", nonce, &pkce_challenge(VERIFIER))
+            .await
+            .expect("expected authorization url")
+    }
+
+    #[tokio::test]
+    async fn when_login_starts_then_url_points_at_the_discovered_endpoint_with_pkce() {
+        let issuer = TestIssuer::start().await;
+        let provider = OidcIdentityProvider::new(issuer.config()).unwrap();
+
+        let url = Url::parse(&authorize(&provider, "nonce-1").await).unwrap();
+
+        assert_eq!(
+            url.as_str().split('?').next(),
+            Some(issuer.authorize_url().as_str())
+        );
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["code_challenge"], pkce_challenge(VERIFIER));
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["state"], "state-1");
+        assert_eq!(params["nonce"], "nonce-1");
+        assert_eq!(params["scope"], SCOPES);
+    }
+
+    #[tokio::test]
+    async fn when_code_is_exchanged_with_its_verifier_then_identity_is_returned_once() {
+        let issuer = TestIssuer::start().await;
+        let provider = OidcIdentityProvider::new(issuer.config()).unwrap();
+        let (_, code) = issuer.approve(&authorize(&provider, "nonce-1").await, "subject-1", "Ace");
+
+        let identity = provider
+            .exchange_code(&code, VERIFIER, "nonce-1")
+            .await
+            .expect("expected exchange to reach the issuer");
+        let replay = provider
+            .exchange_code(&code, VERIFIER, "nonce-1")
+            .await
+            .expect("expected exchange to reach the issuer");
+
+        assert_eq!(
+            identity,
+            Some(ExternalIdentity {
+                subject: "subject-1".to_string(),
+                display_name: Some("Ace".to_string()),
+            })
+        );
+        assert_eq!(replay, None);
+    }
+
+    #[tokio::test]
+    async fn when_verifier_or_nonce_do_not_match_then_no_identity_is_returned() {
+        let issuer = TestIssuer::start().await;
+        let provider = OidcIdentityProvider::new(issuer.config()).unwrap();
+        let url = authorize(&provider, "nonce-1").await;
+
+        let (_, code) = issuer.approve(&url, "subject-1", "Ace");
+        let wrong_verifier = provider
+            .exchange_code(
+                &code,
+                "some-other-verifier-of-a-valid-length-for-pkce",
+                "nonce-1",
+            )
+            .await
+            .unwrap();
+        let (_, code) = issuer.approve(&url, "subject-1", "Ace");
+        let wrong_nonce = provider
+            .exchange_code(&code, VERIFIER, "nonce-2")
+            .await
+            .unwrap();
+
+        assert_eq!(wrong_verifier, None);
+        assert_eq!(wrong_nonce, None);
+    }
+
+    #[tokio::test]
+    async fn when_id_token_is_for_another_client_or_issuer_then_it_is_rejected() {
+        let issuer = TestIssuer::start().await;
+        let provider = OidcIdentityProvider::new(issuer.config()).unwrap();
+        let token = |iss: &str, aud: &str, exp: u64| {
+            let claims = serde_json::json!({
+                "iss": iss, "sub": "subject-1", "aud": aud, "exp": exp, "nonce": "nonce-1",
+            });
+            format!("e30.{}.c2ln", URL_SAFE_NO_PAD.encode(claims.to_string()))
+        };
+        let client_id = issuer.config().client_id;
+        let identity = |id_token: String| {
+            provider.identity_from_id_token(&id_token, &issuer.url, "nonce-1", 1_700_000_000)
+        };
+
+        assert!(identity(token(&issuer.url, &client_id, 1_700_000_300)).is_some());
+        assert!(identity(token(&issuer.url, "other-client", 1_700_000_300)).is_none());
+        assert!(identity(token("https://other.example", &client_id, 1_700_000_300)).is_none());
+        assert!(identity(token(&issuer.url, &client_id, 1_700_000_000)).is_none());
+        assert!(identity("not-a-jwt".to_string()).is_none());
+    }
+
+    #[tokio::test]
+    async fn when_issuer_is_unreachable_then_an_error_is_returned() {
+        let provider = OidcIdentityProvider::new(OidcProviderConfig {
+            // Port 9 (discard) is not listening on the test host.
+            issuer: "http://127.0.0.1:9".to_string(),
+            client_id: "client".to_string(),
+            client_secret: None,
+            redirect_uri: "http://localhost/callback".to_string(),
+            allow_insecure_loopback: true,
+        })
+        .unwrap();
+
+        let result = provider
+            .authorization_url("state", "nonce", "challenge")
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn when_issuer_is_not_https_then_the_provider_is_refused() {
+        let config = |issuer: &str, allow_insecure_loopback| OidcProviderConfig {
+            issuer: issuer.to_string(),
+            client_id: "client".to_string(),
+            client_secret: None,
+            redirect_uri: "https://game.example/oidc".to_string(),
+            allow_insecure_loopback,
+        };
+
+        assert!(OidcIdentityProvider::new(config("https://idp.example", false)).is_ok());
+        assert!(OidcIdentityProvider::new(config("http://127.0.0.1:8080", true)).is_ok());
+        assert!(OidcIdentityProvider::new(config("http://127.0.0.1:8080", false)).is_err());
+        assert!(OidcIdentityProvider::new(config("http://idp.example", true)).is_err());
+        assert!(OidcIdentityProvider::new(config("http://localhost.example", true)).is_err());
+    }
+
+    #[tokio::test]
+    async fn when_discovery_names_a_plain_http_token_endpoint_then_login_fails() {
+        let issuer = TestIssuer::start().await;
+        issuer.advertise_token_endpoint("http://idp.example/token");
+        let provider = OidcIdentityProvider::new(issuer.config()).unwrap();
+
+        let result = provider
+            .authorization_url("state", "nonce", "challenge")
+            .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::ports::PasswordHasher;
+
+// Hash of a throwaway password under `Params::default()`, which production uses. Verifying
+// it costs the same as verifying a real account's hash.
+const DUMMY_PASSWORD_HASH: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$RvoIY2dgS0mTdN9vN/2E1g$8ezFgpG8yMTK/VNWo3a9JTWDeUg0naF7fDMUjJ+her0";
+
+// Argon2id password hashing. Hashes are PHC strings carrying their own parameters,
+// so raising `params` later still verifies existing hashes.
+#[derive(Clone, Default)]
+pub struct Argon2Passwords {
+    pub params: Params,
+}
+
+impl Argon2Passwords {
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+#[async_trait]
+impl PasswordHasher for Argon2Passwords {
+    async fn hash(&self, password: &str) -> Result<String, String> {
+        let argon2 = self.argon2();
+        let password = password.to_string();
+        // Hashing takes tens of milliseconds by design, so keep it off the async workers.
+        tokio::task::spawn_blocking(move || {
+            // 128 random bits from a v4 UUID avoid adding a separate RNG dependency.
+            let salt =
+                SaltString::encode_b64(Uuid::new_v4().as_bytes()).map_err(|err| err.to_string())?;
+            argon2
+                .hash_password(password.as_bytes(), &salt)
+                .map(|hash| hash.to_string())
+                .map_err(|err| err.to_string())
+        })
+        .await
+        .map_err(|err| err.to_string())?
+    }
+
+    async fn verify(&self, password: &str, password_hash: &str) -> Result<bool, String> {
+        let argon2 = self.argon2();
+        let password = password.to_string();
+        let password_hash = password_hash.to_string();
+        tokio::task::spawn_blocking(move || {
+            let parsed = PasswordHash::new(&password_hash).map_err(|err| err.to_string())?;
+            Ok(argon2.verify_password(password.as_bytes(), &parsed).is_ok())
+        })
+        .await
+        .map_err(|err| err.to_string())?
+    }
+
+    fn dummy_hash(&self) -> &str {
+        DUMMY_PASSWORD_HASH
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimal cost so the test stays fast; production uses the argon2 defaults.
+    fn cheap_passwords() -> Argon2Passwords {
+        Argon2Passwords {
+            params: Params::new(1024, 1, 1, None).expect("expected valid argon2 params"),
+        }
+    }
+
+    #[tokio::test]
+    async fn when_password_is_hashed_then_only_that_password_verifies() {
+        let passwords = cheap_passwords();
+
+        let first = passwords
+            .hash("correct horse")
+            .await
+            .expect("expected hashing to succeed");
+        let second = passwords
+            .hash("correct horse")
+            .await
+            .expect("expected hashing to succeed");
+
+        assert!(first.starts_with("$argon2id$"));
+        assert_ne!(first, second, "expected a fresh salt per hash");
+        assert_eq!(passwords.verify("correct horse", &first).await, Ok(true));
+        assert_eq!(passwords.verify("wrong horse", &first).await, Ok(false));
+        assert!(passwords
+            .verify("correct horse", "not-a-hash")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn dummy_hash_costs_the_same_as_production_hashes() {
+        let parsed = PasswordHash::new(DUMMY_PASSWORD_HASH).expect("expected a valid PHC string");
+        let params = Params::try_from(&parsed).expect("expected argon2 params");
+        let production = Params::default();
+
+        assert_eq!(parsed.algorithm, Algorithm::Argon2id.ident());
+        assert_eq!(
+            (params.m_cost(), params.t_cost(), params.p_cost()),
+            (
+                production.m_cost(),
+                production.t_cost(),
+                production.p_cost()
+            )
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::domain::entities::Profile;
+use crate::domain::ports::ProfileStore;
+
+// Profiles keyed by user id.
+pub type ProfileMap = HashMap<u64, Profile>;
+
+// In-memory profile store; profiles do not survive a restart.
+#[derive(Clone)]
+pub struct InMemoryProfileStore {
+    pub profiles: Arc<Mutex<ProfileMap>>,
+}
+
+// PostgreSQL-backed profile store on the `guest_profiles` table, which guest
+// credentials share.
+#[derive(Clone)]
+pub struct PostgresProfileStore {
+    pub db: PgPool,
+}
+
+// Profile store for whichever backend the state was built with.
+#[derive(Clone)]
+pub enum ConfiguredProfileStore {
+    Memory(InMemoryProfileStore),
+    Postgres(PostgresProfileStore),
+}
+
+#[async_trait]
+impl ProfileStore for InMemoryProfileStore {
+    async fn get(&self, user_id: u64) -> Result<Option<Profile>, String> {
+        Ok(self.profiles.lock().await.get(&user_id).cloned())
+    }
+
+    async fn record_login(
+        &self,
+        user_id: u64,
+        display_name: &str,
+        metadata: Option<Value>,
+    ) -> Result<Profile, String> {
+        let mut profiles = self.profiles.lock().await;
+        let profile = profiles.entry(user_id).or_insert_with(|| Profile {
+            user_id,
+            display_name: display_name.to_string(),
+            metadata: Value::Object(Default::default()),
+            display_name_changed_at: None,
+            version: 0,
+        });
+        if let Some(metadata) = metadata {
+            profile.metadata = metadata;
+            profile.version += 1;
+        }
+        Ok(profile.clone())
+    }
+
+    async fn update(&self, profile: &Profile) -> Result<bool, String> {
+        let mut profiles = self.profiles.lock().await;
+        match profiles.get_mut(&profile.user_id) {
+            Some(stored) if stored.version == profile.version => {
+                *stored = Profile {
+                    version: profile.version + 1,
+                    ..profile.clone()
+                };
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[async_trait]
+impl ProfileStore for PostgresProfileStore {
+    async fn get(&self, user_id: u64) -> Result<Option<Profile>, String> {
+        let row = sqlx::query(
+            r#"
+            SELECT display_name, metadata, display_name_changed_at, version FROM guest_profiles
+            WHERE guest_id = $1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        row.map(|row| profile_from_row(user_id, &row)).transpose()
+    }
+
+    async fn record_login(
+        &self,
+        user_id: u64,
+        display_name: &str,
+        metadata: Option<Value>,
+    ) -> Result<Profile, String> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO guest_profiles (guest_id, display_name, metadata)
+            VALUES ($1, $2, COALESCE($3::TEXT, '{}'))
+            ON CONFLICT (guest_id) DO UPDATE SET
+                metadata = COALESCE($3::TEXT, guest_profiles.metadata),
+                version = guest_profiles.version + CASE WHEN $3::TEXT IS NULL THEN 0 ELSE 1 END
+            RETURNING display_name, metadata, display_name_changed_at, version
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(display_name)
+        .bind(metadata.map(|metadata| metadata.to_string()))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        profile_from_row(user_id, &row)
+    }
+
+    async fn update(&self, profile: &Profile) -> Result<bool, String> {
+        let result = sqlx::query(
+            r#"
+            UPDATE guest_profiles
+            SET display_name = $2, metadata = $3, display_name_changed_at = $4,
+                version = version + 1
+            WHERE guest_id = $1 AND version = $5
+            "#,
+        )
+        .bind(profile.user_id.to_string())
+        .bind(&profile.display_name)
+        .bind(profile.metadata.to_string())
+        .bind(profile.display_name_changed_at.map(|at| at as i64))
+        .bind(profile.version as i64)
+        .execute(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+fn profile_from_row(user_id: u64, row: &PgRow) -> Result<Profile, String> {
+    let metadata: String = row.try_get("metadata").map_err(|err| err.to_string())?;
+    Ok(Profile {
+        user_id,
+        display_name: row.try_get("display_name").map_err(|err| err.to_string())?,
+        metadata: serde_json::from_str(&metadata).map_err(|err| err.to_string())?,
+        display_name_changed_at: row
+            .try_get::<Option<i64>, _>("display_name_changed_at")
+            .map_err(|err| err.to_string())?
+            .map(|at| at as u64),
+        version: row
+            .try_get::<i64, _>("version")
+            .map_err(|err| err.to_string())? as u64,
+    })
+}
+
+#[async_trait]
+impl ProfileStore for ConfiguredProfileStore {
+    async fn get(&self, user_id: u64) -> Result<Option<Profile>, String> {
+        match self {
+            ConfiguredProfileStore::Memory(store) => store.get(user_id).await,
+            ConfiguredProfileStore::Postgres(store) => store.get(user_id).await,
+        }
+    }
+
+    async fn record_login(
+        &self,
+        user_id: u64,
+        display_name: &str,
+        metadata: Option<Value>,
+    ) -> Result<Profile, String> {
+        match self {
+            ConfiguredProfileStore::Memory(store) => {
+                store.record_login(user_id, display_name, metadata).await
+            }
+            ConfiguredProfileStore::Postgres(store) => {
+                store.record_login(user_id, display_name, metadata).await
+            }
+        }
+    }
+
+    async fn update(&self, profile: &Profile) -> Result<bool, String> {
+        match self {
+            ConfiguredProfileStore::Memory(store) => store.update(profile).await,
+            ConfiguredProfileStore::Postgres(store) => store.update(profile).await,
+        }
+    }
+}
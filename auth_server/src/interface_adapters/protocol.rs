@@ -12,14 +12,19 @@ pub struct GuestInitRequest {
 #[derive(Debug, Serialize)]
 pub struct GuestInitResponse {
     pub guest_id: u64,
+    // Only returned here; `/auth/guest` requires it from then on.
+    pub guest_secret: String,
     pub token: String,
     pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
 }
 
 // Request payload for guest login.
 #[derive(Debug, Deserialize)]
 pub struct GuestLoginRequest {
     pub guest_id: u64,
+    pub guest_secret: String,
     pub display_name: String,
     pub metadata: Option<Value>,
 }
@@ -29,6 +34,74 @@ pub struct GuestLoginRequest {
 pub struct GuestLoginResponse {
     pub token: String,
     pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
+}
+
+// Request payload for registering an account. `guest_id` with its `guest_secret`
+// upgrades that guest, keeping its id as the account's `user_id` and its profile.
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub password: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub guest_id: Option<u64>,
+    #[serde(default)]
+    pub guest_secret: Option<String>,
+}
+
+// Request payload for username/password login.
+#[derive(Debug, Deserialize)]
+pub struct AccountLoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+// Response payload for starting an external login; send the user to `authorization_url`.
+#[derive(Debug, Serialize)]
+pub struct ExternalLoginStartResponse {
+    pub authorization_url: String,
+    // Comes back with the provider's callback; pass it on unchanged.
+    pub state: String,
+    // Kept by the client that started the login, never put in a URL, and sent
+    // with the callback so a callback link from someone else cannot complete.
+    pub binding: String,
+}
+
+// Request payload carrying the provider's callback parameters.
+#[derive(Debug, Deserialize)]
+pub struct ExternalLoginCallbackRequest {
+    pub state: String,
+    pub code: String,
+    // The `binding` returned when this client started the login.
+    pub binding: String,
+}
+
+// Response payload for account registration, account login and external login.
+#[derive(Debug, Serialize)]
+pub struct AccountSessionResponse {
+    pub user_id: u64,
+    pub display_name: String,
+    pub token: String,
+    pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
+}
+
+// Request payload for trading a refresh token for a new session.
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+// Response payload for a refresh; the presented refresh token is spent.
+#[derive(Debug, Serialize)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
 }
 
 // Request payload for token verification.
@@ -47,6 +120,25 @@ pub struct VerifyTokenResponse {
     pub expires_at: u64,
 }
 
+// Request payload for a profile update; absent fields are left unchanged.
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+// Response payload for reading or updating the caller's profile.
+#[derive(Debug, Serialize)]
+pub struct ProfileResponse {
+    pub user_id: u64,
+    pub display_name: String,
+    pub metadata: Value,
+    // When `display_name` may change again; `null` if it may change now.
+    pub display_name_changeable_at: Option<u64>,
+}
+
 // Request payload for logout.
 #[derive(Debug, Deserialize)]
 pub struct LogoutRequest {
@@ -59,14 +151,124 @@ pub struct LogoutResponse {
     pub revoked: bool,
 }
 
+// One of the caller's live sessions.
+#[derive(Debug, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub expires_at: u64,
+    pub metadata: Option<Value>,
+    // Whether this is the session the request was made with.
+    pub current: bool,
+}
+
+// Response payload for listing the caller's sessions, oldest first.
+#[derive(Debug, Serialize)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionSummary>,
+}
+
+// Response payload for revoking sessions.
+#[derive(Debug, Serialize)]
+pub struct RevokeSessionsResponse {
+    pub revoked: usize,
+}
+
+// Query for polling the revocation list; `since` is the cursor from the last poll.
+#[derive(Debug, Deserialize)]
+pub struct RevocationsQuery {
+    #[serde(default)]
+    pub since: u64,
+}
+
+// One revoked session in the revocation list.
+#[derive(Debug, Serialize)]
+pub struct RevokedSession {
+    pub session_id: String,
+    pub user_id: u64,
+    pub revoked_at: u64,
+    // Local verifiers can forget the revocation once the token has expired.
+    pub expires_at: u64,
+}
+
+// Response payload for the revocation list.
+#[derive(Debug, Serialize)]
+pub struct RevocationsResponse {
+    pub revocations: Vec<RevokedSession>,
+    // Pass back as `since` on the next poll.
+    pub cursor: u64,
+}
+
+// Ed25519 public key in JWK form (RFC 8037).
+#[derive(Debug, Serialize)]
+pub struct JsonWebKey {
+    pub kty: &'static str,
+    pub crv: &'static str,
+    pub alg: &'static str,
+    #[serde(rename = "use")]
+    pub key_use: &'static str,
+    pub kid: String,
+    // Base64url-encoded public key.
+    pub x: String,
+}
+
+// Response payload for the token verification key set.
+#[derive(Debug, Serialize)]
+pub struct KeySetResponse {
+    pub keys: Vec<JsonWebKey>,
+}
+
 // Response payload for liveness checks.
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
     pub status: &'static str,
 }
 
-// Simple error envelope for JSON responses.
+// Error envelope shared by every service; codes are listed in ERROR_CODES.md.
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
+    pub code: ErrorCode,
     pub message: String,
+    pub retryable: bool,
+    pub details: Option<Value>,
+}
+
+// Stable error codes clients branch on; messages are for humans and may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidDisplayName,
+    InvalidGuestId,
+    InvalidUsername,
+    InvalidPassword,
+    InvalidGuestCredentials,
+    InvalidCredentials,
+    InvalidToken,
+    SessionExpired,
+    InvalidRefreshToken,
+    RefreshTokenExpired,
+    RefreshTokenReused,
+    UsernameTaken,
+    AlreadyRegistered,
+    UnknownIdentityProvider,
+    InvalidExternalLogin,
+    DisplayNameCooldown,
+    SessionNotFound,
+    InvalidSignature,
+    InvalidAdminToken,
+    RateLimited,
+    StorageUnavailable,
+    IdentityProviderUnavailable,
+}
+
+impl ErrorCode {
+    // Whether the same request may succeed when sent again after a short wait.
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::RateLimited
+                | ErrorCode::StorageUnavailable
+                | ErrorCode::IdentityProviderUnavailable
+        )
+    }
 }
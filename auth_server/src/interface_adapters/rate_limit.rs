@@ -0,0 +1,633 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request, State},
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use ipnet::IpNet;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+
+use crate::domain::ports::Clock;
+use crate::interface_adapters::metrics::RateLimitMetrics;
+use crate::interface_adapters::protocol::{ErrorCode, ErrorResponse};
+use crate::interface_adapters::state::{hash_token, SystemClock};
+use crate::use_cases::guest_login::refresh_token_family;
+
+// Limited request bodies are small JSON objects; anything larger is refused unread.
+const MAX_LIMITED_BODY_BYTES: usize = 16 * 1024;
+// How often windows that have run out are dropped.
+const PRUNE_INTERVAL_SECONDS: u64 = 60;
+
+// Routes with their own request budgets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimitedRoute {
+    GuestInit,
+    GuestLogin,
+    VerifyToken,
+    AccountLogin,
+    Register,
+    Refresh,
+    ExternalLoginStart,
+    ExternalLoginCallback,
+}
+
+impl LimitedRoute {
+    pub const ALL: [LimitedRoute; 8] = [
+        LimitedRoute::GuestInit,
+        LimitedRoute::GuestLogin,
+        LimitedRoute::VerifyToken,
+        LimitedRoute::AccountLogin,
+        LimitedRoute::Register,
+        LimitedRoute::Refresh,
+        LimitedRoute::ExternalLoginStart,
+        LimitedRoute::ExternalLoginCallback,
+    ];
+
+    // Name used in metrics, logs and `AUTH_RATE_LIMIT_<NAME>_*` env vars.
+    pub const fn name(self) -> &'static str {
+        match self {
+            LimitedRoute::GuestInit => "guest_init",
+            LimitedRoute::GuestLogin => "guest_login",
+            LimitedRoute::VerifyToken => "verify_token",
+            LimitedRoute::AccountLogin => "account_login",
+            LimitedRoute::Register => "register",
+            LimitedRoute::Refresh => "refresh",
+            LimitedRoute::ExternalLoginStart => "oidc_start",
+            LimitedRoute::ExternalLoginCallback => "oidc_callback",
+        }
+    }
+
+    // Whether requests name an identity worth its own budget; new guests have none yet,
+    // and external logins are only known to the provider.
+    pub const fn has_identity(self) -> bool {
+        !matches!(
+            self,
+            LimitedRoute::GuestInit
+                | LimitedRoute::ExternalLoginStart
+                | LimitedRoute::ExternalLoginCallback
+        )
+    }
+}
+
+// What a budget is counted against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimitKey {
+    Ip,
+    Identity,
+}
+
+impl LimitKey {
+    pub const ALL: [LimitKey; 2] = [LimitKey::Ip, LimitKey::Identity];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            LimitKey::Ip => "ip",
+            LimitKey::Identity => "identity",
+        }
+    }
+}
+
+// At most `requests` requests per fixed window of `window_seconds`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Budget {
+    pub requests: u32,
+    pub window_seconds: u64,
+}
+
+impl Budget {
+    pub const fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            window_seconds: 60,
+        }
+    }
+}
+
+// Budgets for one route; `None` leaves that key unlimited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteLimits {
+    pub ip: Option<Budget>,
+    pub identity: Option<Budget>,
+}
+
+impl RouteLimits {
+    pub fn budget(&self, key: LimitKey) -> Option<Budget> {
+        match key {
+            LimitKey::Ip => self.ip,
+            LimitKey::Identity => self.identity,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimits {
+    pub guest_init: RouteLimits,
+    pub guest_login: RouteLimits,
+    pub verify_token: RouteLimits,
+    pub account_login: RouteLimits,
+    pub register: RouteLimits,
+    pub refresh: RouteLimits,
+    pub external_login_start: RouteLimits,
+    pub external_login_callback: RouteLimits,
+    // Networks of proxies, such as head, whose `X-Forwarded-For` names the client.
+    // Empty by default: no peer may pick the address it is counted as.
+    pub trusted_proxies: Vec<IpNet>,
+}
+
+impl RateLimits {
+    pub fn route(&self, route: LimitedRoute) -> &RouteLimits {
+        match route {
+            LimitedRoute::GuestInit => &self.guest_init,
+            LimitedRoute::GuestLogin => &self.guest_login,
+            LimitedRoute::VerifyToken => &self.verify_token,
+            LimitedRoute::AccountLogin => &self.account_login,
+            LimitedRoute::Register => &self.register,
+            LimitedRoute::Refresh => &self.refresh,
+            LimitedRoute::ExternalLoginStart => &self.external_login_start,
+            LimitedRoute::ExternalLoginCallback => &self.external_login_callback,
+        }
+    }
+
+    pub fn route_mut(&mut self, route: LimitedRoute) -> &mut RouteLimits {
+        match route {
+            LimitedRoute::GuestInit => &mut self.guest_init,
+            LimitedRoute::GuestLogin => &mut self.guest_login,
+            LimitedRoute::VerifyToken => &mut self.verify_token,
+            LimitedRoute::AccountLogin => &mut self.account_login,
+            LimitedRoute::Register => &mut self.register,
+            LimitedRoute::Refresh => &mut self.refresh,
+            LimitedRoute::ExternalLoginStart => &mut self.external_login_start,
+            LimitedRoute::ExternalLoginCallback => &mut self.external_login_callback,
+        }
+    }
+}
+
+impl Default for RateLimits {
+    // Identity budgets bound guessing one guest's secret or account's password and
+    // replaying one token; verify-token IP budgets stay high because game servers
+    // verify for every player. Registration hashes a password per call, so it is the
+    // tightest.
+    fn default() -> Self {
+        Self {
+            guest_init: RouteLimits {
+                ip: Some(Budget::per_minute(10)),
+                identity: None,
+            },
+            guest_login: RouteLimits {
+                ip: Some(Budget::per_minute(60)),
+                identity: Some(Budget::per_minute(10)),
+            },
+            verify_token: RouteLimits {
+                ip: Some(Budget::per_minute(600)),
+                identity: Some(Budget::per_minute(120)),
+            },
+            account_login: RouteLimits {
+                ip: Some(Budget::per_minute(30)),
+                identity: Some(Budget::per_minute(10)),
+            },
+            register: RouteLimits {
+                ip: Some(Budget::per_minute(10)),
+                identity: Some(Budget::per_minute(5)),
+            },
+            refresh: RouteLimits {
+                ip: Some(Budget::per_minute(60)),
+                identity: Some(Budget::per_minute(10)),
+            },
+            external_login_start: RouteLimits {
+                ip: Some(Budget::per_minute(30)),
+                identity: None,
+            },
+            external_login_callback: RouteLimits {
+                ip: Some(Budget::per_minute(30)),
+                identity: None,
+            },
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    Throttled {
+        key: LimitKey,
+        retry_after_seconds: u64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Subject {
+    Ip(IpAddr),
+    // Hashed so tokens are not kept in memory.
+    Identity(Vec<u8>),
+}
+
+struct Window {
+    started_at: u64,
+    window_seconds: u64,
+    count: u32,
+}
+
+#[derive(Default)]
+struct Windows {
+    by_subject: HashMap<(LimitedRoute, Subject), Window>,
+    pruned_at: u64,
+}
+
+// Fixed-window request counters per route and key. Counts live in this process,
+// so each auth instance enforces its budgets on its own.
+pub struct RateLimiter {
+    limits: RateLimits,
+    windows: Mutex<Windows>,
+    pub metrics: RateLimitMetrics,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            windows: Mutex::new(Windows::default()),
+            metrics: RateLimitMetrics::default(),
+        }
+    }
+
+    // Counts the request against each budget it falls under and reports the first one
+    // it exceeds. Throttled requests still count, so a caller that keeps retrying
+    // early stays throttled.
+    pub fn check(
+        &self,
+        route: LimitedRoute,
+        ip: Option<IpAddr>,
+        identity: Option<&str>,
+        now: u64,
+    ) -> Decision {
+        let limits = self.limits.route(route);
+        let subjects = [
+            (LimitKey::Ip, ip.map(Subject::Ip)),
+            (
+                LimitKey::Identity,
+                identity.map(|identity| Subject::Identity(hash_token(identity))),
+            ),
+        ];
+
+        let mut windows = self.windows.lock().unwrap_or_else(|err| err.into_inner());
+        windows.prune(now);
+        let mut decision = Decision::Allowed;
+        for (key, subject) in subjects {
+            let (Some(budget), Some(subject)) = (limits.budget(key), subject) else {
+                continue;
+            };
+            let window = windows
+                .by_subject
+                .entry((route, subject))
+                .or_insert(Window {
+                    started_at: now,
+                    window_seconds: budget.window_seconds,
+                    count: 0,
+                });
+            if now >= window.started_at + window.window_seconds {
+                window.started_at = now;
+                window.window_seconds = budget.window_seconds;
+                window.count = 0;
+            }
+            window.count = window.count.saturating_add(1);
+            if window.count > budget.requests && decision == Decision::Allowed {
+                decision = Decision::Throttled {
+                    key,
+                    retry_after_seconds: (window.started_at + window.window_seconds - now).max(1),
+                };
+            }
+        }
+        drop(windows);
+
+        match decision {
+            Decision::Allowed => self.metrics.record_allowed(route),
+            Decision::Throttled { key, .. } => self.metrics.record_throttled(route, key),
+        }
+        decision
+    }
+
+    // Client address to count against: the peer itself, or the client a trusted proxy
+    // forwards for. A proxy's own calls, sent without the header, count against the
+    // proxy. Only requests served without connection info, as in tests, have none.
+    fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer?;
+        let trusted = self
+            .limits
+            .trusted_proxies
+            .iter()
+            .any(|proxies| proxies.contains(&peer.to_canonical()));
+        if !trusted {
+            return Some(peer);
+        }
+        Some(forwarded_client(headers).unwrap_or(peer))
+    }
+}
+
+// The proxy appends the address it saw, so only the last entry is trusted.
+fn forwarded_client(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+impl Windows {
+    fn prune(&mut self, now: u64) {
+        if now < self.pruned_at + PRUNE_INTERVAL_SECONDS {
+            return;
+        }
+        self.by_subject
+            .retain(|_, window| now < window.started_at + window.window_seconds);
+        self.pruned_at = now;
+    }
+}
+
+// Middleware state naming the route a limiter guards.
+#[derive(Clone)]
+pub struct RouteLimiter {
+    pub limiter: Arc<RateLimiter>,
+    pub route: LimitedRoute,
+}
+
+// Rejects requests over their route's budgets with `429` and `Retry-After`.
+pub async fn rate_limit(
+    State(limit): State<RouteLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = limit.limiter.client_ip(peer, request.headers());
+
+    // Identities sit in the JSON body, so it is read here and handed on unchanged.
+    let (request, identity) = if limit.route.has_identity() {
+        let (parts, body) = request.into_parts();
+        let Ok(bytes) = to_bytes(body, MAX_LIMITED_BODY_BYTES).await else {
+            return rejection(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                ErrorCode::InvalidRequest,
+                "request body is too large",
+                None,
+            );
+        };
+        let identity = request_identity(limit.route, &bytes);
+        (Request::from_parts(parts, Body::from(bytes)), identity)
+    } else {
+        (request, None)
+    };
+
+    let now = SystemClock.now_epoch_seconds();
+    match limit
+        .limiter
+        .check(limit.route, ip, identity.as_deref(), now)
+    {
+        Decision::Allowed => next.run(request).await,
+        Decision::Throttled {
+            key,
+            retry_after_seconds,
+        } => {
+            // Identities are secrets or tokens for some routes, so only the key kind is logged.
+            tracing::warn!(
+                route = limit.route.name(),
+                key = key.name(),
+                client_ip = ?ip,
+                retry_after_seconds,
+                "request throttled"
+            );
+            let mut response = rejection(
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorCode::RateLimited,
+                "too many requests",
+                Some(serde_json::json!({ "retry_after_seconds": retry_after_seconds })),
+            );
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after_seconds));
+            response
+        }
+    }
+}
+
+// `guest_id` for guest login, `token` for verification, the username for accounts and
+// the family for refreshes; malformed bodies have none and are left for the handler to
+// reject.
+fn request_identity(route: LimitedRoute, body: &[u8]) -> Option<String> {
+    let field = match route {
+        LimitedRoute::GuestInit
+        | LimitedRoute::ExternalLoginStart
+        | LimitedRoute::ExternalLoginCallback => return None,
+        LimitedRoute::GuestLogin => "guest_id",
+        LimitedRoute::VerifyToken => "token",
+        LimitedRoute::AccountLogin | LimitedRoute::Register => "username",
+        LimitedRoute::Refresh => "refresh_token",
+    };
+    let value = serde_json::from_slice::<Value>(body).ok()?;
+    let identity = match value.get(field)? {
+        Value::String(identity) => identity.clone(),
+        Value::Number(identity) => identity.to_string(),
+        _ => return None,
+    };
+    match route {
+        // Usernames match case-insensitively, so every casing shares one budget.
+        LimitedRoute::AccountLogin | LimitedRoute::Register => Some(identity.to_ascii_lowercase()),
+        // Each refresh rotates the token, so the family is what stays put.
+        LimitedRoute::Refresh => refresh_token_family(&identity).map(str::to_string),
+        _ => Some(identity),
+    }
+}
+
+fn rejection(
+    status: StatusCode,
+    code: ErrorCode,
+    message: &str,
+    details: Option<Value>,
+) -> Response {
+    (
+        status,
+        Json(ErrorResponse {
+            code,
+            message: message.to_string(),
+            retryable: code.retryable(),
+            details,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7));
+    const PROXY: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 2));
+
+    fn limiter(route: RouteLimits) -> RateLimiter {
+        RateLimiter::new(RateLimits {
+            guest_login: route,
+            trusted_proxies: vec![IpNet::from(PROXY)],
+            ..RateLimits::default()
+        })
+    }
+
+    #[test]
+    fn when_ip_budget_is_spent_then_requests_wait_for_the_next_window() {
+        let limiter = limiter(RouteLimits {
+            ip: Some(Budget {
+                requests: 2,
+                window_seconds: 60,
+            }),
+            identity: None,
+        });
+
+        for _ in 0..2 {
+            assert_eq!(
+                limiter.check(LimitedRoute::GuestLogin, Some(CLIENT), None, 1_000),
+                Decision::Allowed
+            );
+        }
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestLogin, Some(CLIENT), None, 1_015),
+            Decision::Throttled {
+                key: LimitKey::Ip,
+                retry_after_seconds: 45,
+            }
+        );
+        // Other routes and other clients keep their own budgets.
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestInit, Some(CLIENT), None, 1_015),
+            Decision::Allowed
+        );
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestLogin, Some(PROXY), None, 1_015),
+            Decision::Allowed
+        );
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestLogin, Some(CLIENT), None, 1_060),
+            Decision::Allowed
+        );
+    }
+
+    #[test]
+    fn when_identity_budget_is_spent_then_other_addresses_are_throttled_too() {
+        let limiter = limiter(RouteLimits {
+            ip: None,
+            identity: Some(Budget {
+                requests: 1,
+                window_seconds: 30,
+            }),
+        });
+
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestLogin, Some(CLIENT), Some("42"), 0),
+            Decision::Allowed
+        );
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestLogin, Some(PROXY), Some("42"), 10),
+            Decision::Throttled {
+                key: LimitKey::Identity,
+                retry_after_seconds: 20,
+            }
+        );
+        assert_eq!(
+            limiter.check(LimitedRoute::GuestLogin, Some(PROXY), Some("43"), 10),
+            Decision::Allowed
+        );
+    }
+
+    #[test]
+    fn when_peer_is_a_trusted_proxy_then_the_forwarded_client_is_limited() {
+        let limiter = limiter(RouteLimits {
+            ip: None,
+            identity: None,
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.1, 203.0.113.7"),
+        );
+
+        assert_eq!(limiter.client_ip(Some(PROXY), &headers), Some(CLIENT));
+        // Without the header the proxy's own calls are counted against it.
+        assert_eq!(
+            limiter.client_ip(Some(PROXY), &HeaderMap::new()),
+            Some(PROXY)
+        );
+        // Untrusted peers cannot pick the address they are counted as.
+        assert_eq!(
+            limiter.client_ip(Some("192.0.2.9".parse().unwrap()), &headers),
+            Some("192.0.2.9".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn when_identities_are_read_then_usernames_fold_case_and_refreshes_count_by_family() {
+        let identity = |route, body: Value| request_identity(route, body.to_string().as_bytes());
+
+        assert_eq!(
+            identity(
+                LimitedRoute::AccountLogin,
+                serde_json::json!({"username": "Pilot", "password": "x"})
+            ),
+            Some("pilot".to_string())
+        );
+        assert_eq!(
+            identity(
+                LimitedRoute::Refresh,
+                serde_json::json!({"refresh_token": "family-1.secret"})
+            ),
+            Some("family-1".to_string())
+        );
+        assert_eq!(
+            identity(
+                LimitedRoute::ExternalLoginCallback,
+                serde_json::json!({"state": "s", "code": "c", "binding": "b"})
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn when_peer_is_private_but_not_listed_then_its_forwarded_header_is_ignored() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7"));
+        let limiter = RateLimiter::new(RateLimits::default());
+
+        // Loopback, LAN and container peers are clients like any other by default.
+        for peer in ["127.0.0.1", "::1", "192.168.1.20", "172.20.0.3", "fd00::3"] {
+            let peer: IpAddr = peer.parse().unwrap();
+            assert_eq!(limiter.client_ip(Some(peer), &headers), Some(peer));
+            assert_eq!(limiter.client_ip(Some(peer), &HeaderMap::new()), Some(peer));
+        }
+
+        let limiter = RateLimiter::new(RateLimits {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..RateLimits::default()
+        });
+        assert_eq!(
+            limiter.client_ip(Some("10.1.2.3".parse().unwrap()), &headers),
+            Some(CLIENT)
+        );
+        // IPv4-mapped peers match their IPv4 network.
+        assert_eq!(
+            limiter.client_ip(Some("::ffff:10.1.2.3".parse().unwrap()), &headers),
+            Some(CLIENT)
+        );
+        assert_eq!(
+            limiter.client_ip(Some("11.0.0.1".parse().unwrap()), &headers),
+            Some("11.0.0.1".parse().unwrap())
+        );
+    }
+}
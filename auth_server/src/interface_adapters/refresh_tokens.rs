@@ -0,0 +1,298 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::domain::entities::{RefreshGrant, RefreshRedemption};
+use crate::domain::ports::RefreshTokenStore;
+use crate::interface_adapters::state::hash_token;
+
+// Refresh tokens keyed by token, with whether each has been spent.
+#[derive(Debug, Default)]
+pub struct RefreshTokenMap {
+    tokens: HashMap<String, (RefreshGrant, bool)>,
+}
+
+impl RefreshTokenMap {
+    fn take_family(&mut self, family_id: &str) -> Vec<RefreshGrant> {
+        let tokens: Vec<String> = self
+            .tokens
+            .iter()
+            .filter(|(_, (grant, _))| grant.family_id == family_id)
+            .map(|(token, _)| token.clone())
+            .collect();
+        tokens
+            .iter()
+            .filter_map(|token| self.tokens.remove(token))
+            .map(|(grant, _)| grant)
+            .collect()
+    }
+}
+
+// In-memory refresh token store adapter for the auth service.
+#[derive(Clone)]
+pub struct InMemoryRefreshTokenStore {
+    pub tokens: Arc<Mutex<RefreshTokenMap>>,
+}
+
+// PostgreSQL-backed refresh token store; only token hashes are written.
+#[derive(Clone)]
+pub struct PostgresRefreshTokenStore {
+    pub db: PgPool,
+}
+
+// Refresh token store for whichever backend the server was configured with.
+#[derive(Clone)]
+pub enum ConfiguredRefreshTokenStore {
+    Memory(InMemoryRefreshTokenStore),
+    Postgres(PostgresRefreshTokenStore),
+}
+
+#[async_trait]
+impl RefreshTokenStore for InMemoryRefreshTokenStore {
+    async fn insert(&self, token: String, grant: RefreshGrant) -> Result<(), String> {
+        let mut map = self.tokens.lock().await;
+        map.tokens.insert(token, (grant, false));
+        Ok(())
+    }
+
+    async fn redeem(&self, token: &str) -> Result<RefreshRedemption, String> {
+        let mut map = self.tokens.lock().await;
+        Ok(match map.tokens.get_mut(token) {
+            None => RefreshRedemption::Unknown,
+            Some((grant, true)) => RefreshRedemption::Reused(grant.clone()),
+            Some((grant, used)) => {
+                *used = true;
+                RefreshRedemption::Fresh(grant.clone())
+            }
+        })
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<Vec<RefreshGrant>, String> {
+        let mut map = self.tokens.lock().await;
+        Ok(map.take_family(family_id))
+    }
+
+    async fn revoke_family_of_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<RefreshGrant>, String> {
+        let mut map = self.tokens.lock().await;
+        let Some(family_id) = map
+            .tokens
+            .values()
+            .find(|(grant, _)| grant.session_id == session_id)
+            .map(|(grant, _)| grant.family_id.clone())
+        else {
+            return Ok(Vec::new());
+        };
+        Ok(map.take_family(&family_id))
+    }
+
+    async fn revoke_user(&self, user_id: u64) -> Result<Vec<RefreshGrant>, String> {
+        let mut map = self.tokens.lock().await;
+        let mut grants = Vec::new();
+        map.tokens.retain(|_, (grant, _)| {
+            if grant.guest_id != user_id {
+                return true;
+            }
+            grants.push(grant.clone());
+            false
+        });
+        Ok(grants)
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let mut map = self.tokens.lock().await;
+        let before = map.tokens.len();
+        map.tokens.retain(|_, (grant, _)| grant.expires_at > now);
+        Ok((before - map.tokens.len()) as u64)
+    }
+}
+
+const GRANT_COLUMNS: &str =
+    "family_id, guest_id, display_name, metadata, session_id, session_expires_at, expires_at";
+
+fn grant_from_row(row: &PgRow) -> Result<RefreshGrant, String> {
+    let metadata = row
+        .try_get::<Option<String>, _>("metadata")
+        .map_err(|err| err.to_string())?
+        .map(|raw| serde_json::from_str(&raw))
+        .transpose()
+        .map_err(|err| err.to_string())?;
+    Ok(RefreshGrant {
+        family_id: row.try_get("family_id").map_err(|err| err.to_string())?,
+        // Guest ids use all 64 bits; the column holds the same bits signed.
+        guest_id: row
+            .try_get::<i64, _>("guest_id")
+            .map_err(|err| err.to_string())? as u64,
+        display_name: row.try_get("display_name").map_err(|err| err.to_string())?,
+        metadata,
+        session_id: row.try_get("session_id").map_err(|err| err.to_string())?,
+        session_expires_at: row
+            .try_get::<i64, _>("session_expires_at")
+            .map_err(|err| err.to_string())? as u64,
+        expires_at: row
+            .try_get::<i64, _>("expires_at")
+            .map_err(|err| err.to_string())? as u64,
+    })
+}
+
+#[async_trait]
+impl RefreshTokenStore for PostgresRefreshTokenStore {
+    async fn insert(&self, token: String, grant: RefreshGrant) -> Result<(), String> {
+        let metadata = grant.metadata.as_ref().map(|value| value.to_string());
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens
+                (token_hash, family_id, guest_id, display_name, metadata, session_id,
+                 session_expires_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(hash_token(&token))
+        .bind(&grant.family_id)
+        .bind(grant.guest_id as i64)
+        .bind(&grant.display_name)
+        .bind(metadata)
+        .bind(&grant.session_id)
+        .bind(grant.session_expires_at as i64)
+        .bind(grant.expires_at as i64)
+        .execute(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    async fn redeem(&self, token: &str) -> Result<RefreshRedemption, String> {
+        // Lock the row so two concurrent redemptions cannot both see it unspent.
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE refresh_tokens AS token SET used = TRUE
+            FROM (
+                SELECT token_hash, used FROM refresh_tokens WHERE token_hash = $1 FOR UPDATE
+            ) AS previous
+            WHERE token.token_hash = previous.token_hash
+            RETURNING previous.used AS was_used, {GRANT_COLUMNS}
+            "#
+        ))
+        .bind(hash_token(token))
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        let Some(row) = row else {
+            return Ok(RefreshRedemption::Unknown);
+        };
+        let grant = grant_from_row(&row)?;
+        let was_used: bool = row.try_get("was_used").map_err(|err| err.to_string())?;
+        Ok(if was_used {
+            RefreshRedemption::Reused(grant)
+        } else {
+            RefreshRedemption::Fresh(grant)
+        })
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<Vec<RefreshGrant>, String> {
+        let rows = sqlx::query(&format!(
+            "DELETE FROM refresh_tokens WHERE family_id = $1 RETURNING {GRANT_COLUMNS}"
+        ))
+        .bind(family_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        rows.iter().map(grant_from_row).collect()
+    }
+
+    async fn revoke_family_of_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<RefreshGrant>, String> {
+        let rows = sqlx::query(&format!(
+            r#"
+            DELETE FROM refresh_tokens
+            WHERE family_id IN (SELECT family_id FROM refresh_tokens WHERE session_id = $1)
+            RETURNING {GRANT_COLUMNS}
+            "#
+        ))
+        .bind(session_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        rows.iter().map(grant_from_row).collect()
+    }
+
+    async fn revoke_user(&self, user_id: u64) -> Result<Vec<RefreshGrant>, String> {
+        let rows = sqlx::query(&format!(
+            "DELETE FROM refresh_tokens WHERE guest_id = $1 RETURNING {GRANT_COLUMNS}"
+        ))
+        .bind(user_id as i64)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        rows.iter().map(grant_from_row).collect()
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at <= $1")
+            .bind(now as i64)
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl RefreshTokenStore for ConfiguredRefreshTokenStore {
+    async fn insert(&self, token: String, grant: RefreshGrant) -> Result<(), String> {
+        match self {
+            ConfiguredRefreshTokenStore::Memory(store) => store.insert(token, grant).await,
+            ConfiguredRefreshTokenStore::Postgres(store) => store.insert(token, grant).await,
+        }
+    }
+
+    async fn redeem(&self, token: &str) -> Result<RefreshRedemption, String> {
+        match self {
+            ConfiguredRefreshTokenStore::Memory(store) => store.redeem(token).await,
+            ConfiguredRefreshTokenStore::Postgres(store) => store.redeem(token).await,
+        }
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<Vec<RefreshGrant>, String> {
+        match self {
+            ConfiguredRefreshTokenStore::Memory(store) => store.revoke_family(family_id).await,
+            ConfiguredRefreshTokenStore::Postgres(store) => store.revoke_family(family_id).await,
+        }
+    }
+
+    async fn revoke_family_of_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<RefreshGrant>, String> {
+        match self {
+            ConfiguredRefreshTokenStore::Memory(store) => {
+                store.revoke_family_of_session(session_id).await
+            }
+            ConfiguredRefreshTokenStore::Postgres(store) => {
+                store.revoke_family_of_session(session_id).await
+            }
+        }
+    }
+
+    async fn revoke_user(&self, user_id: u64) -> Result<Vec<RefreshGrant>, String> {
+        match self {
+            ConfiguredRefreshTokenStore::Memory(store) => store.revoke_user(user_id).await,
+            ConfiguredRefreshTokenStore::Postgres(store) => store.revoke_user(user_id).await,
+        }
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        match self {
+            ConfiguredRefreshTokenStore::Memory(store) => store.remove_expired(now).await,
+            ConfiguredRefreshTokenStore::Postgres(store) => store.remove_expired(now).await,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::domain::ports::Clock;
+use crate::interface_adapters::protocol::{ErrorCode, ErrorResponse};
+use crate::interface_adapters::state::{AppState, SystemClock};
+
+// HMAC-SHA256 request signing shared with head and the game servers for internal routes.
+//
+// The signed message is `{timestamp}\n{METHOD}\n{path}\n{body}`, where `path` includes
+// any `?query`; both headers must be present.
+
+pub const TIMESTAMP_HEADER: &str = "x-internal-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-internal-signature";
+
+// Requests older or newer than this are rejected to bound replay windows.
+const MAX_CLOCK_SKEW_SECS: u64 = 60;
+// Internal requests are polls with empty bodies; anything larger is refused unread.
+const MAX_SIGNED_BODY_BYTES: usize = 16 * 1024;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    Missing,
+    Malformed,
+    Expired,
+    Mismatch,
+}
+
+// Returns the hex-encoded signature for a request; auth only verifies, so tests sign.
+#[cfg(test)]
+pub fn sign(secret: &[u8], timestamp: u64, method: &str, path: &str, body: &[u8]) -> String {
+    hex::encode(
+        mac(secret, timestamp, method, path, body)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+// Checks a signature in constant time and rejects stale timestamps.
+pub fn verify(
+    secret: &[u8],
+    timestamp: &str,
+    signature: &str,
+    method: &str,
+    path: &str,
+    body: &[u8],
+    now: u64,
+) -> Result<(), SignatureError> {
+    let timestamp = timestamp
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| SignatureError::Malformed)?;
+    if now.abs_diff(timestamp) > MAX_CLOCK_SKEW_SECS {
+        return Err(SignatureError::Expired);
+    }
+    let signature = hex::decode(signature.trim()).map_err(|_| SignatureError::Malformed)?;
+
+    mac(secret, timestamp, method, path, body)
+        .verify_slice(&signature)
+        .map_err(|_| SignatureError::Mismatch)
+}
+
+fn mac(secret: &[u8], timestamp: u64, method: &str, path: &str, body: &[u8]) -> HmacSha256 {
+    // HMAC accepts keys of any length, so construction cannot fail.
+    let mut mac = HmacSha256::new_from_slice(secret).expect("hmac accepts any key length");
+    mac.update(format!("{timestamp}\n{method}\n{path}\n").as_bytes());
+    mac.update(body);
+    mac
+}
+
+// Rejects requests to internal routes that are not signed with the shared service secret.
+pub async fn require_internal_signature(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_SIGNED_BODY_BYTES).await else {
+        return rejection("request body is too large to verify");
+    };
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let result = match (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER)) {
+        (Some(timestamp), Some(signature)) => verify(
+            state.internal_api_secret.as_bytes(),
+            timestamp,
+            signature,
+            parts.method.as_str(),
+            request_target(&parts.uri),
+            &body,
+            SystemClock.now_epoch_seconds(),
+        ),
+        _ => Err(SignatureError::Missing),
+    };
+
+    if let Err(error) = result {
+        tracing::warn!(path = %parts.uri.path(), ?error, "rejected unsigned internal request");
+        return rejection("invalid internal request signature");
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+// The path and query a request was signed over, so a query cannot be swapped in transit.
+fn request_target(uri: &Uri) -> &str {
+    uri.path_and_query()
+        .map_or_else(|| uri.path(), |target| target.as_str())
+}
+
+fn rejection(message: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ErrorResponse {
+            code: ErrorCode::InvalidSignature,
+            message: message.to_string(),
+            retryable: ErrorCode::InvalidSignature.retryable(),
+            details: None,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"internal-secret";
+
+    #[test]
+    fn when_signature_matches_then_it_verifies_until_it_is_stale() {
+        let signature = sign(SECRET, 1_000, "GET", "/auth/revocations", b"");
+
+        assert_eq!(
+            verify(
+                SECRET,
+                "1000",
+                &signature,
+                "GET",
+                "/auth/revocations",
+                b"",
+                1_030
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify(
+                SECRET,
+                "1000",
+                &signature,
+                "GET",
+                "/auth/revocations",
+                b"",
+                1_061
+            ),
+            Err(SignatureError::Expired)
+        );
+        assert_eq!(
+            verify(
+                b"other",
+                "1000",
+                &signature,
+                "GET",
+                "/auth/revocations",
+                b"",
+                1_000
+            ),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify(
+                SECRET,
+                "soon",
+                &signature,
+                "GET",
+                "/auth/revocations",
+                b"",
+                1_000
+            ),
+            Err(SignatureError::Malformed)
+        );
+    }
+}
@@ -1,76 +1,753 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 
-use crate::domain::entities::Session;
-use crate::domain::ports::{Clock, SessionStore};
+use crate::domain::entities::{Revocation, RevocationPage, Session};
+use crate::domain::ports::{Clock, RevocationStore, SessionStore};
+use crate::interface_adapters::accounts::{
+    AccountMap, ConfiguredAccountStore, InMemoryAccountStore, PostgresAccountStore,
+};
+use crate::interface_adapters::external_identities::{
+    ConfiguredExternalIdentityStore, ConfiguredPendingExternalLoginStore, ExternalIdentityMap,
+    InMemoryExternalIdentityStore, InMemoryPendingExternalLoginStore, PendingExternalLoginMap,
+    PostgresExternalIdentityStore, PostgresPendingExternalLoginStore,
+};
+use crate::interface_adapters::guest_credentials::{
+    ConfiguredGuestCredentialStore, GuestCredentialMap, InMemoryGuestCredentialStore,
+    PostgresGuestCredentialStore,
+};
+use crate::interface_adapters::metrics::SessionMetrics;
+use crate::interface_adapters::oidc::OidcIdentityProvider;
+use crate::interface_adapters::passwords::Argon2Passwords;
+use crate::interface_adapters::profiles::{
+    ConfiguredProfileStore, InMemoryProfileStore, PostgresProfileStore, ProfileMap,
+};
+use crate::interface_adapters::rate_limit::RateLimiter;
+use crate::interface_adapters::refresh_tokens::{
+    ConfiguredRefreshTokenStore, InMemoryRefreshTokenStore, PostgresRefreshTokenStore,
+    RefreshTokenMap,
+};
+use crate::interface_adapters::tokens::SigningKeyring;
 
 // Application state holding session storage.
 #[derive(Clone)]
 pub struct AppState {
-    pub sessions: Arc<Mutex<HashMap<String, Session>>>,
-    // Shared database pool for guest profile persistence.
+    pub sessions: Arc<Mutex<SessionMap>>,
+    // Refresh tokens for the memory backend, like `sessions`.
+    pub refresh_tokens: Arc<Mutex<RefreshTokenMap>>,
+    // Sessions revoked before expiry for the memory backend, polled by game servers.
+    pub revocations: Arc<Mutex<RevocationLog>>,
+    // Signs session tokens when configured; tokens are opaque otherwise.
+    pub signing_keys: Option<Arc<SigningKeyring>>,
+    // Where sessions and refresh tokens live; the in-memory maps are unused when this is Postgres.
+    pub session_backend: SessionBackend,
+    // Shared database pool for profile and session persistence.
     pub db: PgPool,
+    // Session counters exposed on `/metrics`.
+    pub session_metrics: Arc<SessionMetrics>,
+    // Process-local guest credentials for tests; `None` keeps them in `guest_profiles`,
+    // since guests must be able to log in again after a restart.
+    pub guest_credentials: Option<Arc<Mutex<GuestCredentialMap>>>,
+    // Process-local profiles for tests; `None` keeps them in `guest_profiles`.
+    pub profiles: Option<Arc<Mutex<ProfileMap>>>,
+    // Process-local accounts for tests; `None` keeps them in the `accounts` table.
+    pub accounts: Option<Arc<Mutex<AccountMap>>>,
+    // Password hashing for accounts; tests lower its cost.
+    pub passwords: Argon2Passwords,
+    // External identity providers users can sign in with, by the name used in their routes.
+    pub identity_providers: Arc<HashMap<String, OidcIdentityProvider>>,
+    // Process-local external identity links for tests; `None` keeps them in `external_identities`.
+    pub external_identities: Option<Arc<Mutex<ExternalIdentityMap>>>,
+    // External logins waiting for the provider's callback, for the memory backend.
+    pub pending_external_logins: Arc<Mutex<PendingExternalLoginMap>>,
+    // Request budgets for the guest and token verification routes.
+    pub rate_limiter: Arc<RateLimiter>,
+    // Live sessions one user may hold before their oldest are ended.
+    pub max_sessions_per_user: usize,
+    // Shared with head and game servers; signs their calls to internal routes.
+    pub internal_api_secret: Arc<str>,
+    // Bearer token operators present on `/metrics`; `None` refuses every caller.
+    pub admin_token: Option<Arc<str>>,
+}
+
+impl AppState {
+    // Session store adapter for the configured backend.
+    pub fn session_store(&self) -> ConfiguredSessionStore {
+        match self.session_backend {
+            SessionBackend::Memory => ConfiguredSessionStore::Memory(InMemorySessionStore {
+                sessions: self.sessions.clone(),
+                metrics: self.session_metrics.clone(),
+            }),
+            SessionBackend::Postgres => ConfiguredSessionStore::Postgres(PostgresSessionStore {
+                db: self.db.clone(),
+            }),
+        }
+    }
+
+    // Guest credential store adapter; Postgres unless the state carries an in-memory map.
+    pub fn guest_credential_store(&self) -> ConfiguredGuestCredentialStore {
+        match &self.guest_credentials {
+            Some(guests) => ConfiguredGuestCredentialStore::Memory(InMemoryGuestCredentialStore {
+                guests: guests.clone(),
+            }),
+            None => ConfiguredGuestCredentialStore::Postgres(PostgresGuestCredentialStore {
+                db: self.db.clone(),
+            }),
+        }
+    }
+
+    // Profile store adapter; Postgres unless the state carries an in-memory map.
+    pub fn profile_store(&self) -> ConfiguredProfileStore {
+        match &self.profiles {
+            Some(profiles) => ConfiguredProfileStore::Memory(InMemoryProfileStore {
+                profiles: profiles.clone(),
+            }),
+            None => ConfiguredProfileStore::Postgres(PostgresProfileStore {
+                db: self.db.clone(),
+            }),
+        }
+    }
+
+    // Account store adapter; Postgres unless the state carries an in-memory map.
+    pub fn account_store(&self) -> ConfiguredAccountStore {
+        match &self.accounts {
+            Some(accounts) => ConfiguredAccountStore::Memory(InMemoryAccountStore {
+                accounts: accounts.clone(),
+            }),
+            None => ConfiguredAccountStore::Postgres(PostgresAccountStore {
+                db: self.db.clone(),
+            }),
+        }
+    }
+
+    // External identity store adapter; Postgres unless the state carries an in-memory map.
+    pub fn external_identity_store(&self) -> ConfiguredExternalIdentityStore {
+        match &self.external_identities {
+            Some(identities) => {
+                ConfiguredExternalIdentityStore::Memory(InMemoryExternalIdentityStore {
+                    identities: identities.clone(),
+                })
+            }
+            None => ConfiguredExternalIdentityStore::Postgres(PostgresExternalIdentityStore {
+                db: self.db.clone(),
+            }),
+        }
+    }
+
+    // Pending external login store for the configured backend, so callbacks can
+    // reach any instance.
+    pub fn pending_external_login_store(&self) -> ConfiguredPendingExternalLoginStore {
+        match self.session_backend {
+            SessionBackend::Memory => {
+                ConfiguredPendingExternalLoginStore::Memory(InMemoryPendingExternalLoginStore {
+                    logins: self.pending_external_logins.clone(),
+                })
+            }
+            SessionBackend::Postgres => {
+                ConfiguredPendingExternalLoginStore::Postgres(PostgresPendingExternalLoginStore {
+                    db: self.db.clone(),
+                })
+            }
+        }
+    }
+
+    // Revocation store adapter for the configured backend.
+    pub fn revocation_store(&self) -> ConfiguredRevocationStore {
+        match self.session_backend {
+            SessionBackend::Memory => ConfiguredRevocationStore::Memory(InMemoryRevocationStore {
+                log: self.revocations.clone(),
+            }),
+            SessionBackend::Postgres => {
+                ConfiguredRevocationStore::Postgres(PostgresRevocationStore {
+                    db: self.db.clone(),
+                })
+            }
+        }
+    }
+
+    // Refresh token store adapter for the configured backend.
+    pub fn refresh_token_store(&self) -> ConfiguredRefreshTokenStore {
+        match self.session_backend {
+            SessionBackend::Memory => {
+                ConfiguredRefreshTokenStore::Memory(InMemoryRefreshTokenStore {
+                    tokens: self.refresh_tokens.clone(),
+                })
+            }
+            SessionBackend::Postgres => {
+                ConfiguredRefreshTokenStore::Postgres(PostgresRefreshTokenStore {
+                    db: self.db.clone(),
+                })
+            }
+        }
+    }
+}
+
+// Session storage backend selected at startup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionBackend {
+    // Process-local; sessions are lost on restart and not shared between instances.
+    #[default]
+    Memory,
+    // Shared through the `sessions` table.
+    Postgres,
+}
+
+// Sessions keyed by token, remembering insertion order so the oldest go first at the cap,
+// indexed by user so a user's sessions can be listed, and by session id so revocations
+// find their token without a scan.
+#[derive(Debug)]
+pub struct SessionMap {
+    sessions: HashMap<String, (u64, Session)>,
+    // Insertion sequence to token, oldest first.
+    order: BTreeMap<u64, String>,
+    // User id to the tokens of their sessions.
+    by_user: HashMap<u64, HashSet<String>>,
+    // Session id to the token it was issued under.
+    by_session_id: HashMap<String, String>,
+    next_seq: u64,
+    max_sessions: usize,
+}
+
+impl Default for SessionMap {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
+
+impl SessionMap {
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            order: BTreeMap::new(),
+            by_user: HashMap::new(),
+            by_session_id: HashMap::new(),
+            next_seq: 0,
+            max_sessions,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    // Stores `session`, evicting the oldest sessions to stay within the cap; returns those.
+    pub fn insert(&mut self, token: String, session: Session) -> Vec<Session> {
+        self.remove(&token);
+        let mut evicted = Vec::new();
+        while self.sessions.len() >= self.max_sessions {
+            let Some((_, oldest)) = self.order.first_key_value() else {
+                break;
+            };
+            let oldest = oldest.clone();
+            if let Some(session) = self.get(&oldest).cloned() {
+                evicted.push(session);
+            }
+            self.remove(&oldest);
+        }
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.order.insert(seq, token.clone());
+        self.by_user
+            .entry(session.guest_id)
+            .or_default()
+            .insert(token.clone());
+        self.by_session_id
+            .insert(session.session_id.clone(), token.clone());
+        self.sessions.insert(token, (seq, session));
+        evicted
+    }
+
+    pub fn get(&self, token: &str) -> Option<&Session> {
+        self.sessions.get(token).map(|(_, session)| session)
+    }
+
+    pub fn remove(&mut self, token: &str) -> bool {
+        let Some((seq, session)) = self.sessions.remove(token) else {
+            return false;
+        };
+        self.order.remove(&seq);
+        if let Some(tokens) = self.by_user.get_mut(&session.guest_id) {
+            tokens.remove(token);
+            if tokens.is_empty() {
+                self.by_user.remove(&session.guest_id);
+            }
+        }
+        if self
+            .by_session_id
+            .get(&session.session_id)
+            .map(String::as_str)
+            == Some(token)
+        {
+            self.by_session_id.remove(&session.session_id);
+        }
+        true
+    }
+
+    pub fn remove_by_session_id(&mut self, session_id: &str) -> bool {
+        let Some(token) = self.by_session_id.get(session_id).cloned() else {
+            return false;
+        };
+        self.remove(&token)
+    }
+
+    pub fn remove_expired(&mut self, now: u64) -> u64 {
+        let expired: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, (_, session))| session.expires_at <= now)
+            .map(|(token, _)| token.clone())
+            .collect();
+        for token in &expired {
+            self.remove(token);
+        }
+        expired.len() as u64
+    }
+
+    pub fn list_for_user(&self, user_id: u64) -> Vec<Session> {
+        let mut sessions: Vec<Session> = self
+            .by_user
+            .get(&user_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|token| self.get(token).cloned())
+            .collect();
+        sessions.sort_by(|a, b| (a.expires_at, &a.session_id).cmp(&(b.expires_at, &b.session_id)));
+        sessions
+    }
 }
 
 // In-memory session store adapter for the auth service.
 #[derive(Clone)]
 pub struct InMemorySessionStore {
-    pub sessions: Arc<Mutex<HashMap<String, Session>>>,
+    pub sessions: Arc<Mutex<SessionMap>>,
+    pub metrics: Arc<SessionMetrics>,
+}
+
+// PostgreSQL-backed session store; only token hashes are written.
+#[derive(Clone)]
+pub struct PostgresSessionStore {
+    pub db: PgPool,
+}
+
+// Session store for whichever backend the server was configured with.
+#[derive(Clone)]
+pub enum ConfiguredSessionStore {
+    Memory(InMemorySessionStore),
+    Postgres(PostgresSessionStore),
+}
+
+// Revocation entries keyed by a sequence number that pollers use as their cursor.
+#[derive(Debug, Default)]
+pub struct RevocationLog {
+    last_cursor: u64,
+    entries: VecDeque<(u64, Revocation)>,
 }
 
-// PostgreSQL-backed guest profile store for persistence.
+// In-memory revocation store adapter for the auth service.
 #[derive(Clone)]
-pub struct PostgresGuestProfileStore {
+pub struct InMemoryRevocationStore {
+    pub log: Arc<Mutex<RevocationLog>>,
+}
+
+// PostgreSQL-backed revocation store, shared by every auth instance on the database.
+#[derive(Clone)]
+pub struct PostgresRevocationStore {
     pub db: PgPool,
 }
 
-impl PostgresGuestProfileStore {
-    // Upsert a guest profile record using the latest identity data.
-    pub async fn upsert_guest_profile(
-        &self,
-        guest_id: &str,
-        display_name: &str,
-        metadata_json: &str,
-    ) -> Result<(), sqlx::Error> {
+// Revocation store for whichever backend the server was configured with.
+#[derive(Clone)]
+pub enum ConfiguredRevocationStore {
+    Memory(InMemoryRevocationStore),
+    Postgres(PostgresRevocationStore),
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn insert(&self, token: String, session: Session) -> Result<Vec<Session>, String> {
+        let evicted = self.sessions.lock().await.insert(token, session);
+        if !evicted.is_empty() {
+            self.metrics.record_evicted(evicted.len() as u64);
+            tracing::debug!(
+                evicted = evicted.len(),
+                "session cap reached; evicted oldest sessions"
+            );
+        }
+        Ok(evicted)
+    }
+
+    async fn get(&self, token: &str) -> Result<Option<Session>, String> {
+        let sessions = self.sessions.lock().await;
+        Ok(sessions.get(token).cloned())
+    }
+
+    async fn remove(&self, token: &str) -> Result<bool, String> {
+        let mut sessions = self.sessions.lock().await;
+        Ok(sessions.remove(token))
+    }
+
+    async fn remove_by_session_id(&self, session_id: &str) -> Result<bool, String> {
+        let mut sessions = self.sessions.lock().await;
+        Ok(sessions.remove_by_session_id(session_id))
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let mut sessions = self.sessions.lock().await;
+        Ok(sessions.remove_expired(now))
+    }
+
+    async fn list_for_user(&self, user_id: u64) -> Result<Vec<Session>, String> {
+        let sessions = self.sessions.lock().await;
+        Ok(sessions.list_for_user(user_id))
+    }
+}
+
+pub(crate) fn hash_token(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+#[async_trait]
+impl SessionStore for PostgresSessionStore {
+    async fn insert(&self, token: String, session: Session) -> Result<Vec<Session>, String> {
+        let metadata = session.metadata.as_ref().map(|value| value.to_string());
         sqlx::query(
             r#"
-            INSERT INTO guest_profiles (guest_id, display_name, metadata)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (guest_id) DO UPDATE SET
+            INSERT INTO sessions (token_hash, session_id, guest_id, display_name, metadata, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (token_hash) DO UPDATE SET
+                session_id = EXCLUDED.session_id,
+                guest_id = EXCLUDED.guest_id,
                 display_name = EXCLUDED.display_name,
-                metadata = EXCLUDED.metadata
+                metadata = EXCLUDED.metadata,
+                expires_at = EXCLUDED.expires_at
             "#,
         )
-        .bind(guest_id)
-        .bind(display_name)
-        .bind(metadata_json)
+        .bind(hash_token(&token))
+        .bind(&session.session_id)
+        // Guest ids use all 64 bits; store the same bits in a signed column.
+        .bind(session.guest_id as i64)
+        .bind(&session.display_name)
+        .bind(metadata)
+        .bind(session.expires_at as i64)
         .execute(&self.db)
-        .await?;
+        .await
+        .map_err(|err| err.to_string())?;
+        // Postgres has no store-wide cap, so nothing is evicted.
+        Ok(Vec::new())
+    }
 
-        Ok(())
+    async fn get(&self, token: &str) -> Result<Option<Session>, String> {
+        let row = sqlx::query(
+            r#"
+            SELECT session_id, guest_id, display_name, metadata, expires_at
+            FROM sessions
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        row.as_ref().map(session_from_row).transpose()
+    }
+
+    async fn remove(&self, token: &str) -> Result<bool, String> {
+        let result = sqlx::query("DELETE FROM sessions WHERE token_hash = $1")
+            .bind(hash_token(token))
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_by_session_id(&self, session_id: &str) -> Result<bool, String> {
+        let result = sqlx::query("DELETE FROM sessions WHERE session_id = $1")
+            .bind(session_id)
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= $1")
+            .bind(now as i64)
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected())
+    }
+
+    async fn list_for_user(&self, user_id: u64) -> Result<Vec<Session>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT session_id, guest_id, display_name, metadata, expires_at
+            FROM sessions
+            WHERE guest_id = $1
+            ORDER BY expires_at, session_id
+            "#,
+        )
+        .bind(user_id as i64)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+        rows.iter().map(session_from_row).collect()
+    }
+}
+
+fn session_from_row(row: &PgRow) -> Result<Session, String> {
+    let metadata = row
+        .try_get::<Option<String>, _>("metadata")
+        .map_err(|err| err.to_string())?
+        .map(|raw| serde_json::from_str(&raw))
+        .transpose()
+        .map_err(|err| err.to_string())?;
+    Ok(Session {
+        guest_id: row
+            .try_get::<i64, _>("guest_id")
+            .map_err(|err| err.to_string())? as u64,
+        display_name: row.try_get("display_name").map_err(|err| err.to_string())?,
+        metadata,
+        session_id: row.try_get("session_id").map_err(|err| err.to_string())?,
+        expires_at: row
+            .try_get::<i64, _>("expires_at")
+            .map_err(|err| err.to_string())? as u64,
+    })
+}
+
+impl ConfiguredSessionStore {
+    // Number of sessions currently stored, expired ones not yet swept included.
+    pub async fn count(&self) -> Result<u64, String> {
+        match self {
+            ConfiguredSessionStore::Memory(store) => Ok(store.sessions.lock().await.len() as u64),
+            ConfiguredSessionStore::Postgres(store) => {
+                let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+                    .fetch_one(&store.db)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                Ok(count as u64)
+            }
+        }
     }
 }
 
 #[async_trait]
-impl SessionStore for InMemorySessionStore {
-    async fn insert(&self, token: String, session: Session) -> Result<(), String> {
-        let mut sessions = self.sessions.lock().await;
-        sessions.insert(token, session);
-        Ok(())
+impl SessionStore for ConfiguredSessionStore {
+    async fn insert(&self, token: String, session: Session) -> Result<Vec<Session>, String> {
+        match self {
+            ConfiguredSessionStore::Memory(store) => store.insert(token, session).await,
+            ConfiguredSessionStore::Postgres(store) => store.insert(token, session).await,
+        }
     }
 
     async fn get(&self, token: &str) -> Result<Option<Session>, String> {
-        let sessions = self.sessions.lock().await;
-        Ok(sessions.get(token).cloned())
+        match self {
+            ConfiguredSessionStore::Memory(store) => store.get(token).await,
+            ConfiguredSessionStore::Postgres(store) => store.get(token).await,
+        }
     }
 
     async fn remove(&self, token: &str) -> Result<bool, String> {
-        let mut sessions = self.sessions.lock().await;
-        Ok(sessions.remove(token).is_some())
+        match self {
+            ConfiguredSessionStore::Memory(store) => store.remove(token).await,
+            ConfiguredSessionStore::Postgres(store) => store.remove(token).await,
+        }
+    }
+
+    async fn remove_by_session_id(&self, session_id: &str) -> Result<bool, String> {
+        match self {
+            ConfiguredSessionStore::Memory(store) => store.remove_by_session_id(session_id).await,
+            ConfiguredSessionStore::Postgres(store) => store.remove_by_session_id(session_id).await,
+        }
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        match self {
+            ConfiguredSessionStore::Memory(store) => store.remove_expired(now).await,
+            ConfiguredSessionStore::Postgres(store) => store.remove_expired(now).await,
+        }
+    }
+
+    async fn list_for_user(&self, user_id: u64) -> Result<Vec<Session>, String> {
+        match self {
+            ConfiguredSessionStore::Memory(store) => store.list_for_user(user_id).await,
+            ConfiguredSessionStore::Postgres(store) => store.list_for_user(user_id).await,
+        }
+    }
+}
+
+#[async_trait]
+impl RevocationStore for InMemoryRevocationStore {
+    async fn record(&self, revocation: Revocation) -> Result<(), String> {
+        let mut log = self.log.lock().await;
+        // Expired sessions are rejected by verify anyway; stop publishing them.
+        log.entries
+            .retain(|(_, entry)| entry.expires_at > revocation.revoked_at);
+        log.last_cursor += 1;
+        let cursor = log.last_cursor;
+        log.entries.push_back((cursor, revocation));
+        Ok(())
+    }
+
+    async fn list_since(&self, cursor: u64, now: u64) -> Result<RevocationPage, String> {
+        let mut log = self.log.lock().await;
+        log.entries.retain(|(_, entry)| entry.expires_at > now);
+        // A cursor from before a restart is ahead of this log; replay everything.
+        let cursor = if cursor > log.last_cursor { 0 } else { cursor };
+        let revocations = log
+            .entries
+            .iter()
+            .filter(|(seq, _)| *seq > cursor)
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        Ok(RevocationPage {
+            revocations,
+            cursor: log.last_cursor,
+        })
+    }
+
+    async fn is_revoked(&self, session_id: &str) -> Result<bool, String> {
+        let log = self.log.lock().await;
+        Ok(log
+            .entries
+            .iter()
+            .any(|(_, entry)| entry.session_id == session_id))
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let mut log = self.log.lock().await;
+        let before = log.entries.len();
+        log.entries.retain(|(_, entry)| entry.expires_at > now);
+        Ok((before - log.entries.len()) as u64)
+    }
+}
+
+#[async_trait]
+impl RevocationStore for PostgresRevocationStore {
+    async fn record(&self, revocation: Revocation) -> Result<(), String> {
+        let mut tx = self.db.begin().await.map_err(|err| err.to_string())?;
+        // Sequence values are handed out before commit; serializing writers keeps a
+        // poller from moving its cursor past a revocation that commits late.
+        sqlx::query("LOCK TABLE revocations IN EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| err.to_string())?;
+        sqlx::query(
+            r#"
+            INSERT INTO revocations (session_id, user_id, revoked_at, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (session_id) DO NOTHING
+            "#,
+        )
+        .bind(&revocation.session_id)
+        .bind(revocation.user_id as i64)
+        .bind(revocation.revoked_at as i64)
+        .bind(revocation.expires_at as i64)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| err.to_string())?;
+        tx.commit().await.map_err(|err| err.to_string())
+    }
+
+    async fn list_since(&self, cursor: u64, now: u64) -> Result<RevocationPage, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT seq, session_id, user_id, revoked_at, expires_at
+            FROM revocations
+            WHERE seq > $1
+            ORDER BY seq
+            "#,
+        )
+        .bind(cursor as i64)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+        let mut page = RevocationPage {
+            revocations: Vec::new(),
+            cursor,
+        };
+        for row in &rows {
+            page.cursor = row
+                .try_get::<i64, _>("seq")
+                .map_err(|err| err.to_string())? as u64;
+            let revocation = revocation_from_row(row)?;
+            // Expired rows wait for the sweeper but are no longer published.
+            if revocation.expires_at > now {
+                page.revocations.push(revocation);
+            }
+        }
+        Ok(page)
+    }
+
+    async fn is_revoked(&self, session_id: &str) -> Result<bool, String> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM revocations WHERE session_id = $1)")
+            .bind(session_id)
+            .fetch_one(&self.db)
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let result = sqlx::query("DELETE FROM revocations WHERE expires_at <= $1")
+            .bind(now as i64)
+            .execute(&self.db)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(result.rows_affected())
+    }
+}
+
+fn revocation_from_row(row: &PgRow) -> Result<Revocation, String> {
+    Ok(Revocation {
+        session_id: row.try_get("session_id").map_err(|err| err.to_string())?,
+        user_id: row
+            .try_get::<i64, _>("user_id")
+            .map_err(|err| err.to_string())? as u64,
+        revoked_at: row
+            .try_get::<i64, _>("revoked_at")
+            .map_err(|err| err.to_string())? as u64,
+        expires_at: row
+            .try_get::<i64, _>("expires_at")
+            .map_err(|err| err.to_string())? as u64,
+    })
+}
+
+#[async_trait]
+impl RevocationStore for ConfiguredRevocationStore {
+    async fn record(&self, revocation: Revocation) -> Result<(), String> {
+        match self {
+            ConfiguredRevocationStore::Memory(store) => store.record(revocation).await,
+            ConfiguredRevocationStore::Postgres(store) => store.record(revocation).await,
+        }
+    }
+
+    async fn list_since(&self, cursor: u64, now: u64) -> Result<RevocationPage, String> {
+        match self {
+            ConfiguredRevocationStore::Memory(store) => store.list_since(cursor, now).await,
+            ConfiguredRevocationStore::Postgres(store) => store.list_since(cursor, now).await,
+        }
+    }
+
+    async fn is_revoked(&self, session_id: &str) -> Result<bool, String> {
+        match self {
+            ConfiguredRevocationStore::Memory(store) => store.is_revoked(session_id).await,
+            ConfiguredRevocationStore::Postgres(store) => store.is_revoked(session_id).await,
+        }
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        match self {
+            ConfiguredRevocationStore::Memory(store) => store.remove_expired(now).await,
+            ConfiguredRevocationStore::Postgres(store) => store.remove_expired(now).await,
+        }
     }
 }
 
@@ -86,3 +763,287 @@ impl Clock for SystemClock {
             .as_secs()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::use_cases::test_support::{test_database, TEST_DATABASE_URL_ENV_VAR};
+
+    // Postgres adapter tests need a database and are skipped without one.
+    async fn postgres_store() -> Option<PostgresSessionStore> {
+        let url = std::env::var(TEST_DATABASE_URL_ENV_VAR).ok()?;
+        Some(PostgresSessionStore {
+            db: test_database(&url).await,
+        })
+    }
+
+    fn session(session_id: &str, expires_at: u64) -> Session {
+        Session {
+            guest_id: u64::MAX - 1,
+            display_name: "Pilot".to_string(),
+            metadata: Some(serde_json::json!({ "skin": "red" })),
+            session_id: session_id.to_string(),
+            expires_at,
+        }
+    }
+
+    fn revocation(session_id: &str, revoked_at: u64, expires_at: u64) -> Revocation {
+        Revocation {
+            session_id: session_id.to_string(),
+            user_id: 42,
+            revoked_at,
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_cursor_is_ahead_of_the_log_then_all_live_revocations_are_replayed() {
+        let store = InMemoryRevocationStore {
+            log: Arc::new(Mutex::new(RevocationLog::default())),
+        };
+        store
+            .record(revocation("session-1", 100, 500))
+            .await
+            .unwrap();
+        store
+            .record(revocation("session-2", 110, 500))
+            .await
+            .unwrap();
+
+        let page = store.list_since(1, 120).await.unwrap();
+        assert_eq!(page.revocations, vec![revocation("session-2", 110, 500)]);
+        assert_eq!(page.cursor, 2);
+
+        // A poller that saw cursor 9 before an auth restart must not miss these.
+        let page = store.list_since(9, 120).await.unwrap();
+        assert_eq!(page.revocations.len(), 2);
+        assert_eq!(page.cursor, 2);
+    }
+
+    #[tokio::test]
+    async fn when_revoked_sessions_expire_then_they_are_pruned() {
+        let store = InMemoryRevocationStore {
+            log: Arc::new(Mutex::new(RevocationLog::default())),
+        };
+        store
+            .record(revocation("session-1", 100, 200))
+            .await
+            .unwrap();
+        store
+            .record(revocation("session-2", 110, 500))
+            .await
+            .unwrap();
+
+        let page = store.list_since(0, 300).await.unwrap();
+
+        assert_eq!(page.revocations, vec![revocation("session-2", 110, 500)]);
+        assert_eq!(store.log.lock().await.entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn when_revocations_are_in_postgres_then_every_instance_sees_them_until_swept() {
+        let Some(sessions) = postgres_store().await else {
+            return;
+        };
+        let recording = PostgresRevocationStore {
+            db: sessions.db.clone(),
+        };
+        // A second instance, or this one after a restart, shares only the database.
+        let other_instance = PostgresRevocationStore { db: sessions.db };
+        recording
+            .record(revocation("session-1", 100, 200))
+            .await
+            .unwrap();
+        recording
+            .record(revocation("session-2", 110, 500))
+            .await
+            .unwrap();
+        recording
+            .record(revocation("session-2", 120, 500))
+            .await
+            .unwrap();
+
+        assert!(other_instance.is_revoked("session-1").await.unwrap());
+        let page = other_instance.list_since(0, 150).await.unwrap();
+        assert_eq!(
+            page.revocations,
+            vec![
+                revocation("session-1", 100, 200),
+                revocation("session-2", 110, 500)
+            ]
+        );
+        let page = other_instance.list_since(page.cursor, 150).await.unwrap();
+        assert!(page.revocations.is_empty());
+
+        assert_eq!(other_instance.remove_expired(200).await.unwrap(), 1);
+        assert!(!recording.is_revoked("session-1").await.unwrap());
+        assert!(recording.is_revoked("session-2").await.unwrap());
+        assert_eq!(
+            recording.list_since(0, 300).await.unwrap().revocations,
+            vec![revocation("session-2", 110, 500)]
+        );
+    }
+
+    #[tokio::test]
+    async fn when_session_is_stored_in_postgres_then_only_the_token_hash_is_written() {
+        let Some(store) = postgres_store().await else {
+            return;
+        };
+        let token = uuid::Uuid::new_v4().to_string();
+
+        store
+            .insert(token.clone(), session("session-hashed", 1_700_003_600))
+            .await
+            .unwrap();
+
+        let stored = store.get(&token).await.unwrap().expect("expected session");
+        assert_eq!(stored.guest_id, u64::MAX - 1);
+        assert_eq!(stored.metadata, Some(serde_json::json!({ "skin": "red" })));
+        let plaintext: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE token_hash = $1")
+                .bind(token.as_bytes())
+                .fetch_one(&store.db)
+                .await
+                .unwrap();
+        assert_eq!(plaintext, 0);
+        assert!(store.remove(&token).await.unwrap());
+        assert!(!store.remove(&token).await.unwrap());
+    }
+
+    #[test]
+    fn when_session_cap_is_reached_then_oldest_sessions_are_evicted_first() {
+        let mut sessions = SessionMap::new(2);
+
+        assert!(sessions
+            .insert("token-1".into(), session("session-1", 500))
+            .is_empty());
+        assert!(sessions
+            .insert("token-2".into(), session("session-2", 500))
+            .is_empty());
+        // Re-issuing a token moves it to the back instead of evicting anything.
+        assert!(sessions
+            .insert("token-1".into(), session("session-1", 600))
+            .is_empty());
+        assert_eq!(
+            sessions.insert("token-3".into(), session("session-3", 500)),
+            vec![session("session-2", 500)]
+        );
+
+        assert!(sessions.get("token-2").is_none());
+        assert_eq!(
+            sessions.get("token-1").map(|session| session.expires_at),
+            Some(600)
+        );
+        assert!(sessions.get("token-3").is_some());
+        assert_eq!(sessions.len(), 2);
+    }
+
+    #[test]
+    fn when_expired_sessions_are_swept_then_eviction_order_stays_consistent() {
+        let mut sessions = SessionMap::new(2);
+        sessions.insert("expired".into(), session("session-1", 100));
+        sessions.insert("live".into(), session("session-2", 500));
+
+        assert_eq!(sessions.remove_expired(100), 1);
+        assert!(sessions
+            .insert("newer".into(), session("session-3", 500))
+            .is_empty());
+        assert_eq!(
+            sessions.insert("newest".into(), session("session-4", 500)),
+            vec![session("session-2", 500)]
+        );
+
+        assert!(sessions.get("live").is_none());
+        assert!(sessions.get("newer").is_some());
+        assert!(sessions.get("newest").is_some());
+    }
+
+    #[test]
+    fn when_sessions_come_and_go_then_the_user_index_follows() {
+        let mut sessions = SessionMap::new(3);
+        sessions.insert("token-1".into(), session("session-1", 300));
+        sessions.insert("token-2".into(), session("session-2", 100));
+        sessions.insert(
+            "other".into(),
+            Session {
+                guest_id: 7,
+                ..session("session-3", 200)
+            },
+        );
+        let ids = |sessions: &SessionMap| {
+            sessions
+                .list_for_user(u64::MAX - 1)
+                .into_iter()
+                .map(|session| session.session_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&sessions), vec!["session-2", "session-1"]);
+        sessions.remove_expired(100);
+        assert_eq!(ids(&sessions), vec!["session-1"]);
+        // Evicting for the global cap drops the session from the index too.
+        sessions.insert("token-4".into(), session("session-4", 400));
+        sessions.insert("token-5".into(), session("session-5", 500));
+        assert_eq!(ids(&sessions), vec!["session-4", "session-5"]);
+        assert!(sessions.remove_by_session_id("session-4"));
+        assert!(!sessions.remove_by_session_id("session-4"));
+        assert!(!sessions.remove_by_session_id("session-1"));
+        assert_eq!(ids(&sessions), vec!["session-5"]);
+        assert!(sessions.list_for_user(42).is_empty());
+        // Dropping the last token clears the session id index as well.
+        sessions.remove("token-5");
+        sessions.remove("other");
+        assert!(sessions.by_session_id.is_empty());
+    }
+
+    #[tokio::test]
+    async fn when_postgres_sessions_are_listed_then_only_the_users_are_returned_in_expiry_order() {
+        let Some(store) = postgres_store().await else {
+            return;
+        };
+        for (token, session) in [
+            ("token-1", session("session-1", 300)),
+            ("token-2", session("session-2", 100)),
+            (
+                "token-3",
+                Session {
+                    guest_id: 7,
+                    ..session("session-3", 200)
+                },
+            ),
+        ] {
+            store.insert(token.to_string(), session).await.unwrap();
+        }
+
+        let listed = store.list_for_user(u64::MAX - 1).await.unwrap();
+
+        assert_eq!(
+            listed,
+            vec![session("session-2", 100), session("session-1", 300)]
+        );
+    }
+
+    #[tokio::test]
+    async fn when_postgres_sessions_expire_then_remove_expired_deletes_only_those() {
+        let Some(store) = postgres_store().await else {
+            return;
+        };
+        for (token, session) in [
+            ("expired", session("session-1", 100)),
+            ("expiring", session("session-2", 200)),
+            ("live", session("session-3", 201)),
+        ] {
+            store.insert(token.to_string(), session).await.unwrap();
+        }
+
+        assert_eq!(store.remove_expired(200).await.unwrap(), 2);
+
+        assert!(store.get("expired").await.unwrap().is_none());
+        assert!(store.get("expiring").await.unwrap().is_none());
+        assert_eq!(
+            store.get("live").await.unwrap(),
+            Some(session("session-3", 201))
+        );
+        assert_eq!(store.remove_expired(200).await.unwrap(), 0);
+    }
+}
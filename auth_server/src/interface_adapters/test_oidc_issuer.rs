@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::Url;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+
+use crate::domain::ports::Clock;
+use crate::interface_adapters::oidc::OidcProviderConfig;
+use crate::interface_adapters::state::SystemClock;
+
+const TEST_CLIENT_ID: &str = "jet-raiders-test";
+const TEST_CLIENT_SECRET: &str = "test-client-secret";
+const TEST_REDIRECT_URI: &str = "http://localhost/oidc/callback";
+
+// Local OpenID Connect issuer serving discovery and a token endpoint that enforces PKCE.
+// There is no login page: tests approve logins directly with `approve`.
+#[derive(Clone)]
+pub(crate) struct TestIssuer {
+    pub(crate) url: String,
+    grants: Arc<Mutex<HashMap<String, Grant>>>,
+    // Token endpoint named by discovery in place of the real one, when set.
+    advertised_token_endpoint: Arc<Mutex<Option<String>>>,
+}
+
+// What the user approved, redeemable once by its code.
+struct Grant {
+    code_challenge: String,
+    nonce: String,
+    subject: String,
+    name: String,
+}
+
+impl TestIssuer {
+    pub(crate) async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("expected test issuer to bind");
+        let issuer = Self {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            grants: Arc::default(),
+            advertised_token_endpoint: Arc::default(),
+        };
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/token", post(token))
+            .with_state(issuer.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        issuer
+    }
+
+    pub(crate) fn config(&self) -> OidcProviderConfig {
+        OidcProviderConfig {
+            issuer: self.url.clone(),
+            client_id: TEST_CLIENT_ID.to_string(),
+            client_secret: Some(TEST_CLIENT_SECRET.to_string()),
+            redirect_uri: TEST_REDIRECT_URI.to_string(),
+            allow_insecure_loopback: true,
+        }
+    }
+
+    pub(crate) fn advertise_token_endpoint(&self, url: &str) {
+        *self.advertised_token_endpoint.lock().unwrap() = Some(url.to_string());
+    }
+
+    pub(crate) fn authorize_url(&self) -> String {
+        format!("{}/authorize", self.url)
+    }
+
+    // Plays the user signing in at `authorization_url`; returns its `state` and the code issued.
+    pub(crate) fn approve(
+        &self,
+        authorization_url: &str,
+        subject: &str,
+        name: &str,
+    ) -> (String, String) {
+        let url = Url::parse(authorization_url).expect("expected a valid authorization url");
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], TEST_CLIENT_ID);
+        assert_eq!(params["redirect_uri"], TEST_REDIRECT_URI);
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        let code = uuid::Uuid::new_v4().simple().to_string();
+        self.grants.lock().unwrap().insert(
+            code.clone(),
+            Grant {
+                code_challenge: params["code_challenge"].clone(),
+                nonce: params["nonce"].clone(),
+                subject: subject.to_string(),
+                name: name.to_string(),
+            },
+        );
+        (params["state"].clone(), code)
+    }
+}
+
+async fn discovery(State(issuer): State<TestIssuer>) -> Json<Value> {
+    let token_endpoint = issuer
+        .advertised_token_endpoint
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| format!("{}/token", issuer.url));
+    Json(json!({
+        "issuer": issuer.url,
+        "authorization_endpoint": issuer.authorize_url(),
+        "token_endpoint": token_endpoint,
+    }))
+}
+
+async fn token(
+    State(issuer): State<TestIssuer>,
+    Form(form): Form<HashMap<String, String>>,
+) -> (StatusCode, Json<Value>) {
+    let field = |key: &str| form.get(key).map(String::as_str).unwrap_or_default();
+    if field("client_id") != TEST_CLIENT_ID || field("client_secret") != TEST_CLIENT_SECRET {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "invalid_client" })),
+        );
+    }
+    let grant = issuer.grants.lock().unwrap().remove(field("code"));
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(field("code_verifier").as_bytes()));
+    let grant = match grant {
+        Some(grant)
+            if field("grant_type") == "authorization_code"
+                && field("redirect_uri") == TEST_REDIRECT_URI
+                && grant.code_challenge == challenge =>
+        {
+            grant
+        }
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "invalid_grant" })),
+            )
+        }
+    };
+
+    let claims = json!({
+        "iss": issuer.url,
+        "sub": grant.subject,
+        "aud": TEST_CLIENT_ID,
+        "exp": SystemClock.now_epoch_seconds() + 300,
+        "nonce": grant.nonce,
+        "name": grant.name,
+    });
+    let id_token = format!(
+        "{}.{}.c2lnbmF0dXJl",
+        URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","kid":"test"}"#),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    (
+        StatusCode::OK,
+        Json(json!({
+            "access_token": "test-access-token",
+            "token_type": "Bearer",
+            "id_token": id_token,
+        })),
+    )
+}
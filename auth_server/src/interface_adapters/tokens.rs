@@ -0,0 +1,199 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::entities::{TokenClaims, VerificationKey};
+use crate::domain::ports::TokenIssuer;
+
+// Signed tokens use the JWS compact form: base64url(header).base64url(claims).base64url(signature),
+// with the Ed25519 signature taken over the first two segments.
+const TOKEN_ALG: &str = "EdDSA";
+const TOKEN_TYP: &str = "JWT";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenHeader {
+    alg: String,
+    typ: String,
+    kid: String,
+}
+
+// Ed25519 keys for session tokens. The first key signs; the others only verify,
+// so a rotated-out key keeps accepting its tokens until they expire.
+pub struct SigningKeyring {
+    keys: Vec<(String, SigningKey)>,
+}
+
+impl SigningKeyring {
+    // Builds a keyring from `(kid, seed)` pairs; `None` when no keys are configured.
+    pub fn new(keys: Vec<(String, [u8; 32])>) -> Option<Self> {
+        if keys.is_empty() {
+            return None;
+        }
+        Some(Self {
+            keys: keys
+                .into_iter()
+                .map(|(kid, seed)| (kid, SigningKey::from_bytes(&seed)))
+                .collect(),
+        })
+    }
+
+    fn sign(&self, claims: &TokenClaims) -> Result<String, String> {
+        let (kid, key) = &self.keys[0];
+        let header = TokenHeader {
+            alg: TOKEN_ALG.to_string(),
+            typ: TOKEN_TYP.to_string(),
+            kid: kid.clone(),
+        };
+        let header = serde_json::to_vec(&header).map_err(|err| err.to_string())?;
+        let claims = serde_json::to_vec(claims).map_err(|err| err.to_string())?;
+
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header),
+            URL_SAFE_NO_PAD.encode(claims)
+        );
+        let signature = key.sign(signing_input.as_bytes());
+        Ok(format!(
+            "{signing_input}.{}",
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        ))
+    }
+
+    fn verify(&self, token: &str) -> Option<TokenClaims> {
+        let (signing_input, signature) = token.rsplit_once('.')?;
+        let (header, claims) = signing_input.split_once('.')?;
+
+        let header: TokenHeader =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+        if header.alg != TOKEN_ALG {
+            return None;
+        }
+        let (_, key) = self.keys.iter().find(|(kid, _)| *kid == header.kid)?;
+
+        let signature: [u8; 64] = URL_SAFE_NO_PAD.decode(signature).ok()?.try_into().ok()?;
+        key.verifying_key()
+            .verify_strict(signing_input.as_bytes(), &Signature::from_bytes(&signature))
+            .ok()?;
+
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).ok()?).ok()
+    }
+
+    fn verification_keys(&self) -> Vec<VerificationKey> {
+        self.keys
+            .iter()
+            .map(|(kid, key)| VerificationKey {
+                kid: kid.clone(),
+                public_key: key.verifying_key().to_bytes(),
+            })
+            .collect()
+    }
+}
+
+// Token issuer adapter: signed tokens when a keyring is configured, random opaque ones otherwise.
+#[derive(Clone)]
+pub struct SessionTokens {
+    pub keyring: Option<Arc<SigningKeyring>>,
+}
+
+impl TokenIssuer for SessionTokens {
+    fn issue(&self, claims: &TokenClaims) -> Result<String, String> {
+        match &self.keyring {
+            Some(keyring) => keyring.sign(claims),
+            None => Ok(Uuid::new_v4().to_string()),
+        }
+    }
+
+    fn decode(&self, token: &str) -> Option<TokenClaims> {
+        self.keyring.as_ref()?.verify(token)
+    }
+
+    fn verification_keys(&self) -> Vec<VerificationKey> {
+        self.keyring
+            .as_ref()
+            .map(|keyring| keyring.verification_keys())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims() -> TokenClaims {
+        TokenClaims {
+            user_id: 42,
+            display_name: "Pilot".to_string(),
+            session_id: "session-1".to_string(),
+            exp: 1_700_003_600,
+        }
+    }
+
+    fn tokens(keys: &[(&str, u8)]) -> SessionTokens {
+        SessionTokens {
+            keyring: SigningKeyring::new(
+                keys.iter()
+                    .map(|(kid, seed)| (kid.to_string(), [*seed; 32]))
+                    .collect(),
+            )
+            .map(Arc::new),
+        }
+    }
+
+    #[test]
+    fn when_keyring_is_configured_then_issued_tokens_decode_to_their_claims() {
+        let tokens = tokens(&[("key-1", 1)]);
+
+        let token = tokens
+            .issue(&claims())
+            .expect("expected token to be signed");
+
+        assert_eq!(token.split('.').count(), 3);
+        assert_eq!(tokens.decode(&token), Some(claims()));
+    }
+
+    #[test]
+    fn when_key_is_rotated_out_of_signing_then_its_tokens_still_verify() {
+        let old = tokens(&[("key-1", 1)]);
+        let rotated = tokens(&[("key-2", 2), ("key-1", 1)]);
+        let retired = tokens(&[("key-2", 2)]);
+
+        let old_token = old.issue(&claims()).unwrap();
+        let new_token = rotated.issue(&claims()).unwrap();
+
+        assert_eq!(rotated.decode(&old_token), Some(claims()));
+        assert_eq!(retired.decode(&new_token), Some(claims()));
+        assert_eq!(retired.decode(&old_token), None);
+    }
+
+    #[test]
+    fn when_token_is_tampered_or_forged_then_it_does_not_decode() {
+        let forger = tokens(&[("key-1", 9)]);
+        let tokens = tokens(&[("key-1", 1)]);
+        let token = tokens.issue(&claims()).unwrap();
+
+        let (header, rest) = token.split_once('.').unwrap();
+        let (_, signature) = rest.split_once('.').unwrap();
+        let mut elevated = claims();
+        elevated.user_id = 1;
+        let elevated = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&elevated).unwrap());
+        let tampered = format!("{header}.{elevated}.{signature}");
+
+        assert_eq!(tokens.decode(&tampered), None);
+        assert_eq!(tokens.decode(&forger.issue(&claims()).unwrap()), None);
+        assert_eq!(tokens.decode("not-a-token"), None);
+    }
+
+    #[test]
+    fn when_no_keyring_is_configured_then_tokens_are_opaque() {
+        let tokens = tokens(&[]);
+
+        let token = tokens.issue(&claims()).unwrap();
+
+        assert!(Uuid::parse_str(&token).is_ok());
+        assert_eq!(tokens.decode(&token), None);
+        assert!(tokens.verification_keys().is_empty());
+    }
+}
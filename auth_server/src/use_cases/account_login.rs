@@ -0,0 +1,212 @@
+use uuid::Uuid;
+
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    AccountStore, Clock, PasswordHasher, ProfileStore, RefreshTokenStore, RevocationStore,
+    SessionStore, TokenIssuer,
+};
+use crate::use_cases::guest_login::{issue_session, IssuedSession, NewSession};
+use crate::use_cases::profile::record_login_profile;
+
+// Input owned by the use-case layer for username/password login.
+pub struct AccountLoginInput {
+    pub username: String,
+    pub password: String,
+}
+
+// Session and the account it was issued to.
+pub struct AccountSession {
+    pub user_id: u64,
+    pub session: IssuedSession,
+}
+
+// Account login use case: trades a username and password for a session.
+pub struct AccountLoginUseCase<C, S, T, F, A, P, Q, R> {
+    pub clock: C,
+    pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
+    pub accounts: A,
+    pub passwords: P,
+    pub profiles: Q,
+    pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    pub max_sessions_per_user: usize,
+}
+
+impl<C, S, T, F, A, P, Q, R> AccountLoginUseCase<C, S, T, F, A, P, Q, R>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    A: AccountStore,
+    P: PasswordHasher,
+    Q: ProfileStore,
+    R: RevocationStore,
+{
+    pub async fn execute(&self, payload: AccountLoginInput) -> Result<AccountSession, AuthError> {
+        let account = self
+            .accounts
+            .find_by_username(&payload.username.to_ascii_lowercase())
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+
+        // Unknown usernames still pay for a hash check, so timing does not reveal which exist.
+        let password_hash = account
+            .as_ref()
+            .map_or(self.passwords.dummy_hash(), |account| {
+                &account.password_hash
+            });
+        let verified = self
+            .passwords
+            .verify(&payload.password, password_hash)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let Some(account) = account.filter(|_| verified) else {
+            return Err(AuthError::InvalidCredentials);
+        };
+        let profile =
+            record_login_profile(&self.profiles, account.user_id, &account.display_name, None)
+                .await?;
+
+        // Each login starts a new refresh family.
+        let session = issue_session(
+            &self.store,
+            &self.tokens,
+            &self.refresh_tokens,
+            &self.revocations,
+            NewSession {
+                guest_id: account.user_id,
+                display_name: profile.display_name,
+                metadata: None,
+                family_id: Uuid::new_v4().to_string(),
+                now: self.clock.now_epoch_seconds(),
+                ttl_seconds: self.ttl_seconds,
+                refresh_ttl_seconds: self.refresh_ttl_seconds,
+                max_sessions_per_user: self.max_sessions_per_user,
+            },
+        )
+        .await?;
+
+        Ok(AccountSession {
+            user_id: account.user_id,
+            session,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::Account;
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingAccounts, RecordingProfiles, RecordingRefreshTokens,
+        RecordingRevocations, RecordingStore, TestPasswords, TestTokens,
+    };
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    // Test hashes, recording every hash a login verified.
+    #[derive(Clone, Default)]
+    struct VerifiedHashes(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl PasswordHasher for VerifiedHashes {
+        async fn hash(&self, password: &str) -> Result<String, String> {
+            TestPasswords.hash(password).await
+        }
+
+        async fn verify(&self, password: &str, password_hash: &str) -> Result<bool, String> {
+            self.0.lock().unwrap().push(password_hash.to_string());
+            TestPasswords.verify(password, password_hash).await
+        }
+
+        fn dummy_hash(&self) -> &str {
+            TestPasswords.dummy_hash()
+        }
+    }
+
+    async fn login_use_case() -> AccountLoginUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRefreshTokens,
+        RecordingAccounts,
+        VerifiedHashes,
+        RecordingProfiles,
+        RecordingRevocations,
+    > {
+        let accounts = RecordingAccounts::new().await;
+        accounts
+            .create(Account {
+                user_id: 42,
+                username: "pilot".to_string(),
+                password_hash: TestPasswords
+                    .hash("correct horse")
+                    .await
+                    .expect("expected test password to hash"),
+                display_name: "Pilot".to_string(),
+            })
+            .await
+            .expect("expected test account to be created");
+        AccountLoginUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            accounts,
+            passwords: VerifiedHashes::default(),
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_password_matches_then_session_is_issued_for_the_account_user_id() {
+        let use_case = login_use_case().await;
+
+        let login = use_case
+            .execute(AccountLoginInput {
+                username: "Pilot".to_string(),
+                password: "correct horse".to_string(),
+            })
+            .await
+            .expect("expected login to succeed");
+
+        assert_eq!(login.user_id, 42);
+        assert_eq!(login.session.expires_at, 1_700_003_600);
+        let session = use_case
+            .store
+            .get_test_session(&login.session.token)
+            .await
+            .expect("expected session to be stored");
+        assert_eq!(session.guest_id, 42);
+        assert_eq!(session.display_name, "Pilot");
+    }
+
+    #[tokio::test]
+    async fn when_password_is_wrong_or_username_unknown_then_returns_invalid_credentials() {
+        let use_case = login_use_case().await;
+
+        for (username, password) in [("pilot", "wrong password"), ("nobody", "correct horse")] {
+            let result = use_case
+                .execute(AccountLoginInput {
+                    username: username.to_string(),
+                    password: password.to_string(),
+                })
+                .await;
+
+            assert!(matches!(result, Err(AuthError::InvalidCredentials)));
+        }
+        // The unknown username was checked against the dummy hash, like a wrong password.
+        assert_eq!(
+            *use_case.passwords.0.lock().unwrap(),
+            ["test-hash:correct horse", "test-dummy-hash"]
+        );
+    }
+}
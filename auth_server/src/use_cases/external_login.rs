@@ -0,0 +1,468 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::domain::entities::PendingExternalLogin;
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    Clock, ExternalIdentityStore, IdentityProvider, PendingExternalLoginStore, ProfileStore,
+    RefreshTokenStore, RevocationStore, SessionStore, TokenIssuer,
+};
+use crate::use_cases::guest_login::{
+    issue_session, validate_display_name, IssuedSession, NewSession,
+};
+use crate::use_cases::profile::record_login_profile;
+
+// Name for users whose provider shares no usable one.
+const FALLBACK_DISPLAY_NAME: &str = "Pilot";
+
+// Where to send the user, and the `state` the provider hands back with the code.
+pub struct ExternalLoginStart {
+    pub authorization_url: String,
+    pub state: String,
+    // Secret the client keeps and echoes on the callback; only its hash is stored.
+    pub binding: String,
+}
+
+// Start use case: remembers a PKCE verifier, nonce and client binding and builds
+// the provider redirect.
+pub struct StartExternalLoginUseCase<C, P, Q> {
+    pub clock: C,
+    pub provider: P,
+    pub pending: Q,
+    pub provider_name: String,
+    // How long the user has to sign in at the provider.
+    pub ttl_seconds: u64,
+}
+
+impl<C, P, Q> StartExternalLoginUseCase<C, P, Q>
+where
+    C: Clock,
+    P: IdentityProvider,
+    Q: PendingExternalLoginStore,
+{
+    pub async fn execute(&self) -> Result<ExternalLoginStart, AuthError> {
+        let state = random_token();
+        let nonce = random_token();
+        let binding = random_token();
+        // 64 characters, inside the 43..=128 RFC 7636 allows.
+        let code_verifier = format!("{}{}", random_token(), random_token());
+
+        let authorization_url = self
+            .provider
+            .authorization_url(&state, &nonce, &pkce_challenge(&code_verifier))
+            .await
+            .map_err(|_| AuthError::IdentityProviderUnavailable)?;
+        self.pending
+            .insert(
+                state.clone(),
+                PendingExternalLogin {
+                    provider: self.provider_name.clone(),
+                    code_verifier,
+                    nonce,
+                    binding_hash: Sha256::digest(binding.as_bytes()).to_vec(),
+                    expires_at: self.clock.now_epoch_seconds() + self.ttl_seconds,
+                },
+            )
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+
+        Ok(ExternalLoginStart {
+            authorization_url,
+            state,
+            binding,
+        })
+    }
+}
+
+// Input owned by the use-case layer for the provider's callback.
+pub struct CompleteExternalLoginInput {
+    pub state: String,
+    pub code: String,
+    // Echoed by the client that started the login.
+    pub binding: String,
+    // Id for the user if this is the subject's first login.
+    pub new_user_id: u64,
+}
+
+// Session for the user the external identity maps to.
+pub struct ExternalLoginSession {
+    pub user_id: u64,
+    // True when this login linked the subject to `new_user_id`.
+    pub first_login: bool,
+    pub session: IssuedSession,
+}
+
+// Complete use case: redeems the code, maps the subject to a user id and issues a session.
+pub struct CompleteExternalLoginUseCase<C, S, T, F, P, Q, X, U, R> {
+    pub clock: C,
+    pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
+    pub provider: P,
+    pub pending: Q,
+    pub identities: X,
+    pub profiles: U,
+    pub provider_name: String,
+    pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    pub max_sessions_per_user: usize,
+}
+
+impl<C, S, T, F, P, Q, X, U, R> CompleteExternalLoginUseCase<C, S, T, F, P, Q, X, U, R>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    P: IdentityProvider,
+    Q: PendingExternalLoginStore,
+    X: ExternalIdentityStore,
+    U: ProfileStore,
+    R: RevocationStore,
+{
+    pub async fn execute(
+        &self,
+        payload: CompleteExternalLoginInput,
+    ) -> Result<ExternalLoginSession, AuthError> {
+        if payload.new_user_id == 0 {
+            return Err(AuthError::InvalidGuestId);
+        }
+        let now = self.clock.now_epoch_seconds();
+        // Taken before anything else, so a state is spent even if the exchange fails.
+        let login = self
+            .pending
+            .take(&payload.state)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?
+            .filter(|login| login.provider == self.provider_name && login.expires_at > now)
+            .ok_or(AuthError::InvalidExternalLogin)?;
+        // A callback link planted by someone else carries a state this client never started.
+        let bound: bool = Sha256::digest(payload.binding.as_bytes())
+            .as_slice()
+            .ct_eq(&login.binding_hash)
+            .into();
+        if !bound {
+            return Err(AuthError::InvalidExternalLogin);
+        }
+
+        let identity = self
+            .provider
+            .exchange_code(&payload.code, &login.code_verifier, &login.nonce)
+            .await
+            .map_err(|_| AuthError::IdentityProviderUnavailable)?
+            .ok_or(AuthError::InvalidExternalLogin)?;
+
+        let user_id = self
+            .identities
+            .link(&self.provider_name, &identity.subject, payload.new_user_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let display_name = identity
+            .display_name
+            .and_then(|name| validate_display_name(name.trim()).ok())
+            .unwrap_or_else(|| FALLBACK_DISPLAY_NAME.to_string());
+        // The provider's name only seeds the profile of a new user.
+        let profile = record_login_profile(&self.profiles, user_id, &display_name, None).await?;
+
+        // Each login starts a new refresh family.
+        let session = issue_session(
+            &self.store,
+            &self.tokens,
+            &self.refresh_tokens,
+            &self.revocations,
+            NewSession {
+                guest_id: user_id,
+                display_name: profile.display_name,
+                metadata: None,
+                family_id: Uuid::new_v4().to_string(),
+                now,
+                ttl_seconds: self.ttl_seconds,
+                refresh_ttl_seconds: self.refresh_ttl_seconds,
+                max_sessions_per_user: self.max_sessions_per_user,
+            },
+        )
+        .await?;
+
+        Ok(ExternalLoginSession {
+            user_id,
+            first_login: user_id == payload.new_user_id,
+            session,
+        })
+    }
+}
+
+// S256 code challenge for a PKCE verifier (RFC 7636 §4.2).
+pub(crate) fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn random_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::ExternalIdentity;
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingExternalIdentities, RecordingPendingLogins, RecordingProfiles,
+        RecordingRefreshTokens, RecordingRevocations, RecordingStore, TestTokens,
+    };
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    // Provider double that hands out `identity` for any code, recording what it was sent.
+    #[derive(Clone, Default)]
+    struct FakeProvider {
+        identity: Option<ExternalIdentity>,
+        unavailable: bool,
+        // (code_challenge, nonce) sent with each redirect.
+        challenges: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    impl FakeProvider {
+        fn signing_in(subject: &str, name: Option<&str>) -> Self {
+            Self {
+                identity: Some(ExternalIdentity {
+                    subject: subject.to_string(),
+                    display_name: name.map(str::to_string),
+                }),
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl IdentityProvider for FakeProvider {
+        async fn authorization_url(
+            &self,
+            state: &str,
+            nonce: &str,
+            code_challenge: &str,
+        ) -> Result<String, String> {
+            if self.unavailable {
+                return Err("provider down".to_string());
+            }
+            self.challenges
+                .lock()
+                .unwrap()
+                .push((code_challenge.to_string(), nonce.to_string()));
+            Ok(format!("https://provider.test/authorize?state={state}"))
+        }
+
+        async fn exchange_code(
+            &self,
+            _code: &str,
+            code_verifier: &str,
+            nonce: &str,
+        ) -> Result<Option<ExternalIdentity>, String> {
+            if self.unavailable {
+                return Err("provider down".to_string());
+            }
+            // Like a real provider, only vouch for the login the redirect was built for.
+            let challenge = (pkce_challenge(code_verifier), nonce.to_string());
+            if !self.challenges.lock().unwrap().contains(&challenge) {
+                return Ok(None);
+            }
+            Ok(self.identity.clone())
+        }
+    }
+
+    struct Fixture {
+        start: StartExternalLoginUseCase<FixedClock, FakeProvider, RecordingPendingLogins>,
+        complete: CompleteExternalLoginUseCase<
+            FixedClock,
+            RecordingStore,
+            TestTokens,
+            RecordingRefreshTokens,
+            FakeProvider,
+            RecordingPendingLogins,
+            RecordingExternalIdentities,
+            RecordingProfiles,
+            RecordingRevocations,
+        >,
+    }
+
+    async fn fixture(provider: FakeProvider, identities: RecordingExternalIdentities) -> Fixture {
+        let pending = RecordingPendingLogins::new().await;
+        Fixture {
+            start: StartExternalLoginUseCase {
+                clock: FixedClock(1_700_000_000),
+                provider: provider.clone(),
+                pending: pending.clone(),
+                provider_name: "test".to_string(),
+                ttl_seconds: 600,
+            },
+            complete: CompleteExternalLoginUseCase {
+                clock: FixedClock(1_700_000_100),
+                store: RecordingStore::new().await,
+                tokens: TestTokens::opaque(),
+                refresh_tokens: RecordingRefreshTokens::new().await,
+                revocations: RecordingRevocations::new(),
+                provider,
+                pending,
+                identities,
+                provider_name: "test".to_string(),
+                profiles: RecordingProfiles::new().await,
+                ttl_seconds: 3600,
+                refresh_ttl_seconds: 86_400,
+                max_sessions_per_user: 10,
+            },
+        }
+    }
+
+    fn callback(started: &ExternalLoginStart, new_user_id: u64) -> CompleteExternalLoginInput {
+        CompleteExternalLoginInput {
+            state: started.state.clone(),
+            code: "code".to_string(),
+            binding: started.binding.clone(),
+            new_user_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_subject_signs_in_again_then_it_maps_to_the_same_user_id() {
+        let identities = RecordingExternalIdentities::new().await;
+        let fixture = fixture(
+            FakeProvider::signing_in("subject-1", Some("Ace")),
+            identities,
+        )
+        .await;
+
+        let first_start = fixture.start.execute().await.expect("expected start");
+        let first = fixture
+            .complete
+            .execute(callback(&first_start, 7))
+            .await
+            .expect("expected first login to succeed");
+        let second_start = fixture.start.execute().await.expect("expected start");
+        let second = fixture
+            .complete
+            .execute(callback(&second_start, 8))
+            .await
+            .expect("expected second login to succeed");
+
+        assert_eq!((first.user_id, first.first_login), (7, true));
+        assert_eq!((second.user_id, second.first_login), (7, false));
+        assert_ne!(first_start.state, second_start.state);
+        let session = fixture
+            .complete
+            .store
+            .get_test_session(&second.session.token)
+            .await
+            .expect("expected session to be stored");
+        assert_eq!(session.guest_id, 7);
+        assert_eq!(session.display_name, "Ace");
+    }
+
+    #[tokio::test]
+    async fn when_state_is_unknown_replayed_or_expired_then_returns_invalid_external_login() {
+        let fixture = fixture(
+            FakeProvider::signing_in("subject-1", Some("Ace")),
+            RecordingExternalIdentities::new().await,
+        )
+        .await;
+        let started = fixture.start.execute().await.expect("expected start");
+        fixture
+            .complete
+            .execute(callback(&started, 7))
+            .await
+            .expect("expected login to succeed");
+        let stale = fixture.start.execute().await.expect("expected start");
+        let late = CompleteExternalLoginUseCase {
+            clock: FixedClock(1_700_000_600),
+            ..fixture.complete
+        };
+
+        let unknown = ExternalLoginStart {
+            state: "unknown".to_string(),
+            ..fixture.start.execute().await.expect("expected start")
+        };
+
+        for started in [&unknown, &started, &stale] {
+            let result = late.execute(callback(started, 8)).await;
+            assert!(matches!(result, Err(AuthError::InvalidExternalLogin)));
+        }
+    }
+
+    #[tokio::test]
+    async fn when_callback_was_started_by_another_client_then_returns_invalid_external_login() {
+        let fixture = fixture(
+            FakeProvider::signing_in("attacker", Some("Ace")),
+            RecordingExternalIdentities::new().await,
+        )
+        .await;
+        // The attacker's own login, whose callback link they send to the victim.
+        let planted = fixture.start.execute().await.expect("expected start");
+        let victims = fixture.start.execute().await.expect("expected start");
+
+        let result = fixture
+            .complete
+            .execute(CompleteExternalLoginInput {
+                binding: victims.binding.clone(),
+                ..callback(&planted, 7)
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuthError::InvalidExternalLogin)));
+        // The planted state is spent, so the attacker cannot retry it either.
+        assert!(matches!(
+            fixture.complete.execute(callback(&planted, 7)).await,
+            Err(AuthError::InvalidExternalLogin)
+        ));
+        fixture
+            .complete
+            .execute(callback(&victims, 8))
+            .await
+            .expect("expected the victim's own login to succeed");
+    }
+
+    #[tokio::test]
+    async fn when_provider_rejects_or_is_down_then_errors_are_told_apart() {
+        let rejecting = fixture(
+            FakeProvider::default(),
+            RecordingExternalIdentities::new().await,
+        )
+        .await;
+        let started = rejecting.start.execute().await.expect("expected start");
+        let rejected = rejecting.complete.execute(callback(&started, 7)).await;
+
+        let down = fixture(
+            FakeProvider {
+                unavailable: true,
+                ..FakeProvider::default()
+            },
+            RecordingExternalIdentities::new().await,
+        )
+        .await;
+        let unavailable = down.start.execute().await;
+
+        assert!(matches!(rejected, Err(AuthError::InvalidExternalLogin)));
+        assert!(matches!(
+            unavailable,
+            Err(AuthError::IdentityProviderUnavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn when_provider_name_is_unusable_then_the_fallback_display_name_is_used() {
+        let fixture = fixture(
+            FakeProvider::signing_in("subject-1", Some("x")),
+            RecordingExternalIdentities::new().await,
+        )
+        .await;
+        let started = fixture.start.execute().await.expect("expected start");
+
+        let login = fixture
+            .complete
+            .execute(callback(&started, 7))
+            .await
+            .expect("expected login to succeed");
+
+        assert_eq!(login.session.display_name, FALLBACK_DISPLAY_NAME);
+    }
+}
@@ -0,0 +1,215 @@
+use uuid::Uuid;
+
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    Clock, GuestCredentialStore, ProfileStore, RefreshTokenStore, RevocationStore, SessionStore,
+    TokenIssuer,
+};
+use crate::use_cases::guest_login::{
+    hash_guest_secret, issue_session, validate_display_name, IssuedSession, NewSession,
+};
+use crate::use_cases::profile::record_login_profile;
+
+// Input owned by the use-case layer for first-time guest creation.
+pub struct GuestInitInput {
+    pub guest_id: u64,
+    pub display_name: String,
+    pub metadata: Option<serde_json::Value>,
+}
+
+// New guest's secret, returned only here, and its first session.
+pub struct GuestInitOutput {
+    pub guest_secret: String,
+    pub session: IssuedSession,
+}
+
+// Guest init use case: registers a guest with a fresh secret and logs it in.
+pub struct GuestInitUseCase<C, S, T, F, G, P, R> {
+    pub clock: C,
+    pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
+    pub credentials: G,
+    pub profiles: P,
+    pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    pub max_sessions_per_user: usize,
+}
+
+impl<C, S, T, F, G, P, R> GuestInitUseCase<C, S, T, F, G, P, R>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    G: GuestCredentialStore,
+    P: ProfileStore,
+    R: RevocationStore,
+{
+    pub async fn execute(&self, payload: GuestInitInput) -> Result<GuestInitOutput, AuthError> {
+        if payload.guest_id == 0 {
+            return Err(AuthError::InvalidGuestId);
+        }
+        let display_name = validate_display_name(&payload.display_name)?;
+
+        // 244 random bits from two v4 UUIDs, as 64 hex characters.
+        let guest_secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        self.credentials
+            .create(
+                payload.guest_id,
+                &display_name,
+                hash_guest_secret(&guest_secret),
+            )
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let profile = record_login_profile(
+            &self.profiles,
+            payload.guest_id,
+            &display_name,
+            payload.metadata.clone(),
+        )
+        .await?;
+
+        let session = issue_session(
+            &self.store,
+            &self.tokens,
+            &self.refresh_tokens,
+            &self.revocations,
+            NewSession {
+                guest_id: payload.guest_id,
+                display_name: profile.display_name,
+                metadata: payload.metadata,
+                family_id: Uuid::new_v4().to_string(),
+                now: self.clock.now_epoch_seconds(),
+                ttl_seconds: self.ttl_seconds,
+                refresh_ttl_seconds: self.refresh_ttl_seconds,
+                max_sessions_per_user: self.max_sessions_per_user,
+            },
+        )
+        .await?;
+
+        Ok(GuestInitOutput {
+            guest_secret,
+            session,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::use_cases::guest_login::{GuestLoginInput, GuestLoginUseCase};
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingGuestCredentials, RecordingProfiles, RecordingRefreshTokens,
+        RecordingRevocations, RecordingStore, TestTokens,
+    };
+
+    async fn guest_init_use_case(
+        credentials: RecordingGuestCredentials,
+    ) -> GuestInitUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRefreshTokens,
+        RecordingGuestCredentials,
+        RecordingProfiles,
+        RecordingRevocations,
+    > {
+        GuestInitUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            credentials,
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_guest_is_created_then_its_secret_logs_it_in_and_is_stored_hashed() {
+        let credentials = RecordingGuestCredentials::new().await;
+        let use_case = guest_init_use_case(credentials.clone()).await;
+
+        let created = use_case
+            .execute(GuestInitInput {
+                guest_id: 42,
+                display_name: "Pilot".to_string(),
+                metadata: None,
+            })
+            .await
+            .expect("expected guest init to succeed");
+
+        assert_eq!(created.guest_secret.len(), 64);
+        assert_eq!(created.session.expires_at, 1_700_003_600);
+        let stored = credentials
+            .get(42)
+            .await
+            .expect("expected credential lookup to succeed")
+            .expect("expected credential to be stored");
+        assert_eq!(stored.secret_hash, hash_guest_secret(&created.guest_secret));
+
+        let login = GuestLoginUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            credentials,
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        };
+        login
+            .execute(GuestLoginInput {
+                guest_id: 42,
+                guest_secret: created.guest_secret,
+                display_name: "Pilot".to_string(),
+                metadata: None,
+            })
+            .await
+            .expect("expected the issued secret to log the guest in");
+    }
+
+    #[tokio::test]
+    async fn when_display_name_is_invalid_then_no_guest_is_created() {
+        let credentials = RecordingGuestCredentials::new().await;
+        let use_case = guest_init_use_case(credentials.clone()).await;
+
+        let result = use_case
+            .execute(GuestInitInput {
+                guest_id: 42,
+                display_name: "xx".to_string(),
+                metadata: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuthError::InvalidDisplayName)));
+        assert!(credentials
+            .get(42)
+            .await
+            .expect("expected credential lookup to succeed")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn when_guest_id_is_already_taken_then_returns_storage_failure() {
+        let credentials = RecordingGuestCredentials::with_guest(42, "old-secret").await;
+        let use_case = guest_init_use_case(credentials).await;
+
+        let result = use_case
+            .execute(GuestInitInput {
+                guest_id: 42,
+                display_name: "Pilot".to_string(),
+                metadata: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuthError::StorageFailure)));
+    }
+}
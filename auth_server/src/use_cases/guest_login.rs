@@ -1,67 +1,276 @@
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
 use uuid::Uuid;
 
-use crate::domain::entities::Session;
+use crate::domain::entities::{RefreshGrant, Revocation, Session, TokenClaims};
 use crate::domain::errors::AuthError;
-use crate::domain::ports::{Clock, SessionStore};
+use crate::domain::ports::{
+    Clock, GuestCredentialStore, ProfileStore, RefreshTokenStore, RevocationStore, SessionStore,
+    TokenIssuer,
+};
+use crate::use_cases::profile::record_login_profile;
+use crate::use_cases::refresh_session::end_session;
 
 // Input owned by the use-case layer for guest login/session creation.
 pub struct GuestLoginInput {
     pub guest_id: u64,
+    // Secret returned by guest init, proving the caller owns `guest_id`.
+    pub guest_secret: String,
     pub display_name: String,
     pub metadata: Option<serde_json::Value>,
 }
 
-// Response returned by the guest login use case.
-pub struct GuestLoginResponse {
+// Session and refresh token issued by guest login or a refresh.
+pub struct IssuedSession {
     pub token: String,
     pub expires_at: u64,
     pub display_name: String,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
 }
 
 // Guest login use case with injected dependencies.
-pub struct GuestLoginUseCase<C, S> {
+pub struct GuestLoginUseCase<C, S, T, F, G, P, R> {
     pub clock: C,
     pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
+    pub credentials: G,
+    pub profiles: P,
     pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    pub max_sessions_per_user: usize,
 }
 
-impl<C, S> GuestLoginUseCase<C, S>
+impl<C, S, T, F, G, P, R> GuestLoginUseCase<C, S, T, F, G, P, R>
 where
     C: Clock,
     S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    G: GuestCredentialStore,
+    P: ProfileStore,
+    R: RevocationStore,
 {
-    pub async fn execute(&self, payload: GuestLoginInput) -> Result<GuestLoginResponse, AuthError> {
+    pub async fn execute(&self, payload: GuestLoginInput) -> Result<IssuedSession, AuthError> {
         if payload.guest_id == 0 {
             return Err(AuthError::InvalidGuestId);
         }
         let display_name = validate_display_name(&payload.display_name)?;
+        verify_guest_secret(&self.credentials, payload.guest_id, &payload.guest_secret).await?;
+        let profile = record_login_profile(
+            &self.profiles,
+            payload.guest_id,
+            &display_name,
+            payload.metadata.clone(),
+        )
+        .await?;
+
+        // Each login starts a new refresh family.
+        issue_session(
+            &self.store,
+            &self.tokens,
+            &self.refresh_tokens,
+            &self.revocations,
+            NewSession {
+                guest_id: payload.guest_id,
+                display_name: profile.display_name,
+                metadata: payload.metadata,
+                family_id: Uuid::new_v4().to_string(),
+                now: self.clock.now_epoch_seconds(),
+                ttl_seconds: self.ttl_seconds,
+                refresh_ttl_seconds: self.refresh_ttl_seconds,
+                max_sessions_per_user: self.max_sessions_per_user,
+            },
+        )
+        .await
+    }
+}
 
-        let token = Uuid::new_v4().to_string();
-        let session_id = Uuid::new_v4().to_string();
-        let expires_at = self.clock.now_epoch_seconds() + self.ttl_seconds;
-
-        let session = Session {
-            guest_id: payload.guest_id,
-            display_name: display_name.clone(),
-            metadata: payload.metadata,
-            session_id,
-            expires_at,
-        };
+// Checks a guest's secret, counting wrong ones against the guest.
+pub(crate) async fn verify_guest_secret<G>(
+    credentials: &G,
+    guest_id: u64,
+    secret: &str,
+) -> Result<(), AuthError>
+where
+    G: GuestCredentialStore,
+{
+    // Unknown guests get the same answer as a wrong secret, so ids cannot be probed.
+    let credential = credentials
+        .get(guest_id)
+        .await
+        .map_err(|_| AuthError::StorageFailure)?
+        .ok_or(AuthError::InvalidGuestSecret)?;
+
+    let matches: bool = hash_guest_secret(secret)
+        .ct_eq(&credential.secret_hash)
+        .into();
+    if !matches {
+        credentials
+            .record_failed_attempt(guest_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        return Err(AuthError::InvalidGuestSecret);
+    }
 
-        self.store
-            .insert(token.clone(), session)
+    if credential.failed_attempts > 0 {
+        credentials
+            .reset_failed_attempts(guest_id)
             .await
             .map_err(|_| AuthError::StorageFailure)?;
+    }
+    Ok(())
+}
 
-        Ok(GuestLoginResponse {
-            token,
-            expires_at,
-            display_name,
+// Hash a guest secret is stored and compared under. Secrets are random, so a fast hash suffices.
+pub(crate) fn hash_guest_secret(secret: &str) -> Vec<u8> {
+    Sha256::digest(secret.as_bytes()).to_vec()
+}
+
+// Identity and lifetimes for a session about to be issued.
+pub(crate) struct NewSession {
+    pub guest_id: u64,
+    pub display_name: String,
+    pub metadata: Option<serde_json::Value>,
+    pub family_id: String,
+    pub now: u64,
+    pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    // Live sessions the user may hold once this one is stored; the oldest beyond it are ended.
+    pub max_sessions_per_user: usize,
+}
+
+// Stores a new session and the refresh token that renews it.
+pub(crate) async fn issue_session<S, T, F, R>(
+    store: &S,
+    tokens: &T,
+    refresh_tokens: &F,
+    revocations: &R,
+    new: NewSession,
+) -> Result<IssuedSession, AuthError>
+where
+    S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    R: RevocationStore,
+{
+    let session_id = Uuid::new_v4().to_string();
+    let expires_at = new.now + new.ttl_seconds;
+    let token = tokens
+        .issue(&TokenClaims {
+            user_id: new.guest_id,
+            display_name: new.display_name.clone(),
+            session_id: session_id.clone(),
+            exp: expires_at,
         })
+        .map_err(|_| AuthError::StorageFailure)?;
+
+    let session = Session {
+        guest_id: new.guest_id,
+        display_name: new.display_name.clone(),
+        metadata: new.metadata.clone(),
+        session_id: session_id.clone(),
+        expires_at,
+    };
+
+    let evicted = store
+        .insert(token.clone(), session)
+        .await
+        .map_err(|_| AuthError::StorageFailure)?;
+    // Sessions dropped for the store's global cap are revoked like the per-user ones, so
+    // their signed tokens stop verifying.
+    for session in evicted {
+        end_session(
+            store,
+            revocations,
+            Revocation {
+                session_id: session.session_id,
+                user_id: session.guest_id,
+                revoked_at: new.now,
+                expires_at: session.expires_at,
+            },
+        )
+        .await?;
+    }
+    evict_oldest_sessions(store, revocations, &new, &session_id).await?;
+
+    // The family travels in the token so refreshes can be rate limited per family
+    // without a lookup; the random part alone keeps the token unguessable.
+    let refresh_token = format!("{}.{}", new.family_id, Uuid::new_v4());
+    let refresh_expires_at = new.now + new.refresh_ttl_seconds;
+    refresh_tokens
+        .insert(
+            refresh_token.clone(),
+            RefreshGrant {
+                family_id: new.family_id,
+                guest_id: new.guest_id,
+                display_name: new.display_name.clone(),
+                metadata: new.metadata,
+                session_id,
+                session_expires_at: expires_at,
+                expires_at: refresh_expires_at,
+            },
+        )
+        .await
+        .map_err(|_| AuthError::StorageFailure)?;
+
+    Ok(IssuedSession {
+        token,
+        expires_at,
+        display_name: new.display_name,
+        refresh_token,
+        refresh_expires_at,
+    })
+}
+
+// Refresh family a refresh token names, for tokens issued with one.
+pub(crate) fn refresh_token_family(token: &str) -> Option<&str> {
+    token
+        .split_once('.')
+        .map(|(family, _)| family)
+        .filter(|family| !family.is_empty())
+}
+
+// Ends the user's oldest live sessions until the one just stored fits under the cap.
+async fn evict_oldest_sessions<S, R>(
+    store: &S,
+    revocations: &R,
+    new: &NewSession,
+    current_session_id: &str,
+) -> Result<(), AuthError>
+where
+    S: SessionStore,
+    R: RevocationStore,
+{
+    // Sessions share one TTL, so the soonest to expire is the oldest.
+    let others: Vec<Session> = store
+        .list_for_user(new.guest_id)
+        .await
+        .map_err(|_| AuthError::StorageFailure)?
+        .into_iter()
+        .filter(|session| session.expires_at > new.now && session.session_id != current_session_id)
+        .collect();
+    let excess = (others.len() + 1).saturating_sub(new.max_sessions_per_user.max(1));
+
+    for session in others.into_iter().take(excess) {
+        end_session(
+            store,
+            revocations,
+            Revocation {
+                session_id: session.session_id,
+                user_id: session.guest_id,
+                revoked_at: new.now,
+                expires_at: session.expires_at,
+            },
+        )
+        .await?;
     }
+    Ok(())
 }
 
-fn validate_display_name(value: &str) -> Result<String, AuthError> {
+pub(crate) fn validate_display_name(value: &str) -> Result<String, AuthError> {
     // Keep names compact and readable for game UI and logs.
     const MIN_LEN: usize = 3;
     const MAX_LEN: usize = 32;
@@ -89,21 +298,33 @@ fn validate_display_name(value: &str) -> Result<String, AuthError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::use_cases::test_support::{FailureFlags, FixedClock, RecordingStore};
+    use crate::use_cases::test_support::{
+        FailureFlags, FixedClock, RecordingGuestCredentials, RecordingProfiles,
+        RecordingRefreshTokens, RecordingRevocations, RecordingStore, TestTokens,
+        TEST_GUEST_SECRET,
+    };
     use serde_json::json;
 
     #[tokio::test]
     async fn when_payload_is_valid_then_session_is_stored_and_response_is_returned() {
-        let store = RecordingStore::new();
+        let store = RecordingStore::new().await;
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
             store: store.clone(),
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot_42".to_string(),
                 metadata: None,
             })
@@ -116,6 +337,7 @@ mod tests {
         // Verify that the generated token points to the stored canonical session.
         let saved = store
             .get_test_session(&result.token)
+            .await
             .expect("expected session to be stored");
         assert_eq!(saved.guest_id, 42);
         assert_eq!(saved.display_name, "Pilot_42");
@@ -126,13 +348,21 @@ mod tests {
     async fn when_guest_id_is_zero_then_returns_invalid_guest_id() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 0,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: None,
             })
@@ -145,13 +375,21 @@ mod tests {
     async fn when_display_name_contains_invalid_characters_then_returns_invalid_display_name() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot!".to_string(),
                 metadata: None,
             })
@@ -166,16 +404,24 @@ mod tests {
         // the domain-level error contract instead of leaking raw store errors.
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new().with_failures(FailureFlags {
+            store: RecordingStore::new().await.with_failures(FailureFlags {
                 insert: true,
                 ..Default::default()
             }),
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: None,
             })
@@ -188,13 +434,21 @@ mod tests {
     async fn when_display_name_length_is_two_then_returns_invalid_display_name() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "AB".to_string(),
                 metadata: None,
             })
@@ -207,13 +461,21 @@ mod tests {
     async fn when_display_name_length_is_three_then_guest_login_succeeds() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "ABC".to_string(),
                 metadata: None,
             })
@@ -227,13 +489,21 @@ mod tests {
     async fn when_display_name_length_is_thirty_two_then_guest_login_succeeds() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "A".repeat(32),
                 metadata: None,
             })
@@ -247,13 +517,21 @@ mod tests {
     async fn when_display_name_length_is_thirty_three_then_returns_invalid_display_name() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "A".repeat(33),
                 metadata: None,
             })
@@ -266,13 +544,21 @@ mod tests {
     async fn when_display_name_uses_allowed_symbols_then_guest_login_succeeds() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Ace Pilot-1_2".to_string(),
                 metadata: None,
             })
@@ -286,13 +572,21 @@ mod tests {
     async fn when_display_name_contains_internal_space_then_guest_login_succeeds() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Blue Falcon".to_string(),
                 metadata: None,
             })
@@ -306,13 +600,21 @@ mod tests {
     async fn when_display_name_has_trailing_whitespace_then_returns_invalid_display_name() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Blue Falcon ".to_string(),
                 metadata: None,
             })
@@ -325,13 +627,21 @@ mod tests {
     async fn when_display_name_has_leading_whitespace_then_returns_invalid_display_name() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: " Blue Falcon".to_string(),
                 metadata: None,
             })
@@ -342,11 +652,18 @@ mod tests {
 
     #[tokio::test]
     async fn when_metadata_is_present_then_it_is_saved_in_session() {
-        let store = RecordingStore::new();
+        let store = RecordingStore::new().await;
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
             store: store.clone(),
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
         let metadata = json!({
             "ship": "falcon",
@@ -356,6 +673,7 @@ mod tests {
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: Some(metadata.clone()),
             })
@@ -364,22 +682,31 @@ mod tests {
 
         let saved = store
             .get_test_session(&result.token)
+            .await
             .expect("expected session to be stored");
         assert_eq!(saved.metadata, Some(metadata));
     }
 
     #[tokio::test]
     async fn when_metadata_is_none_then_session_metadata_stays_none() {
-        let store = RecordingStore::new();
+        let store = RecordingStore::new().await;
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
             store: store.clone(),
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: None,
             })
@@ -388,17 +715,25 @@ mod tests {
 
         let saved = store
             .get_test_session(&result.token)
+            .await
             .expect("expected session to be stored");
         assert_eq!(saved.metadata, None);
     }
 
     #[tokio::test]
     async fn when_metadata_is_nested_json_then_it_is_preserved_exactly() {
-        let store = RecordingStore::new();
+        let store = RecordingStore::new().await;
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
             store: store.clone(),
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
         let metadata = json!({
             "device": {
@@ -413,6 +748,7 @@ mod tests {
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: Some(metadata.clone()),
             })
@@ -421,6 +757,7 @@ mod tests {
 
         let saved = store
             .get_test_session(&result.token)
+            .await
             .expect("expected session to be stored");
         assert_eq!(saved.metadata, Some(metadata));
     }
@@ -429,13 +766,21 @@ mod tests {
     async fn when_ttl_is_zero_then_expires_at_matches_current_time() {
         let use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 0,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let result = use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: None,
             })
@@ -444,4 +789,179 @@ mod tests {
 
         assert_eq!(result.expires_at, 1_700_000_000);
     }
+
+    #[tokio::test]
+    async fn when_tokens_are_signed_then_token_carries_the_session_claims() {
+        let store = RecordingStore::new().await;
+        let tokens = TestTokens::signed();
+        let use_case = GuestLoginUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: store.clone(),
+            tokens,
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+        };
+
+        let result = use_case
+            .execute(GuestLoginInput {
+                guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
+                display_name: "Pilot".to_string(),
+                metadata: None,
+            })
+            .await
+            .expect("expected guest login to succeed");
+
+        let saved = store
+            .get_test_session(&result.token)
+            .await
+            .expect("expected session to be stored");
+        assert_eq!(
+            tokens.decode(&result.token),
+            Some(TokenClaims {
+                user_id: 42,
+                display_name: "Pilot".to_string(),
+                session_id: saved.session_id,
+                exp: 1_700_003_600,
+            })
+        );
+    }
+
+    async fn login_use_case(
+        credentials: RecordingGuestCredentials,
+        store: RecordingStore,
+        refresh_tokens: RecordingRefreshTokens,
+    ) -> GuestLoginUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRefreshTokens,
+        RecordingGuestCredentials,
+        RecordingProfiles,
+        RecordingRevocations,
+    > {
+        GuestLoginUseCase {
+            clock: FixedClock(1_700_000_000),
+            store,
+            tokens: TestTokens::opaque(),
+            refresh_tokens,
+            revocations: RecordingRevocations::new(),
+            credentials,
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        }
+    }
+
+    fn login_input(guest_id: u64, guest_secret: &str) -> GuestLoginInput {
+        GuestLoginInput {
+            guest_id,
+            guest_secret: guest_secret.to_string(),
+            display_name: "Pilot".to_string(),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_guest_secret_is_wrong_then_login_is_rejected_and_the_attempt_counted() {
+        let credentials = RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await;
+        let use_case = login_use_case(
+            credentials.clone(),
+            RecordingStore::new().await,
+            RecordingRefreshTokens::new().await,
+        )
+        .await;
+
+        for _ in 0..2 {
+            let result = use_case.execute(login_input(42, "guessed-secret")).await;
+            assert!(matches!(result, Err(AuthError::InvalidGuestSecret)));
+        }
+
+        let stored = credentials
+            .get(42)
+            .await
+            .expect("expected credential lookup to succeed")
+            .expect("expected credential to exist");
+        assert_eq!(stored.failed_attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn when_guest_is_unknown_or_secret_is_empty_then_returns_invalid_guest_secret() {
+        let use_case = login_use_case(
+            RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+            RecordingStore::new().await,
+            RecordingRefreshTokens::new().await,
+        )
+        .await;
+
+        let unknown = use_case.execute(login_input(7, TEST_GUEST_SECRET)).await;
+        let empty = use_case.execute(login_input(42, "")).await;
+
+        assert!(matches!(unknown, Err(AuthError::InvalidGuestSecret)));
+        assert!(matches!(empty, Err(AuthError::InvalidGuestSecret)));
+    }
+
+    #[tokio::test]
+    async fn when_guest_secret_is_correct_after_failures_then_the_count_is_reset() {
+        let credentials = RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await;
+        let use_case = login_use_case(
+            credentials.clone(),
+            RecordingStore::new().await,
+            RecordingRefreshTokens::new().await,
+        )
+        .await;
+        let _ = use_case.execute(login_input(42, "guessed-secret")).await;
+
+        use_case
+            .execute(login_input(42, TEST_GUEST_SECRET))
+            .await
+            .expect("expected the right secret to log in");
+
+        let stored = credentials
+            .get(42)
+            .await
+            .expect("expected credential lookup to succeed")
+            .expect("expected credential to exist");
+        assert_eq!(stored.failed_attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn when_user_is_at_the_session_cap_then_logging_in_ends_their_oldest_session() {
+        let store = RecordingStore::new().await;
+        let revocations = RecordingRevocations::new();
+        let mut use_case = login_use_case(
+            RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+            store.clone(),
+            RecordingRefreshTokens::new().await,
+        )
+        .await;
+        use_case.revocations = revocations.clone();
+        use_case.max_sessions_per_user = 2;
+
+        let mut tokens = Vec::new();
+        for offset in 0..3 {
+            use_case.clock = FixedClock(1_700_000_000 + offset);
+            let session = use_case
+                .execute(login_input(42, TEST_GUEST_SECRET))
+                .await
+                .expect("expected login to succeed");
+            tokens.push(session.token);
+        }
+
+        let oldest = revocations.recorded();
+        assert_eq!(oldest.len(), 1);
+        assert_eq!(oldest[0].expires_at, 1_700_003_600);
+        assert!(store.get(&tokens[0]).await.unwrap().is_none());
+        let remaining = store.list_for_user(42).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining
+            .iter()
+            .all(|session| session.session_id != oldest[0].session_id));
+    }
 }
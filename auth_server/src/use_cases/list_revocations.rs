@@ -0,0 +1,62 @@
+use crate::domain::entities::RevocationPage;
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{Clock, RevocationStore};
+
+// Revocation list use case polled by game servers to drop revoked sessions mid-match.
+pub struct ListRevocationsUseCase<C, R> {
+    pub clock: C,
+    pub revocations: R,
+}
+
+impl<C, R> ListRevocationsUseCase<C, R>
+where
+    C: Clock,
+    R: RevocationStore,
+{
+    pub async fn execute(&self, cursor: u64) -> Result<RevocationPage, AuthError> {
+        self.revocations
+            .list_since(cursor, self.clock.now_epoch_seconds())
+            .await
+            .map_err(|_| AuthError::StorageFailure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::Revocation;
+    use crate::use_cases::test_support::{FixedClock, RecordingRevocations};
+
+    fn revocation(session_id: &str, expires_at: u64) -> Revocation {
+        Revocation {
+            session_id: session_id.to_string(),
+            user_id: 42,
+            revoked_at: 1_700_000_000,
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_polled_with_cursor_then_only_newer_unexpired_revocations_are_returned() {
+        let revocations = RecordingRevocations::new();
+        for record in [
+            revocation("session-1", 1_700_003_600),
+            revocation("session-2", 1_700_000_050),
+            revocation("session-3", 1_700_003_600),
+        ] {
+            revocations.record(record).await.unwrap();
+        }
+        let use_case = ListRevocationsUseCase {
+            clock: FixedClock(1_700_000_100),
+            revocations,
+        };
+
+        let page = use_case.execute(1).await.expect("expected list to succeed");
+
+        assert_eq!(
+            page.revocations,
+            vec![revocation("session-3", 1_700_003_600)]
+        );
+        assert_eq!(page.cursor, 3);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::domain::entities::VerificationKey;
+use crate::domain::ports::TokenIssuer;
+
+// Publishes the keys that verify signed session tokens so services can check them locally.
+pub struct ListVerificationKeysUseCase<T> {
+    pub tokens: T,
+}
+
+impl<T> ListVerificationKeysUseCase<T>
+where
+    T: TokenIssuer,
+{
+    pub fn execute(&self) -> Vec<VerificationKey> {
+        self.tokens.verification_keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::use_cases::test_support::TestTokens;
+
+    #[test]
+    fn when_tokens_are_opaque_then_no_keys_are_published() {
+        let use_case = ListVerificationKeysUseCase {
+            tokens: TestTokens::opaque(),
+        };
+
+        assert!(use_case.execute().is_empty());
+    }
+
+    #[test]
+    fn when_tokens_are_signed_then_verification_keys_are_published() {
+        let use_case = ListVerificationKeysUseCase {
+            tokens: TestTokens::signed(),
+        };
+
+        let keys = use_case.execute();
+
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].kid, "test-key");
+    }
+}
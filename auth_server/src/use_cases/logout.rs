@@ -1,5 +1,7 @@
+use crate::domain::entities::Revocation;
 use crate::domain::errors::AuthError;
-use crate::domain::ports::SessionStore;
+use crate::domain::ports::{Clock, RefreshTokenStore, RevocationStore, SessionStore, TokenIssuer};
+use crate::use_cases::refresh_session::end_family_sessions;
 
 // Response returned by the logout use case.
 pub struct LogoutResponse {
@@ -7,37 +9,132 @@ pub struct LogoutResponse {
 }
 
 // Logout use case with injected dependencies.
-pub struct LogoutUseCase<S> {
+pub struct LogoutUseCase<C, S, T, F, R> {
+    pub clock: C,
     pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
 }
 
-impl<S> LogoutUseCase<S>
+impl<C, S, T, F, R> LogoutUseCase<C, S, T, F, R>
 where
+    C: Clock,
     S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    R: RevocationStore,
 {
     pub async fn execute(&self, token: String) -> Result<LogoutResponse, AuthError> {
-        let revoked = self
+        let session = self
+            .store
+            .get(&token)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let removed = self
             .store
             .remove(&token)
             .await
             .map_err(|_| AuthError::StorageFailure)?;
 
+        let now = self.clock.now_epoch_seconds();
+        let (revoked, revocation) = match session {
+            Some(session) => (
+                removed,
+                removed.then_some(Revocation {
+                    session_id: session.session_id,
+                    user_id: session.guest_id,
+                    revoked_at: now,
+                    expires_at: session.expires_at,
+                }),
+            ),
+            // Signed tokens stay verifiable without a stored session, so revoke them by their claims.
+            None => match self.tokens.decode(&token) {
+                Some(claims)
+                    if claims.exp > now && !self.is_revoked(&claims.session_id).await? =>
+                {
+                    (
+                        true,
+                        Some(Revocation {
+                            session_id: claims.session_id,
+                            user_id: claims.user_id,
+                            revoked_at: now,
+                            expires_at: claims.exp,
+                        }),
+                    )
+                }
+                _ => (false, None),
+            },
+        };
+
+        // Publish the revocation so game servers can drop sessions that are mid-match.
+        let session_id = revocation
+            .as_ref()
+            .map(|revocation| revocation.session_id.clone());
+        if let Some(revocation) = revocation.filter(|revocation| revocation.expires_at > now) {
+            self.revocations
+                .record(revocation)
+                .await
+                .map_err(|_| AuthError::StorageFailure)?;
+        }
+
+        // Logging out also retires the refresh family, including sessions it rotated into.
+        if let Some(session_id) = session_id {
+            let grants = self
+                .refresh_tokens
+                .revoke_family_of_session(&session_id)
+                .await
+                .map_err(|_| AuthError::StorageFailure)?;
+            end_family_sessions(&self.store, &self.revocations, &grants, now).await?;
+        }
+
         Ok(LogoutResponse { revoked })
     }
+
+    async fn is_revoked(&self, session_id: &str) -> Result<bool, AuthError> {
+        self.revocations
+            .is_revoked(session_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::entities::{Session, TokenClaims};
     use crate::use_cases::guest_login::{GuestLoginInput, GuestLoginUseCase};
-    use crate::use_cases::test_support::{FailureFlags, FixedClock, RecordingStore};
+    use crate::use_cases::refresh_session::RefreshSessionUseCase;
+    use crate::use_cases::test_support::{
+        FailureFlags, FixedClock, RecordingGuestCredentials, RecordingProfiles,
+        RecordingRefreshTokens, RecordingRevocations, RecordingStore, TestTokens,
+        TEST_GUEST_SECRET,
+    };
     use crate::use_cases::verify_token::VerifyTokenUseCase;
 
+    async fn logout_use_case(
+        store: RecordingStore,
+    ) -> LogoutUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRefreshTokens,
+        RecordingRevocations,
+    > {
+        LogoutUseCase {
+            clock: FixedClock(1_700_000_000),
+            store,
+            tokens: TestTokens::opaque(),
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+        }
+    }
+
     #[tokio::test]
     async fn when_token_exists_then_logout_returns_revoked_true() {
-        let store = RecordingStore::new();
-        store.insert_test_token("token-1");
-        let use_case = LogoutUseCase { store };
+        let store = RecordingStore::new().await;
+        store.insert_test_token("token-1").await;
+        let use_case = logout_use_case(store).await;
 
         let result = use_case
             .execute("token-1".to_string())
@@ -49,9 +146,7 @@ mod tests {
 
     #[tokio::test]
     async fn when_token_does_not_exist_then_logout_returns_revoked_false() {
-        let use_case = LogoutUseCase {
-            store: RecordingStore::new(),
-        };
+        let use_case = logout_use_case(RecordingStore::new().await).await;
 
         let result = use_case
             .execute("missing-token".to_string())
@@ -63,12 +158,11 @@ mod tests {
 
     #[tokio::test]
     async fn when_store_remove_fails_then_returns_storage_failure() {
-        let use_case = LogoutUseCase {
-            store: RecordingStore::new().with_failures(FailureFlags {
-                remove: true,
-                ..Default::default()
-            }),
-        };
+        let use_case = logout_use_case(RecordingStore::new().await.with_failures(FailureFlags {
+            remove: true,
+            ..Default::default()
+        }))
+        .await;
 
         let result = use_case.execute("token-1".to_string()).await;
 
@@ -77,9 +171,7 @@ mod tests {
 
     #[tokio::test]
     async fn when_token_is_empty_then_logout_returns_revoked_false() {
-        let use_case = LogoutUseCase {
-            store: RecordingStore::new(),
-        };
+        let use_case = logout_use_case(RecordingStore::new().await).await;
 
         let result = use_case
             .execute(String::new())
@@ -91,9 +183,9 @@ mod tests {
 
     #[tokio::test]
     async fn when_token_has_whitespace_then_logout_does_not_trim_and_returns_false() {
-        let store = RecordingStore::new();
-        store.insert_test_token("token-1");
-        let use_case = LogoutUseCase { store };
+        let store = RecordingStore::new().await;
+        store.insert_test_token("token-1").await;
+        let use_case = logout_use_case(store).await;
 
         let result = use_case
             .execute(" token-1 ".to_string())
@@ -105,26 +197,32 @@ mod tests {
 
     #[tokio::test]
     async fn when_token_is_logged_out_then_verify_token_returns_invalid_token() {
-        let shared_store = RecordingStore::new();
+        let shared_store = RecordingStore::new().await;
         let login_use_case = GuestLoginUseCase {
             clock: FixedClock(1_700_000_000),
             store: shared_store.clone(),
+            tokens: TestTokens::opaque(),
+            profiles: RecordingProfiles::new().await,
             ttl_seconds: 3600,
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
         };
 
         let login_result = login_use_case
             .execute(GuestLoginInput {
                 guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
                 display_name: "Pilot".to_string(),
                 metadata: None,
             })
             .await
             .expect("expected login to succeed");
 
-        let logout_use_case = LogoutUseCase {
-            store: shared_store.clone(),
-        };
-        let logout_result = logout_use_case
+        let logout_result = logout_use_case(shared_store.clone())
+            .await
             .execute(login_result.token.clone())
             .await
             .expect("expected logout to succeed");
@@ -133,9 +231,154 @@ mod tests {
         let verify_use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_001),
             store: shared_store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
         let verify_result = verify_use_case.execute(login_result.token).await;
 
         assert!(matches!(verify_result, Err(AuthError::InvalidToken)));
     }
+
+    #[tokio::test]
+    async fn when_session_is_logged_out_then_its_refresh_family_is_revoked() {
+        let store = RecordingStore::new().await;
+        let use_case = logout_use_case(store.clone()).await;
+        let login = GuestLoginUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: store.clone(),
+            tokens: TestTokens::opaque(),
+            refresh_tokens: use_case.refresh_tokens.clone(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+        };
+        let first = login
+            .execute(GuestLoginInput {
+                guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
+                display_name: "Pilot".to_string(),
+                metadata: None,
+            })
+            .await
+            .expect("expected login to succeed");
+        let refresh = RefreshSessionUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: store.clone(),
+            tokens: TestTokens::opaque(),
+            refresh_tokens: use_case.refresh_tokens.clone(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        };
+        let rotated = refresh
+            .execute(first.refresh_token)
+            .await
+            .expect("expected refresh to succeed");
+
+        use_case
+            .execute(rotated.token.clone())
+            .await
+            .expect("expected logout to succeed");
+
+        // The session left running by the rotation ends with the rest of the family.
+        assert!(store.get_test_session(&first.token).await.is_none());
+        assert_eq!(use_case.revocations.recorded().len(), 2);
+        let result = refresh.execute(rotated.refresh_token).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn when_live_session_is_logged_out_then_revocation_is_recorded() {
+        let store = RecordingStore::new().await;
+        store
+            .insert_test_session(
+                "token-1",
+                Session {
+                    guest_id: 42,
+                    display_name: "Pilot".to_string(),
+                    metadata: None,
+                    session_id: "session-1".to_string(),
+                    expires_at: 1_700_003_600,
+                },
+            )
+            .await;
+        let use_case = logout_use_case(store).await;
+
+        use_case
+            .execute("token-1".to_string())
+            .await
+            .expect("expected logout to succeed");
+
+        assert_eq!(
+            use_case.revocations.recorded(),
+            vec![Revocation {
+                session_id: "session-1".to_string(),
+                user_id: 42,
+                revoked_at: 1_700_000_000,
+                expires_at: 1_700_003_600,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn when_session_is_unknown_or_already_expired_then_nothing_is_recorded() {
+        // The default test session expired long ago, so nobody can still be using it.
+        let store = RecordingStore::new().await;
+        store.insert_test_token("token-1").await;
+        let use_case = logout_use_case(store).await;
+
+        use_case
+            .execute("token-1".to_string())
+            .await
+            .expect("expected logout to succeed");
+        use_case
+            .execute("missing-token".to_string())
+            .await
+            .expect("expected logout to succeed");
+
+        assert!(use_case.revocations.recorded().is_empty());
+    }
+
+    #[tokio::test]
+    async fn when_signed_token_has_no_stored_session_then_it_is_revoked_once_by_its_claims() {
+        let token = TestTokens::signed()
+            .issue(&TokenClaims {
+                user_id: 42,
+                display_name: "Pilot".to_string(),
+                session_id: "session-1".to_string(),
+                exp: 1_700_003_600,
+            })
+            .expect("expected test token to be issued");
+        let use_case = LogoutUseCase {
+            tokens: TestTokens::signed(),
+            ..logout_use_case(RecordingStore::new().await).await
+        };
+
+        let first = use_case
+            .execute(token.clone())
+            .await
+            .expect("expected logout to succeed");
+        let second = use_case
+            .execute(token)
+            .await
+            .expect("expected logout to succeed");
+
+        assert!(first.revoked);
+        assert!(!second.revoked);
+        assert_eq!(
+            use_case.revocations.recorded(),
+            vec![Revocation {
+                session_id: "session-1".to_string(),
+                user_id: 42,
+                revoked_at: 1_700_000_000,
+                expires_at: 1_700_003_600,
+            }]
+        );
+    }
 }
@@ -1,5 +1,15 @@
+pub mod account_login;
+pub mod external_login;
+pub mod guest_init;
 pub mod guest_login;
+pub mod list_revocations;
+pub mod list_verification_keys;
 pub mod logout;
+pub mod profile;
+pub mod refresh_session;
+pub mod register_account;
+pub mod sessions;
+pub mod sweep_expired_sessions;
 #[cfg(test)]
 pub(crate) mod test_support;
 pub mod verify_token;
@@ -0,0 +1,367 @@
+use serde_json::Value;
+
+use crate::domain::entities::Profile;
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{Clock, ProfileStore, RevocationStore, SessionStore, TokenIssuer};
+use crate::use_cases::guest_login::validate_display_name;
+use crate::use_cases::verify_token::VerifyTokenUseCase;
+
+// Saves are retried this often when another update lands between read and write.
+const UPDATE_ATTEMPTS: usize = 3;
+
+// Changes requested by the profile owner; absent fields are left as they are.
+pub struct ProfileUpdate {
+    pub display_name: Option<String>,
+    pub metadata: Option<Value>,
+}
+
+// A profile as shown to its owner.
+pub struct ProfileView {
+    pub profile: Profile,
+    // When the display name may change again; `None` if it may change now.
+    pub display_name_changeable_at: Option<u64>,
+}
+
+// Profile lookup for the holder of a session token.
+pub struct GetProfileUseCase<C, S, T, R, P> {
+    pub verify: VerifyTokenUseCase<C, S, T, R, P>,
+    pub rename_cooldown_seconds: u64,
+}
+
+impl<C, S, T, R, P> GetProfileUseCase<C, S, T, R, P>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    R: RevocationStore,
+    P: ProfileStore,
+{
+    pub async fn execute(&self, token: String) -> Result<ProfileView, AuthError> {
+        let identity = self.verify.execute(token).await?;
+        // Users who last logged in before profiles existed get one from their session.
+        let profile = record_login_profile(
+            &self.verify.profiles,
+            identity.user_id,
+            &identity.display_name,
+            None,
+        )
+        .await?;
+
+        Ok(view(
+            profile,
+            self.verify.clock.now_epoch_seconds(),
+            self.rename_cooldown_seconds,
+        ))
+    }
+}
+
+// Profile update for the holder of a session token. Renames are limited to one per cooldown.
+pub struct UpdateProfileUseCase<C, S, T, R, P> {
+    pub verify: VerifyTokenUseCase<C, S, T, R, P>,
+    pub rename_cooldown_seconds: u64,
+}
+
+impl<C, S, T, R, P> UpdateProfileUseCase<C, S, T, R, P>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    R: RevocationStore,
+    P: ProfileStore,
+{
+    pub async fn execute(
+        &self,
+        token: String,
+        update: ProfileUpdate,
+    ) -> Result<ProfileView, AuthError> {
+        let display_name = update
+            .display_name
+            .as_deref()
+            .map(validate_display_name)
+            .transpose()?;
+        let identity = self.verify.execute(token).await?;
+        let now = self.verify.clock.now_epoch_seconds();
+
+        for _ in 0..UPDATE_ATTEMPTS {
+            let mut profile = record_login_profile(
+                &self.verify.profiles,
+                identity.user_id,
+                &identity.display_name,
+                None,
+            )
+            .await?;
+            let renamed_at = profile.display_name_changed_at;
+
+            if let Some(display_name) = &display_name {
+                // Asking for the current name again is not a rename.
+                if *display_name != profile.display_name {
+                    if changeable_at(renamed_at, now, self.rename_cooldown_seconds).is_some() {
+                        return Err(AuthError::DisplayNameCooldown);
+                    }
+                    profile.display_name = display_name.clone();
+                    profile.display_name_changed_at = Some(now);
+                }
+            }
+            if let Some(metadata) = &update.metadata {
+                profile.metadata = metadata.clone();
+            }
+
+            let saved = self
+                .verify
+                .profiles
+                .update(&profile)
+                .await
+                .map_err(|_| AuthError::StorageFailure)?;
+            if saved {
+                profile.version += 1;
+                return Ok(view(profile, now, self.rename_cooldown_seconds));
+            }
+        }
+        Err(AuthError::StorageFailure)
+    }
+}
+
+// Profile for a user who just logged in, created from the login the first time. After
+// that the login's display name is ignored: only a profile update renames the user.
+pub(crate) async fn record_login_profile<P>(
+    profiles: &P,
+    user_id: u64,
+    display_name: &str,
+    metadata: Option<Value>,
+) -> Result<Profile, AuthError>
+where
+    P: ProfileStore,
+{
+    profiles
+        .record_login(user_id, display_name, metadata)
+        .await
+        .map_err(|_| AuthError::StorageFailure)
+}
+
+fn view(profile: Profile, now: u64, cooldown_seconds: u64) -> ProfileView {
+    ProfileView {
+        display_name_changeable_at: changeable_at(
+            profile.display_name_changed_at,
+            now,
+            cooldown_seconds,
+        ),
+        profile,
+    }
+}
+
+// End of the rename cooldown, if it is still running.
+fn changeable_at(renamed_at: Option<u64>, now: u64, cooldown_seconds: u64) -> Option<u64> {
+    renamed_at
+        .map(|renamed_at| renamed_at + cooldown_seconds)
+        .filter(|at| *at > now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::Session;
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingProfiles, RecordingRevocations, RecordingStore, TestTokens,
+    };
+    use serde_json::json;
+
+    const COOLDOWN: u64 = 86_400;
+
+    async fn verify_at(
+        now: u64,
+        store: RecordingStore,
+        profiles: RecordingProfiles,
+    ) -> VerifyTokenUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRevocations,
+        RecordingProfiles,
+    > {
+        VerifyTokenUseCase {
+            clock: FixedClock(now),
+            store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles,
+        }
+    }
+
+    async fn session_store(token: &str) -> RecordingStore {
+        let store = RecordingStore::new().await;
+        store
+            .insert_test_session(
+                token,
+                Session {
+                    guest_id: 7,
+                    display_name: "Pilot".to_string(),
+                    metadata: None,
+                    session_id: "session-1".to_string(),
+                    expires_at: 1_800_000_000,
+                },
+            )
+            .await;
+        store
+    }
+
+    fn rename(display_name: &str) -> ProfileUpdate {
+        ProfileUpdate {
+            display_name: Some(display_name.to_string()),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_profile_is_read_then_a_missing_one_is_created_from_the_session() {
+        let profiles = RecordingProfiles::new().await;
+        let use_case = GetProfileUseCase {
+            verify: verify_at(
+                1_700_000_000,
+                session_store("token").await,
+                profiles.clone(),
+            )
+            .await,
+            rename_cooldown_seconds: COOLDOWN,
+        };
+
+        let view = use_case.execute("token".to_string()).await.unwrap();
+
+        assert_eq!(view.profile.user_id, 7);
+        assert_eq!(view.profile.display_name, "Pilot");
+        assert_eq!(view.profile.metadata, json!({}));
+        assert_eq!(view.display_name_changeable_at, None);
+        assert_eq!(profiles.get(7).await.unwrap(), Some(view.profile));
+    }
+
+    #[tokio::test]
+    async fn when_display_name_changes_then_the_next_rename_waits_for_the_cooldown() {
+        let profiles = RecordingProfiles::new().await;
+        let store = session_store("token").await;
+        let at = |now: u64| {
+            let store = store.clone();
+            let profiles = profiles.clone();
+            async move {
+                UpdateProfileUseCase {
+                    verify: verify_at(now, store, profiles).await,
+                    rename_cooldown_seconds: COOLDOWN,
+                }
+            }
+        };
+
+        let renamed = at(1_700_000_000)
+            .await
+            .execute("token".to_string(), rename("Maverick"))
+            .await
+            .unwrap();
+        let too_soon = at(1_700_000_000 + COOLDOWN - 1)
+            .await
+            .execute("token".to_string(), rename("Iceman"))
+            .await;
+        let later = at(1_700_000_000 + COOLDOWN)
+            .await
+            .execute("token".to_string(), rename("Iceman"))
+            .await
+            .unwrap();
+
+        assert_eq!(renamed.profile.display_name, "Maverick");
+        assert_eq!(
+            renamed.display_name_changeable_at,
+            Some(1_700_000_000 + COOLDOWN)
+        );
+        assert!(matches!(too_soon, Err(AuthError::DisplayNameCooldown)));
+        assert_eq!(later.profile.display_name, "Iceman");
+    }
+
+    #[tokio::test]
+    async fn when_only_metadata_or_the_same_name_is_sent_then_the_cooldown_is_not_started() {
+        let profiles = RecordingProfiles::new().await;
+        let use_case = UpdateProfileUseCase {
+            verify: verify_at(1_700_000_000, session_store("token").await, profiles).await,
+            rename_cooldown_seconds: COOLDOWN,
+        };
+
+        let updated = use_case
+            .execute(
+                "token".to_string(),
+                ProfileUpdate {
+                    display_name: Some("Pilot".to_string()),
+                    metadata: Some(json!({ "skin": "red" })),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.profile.metadata, json!({ "skin": "red" }));
+        assert_eq!(updated.profile.display_name_changed_at, None);
+        assert_eq!(updated.display_name_changeable_at, None);
+    }
+
+    #[tokio::test]
+    async fn when_metadata_updates_race_from_the_same_read_then_only_the_first_is_saved() {
+        let profiles = RecordingProfiles::new().await;
+        let read = profiles.record_login(7, "Pilot", None).await.unwrap();
+        let first = Profile {
+            metadata: json!({ "skin": "red" }),
+            ..read.clone()
+        };
+        let second = Profile {
+            metadata: json!({ "skin": "blue" }),
+            ..read
+        };
+
+        assert!(profiles.update(&first).await.unwrap());
+        assert!(!profiles.update(&second).await.unwrap());
+
+        let stored = profiles.get(7).await.unwrap().unwrap();
+        assert_eq!(stored.metadata, json!({ "skin": "red" }));
+        assert_eq!(stored.version, first.version + 1);
+
+        // The use case rereads after losing a race, so its update lands on top.
+        let use_case = UpdateProfileUseCase {
+            verify: verify_at(
+                1_700_000_000,
+                session_store("token").await,
+                profiles.clone(),
+            )
+            .await,
+            rename_cooldown_seconds: COOLDOWN,
+        };
+        let updated = use_case
+            .execute(
+                "token".to_string(),
+                ProfileUpdate {
+                    display_name: None,
+                    metadata: Some(json!({ "skin": "blue" })),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.profile.version, stored.version + 1);
+        assert_eq!(profiles.get(7).await.unwrap(), Some(updated.profile));
+    }
+
+    #[tokio::test]
+    async fn when_update_is_invalid_or_unauthenticated_then_nothing_is_saved() {
+        let profiles = RecordingProfiles::new().await;
+        let use_case = UpdateProfileUseCase {
+            verify: verify_at(
+                1_700_000_000,
+                session_store("token").await,
+                profiles.clone(),
+            )
+            .await,
+            rename_cooldown_seconds: COOLDOWN,
+        };
+
+        let invalid = use_case
+            .execute("token".to_string(), rename(" padded "))
+            .await;
+        let unknown = use_case
+            .execute("unknown-token".to_string(), rename("Maverick"))
+            .await;
+
+        assert!(matches!(invalid, Err(AuthError::InvalidDisplayName)));
+        assert!(matches!(unknown, Err(AuthError::InvalidToken)));
+        assert_eq!(profiles.get(7).await.unwrap(), None);
+    }
+}
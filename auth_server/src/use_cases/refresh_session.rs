@@ -0,0 +1,308 @@
+use std::collections::HashSet;
+
+use crate::domain::entities::{RefreshGrant, RefreshRedemption, Revocation};
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    Clock, ProfileStore, RefreshTokenStore, RevocationStore, SessionStore, TokenIssuer,
+};
+use crate::use_cases::guest_login::{issue_session, IssuedSession, NewSession};
+
+// Refresh use case: trades a refresh token for a new session and a rotated refresh token.
+pub struct RefreshSessionUseCase<C, S, T, F, R, P> {
+    pub clock: C,
+    pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
+    pub profiles: P,
+    pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    pub max_sessions_per_user: usize,
+}
+
+impl<C, S, T, F, R, P> RefreshSessionUseCase<C, S, T, F, R, P>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    R: RevocationStore,
+    P: ProfileStore,
+{
+    pub async fn execute(&self, refresh_token: String) -> Result<IssuedSession, AuthError> {
+        let now = self.clock.now_epoch_seconds();
+        let redemption = self
+            .refresh_tokens
+            .redeem(&refresh_token)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+
+        let grant = match redemption {
+            RefreshRedemption::Unknown => return Err(AuthError::InvalidToken),
+            // A spent token came back, so whoever holds the family may be an attacker: end it all.
+            RefreshRedemption::Reused(grant) => {
+                let grants = self
+                    .refresh_tokens
+                    .revoke_family(&grant.family_id)
+                    .await
+                    .map_err(|_| AuthError::StorageFailure)?;
+                end_family_sessions(&self.store, &self.revocations, &grants, now).await?;
+                return Err(AuthError::RefreshTokenReused);
+            }
+            RefreshRedemption::Fresh(grant) => grant,
+        };
+        if grant.expires_at <= now {
+            return Err(AuthError::SessionExpired);
+        }
+        // A rename since the last login reaches signed tokens here.
+        let display_name = self
+            .profiles
+            .get(grant.guest_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?
+            .map_or(grant.display_name, |profile| profile.display_name);
+
+        // The previous session is left to expire so a match in progress is not cut off.
+        issue_session(
+            &self.store,
+            &self.tokens,
+            &self.refresh_tokens,
+            &self.revocations,
+            NewSession {
+                guest_id: grant.guest_id,
+                display_name,
+                metadata: grant.metadata,
+                family_id: grant.family_id,
+                now,
+                ttl_seconds: self.ttl_seconds,
+                refresh_ttl_seconds: self.refresh_ttl_seconds,
+                max_sessions_per_user: self.max_sessions_per_user,
+            },
+        )
+        .await
+    }
+}
+
+// Ends every still-live session issued to a revoked refresh family and publishes the revocations.
+pub(crate) async fn end_family_sessions<S, R>(
+    store: &S,
+    revocations: &R,
+    grants: &[RefreshGrant],
+    now: u64,
+) -> Result<(), AuthError>
+where
+    S: SessionStore,
+    R: RevocationStore,
+{
+    let mut seen = HashSet::new();
+    for grant in grants {
+        if grant.session_expires_at <= now || !seen.insert(grant.session_id.as_str()) {
+            continue;
+        }
+        end_session(
+            store,
+            revocations,
+            Revocation {
+                session_id: grant.session_id.clone(),
+                user_id: grant.guest_id,
+                revoked_at: now,
+                expires_at: grant.session_expires_at,
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+// Removes a live session and publishes its revocation once, so signed tokens stop verifying too.
+pub(crate) async fn end_session<S, R>(
+    store: &S,
+    revocations: &R,
+    revocation: Revocation,
+) -> Result<(), AuthError>
+where
+    S: SessionStore,
+    R: RevocationStore,
+{
+    store
+        .remove_by_session_id(&revocation.session_id)
+        .await
+        .map_err(|_| AuthError::StorageFailure)?;
+
+    let revoked = revocations
+        .is_revoked(&revocation.session_id)
+        .await
+        .map_err(|_| AuthError::StorageFailure)?;
+    if !revoked {
+        revocations
+            .record(revocation)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::use_cases::guest_login::{GuestLoginInput, GuestLoginUseCase};
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingGuestCredentials, RecordingProfiles, RecordingRefreshTokens,
+        RecordingRevocations, RecordingStore, TestTokens, TEST_GUEST_SECRET,
+    };
+
+    async fn login(
+        store: &RecordingStore,
+        refresh_tokens: &RecordingRefreshTokens,
+    ) -> IssuedSession {
+        GuestLoginUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: store.clone(),
+            tokens: TestTokens::opaque(),
+            refresh_tokens: refresh_tokens.clone(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+            credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+        }
+        .execute(GuestLoginInput {
+            guest_id: 42,
+            guest_secret: TEST_GUEST_SECRET.to_string(),
+            display_name: "Pilot".to_string(),
+            metadata: None,
+        })
+        .await
+        .expect("expected login to succeed")
+    }
+
+    async fn refresh_use_case(
+        now: u64,
+        store: &RecordingStore,
+        refresh_tokens: &RecordingRefreshTokens,
+    ) -> RefreshSessionUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRefreshTokens,
+        RecordingRevocations,
+        RecordingProfiles,
+    > {
+        RefreshSessionUseCase {
+            clock: FixedClock(now),
+            store: store.clone(),
+            tokens: TestTokens::opaque(),
+            refresh_tokens: refresh_tokens.clone(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_refresh_token_is_fresh_then_both_tokens_are_rotated() {
+        let store = RecordingStore::new().await;
+        let refresh_tokens = RecordingRefreshTokens::new().await;
+        let issued = login(&store, &refresh_tokens).await;
+        let use_case = refresh_use_case(1_700_001_000, &store, &refresh_tokens).await;
+
+        let refreshed = use_case
+            .execute(issued.refresh_token.clone())
+            .await
+            .expect("expected refresh to succeed");
+
+        assert_ne!(refreshed.token, issued.token);
+        assert_ne!(refreshed.refresh_token, issued.refresh_token);
+        assert_eq!(refreshed.expires_at, 1_700_004_600);
+        assert_eq!(refreshed.refresh_expires_at, 1_700_087_400);
+        assert_eq!(refreshed.display_name, "Pilot");
+        let session = store
+            .get_test_session(&refreshed.token)
+            .await
+            .expect("expected refreshed session to be stored");
+        assert_eq!(session.guest_id, 42);
+        // The old session keeps running until it expires.
+        assert!(store.get_test_session(&issued.token).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn when_profile_was_renamed_then_the_refreshed_session_carries_the_new_name() {
+        let store = RecordingStore::new().await;
+        let refresh_tokens = RecordingRefreshTokens::new().await;
+        let issued = login(&store, &refresh_tokens).await;
+        let profiles = RecordingProfiles::new().await;
+        let mut profile = profiles
+            .record_login(42, "Pilot", None)
+            .await
+            .expect("expected profile to be stored");
+        profile.display_name = "Maverick".to_string();
+        profile.display_name_changed_at = Some(1_700_000_500);
+        assert!(profiles.update(&profile).await.unwrap());
+        let use_case = RefreshSessionUseCase {
+            profiles,
+            ..refresh_use_case(1_700_001_000, &store, &refresh_tokens).await
+        };
+
+        let refreshed = use_case
+            .execute(issued.refresh_token)
+            .await
+            .expect("expected refresh to succeed");
+
+        assert_eq!(refreshed.display_name, "Maverick");
+    }
+
+    #[tokio::test]
+    async fn when_refresh_token_is_unknown_then_returns_invalid_token() {
+        let store = RecordingStore::new().await;
+        let refresh_tokens = RecordingRefreshTokens::new().await;
+        let use_case = refresh_use_case(1_700_000_000, &store, &refresh_tokens).await;
+
+        let result = use_case.execute("missing-token".to_string()).await;
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn when_refresh_token_has_expired_then_returns_session_expired() {
+        let store = RecordingStore::new().await;
+        let refresh_tokens = RecordingRefreshTokens::new().await;
+        let issued = login(&store, &refresh_tokens).await;
+        let use_case = refresh_use_case(issued.refresh_expires_at, &store, &refresh_tokens).await;
+
+        let result = use_case.execute(issued.refresh_token).await;
+
+        assert!(matches!(result, Err(AuthError::SessionExpired)));
+    }
+
+    #[tokio::test]
+    async fn when_spent_refresh_token_is_replayed_then_the_whole_family_is_revoked() {
+        let store = RecordingStore::new().await;
+        let refresh_tokens = RecordingRefreshTokens::new().await;
+        let issued = login(&store, &refresh_tokens).await;
+        let use_case = refresh_use_case(1_700_001_000, &store, &refresh_tokens).await;
+        let rotated = use_case
+            .execute(issued.refresh_token.clone())
+            .await
+            .expect("expected refresh to succeed");
+
+        let replay = use_case.execute(issued.refresh_token).await;
+
+        assert!(matches!(replay, Err(AuthError::RefreshTokenReused)));
+        assert!(store.get_test_session(&issued.token).await.is_none());
+        assert!(store.get_test_session(&rotated.token).await.is_none());
+        let mut revoked: Vec<_> = use_case
+            .revocations
+            .recorded()
+            .into_iter()
+            .map(|revocation| revocation.expires_at)
+            .collect();
+        revoked.sort_unstable();
+        assert_eq!(revoked, vec![1_700_003_600, 1_700_004_600]);
+        // The legitimate holder's latest token died with the family.
+        let next = use_case.execute(rotated.refresh_token).await;
+        assert!(matches!(next, Err(AuthError::InvalidToken)));
+    }
+}
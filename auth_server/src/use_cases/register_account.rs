@@ -0,0 +1,337 @@
+use uuid::Uuid;
+
+use crate::domain::entities::{Account, AccountCreation};
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    AccountStore, Clock, GuestCredentialStore, PasswordHasher, ProfileStore, RefreshTokenStore,
+    RevocationStore, SessionStore, TokenIssuer,
+};
+use crate::use_cases::guest_login::{
+    issue_session, validate_display_name, verify_guest_secret, IssuedSession, NewSession,
+};
+use crate::use_cases::profile::record_login_profile;
+
+// Input owned by the use-case layer for account registration.
+pub struct RegisterAccountInput {
+    // Id for a brand-new user; unused when upgrading a guest.
+    pub new_user_id: u64,
+    pub username: String,
+    pub password: String,
+    // Name of a new user; an upgraded guest keeps its profile's name.
+    pub display_name: String,
+    // Guest to upgrade; the account then keeps the guest's id.
+    pub guest: Option<GuestProof>,
+}
+
+// A guest id with the secret proving the caller owns it.
+pub struct GuestProof {
+    pub guest_id: u64,
+    pub guest_secret: String,
+}
+
+// The registered account's id and its first session.
+pub struct RegisteredAccount {
+    pub user_id: u64,
+    pub session: IssuedSession,
+}
+
+// Register use case: creates an account, optionally taking over a guest's user id.
+pub struct RegisterAccountUseCase<C, S, T, F, G, A, P, Q, R> {
+    pub clock: C,
+    pub store: S,
+    pub tokens: T,
+    pub refresh_tokens: F,
+    pub revocations: R,
+    pub credentials: G,
+    pub accounts: A,
+    pub passwords: P,
+    pub profiles: Q,
+    pub ttl_seconds: u64,
+    pub refresh_ttl_seconds: u64,
+    pub max_sessions_per_user: usize,
+}
+
+impl<C, S, T, F, G, A, P, Q, R> RegisterAccountUseCase<C, S, T, F, G, A, P, Q, R>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    F: RefreshTokenStore,
+    G: GuestCredentialStore,
+    A: AccountStore,
+    P: PasswordHasher,
+    Q: ProfileStore,
+    R: RevocationStore,
+{
+    pub async fn execute(
+        &self,
+        payload: RegisterAccountInput,
+    ) -> Result<RegisteredAccount, AuthError> {
+        let username = validate_username(&payload.username)?;
+        validate_password(&payload.password)?;
+        let display_name = validate_display_name(&payload.display_name)?;
+
+        let user_id = match &payload.guest {
+            Some(guest) => {
+                if guest.guest_id == 0 {
+                    return Err(AuthError::InvalidGuestId);
+                }
+                verify_guest_secret(&self.credentials, guest.guest_id, &guest.guest_secret).await?;
+                guest.guest_id
+            }
+            None if payload.new_user_id == 0 => return Err(AuthError::InvalidGuestId),
+            None => payload.new_user_id,
+        };
+
+        let password_hash = self
+            .passwords
+            .hash(&payload.password)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let created = self
+            .accounts
+            .create(Account {
+                user_id,
+                username,
+                password_hash,
+                display_name: display_name.clone(),
+            })
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        match created {
+            AccountCreation::Created => {}
+            AccountCreation::UsernameTaken => return Err(AuthError::UsernameTaken),
+            AccountCreation::UserAlreadyRegistered => return Err(AuthError::AlreadyRegistered),
+        }
+        let profile = record_login_profile(&self.profiles, user_id, &display_name, None).await?;
+
+        let session = issue_session(
+            &self.store,
+            &self.tokens,
+            &self.refresh_tokens,
+            &self.revocations,
+            NewSession {
+                guest_id: user_id,
+                display_name: profile.display_name,
+                metadata: None,
+                family_id: Uuid::new_v4().to_string(),
+                now: self.clock.now_epoch_seconds(),
+                ttl_seconds: self.ttl_seconds,
+                refresh_ttl_seconds: self.refresh_ttl_seconds,
+                max_sessions_per_user: self.max_sessions_per_user,
+            },
+        )
+        .await?;
+
+        Ok(RegisteredAccount { user_id, session })
+    }
+}
+
+// Returns the canonical lowercase username.
+pub(crate) fn validate_username(value: &str) -> Result<String, AuthError> {
+    const MIN_LEN: usize = 3;
+    const MAX_LEN: usize = 32;
+
+    let len = value.chars().count();
+    if !(MIN_LEN..=MAX_LEN).contains(&len) {
+        return Err(AuthError::InvalidUsername);
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(AuthError::InvalidUsername);
+    }
+
+    Ok(value.to_ascii_lowercase())
+}
+
+fn validate_password(value: &str) -> Result<(), AuthError> {
+    // Long passphrases are welcome; the upper bound only caps hashing work.
+    const MIN_LEN: usize = 8;
+    const MAX_LEN: usize = 128;
+
+    if !(MIN_LEN..=MAX_LEN).contains(&value.chars().count()) {
+        return Err(AuthError::InvalidPassword);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingAccounts, RecordingGuestCredentials, RecordingProfiles,
+        RecordingRefreshTokens, RecordingRevocations, RecordingStore, TestPasswords, TestTokens,
+        TEST_GUEST_SECRET,
+    };
+
+    async fn register_use_case(
+        credentials: RecordingGuestCredentials,
+        accounts: RecordingAccounts,
+    ) -> RegisterAccountUseCase<
+        FixedClock,
+        RecordingStore,
+        TestTokens,
+        RecordingRefreshTokens,
+        RecordingGuestCredentials,
+        RecordingAccounts,
+        TestPasswords,
+        RecordingProfiles,
+        RecordingRevocations,
+    > {
+        RegisterAccountUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            revocations: RecordingRevocations::new(),
+            credentials,
+            accounts,
+            passwords: TestPasswords,
+            profiles: RecordingProfiles::new().await,
+            ttl_seconds: 3600,
+            refresh_ttl_seconds: 86_400,
+            max_sessions_per_user: 10,
+        }
+    }
+
+    fn register_input(username: &str, guest: Option<GuestProof>) -> RegisterAccountInput {
+        RegisterAccountInput {
+            new_user_id: 7,
+            username: username.to_string(),
+            password: "correct horse".to_string(),
+            display_name: "Pilot".to_string(),
+            guest,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_new_user_registers_then_account_is_stored_with_hashed_password() {
+        let accounts = RecordingAccounts::new().await;
+        let use_case =
+            register_use_case(RecordingGuestCredentials::new().await, accounts.clone()).await;
+
+        let registered = use_case
+            .execute(register_input("Ace.Pilot", None))
+            .await
+            .expect("expected registration to succeed");
+
+        assert_eq!(registered.user_id, 7);
+        let session = use_case
+            .store
+            .get_test_session(&registered.session.token)
+            .await
+            .expect("expected session to be stored");
+        assert_eq!(session.guest_id, 7);
+        let account = accounts
+            .find_by_username("ace.pilot")
+            .await
+            .expect("expected account lookup to succeed")
+            .expect("expected account to be stored");
+        assert_eq!(account.user_id, 7);
+        assert_ne!(account.password_hash, "correct horse");
+    }
+
+    #[tokio::test]
+    async fn when_guest_upgrades_then_account_keeps_the_guest_user_id() {
+        let use_case = register_use_case(
+            RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+            RecordingAccounts::new().await,
+        )
+        .await;
+
+        let registered = use_case
+            .execute(register_input(
+                "pilot",
+                Some(GuestProof {
+                    guest_id: 42,
+                    guest_secret: TEST_GUEST_SECRET.to_string(),
+                }),
+            ))
+            .await
+            .expect("expected upgrade to succeed");
+
+        assert_eq!(registered.user_id, 42);
+        let second = use_case
+            .execute(register_input(
+                "other-name",
+                Some(GuestProof {
+                    guest_id: 42,
+                    guest_secret: TEST_GUEST_SECRET.to_string(),
+                }),
+            ))
+            .await;
+        assert!(matches!(second, Err(AuthError::AlreadyRegistered)));
+    }
+
+    #[tokio::test]
+    async fn when_guest_secret_is_wrong_then_upgrade_is_rejected() {
+        let accounts = RecordingAccounts::new().await;
+        let use_case = register_use_case(
+            RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+            accounts.clone(),
+        )
+        .await;
+
+        let result = use_case
+            .execute(register_input(
+                "pilot",
+                Some(GuestProof {
+                    guest_id: 42,
+                    guest_secret: "wrong-secret".to_string(),
+                }),
+            ))
+            .await;
+
+        assert!(matches!(result, Err(AuthError::InvalidGuestSecret)));
+        assert!(accounts
+            .find_by_username("pilot")
+            .await
+            .expect("expected account lookup to succeed")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn when_username_is_taken_in_any_case_then_returns_username_taken() {
+        let use_case = register_use_case(
+            RecordingGuestCredentials::new().await,
+            RecordingAccounts::new().await,
+        )
+        .await;
+        use_case
+            .execute(register_input("pilot", None))
+            .await
+            .expect("expected registration to succeed");
+
+        let result = use_case
+            .execute(RegisterAccountInput {
+                new_user_id: 8,
+                ..register_input("PILOT", None)
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuthError::UsernameTaken)));
+    }
+
+    #[tokio::test]
+    async fn when_username_or_password_is_invalid_then_returns_validation_errors() {
+        let use_case = register_use_case(
+            RecordingGuestCredentials::new().await,
+            RecordingAccounts::new().await,
+        )
+        .await;
+
+        for username in ["ab", "pilot name", "pilot!", &"a".repeat(33)] {
+            let result = use_case.execute(register_input(username, None)).await;
+            assert!(matches!(result, Err(AuthError::InvalidUsername)));
+        }
+        let short_password = use_case
+            .execute(RegisterAccountInput {
+                password: "short".to_string(),
+                ..register_input("pilot", None)
+            })
+            .await;
+        assert!(matches!(short_password, Err(AuthError::InvalidPassword)));
+    }
+}
@@ -0,0 +1,388 @@
+use crate::domain::entities::{Revocation, Session};
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    Clock, ProfileStore, RefreshTokenStore, RevocationStore, SessionStore, TokenIssuer,
+};
+use crate::use_cases::refresh_session::{end_family_sessions, end_session};
+use crate::use_cases::verify_token::VerifyTokenUseCase;
+
+// A live session as shown to its owner.
+pub struct SessionView {
+    pub session: Session,
+    // Whether this is the session the request was made with.
+    pub current: bool,
+}
+
+// Lists the live sessions of the holder of a session token, oldest first.
+pub struct ListSessionsUseCase<C, S, T, R, P> {
+    pub verify: VerifyTokenUseCase<C, S, T, R, P>,
+}
+
+impl<C, S, T, R, P> ListSessionsUseCase<C, S, T, R, P>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    R: RevocationStore,
+    P: ProfileStore,
+{
+    pub async fn execute(&self, token: String) -> Result<Vec<SessionView>, AuthError> {
+        let identity = self.verify.execute(token).await?;
+        let sessions = live_sessions(
+            &self.verify.store,
+            identity.user_id,
+            self.verify.clock.now_epoch_seconds(),
+        )
+        .await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| SessionView {
+                current: session.session_id == identity.session_id,
+                session,
+            })
+            .collect())
+    }
+}
+
+// Ends one of the caller's sessions, along with the refresh family that renews it.
+pub struct RevokeSessionUseCase<C, S, T, R, P, F> {
+    pub verify: VerifyTokenUseCase<C, S, T, R, P>,
+    pub refresh_tokens: F,
+}
+
+impl<C, S, T, R, P, F> RevokeSessionUseCase<C, S, T, R, P, F>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    R: RevocationStore,
+    P: ProfileStore,
+    F: RefreshTokenStore,
+{
+    pub async fn execute(&self, token: String, session_id: &str) -> Result<(), AuthError> {
+        let identity = self.verify.execute(token).await?;
+        let now = self.verify.clock.now_epoch_seconds();
+
+        // Other users' sessions look the same as missing ones, so ids cannot be probed.
+        let session = live_sessions(&self.verify.store, identity.user_id, now)
+            .await?
+            .into_iter()
+            .find(|session| session.session_id == session_id)
+            .ok_or(AuthError::SessionNotFound)?;
+
+        end_session(
+            &self.verify.store,
+            &self.verify.revocations,
+            revocation_of(session, now),
+        )
+        .await?;
+        let grants = self
+            .refresh_tokens
+            .revoke_family_of_session(session_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        end_family_sessions(&self.verify.store, &self.verify.revocations, &grants, now).await
+    }
+}
+
+// Logs the caller out everywhere: every session, the current one included, and every refresh token.
+pub struct RevokeAllSessionsUseCase<C, S, T, R, P, F> {
+    pub verify: VerifyTokenUseCase<C, S, T, R, P>,
+    pub refresh_tokens: F,
+}
+
+impl<C, S, T, R, P, F> RevokeAllSessionsUseCase<C, S, T, R, P, F>
+where
+    C: Clock,
+    S: SessionStore,
+    T: TokenIssuer,
+    R: RevocationStore,
+    P: ProfileStore,
+    F: RefreshTokenStore,
+{
+    // Returns how many sessions were ended.
+    pub async fn execute(&self, token: String) -> Result<usize, AuthError> {
+        let identity = self.verify.execute(token).await?;
+        let now = self.verify.clock.now_epoch_seconds();
+
+        let mut sessions = live_sessions(&self.verify.store, identity.user_id, now).await?;
+        // A signed token can verify without a stored session, so end it by its claims.
+        if !sessions
+            .iter()
+            .any(|session| session.session_id == identity.session_id)
+        {
+            sessions.push(Session {
+                guest_id: identity.user_id,
+                display_name: identity.display_name,
+                metadata: identity.metadata,
+                session_id: identity.session_id,
+                expires_at: identity.expires_at,
+            });
+        }
+
+        let ended = sessions.len();
+        for session in sessions {
+            end_session(
+                &self.verify.store,
+                &self.verify.revocations,
+                revocation_of(session, now),
+            )
+            .await?;
+        }
+        let grants = self
+            .refresh_tokens
+            .revoke_user(identity.user_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        end_family_sessions(&self.verify.store, &self.verify.revocations, &grants, now).await?;
+
+        Ok(ended)
+    }
+}
+
+async fn live_sessions<S>(store: &S, user_id: u64, now: u64) -> Result<Vec<Session>, AuthError>
+where
+    S: SessionStore,
+{
+    let sessions = store
+        .list_for_user(user_id)
+        .await
+        .map_err(|_| AuthError::StorageFailure)?;
+    Ok(sessions
+        .into_iter()
+        .filter(|session| session.expires_at > now)
+        .collect())
+}
+
+fn revocation_of(session: Session, now: u64) -> Revocation {
+    Revocation {
+        session_id: session.session_id,
+        user_id: session.guest_id,
+        revoked_at: now,
+        expires_at: session.expires_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::RefreshRedemption;
+    use crate::use_cases::guest_login::{GuestLoginInput, GuestLoginUseCase, IssuedSession};
+    use crate::use_cases::test_support::{
+        FixedClock, RecordingGuestCredentials, RecordingProfiles, RecordingRefreshTokens,
+        RecordingRevocations, RecordingStore, TestTokens, TEST_GUEST_SECRET,
+    };
+
+    const NOW: u64 = 1_700_000_000;
+
+    struct Stores {
+        store: RecordingStore,
+        refresh_tokens: RecordingRefreshTokens,
+        revocations: RecordingRevocations,
+    }
+
+    impl Stores {
+        async fn new() -> Self {
+            Self {
+                store: RecordingStore::new().await,
+                refresh_tokens: RecordingRefreshTokens::new().await,
+                revocations: RecordingRevocations::new(),
+            }
+        }
+
+        // Logs guest 42 in at `now`, so later logins are the newer sessions.
+        async fn login(&self, now: u64) -> IssuedSession {
+            GuestLoginUseCase {
+                clock: FixedClock(now),
+                store: self.store.clone(),
+                tokens: TestTokens::opaque(),
+                refresh_tokens: self.refresh_tokens.clone(),
+                revocations: self.revocations.clone(),
+                credentials: RecordingGuestCredentials::with_guest(42, TEST_GUEST_SECRET).await,
+                profiles: RecordingProfiles::new().await,
+                ttl_seconds: 3600,
+                refresh_ttl_seconds: 86_400,
+                max_sessions_per_user: 10,
+            }
+            .execute(GuestLoginInput {
+                guest_id: 42,
+                guest_secret: TEST_GUEST_SECRET.to_string(),
+                display_name: "Pilot".to_string(),
+                metadata: None,
+            })
+            .await
+            .expect("expected login to succeed")
+        }
+
+        async fn verify(
+            &self,
+        ) -> VerifyTokenUseCase<
+            FixedClock,
+            RecordingStore,
+            TestTokens,
+            RecordingRevocations,
+            RecordingProfiles,
+        > {
+            VerifyTokenUseCase {
+                clock: FixedClock(NOW + 60),
+                store: self.store.clone(),
+                tokens: TestTokens::opaque(),
+                revocations: self.revocations.clone(),
+                profiles: RecordingProfiles::new().await,
+            }
+        }
+
+        async fn list(&self, token: &str) -> Result<Vec<SessionView>, AuthError> {
+            ListSessionsUseCase {
+                verify: self.verify().await,
+            }
+            .execute(token.to_string())
+            .await
+        }
+
+        async fn revoke(&self, token: &str, session_id: &str) -> Result<(), AuthError> {
+            RevokeSessionUseCase {
+                verify: self.verify().await,
+                refresh_tokens: self.refresh_tokens.clone(),
+            }
+            .execute(token.to_string(), session_id)
+            .await
+        }
+
+        async fn revoke_all(&self, token: &str) -> Result<usize, AuthError> {
+            RevokeAllSessionsUseCase {
+                verify: self.verify().await,
+                refresh_tokens: self.refresh_tokens.clone(),
+            }
+            .execute(token.to_string())
+            .await
+        }
+    }
+
+    fn session_ids(views: &[SessionView]) -> Vec<String> {
+        views
+            .iter()
+            .map(|view| view.session.session_id.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn when_sessions_are_listed_then_only_the_callers_live_ones_are_returned_oldest_first() {
+        let stores = Stores::new().await;
+        let older = stores.login(NOW).await;
+        let newer = stores.login(NOW + 10).await;
+        stores
+            .store
+            .insert_test_session(
+                "other-user",
+                Session {
+                    guest_id: 7,
+                    display_name: "Other".to_string(),
+                    metadata: None,
+                    session_id: "other-session".to_string(),
+                    expires_at: NOW + 3600,
+                },
+            )
+            .await;
+
+        let views = stores.list(&newer.token).await.expect("expected sessions");
+
+        assert_eq!(views.len(), 2);
+        assert_eq!(views[0].session.expires_at, older.expires_at);
+        assert!(!views[0].current);
+        assert_eq!(views[1].session.expires_at, newer.expires_at);
+        assert!(views[1].current);
+    }
+
+    #[tokio::test]
+    async fn when_another_session_is_revoked_then_it_and_its_refresh_token_stop_working() {
+        let stores = Stores::new().await;
+        let other = stores.login(NOW).await;
+        let current = stores.login(NOW + 10).await;
+        let views = stores
+            .list(&current.token)
+            .await
+            .expect("expected sessions");
+        let other_id = views[0].session.session_id.clone();
+
+        stores
+            .revoke(&current.token, &other_id)
+            .await
+            .expect("expected revoke to succeed");
+
+        assert!(matches!(
+            stores.list(&other.token).await,
+            Err(AuthError::InvalidToken)
+        ));
+        let remaining = stores
+            .list(&current.token)
+            .await
+            .expect("expected sessions");
+        assert_eq!(
+            session_ids(&remaining),
+            vec![views[1].session.session_id.clone()]
+        );
+        assert_eq!(stores.revocations.recorded()[0].session_id, other_id);
+        assert!(matches!(
+            stores.refresh_tokens.redeem(&other.refresh_token).await,
+            Ok(RefreshRedemption::Unknown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn when_another_users_session_is_revoked_then_it_is_not_found_and_left_alone() {
+        let stores = Stores::new().await;
+        let current = stores.login(NOW).await;
+        stores
+            .store
+            .insert_test_session(
+                "other-user",
+                Session {
+                    guest_id: 7,
+                    display_name: "Other".to_string(),
+                    metadata: None,
+                    session_id: "other-session".to_string(),
+                    expires_at: NOW + 3600,
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            stores.revoke(&current.token, "other-session").await,
+            Err(AuthError::SessionNotFound)
+        ));
+        assert!(stores
+            .store
+            .get("other-user")
+            .await
+            .expect("expected lookup")
+            .is_some());
+        assert!(stores.revocations.recorded().is_empty());
+    }
+
+    #[tokio::test]
+    async fn when_logging_out_everywhere_then_every_session_and_refresh_token_ends() {
+        let stores = Stores::new().await;
+        let first = stores.login(NOW).await;
+        let second = stores.login(NOW + 10).await;
+
+        let ended = stores
+            .revoke_all(&second.token)
+            .await
+            .expect("expected logout everywhere to succeed");
+
+        assert_eq!(ended, 2);
+        for session in [&first, &second] {
+            assert!(matches!(
+                stores.list(&session.token).await,
+                Err(AuthError::InvalidToken)
+            ));
+            assert!(matches!(
+                stores.refresh_tokens.redeem(&session.refresh_token).await,
+                Ok(RefreshRedemption::Unknown)
+            ));
+        }
+        assert_eq!(stores.revocations.recorded().len(), 2);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::domain::errors::AuthError;
+use crate::domain::ports::{
+    Clock, PendingExternalLoginStore, RefreshTokenStore, RevocationStore, SessionStore,
+};
+
+// How many expired records one sweep removed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SweepOutcome {
+    pub sessions: u64,
+    pub refresh_tokens: u64,
+    pub pending_logins: u64,
+    pub revocations: u64,
+}
+
+// Expiry sweep run periodically so sessions that are never verified again still get dropped.
+pub struct SweepExpiredSessionsUseCase<C, S, F, Q, R> {
+    pub clock: C,
+    pub store: S,
+    pub refresh_tokens: F,
+    // External logins whose user never came back from the provider.
+    pub pending_logins: Q,
+    // Revoked sessions stop being published once they would have expired.
+    pub revocations: R,
+}
+
+impl<C, S, F, Q, R> SweepExpiredSessionsUseCase<C, S, F, Q, R>
+where
+    C: Clock,
+    S: SessionStore,
+    F: RefreshTokenStore,
+    Q: PendingExternalLoginStore,
+    R: RevocationStore,
+{
+    pub async fn execute(&self) -> Result<SweepOutcome, AuthError> {
+        let now = self.clock.now_epoch_seconds();
+        let sessions = self
+            .store
+            .remove_expired(now)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        // Refresh tokens outlive their sessions, so they are swept on their own expiry.
+        let refresh_tokens = self
+            .refresh_tokens
+            .remove_expired(now)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let pending_logins = self
+            .pending_logins
+            .remove_expired(now)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        let revocations = self
+            .revocations
+            .remove_expired(now)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        Ok(SweepOutcome {
+            sessions,
+            refresh_tokens,
+            pending_logins,
+            revocations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{PendingExternalLogin, RefreshGrant, Revocation, Session};
+    use crate::use_cases::test_support::{
+        FailureFlags, FixedClock, RecordingPendingLogins, RecordingRefreshTokens,
+        RecordingRevocations, RecordingStore,
+    };
+
+    fn session(session_id: &str, expires_at: u64) -> Session {
+        Session {
+            guest_id: 42,
+            display_name: "Pilot".to_string(),
+            metadata: None,
+            session_id: session_id.to_string(),
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn when_sweep_runs_then_only_expired_sessions_are_removed() {
+        let store = RecordingStore::new().await;
+        store
+            .insert_test_session("expired", session("session-1", 1_699_999_000))
+            .await;
+        store
+            .insert_test_session("expiring-now", session("session-2", 1_700_000_000))
+            .await;
+        store
+            .insert_test_session("live", session("session-3", 1_700_003_600))
+            .await;
+        let refresh_tokens = RecordingRefreshTokens::new().await;
+        for (token, expires_at) in [("spent", 1_699_999_000), ("usable", 1_700_086_400)] {
+            refresh_tokens
+                .insert(
+                    token.to_string(),
+                    RefreshGrant {
+                        family_id: "family-1".to_string(),
+                        guest_id: 42,
+                        display_name: "Pilot".to_string(),
+                        metadata: None,
+                        session_id: "session-1".to_string(),
+                        session_expires_at: 1_699_999_000,
+                        expires_at,
+                    },
+                )
+                .await
+                .expect("expected refresh token to be stored");
+        }
+        let pending_logins = RecordingPendingLogins::new().await;
+        for (state, expires_at) in [("abandoned", 1_699_999_600), ("signing-in", 1_700_000_300)] {
+            pending_logins
+                .insert(
+                    state.to_string(),
+                    PendingExternalLogin {
+                        provider: "test".to_string(),
+                        code_verifier: "verifier".to_string(),
+                        nonce: "nonce".to_string(),
+                        binding_hash: b"binding".to_vec(),
+                        expires_at,
+                    },
+                )
+                .await
+                .expect("expected pending login to be stored");
+        }
+        let revocations = RecordingRevocations::new();
+        for (session_id, expires_at) in [("session-1", 1_699_999_000), ("session-4", 1_700_003_600)]
+        {
+            revocations
+                .record(Revocation {
+                    session_id: session_id.to_string(),
+                    user_id: 42,
+                    revoked_at: 1_699_998_000,
+                    expires_at,
+                })
+                .await
+                .expect("expected revocation to be recorded");
+        }
+        let use_case = SweepExpiredSessionsUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: store.clone(),
+            refresh_tokens,
+            pending_logins,
+            revocations: revocations.clone(),
+        };
+
+        let removed = use_case.execute().await.expect("expected sweep to succeed");
+
+        assert_eq!(
+            removed,
+            SweepOutcome {
+                sessions: 2,
+                refresh_tokens: 1,
+                pending_logins: 1,
+                revocations: 1,
+            }
+        );
+        assert_eq!(revocations.recorded()[0].session_id, "session-4");
+        assert!(store.get_test_session("expired").await.is_none());
+        assert!(store.get_test_session("expiring-now").await.is_none());
+        assert!(store.get_test_session("live").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn when_store_sweep_fails_then_returns_storage_failure() {
+        let use_case = SweepExpiredSessionsUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await.with_failures(FailureFlags {
+                remove: true,
+                ..FailureFlags::default()
+            }),
+            refresh_tokens: RecordingRefreshTokens::new().await,
+            pending_logins: RecordingPendingLogins::new().await,
+            revocations: RecordingRevocations::new(),
+        };
+
+        let result = use_case.execute().await;
+
+        assert!(matches!(result, Err(AuthError::StorageFailure)));
+    }
+}
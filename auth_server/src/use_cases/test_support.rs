@@ -2,11 +2,42 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{Connection, PgConnection, PgPool};
+use tokio::sync::OnceCell;
 
-use crate::domain::entities::Session;
-use crate::domain::ports::{Clock, SessionStore};
-
-pub(crate) type SessionTable = Arc<Mutex<HashMap<String, Session>>>;
+use crate::domain::entities::{
+    Account, AccountCreation, GuestCredential, PendingExternalLogin, Profile, RefreshGrant,
+    RefreshRedemption, Revocation, RevocationPage, Session, TokenClaims, VerificationKey,
+};
+use crate::domain::ports::{
+    AccountStore, Clock, ExternalIdentityStore, GuestCredentialStore, PasswordHasher,
+    PendingExternalLoginStore, ProfileStore, RefreshTokenStore, RevocationStore, SessionStore,
+    TokenIssuer,
+};
+use crate::frameworks::db;
+use crate::interface_adapters::accounts::{
+    ConfiguredAccountStore, InMemoryAccountStore, PostgresAccountStore,
+};
+use crate::interface_adapters::external_identities::{
+    ConfiguredExternalIdentityStore, ConfiguredPendingExternalLoginStore,
+    InMemoryExternalIdentityStore, InMemoryPendingExternalLoginStore,
+    PostgresExternalIdentityStore, PostgresPendingExternalLoginStore,
+};
+use crate::interface_adapters::guest_credentials::{
+    ConfiguredGuestCredentialStore, InMemoryGuestCredentialStore, PostgresGuestCredentialStore,
+};
+use crate::interface_adapters::metrics::SessionMetrics;
+use crate::interface_adapters::profiles::{
+    ConfiguredProfileStore, InMemoryProfileStore, PostgresProfileStore,
+};
+use crate::interface_adapters::refresh_tokens::{
+    ConfiguredRefreshTokenStore, InMemoryRefreshTokenStore, PostgresRefreshTokenStore,
+};
+use crate::interface_adapters::state::{
+    ConfiguredSessionStore, InMemorySessionStore, PostgresSessionStore, SessionMap,
+};
+use crate::use_cases::guest_login::hash_guest_secret;
 
 // Shared fixed time source for deterministic use-case tests.
 pub(crate) struct FixedClock(pub(crate) u64);
@@ -24,16 +55,30 @@ pub(crate) struct FailureFlags {
     pub remove: bool,
 }
 
+// Session store double with injectable failures. Sessions live in memory, or in
+// Postgres when `AUTH_TEST_DATABASE_URL` is set, so the same use-case tests cover
+// both adapters.
 #[derive(Clone)]
 pub(crate) struct RecordingStore {
-    sessions: SessionTable,
+    inner: ConfiguredSessionStore,
     failures: FailureFlags,
 }
 
 impl RecordingStore {
-    pub(crate) fn new() -> Self {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredSessionStore::Postgres(PostgresSessionStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredSessionStore::Memory(InMemorySessionStore {
+                sessions: Arc::new(tokio::sync::Mutex::new(SessionMap::default())),
+                metrics: Arc::new(SessionMetrics::default()),
+            }),
+        };
         Self {
-            sessions: Arc::new(Mutex::new(HashMap::new())),
+            inner,
             failures: FailureFlags::default(),
         }
     }
@@ -43,12 +88,14 @@ impl RecordingStore {
         self
     }
 
-    pub(crate) fn insert_test_session(&self, token: impl Into<String>, session: Session) {
-        let mut guard = self.sessions.lock().expect("sessions mutex poisoned");
-        guard.insert(token.into(), session);
+    pub(crate) async fn insert_test_session(&self, token: impl Into<String>, session: Session) {
+        self.inner
+            .insert(token.into(), session)
+            .await
+            .expect("expected test session to be stored");
     }
 
-    pub(crate) fn insert_test_token(&self, token: impl Into<String>) {
+    pub(crate) async fn insert_test_token(&self, token: impl Into<String>) {
         let session = Session {
             guest_id: 0,
             display_name: "Test".to_string(),
@@ -56,25 +103,138 @@ impl RecordingStore {
             session_id: "test-session".to_string(),
             expires_at: 0,
         };
-        self.insert_test_session(token, session);
+        self.insert_test_session(token, session).await;
     }
 
-    pub(crate) fn get_test_session(&self, token: &str) -> Option<Session> {
-        let guard = self.sessions.lock().expect("sessions mutex poisoned");
-        guard.get(token).cloned()
+    pub(crate) async fn get_test_session(&self, token: &str) -> Option<Session> {
+        self.inner
+            .get(token)
+            .await
+            .expect("expected test session lookup to succeed")
     }
 }
 
+// Postgres database the use-case tests run against instead of memory when set.
+pub(crate) const TEST_DATABASE_URL_ENV_VAR: &str = "AUTH_TEST_DATABASE_URL";
+
+// Advisory lock class for test runs; the object id is the run's id.
+const TEST_RUN_LOCK_CLASS: i32 = 0x6175_7468;
+
+// This process's run id, held as an advisory lock for as long as the process lives.
+static TEST_RUN: OnceCell<u32> = OnceCell::const_new();
+
+// Pool on a fresh, migrated schema, so sweeps never touch another test's sessions.
+pub(crate) async fn test_database(url: &str) -> PgPool {
+    let run = *TEST_RUN.get_or_init(|| start_test_run(url)).await;
+    let schema = format!("auth_test_{run}_{}", uuid::Uuid::new_v4().simple());
+    let options = url
+        .parse::<PgConnectOptions>()
+        .expect("expected a valid test database url")
+        .options([("search_path", schema.as_str())]);
+    let db = PgPoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await
+        .expect("expected test database to be reachable");
+
+    sqlx::query(&format!("CREATE SCHEMA {schema}"))
+        .execute(&db)
+        .await
+        .expect("expected test schema to be created");
+    db::run_migrations(&db)
+        .await
+        .expect("expected test database migrations to run");
+    db
+}
+
+// Claims a run id and clears schemas left behind by runs that have ended.
+//
+// Each run holds its lock on a connection that closes only when the process exits, so a
+// lock another run can take means that run is over. Runs still going, in this process
+// or any other sharing the database, keep their schemas.
+async fn start_test_run(url: &str) -> u32 {
+    let run = uuid::Uuid::new_v4().as_u128() as u32;
+    let options = url
+        .parse::<PgConnectOptions>()
+        .expect("expected a valid test database url");
+
+    // Test runtimes end with their test, so the lock lives on a thread of its own.
+    let (locked_tx, locked_rx) = tokio::sync::oneshot::channel();
+    let lock_options = options.clone();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("expected a runtime for the test run lock");
+        runtime.block_on(async move {
+            let mut conn = PgConnection::connect_with(&lock_options)
+                .await
+                .expect("expected test database to be reachable");
+            sqlx::query("SELECT pg_advisory_lock($1, $2)")
+                .bind(TEST_RUN_LOCK_CLASS)
+                .bind(run as i32)
+                .execute(&mut conn)
+                .await
+                .expect("expected the test run lock");
+            let _ = locked_tx.send(());
+            std::future::pending::<()>().await;
+        });
+    });
+    locked_rx
+        .await
+        .expect("expected the test run lock to be taken");
+
+    let mut conn = PgConnection::connect_with(&options)
+        .await
+        .expect("expected test database to be reachable");
+    let schemas: Vec<String> = sqlx::query_scalar(
+        "SELECT nspname::TEXT FROM pg_namespace WHERE nspname LIKE 'auth_test_%'",
+    )
+    .fetch_all(&mut conn)
+    .await
+    .expect("expected test schemas to be listed");
+    let mut ended: HashMap<u32, bool> = HashMap::new();
+    for schema in schemas {
+        let Some(owner) = schema
+            .strip_prefix("auth_test_")
+            .and_then(|rest| rest.split('_').next())
+            .and_then(|owner| owner.parse::<u32>().ok())
+            .filter(|owner| *owner != run)
+        else {
+            continue;
+        };
+        let is_over = match ended.get(&owner) {
+            Some(is_over) => *is_over,
+            None => {
+                let is_over: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1, $2)")
+                    .bind(TEST_RUN_LOCK_CLASS)
+                    .bind(owner as i32)
+                    .fetch_one(&mut conn)
+                    .await
+                    .expect("expected the test run lock to be checked");
+                ended.insert(owner, is_over);
+                is_over
+            }
+        };
+        if is_over {
+            let _ = sqlx::query(&format!("DROP SCHEMA IF EXISTS {schema} CASCADE"))
+                .execute(&mut conn)
+                .await;
+        }
+    }
+    // The locks taken above die with this connection.
+    let _ = conn.close().await;
+    run
+}
+
 #[async_trait]
 impl SessionStore for RecordingStore {
-    async fn insert(&self, token: String, session: Session) -> Result<(), String> {
+    async fn insert(&self, token: String, session: Session) -> Result<Vec<Session>, String> {
         if self.failures.insert {
             return Err("insert failed".to_string());
         }
 
-        let mut guard = self.sessions.lock().expect("sessions mutex poisoned");
-        guard.insert(token, session);
-        Ok(())
+        self.inner.insert(token, session).await
     }
 
     async fn get(&self, token: &str) -> Result<Option<Session>, String> {
@@ -82,8 +242,7 @@ impl SessionStore for RecordingStore {
             return Err("get failed".to_string());
         }
 
-        let guard = self.sessions.lock().expect("sessions mutex poisoned");
-        Ok(guard.get(token).cloned())
+        self.inner.get(token).await
     }
 
     async fn remove(&self, token: &str) -> Result<bool, String> {
@@ -91,7 +250,405 @@ impl SessionStore for RecordingStore {
             return Err("remove failed".to_string());
         }
 
-        let mut guard = self.sessions.lock().expect("sessions mutex poisoned");
-        Ok(guard.remove(token).is_some())
+        self.inner.remove(token).await
+    }
+
+    async fn remove_by_session_id(&self, session_id: &str) -> Result<bool, String> {
+        if self.failures.remove {
+            return Err("remove failed".to_string());
+        }
+
+        self.inner.remove_by_session_id(session_id).await
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        if self.failures.remove {
+            return Err("remove failed".to_string());
+        }
+
+        self.inner.remove_expired(now).await
+    }
+
+    async fn list_for_user(&self, user_id: u64) -> Result<Vec<Session>, String> {
+        if self.failures.get {
+            return Err("get failed".to_string());
+        }
+
+        self.inner.list_for_user(user_id).await
+    }
+}
+
+// Refresh token store backed like `RecordingStore`: memory, or Postgres when configured.
+#[derive(Clone)]
+pub(crate) struct RecordingRefreshTokens {
+    inner: ConfiguredRefreshTokenStore,
+}
+
+impl RecordingRefreshTokens {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredRefreshTokenStore::Postgres(PostgresRefreshTokenStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredRefreshTokenStore::Memory(InMemoryRefreshTokenStore {
+                tokens: Arc::default(),
+            }),
+        };
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl RefreshTokenStore for RecordingRefreshTokens {
+    async fn insert(&self, token: String, grant: RefreshGrant) -> Result<(), String> {
+        self.inner.insert(token, grant).await
+    }
+
+    async fn redeem(&self, token: &str) -> Result<RefreshRedemption, String> {
+        self.inner.redeem(token).await
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<Vec<RefreshGrant>, String> {
+        self.inner.revoke_family(family_id).await
+    }
+
+    async fn revoke_family_of_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<RefreshGrant>, String> {
+        self.inner.revoke_family_of_session(session_id).await
+    }
+
+    async fn revoke_user(&self, user_id: u64) -> Result<Vec<RefreshGrant>, String> {
+        self.inner.revoke_user(user_id).await
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        self.inner.remove_expired(now).await
+    }
+}
+
+// Secret the shared test guest logs in with.
+pub(crate) const TEST_GUEST_SECRET: &str = "test-guest-secret";
+
+// Guest credential store backed like `RecordingStore`: memory, or Postgres when configured.
+#[derive(Clone)]
+pub(crate) struct RecordingGuestCredentials {
+    inner: ConfiguredGuestCredentialStore,
+}
+
+impl RecordingGuestCredentials {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredGuestCredentialStore::Postgres(PostgresGuestCredentialStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredGuestCredentialStore::Memory(InMemoryGuestCredentialStore {
+                guests: Arc::default(),
+            }),
+        };
+        Self { inner }
+    }
+
+    // Store holding one guest that logs in with `secret`.
+    pub(crate) async fn with_guest(guest_id: u64, secret: &str) -> Self {
+        let credentials = Self::new().await;
+        credentials
+            .create(guest_id, "Test", hash_guest_secret(secret))
+            .await
+            .expect("expected test guest to be created");
+        credentials
+    }
+}
+
+#[async_trait]
+impl GuestCredentialStore for RecordingGuestCredentials {
+    async fn create(
+        &self,
+        guest_id: u64,
+        display_name: &str,
+        secret_hash: Vec<u8>,
+    ) -> Result<(), String> {
+        self.inner.create(guest_id, display_name, secret_hash).await
+    }
+
+    async fn get(&self, guest_id: u64) -> Result<Option<GuestCredential>, String> {
+        self.inner.get(guest_id).await
+    }
+
+    async fn record_failed_attempt(&self, guest_id: u64) -> Result<u64, String> {
+        self.inner.record_failed_attempt(guest_id).await
+    }
+
+    async fn reset_failed_attempts(&self, guest_id: u64) -> Result<(), String> {
+        self.inner.reset_failed_attempts(guest_id).await
+    }
+}
+
+// Account store backed like `RecordingStore`: memory, or Postgres when configured.
+#[derive(Clone)]
+pub(crate) struct RecordingAccounts {
+    inner: ConfiguredAccountStore,
+}
+
+impl RecordingAccounts {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredAccountStore::Postgres(PostgresAccountStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredAccountStore::Memory(InMemoryAccountStore {
+                accounts: Arc::default(),
+            }),
+        };
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl AccountStore for RecordingAccounts {
+    async fn create(&self, account: Account) -> Result<AccountCreation, String> {
+        self.inner.create(account).await
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, String> {
+        self.inner.find_by_username(username).await
+    }
+}
+
+// Profile store backed like `RecordingStore`: memory, or Postgres when configured.
+#[derive(Clone)]
+pub(crate) struct RecordingProfiles {
+    inner: ConfiguredProfileStore,
+}
+
+impl RecordingProfiles {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredProfileStore::Postgres(PostgresProfileStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredProfileStore::Memory(InMemoryProfileStore {
+                profiles: Arc::default(),
+            }),
+        };
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl ProfileStore for RecordingProfiles {
+    async fn get(&self, user_id: u64) -> Result<Option<Profile>, String> {
+        self.inner.get(user_id).await
+    }
+
+    async fn record_login(
+        &self,
+        user_id: u64,
+        display_name: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<Profile, String> {
+        self.inner
+            .record_login(user_id, display_name, metadata)
+            .await
+    }
+
+    async fn update(&self, profile: &Profile) -> Result<bool, String> {
+        self.inner.update(profile).await
+    }
+}
+
+// External identity store backed like `RecordingStore`: memory, or Postgres when configured.
+#[derive(Clone)]
+pub(crate) struct RecordingExternalIdentities {
+    inner: ConfiguredExternalIdentityStore,
+}
+
+impl RecordingExternalIdentities {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredExternalIdentityStore::Postgres(PostgresExternalIdentityStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredExternalIdentityStore::Memory(InMemoryExternalIdentityStore {
+                identities: Arc::default(),
+            }),
+        };
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl ExternalIdentityStore for RecordingExternalIdentities {
+    async fn link(&self, provider: &str, subject: &str, new_user_id: u64) -> Result<u64, String> {
+        self.inner.link(provider, subject, new_user_id).await
+    }
+}
+
+// Pending external login store backed like `RecordingStore`: memory, or Postgres when configured.
+#[derive(Clone)]
+pub(crate) struct RecordingPendingLogins {
+    inner: ConfiguredPendingExternalLoginStore,
+}
+
+impl RecordingPendingLogins {
+    pub(crate) async fn new() -> Self {
+        let inner = match std::env::var(TEST_DATABASE_URL_ENV_VAR) {
+            Ok(url) if !url.trim().is_empty() => {
+                ConfiguredPendingExternalLoginStore::Postgres(PostgresPendingExternalLoginStore {
+                    db: test_database(&url).await,
+                })
+            }
+            _ => ConfiguredPendingExternalLoginStore::Memory(InMemoryPendingExternalLoginStore {
+                logins: Arc::default(),
+            }),
+        };
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl PendingExternalLoginStore for RecordingPendingLogins {
+    async fn insert(&self, state: String, login: PendingExternalLogin) -> Result<(), String> {
+        self.inner.insert(state, login).await
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingExternalLogin>, String> {
+        self.inner.take(state).await
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        self.inner.remove_expired(now).await
+    }
+}
+
+// Instant stand-in for Argon2; the real hasher is covered in `passwords.rs`.
+#[derive(Clone, Copy)]
+pub(crate) struct TestPasswords;
+
+#[async_trait]
+impl PasswordHasher for TestPasswords {
+    async fn hash(&self, password: &str) -> Result<String, String> {
+        Ok(format!("test-hash:{password}"))
+    }
+
+    async fn verify(&self, password: &str, password_hash: &str) -> Result<bool, String> {
+        Ok(password_hash == format!("test-hash:{password}"))
+    }
+
+    fn dummy_hash(&self) -> &str {
+        "test-dummy-hash"
+    }
+}
+
+// Revocation log that keeps every record, until swept, with its 1-based cursor.
+#[derive(Clone, Default)]
+pub(crate) struct RecordingRevocations {
+    entries: Arc<Mutex<Vec<Revocation>>>,
+}
+
+impl RecordingRevocations {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn recorded(&self) -> Vec<Revocation> {
+        self.entries
+            .lock()
+            .expect("revocations mutex poisoned")
+            .clone()
+    }
+}
+
+#[async_trait]
+impl RevocationStore for RecordingRevocations {
+    async fn record(&self, revocation: Revocation) -> Result<(), String> {
+        let mut guard = self.entries.lock().expect("revocations mutex poisoned");
+        guard.push(revocation);
+        Ok(())
+    }
+
+    async fn list_since(&self, cursor: u64, now: u64) -> Result<RevocationPage, String> {
+        let guard = self.entries.lock().expect("revocations mutex poisoned");
+        let revocations = guard
+            .iter()
+            .skip(cursor as usize)
+            .filter(|revocation| revocation.expires_at > now)
+            .cloned()
+            .collect();
+        Ok(RevocationPage {
+            revocations,
+            cursor: guard.len() as u64,
+        })
+    }
+
+    async fn is_revoked(&self, session_id: &str) -> Result<bool, String> {
+        let guard = self.entries.lock().expect("revocations mutex poisoned");
+        Ok(guard
+            .iter()
+            .any(|revocation| revocation.session_id == session_id))
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<u64, String> {
+        let mut guard = self.entries.lock().expect("revocations mutex poisoned");
+        let before = guard.len();
+        guard.retain(|revocation| revocation.expires_at > now);
+        Ok((before - guard.len()) as u64)
+    }
+}
+
+// Token issuer double: opaque tokens are random, signed ones carry their claims in the clear.
+#[derive(Clone, Copy)]
+pub(crate) struct TestTokens {
+    signed: bool,
+}
+
+const TEST_SIGNED_PREFIX: &str = "signed.";
+
+impl TestTokens {
+    pub(crate) fn opaque() -> Self {
+        Self { signed: false }
+    }
+
+    pub(crate) fn signed() -> Self {
+        Self { signed: true }
+    }
+}
+
+impl TokenIssuer for TestTokens {
+    fn issue(&self, claims: &TokenClaims) -> Result<String, String> {
+        if !self.signed {
+            return Ok(uuid::Uuid::new_v4().to_string());
+        }
+        let claims = serde_json::to_string(claims).map_err(|err| err.to_string())?;
+        Ok(format!("{TEST_SIGNED_PREFIX}{claims}"))
+    }
+
+    fn decode(&self, token: &str) -> Option<TokenClaims> {
+        if !self.signed {
+            return None;
+        }
+        let claims = token.strip_prefix(TEST_SIGNED_PREFIX)?;
+        serde_json::from_str(claims).ok()
+    }
+
+    fn verification_keys(&self) -> Vec<VerificationKey> {
+        if !self.signed {
+            return Vec::new();
+        }
+        vec![VerificationKey {
+            kid: "test-key".to_string(),
+            public_key: [7; 32],
+        }]
     }
 }
@@ -1,6 +1,6 @@
-use crate::domain::entities::Session;
+use crate::domain::entities::{Session, TokenClaims};
 use crate::domain::errors::AuthError;
-use crate::domain::ports::{Clock, SessionStore};
+use crate::domain::ports::{Clock, ProfileStore, RevocationStore, SessionStore, TokenIssuer};
 
 // Response returned by the token verification use case.
 pub struct VerifyTokenResponse {
@@ -11,33 +11,83 @@ pub struct VerifyTokenResponse {
     pub expires_at: u64,
 }
 
-// Token verification use case with injected dependencies.
-pub struct VerifyTokenUseCase<C, S> {
+// Token verification use case with injected dependencies. The identity comes
+// from the user's profile, so renames apply to sessions issued before them.
+pub struct VerifyTokenUseCase<C, S, T, R, P> {
     pub clock: C,
     pub store: S,
+    pub tokens: T,
+    pub revocations: R,
+    pub profiles: P,
 }
 
-impl<C, S> VerifyTokenUseCase<C, S>
+impl<C, S, T, R, P> VerifyTokenUseCase<C, S, T, R, P>
 where
     C: Clock,
     S: SessionStore,
+    T: TokenIssuer,
+    R: RevocationStore,
+    P: ProfileStore,
 {
     pub async fn execute(&self, token: String) -> Result<VerifyTokenResponse, AuthError> {
-        let session = self
+        let verified = self.verify_session(&token).await?;
+        self.with_profile(verified).await
+    }
+
+    async fn verify_session(&self, token: &str) -> Result<VerifyTokenResponse, AuthError> {
+        let Some(session) = self
             .store
-            .get(&token)
+            .get(token)
             .await
             .map_err(|_| AuthError::StorageFailure)?
-            .ok_or(AuthError::InvalidToken)?;
+        else {
+            return self.verify_claims(token).await;
+        };
 
         if session.expires_at <= self.clock.now_epoch_seconds() {
             // Best-effort cleanup of expired session.
-            let _ = self.store.remove(&token).await;
+            let _ = self.store.remove(token).await;
             return Err(AuthError::SessionExpired);
         }
 
         Ok(map_session(session))
     }
+
+    // Users without a profile yet keep the identity their session was issued with.
+    async fn with_profile(
+        &self,
+        mut verified: VerifyTokenResponse,
+    ) -> Result<VerifyTokenResponse, AuthError> {
+        let profile = self
+            .profiles
+            .get(verified.user_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        if let Some(profile) = profile {
+            verified.display_name = profile.display_name;
+            verified.metadata = Some(profile.metadata);
+        }
+        Ok(verified)
+    }
+
+    // Signed tokens outlive the session store (e.g. across restarts), so their
+    // claims stand in for the session unless the session was revoked.
+    async fn verify_claims(&self, token: &str) -> Result<VerifyTokenResponse, AuthError> {
+        let claims = self.tokens.decode(token).ok_or(AuthError::InvalidToken)?;
+        if claims.exp <= self.clock.now_epoch_seconds() {
+            return Err(AuthError::SessionExpired);
+        }
+        let revoked = self
+            .revocations
+            .is_revoked(&claims.session_id)
+            .await
+            .map_err(|_| AuthError::StorageFailure)?;
+        if revoked {
+            return Err(AuthError::InvalidToken);
+        }
+
+        Ok(map_claims(claims))
+    }
 }
 
 fn map_session(session: Session) -> VerifyTokenResponse {
@@ -51,10 +101,25 @@ fn map_session(session: Session) -> VerifyTokenResponse {
     }
 }
 
+fn map_claims(claims: TokenClaims) -> VerifyTokenResponse {
+    VerifyTokenResponse {
+        user_id: claims.user_id,
+        display_name: claims.display_name,
+        // Metadata lives only in the session store and the profile.
+        metadata: None,
+        session_id: claims.session_id,
+        expires_at: claims.exp,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::use_cases::test_support::{FailureFlags, FixedClock, RecordingStore};
+    use crate::domain::entities::Revocation;
+    use crate::use_cases::test_support::{
+        FailureFlags, FixedClock, RecordingProfiles, RecordingRevocations, RecordingStore,
+        TestTokens,
+    };
     use serde_json::json;
 
     #[tokio::test]
@@ -67,12 +132,15 @@ mod tests {
             session_id: "session-1".to_string(),
             expires_at: 1_700_000_100,
         };
-        let store = RecordingStore::new();
-        store.insert_test_session(token.clone(), session);
+        let store = RecordingStore::new().await;
+        store.insert_test_session(token.clone(), session).await;
 
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
             store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case
@@ -90,7 +158,10 @@ mod tests {
     async fn when_token_does_not_exist_then_returns_invalid_token() {
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute("missing".to_string()).await;
@@ -108,11 +179,14 @@ mod tests {
             session_id: "session-1".to_string(),
             expires_at: 1_700_000_000,
         };
-        let store = RecordingStore::new();
-        store.insert_test_session(token.clone(), session);
+        let store = RecordingStore::new().await;
+        store.insert_test_session(token.clone(), session).await;
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
             store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute(token.clone()).await;
@@ -124,10 +198,13 @@ mod tests {
     async fn when_store_get_fails_then_returns_storage_failure() {
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new().with_failures(FailureFlags {
+            store: RecordingStore::new().await.with_failures(FailureFlags {
                 get: true,
                 ..Default::default()
             }),
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute("any-token".to_string()).await;
@@ -146,12 +223,15 @@ mod tests {
             expires_at: 1_700_000_100,
         };
 
-        let store = RecordingStore::new();
-        store.insert_test_session(stored_token, session);
+        let store = RecordingStore::new().await;
+        store.insert_test_session(stored_token, session).await;
 
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
             store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute("  session-token  ".to_string()).await;
@@ -163,7 +243,10 @@ mod tests {
     async fn when_token_is_empty_then_returns_invalid_token() {
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute(String::new()).await;
@@ -181,12 +264,15 @@ mod tests {
             session_id: "session-1".to_string(),
             expires_at: 1_700_000_000,
         };
-        let store = RecordingStore::new();
-        store.insert_test_session(token.clone(), session);
+        let store = RecordingStore::new().await;
+        store.insert_test_session(token.clone(), session).await;
 
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
             store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute(token).await;
@@ -208,12 +294,15 @@ mod tests {
             session_id: "session-1".to_string(),
             expires_at: 1_700_000_100,
         };
-        let store = RecordingStore::new();
-        store.insert_test_session(token.clone(), session);
+        let store = RecordingStore::new().await;
+        store.insert_test_session(token.clone(), session).await;
 
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
             store,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case
@@ -224,15 +313,133 @@ mod tests {
         assert_eq!(result.metadata, Some(metadata));
     }
 
+    #[tokio::test]
+    async fn when_user_has_a_profile_then_its_name_and_metadata_replace_the_session_snapshot() {
+        let profiles = RecordingProfiles::new().await;
+        profiles
+            .record_login(9, "Maverick", Some(json!({ "skin": "red" })))
+            .await
+            .expect("expected profile to be stored");
+        let store = RecordingStore::new().await;
+        store
+            .insert_test_session(
+                "session-token",
+                Session {
+                    guest_id: 9,
+                    display_name: "Pilot".to_string(),
+                    metadata: Some(json!({ "skin": "blue" })),
+                    session_id: "session-1".to_string(),
+                    expires_at: 1_700_000_100,
+                },
+            )
+            .await;
+        let use_case = VerifyTokenUseCase {
+            clock: FixedClock(1_700_000_000),
+            store,
+            tokens: TestTokens::signed(),
+            revocations: RecordingRevocations::new(),
+            profiles,
+        };
+
+        let from_session = use_case
+            .execute("session-token".to_string())
+            .await
+            .expect("expected session to verify");
+        let from_claims = use_case
+            .execute(signed_token(1_700_000_100))
+            .await
+            .expect("expected signed token to verify");
+
+        for result in [from_session, from_claims] {
+            assert_eq!(result.display_name, "Maverick");
+            assert_eq!(result.metadata, Some(json!({ "skin": "red" })));
+        }
+    }
+
     #[tokio::test]
     async fn when_token_is_random_garbage_then_returns_invalid_token() {
         let use_case = VerifyTokenUseCase {
             clock: FixedClock(1_700_000_000),
-            store: RecordingStore::new(),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::opaque(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
         };
 
         let result = use_case.execute("%%%not-a-token%%%".to_string()).await;
 
         assert!(matches!(result, Err(AuthError::InvalidToken)));
     }
+
+    fn signed_token(exp: u64) -> String {
+        TestTokens::signed()
+            .issue(&TokenClaims {
+                user_id: 9,
+                display_name: "Pilot".to_string(),
+                session_id: "session-1".to_string(),
+                exp,
+            })
+            .expect("expected test token to be issued")
+    }
+
+    #[tokio::test]
+    async fn when_signed_token_is_not_in_store_then_its_claims_are_trusted() {
+        let use_case = VerifyTokenUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::signed(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
+        };
+
+        let result = use_case
+            .execute(signed_token(1_700_000_100))
+            .await
+            .expect("expected signed token to verify");
+
+        assert_eq!(result.user_id, 9);
+        assert_eq!(result.session_id, "session-1");
+        assert_eq!(result.expires_at, 1_700_000_100);
+        assert_eq!(result.metadata, None);
+    }
+
+    #[tokio::test]
+    async fn when_signed_token_claims_are_expired_then_returns_session_expired() {
+        let use_case = VerifyTokenUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::signed(),
+            revocations: RecordingRevocations::new(),
+            profiles: RecordingProfiles::new().await,
+        };
+
+        let result = use_case.execute(signed_token(1_700_000_000)).await;
+
+        assert!(matches!(result, Err(AuthError::SessionExpired)));
+    }
+
+    #[tokio::test]
+    async fn when_signed_token_session_was_revoked_then_returns_invalid_token() {
+        let revocations = RecordingRevocations::new();
+        revocations
+            .record(Revocation {
+                session_id: "session-1".to_string(),
+                user_id: 9,
+                revoked_at: 1_699_999_000,
+                expires_at: 1_700_000_100,
+            })
+            .await
+            .expect("expected revocation to be recorded");
+        let use_case = VerifyTokenUseCase {
+            clock: FixedClock(1_700_000_000),
+            store: RecordingStore::new().await,
+            tokens: TestTokens::signed(),
+            revocations,
+            profiles: RecordingProfiles::new().await,
+        };
+
+        let result = use_case.execute(signed_token(1_700_000_100)).await;
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
 }
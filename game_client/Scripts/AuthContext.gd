@@ -7,9 +7,14 @@ const MAX_U64 = "18446744073709551615"
 
 var local_player_id: String
 var auth_token: String
+# Single-use token from head that renews auth_token before session_expires_at.
+var refresh_token: String = ""
+var session_expires_at: int = 0
 var local_username: String
 # Keep guest_id as string at the JSON boundary to avoid large integer precision loss.
 var guest_id: String = ""
+# Issued once by guest init and required for every guest login; it cannot be recovered.
+var guest_secret: String = ""
 
 @onready var username_input: LineEdit = $"../NetworkUI/VBoxContainer/UsernameInput"
 
@@ -43,6 +48,8 @@ func apply_profile_data(data: Dictionary) -> void:
 	else:
 		guest_id = ""
 
+	guest_secret = str(data.get("guest_secret", "")).strip_edges()
+
 func finish_profile_setup() -> void:
 	username_input.text = local_username
 	_save_profile()
@@ -54,9 +61,30 @@ func normalize_display_name_for_auth() -> void:
 		username_input.text = local_username
 		_save_profile()
 
+# Keep the refresh token and expiry from a login or refresh response; blank when absent.
+func store_session_renewal(json: Dictionary) -> void:
+	refresh_token = str(json.get("refresh_token", "")).strip_edges()
+	session_expires_at = int(json.get("expires_at", 0))
+
+func clear_session() -> void:
+	auth_token = ""
+	refresh_token = ""
+	session_expires_at = 0
+	local_player_id = ""
+
 func has_valid_guest_id() -> bool:
 	return is_guest_id_valid(guest_id)
 
+# Profiles saved before guest secrets existed only have a guest_id and need a new identity.
+func has_guest_credentials() -> bool:
+	return has_valid_guest_id() and not guest_secret.is_empty()
+
+# Drop a guest identity auth no longer accepts so the next login creates a new one.
+func forget_guest_identity() -> void:
+	guest_id = ""
+	guest_secret = ""
+	_save_profile()
+
 func _save_profile() -> void:
 	var file = FileAccess.open(PROFILE_PATH, FileAccess.WRITE)
 	if not file:
@@ -68,6 +96,7 @@ func _save_profile() -> void:
 	# Persist a minimal guest profile for future sessions.
 	var data = {
 		"guest_id": guest_id,
+		"guest_secret": guest_secret,
 		"display_name": local_username
 	}
 	file.store_string(JSON.stringify(data))
@@ -136,6 +136,9 @@ func _handle_server_message(json_str: String) -> void:
 			if msg.data is Dictionary and msg.data.has("player_id"):
 				auth_context.local_player_id = str(msg.data.player_id)
 				print("Assigned Player ID: ", auth_context.local_player_id)
+			# Players ready up as soon as they are placed; the server runs the countdown.
+			if msg.data is Dictionary and msg.data.get("mode", "Player") == "Player":
+				_send_ready()
 		"WorldUpdate":
 			# { "type": "WorldUpdate", "data": { "tick": 1, "entities": [...] } }
 			if msg.data is Dictionary and msg.data.has("entities"):
@@ -144,6 +147,16 @@ func _handle_server_message(json_str: String) -> void:
 		"GameState":
 			# { "type": "GameState", "data": { ... } } or "MatchRunning"
 			print("Game State Update: ", msg.data)
+		"JoinRejected":
+			# { "type": "JoinRejected", "data": { "reason": "NotInRoster" } }
+			# The server closes the socket right after this message.
+			if msg.data is Dictionary and msg.data.has("reason"):
+				push_warning("Join rejected: %s" % msg.data.reason)
+		"ServerShutdown":
+			# { "type": "ServerShutdown", "data": { "reason": "Drained", "reconnect_hint": "Requeue" } }
+			# The server closes the socket right after this message.
+			if msg.data is Dictionary:
+				push_warning("Server shutting down: %s (%s)" % [msg.data.get("reason", "?"), msg.data.get("reconnect_hint", "?")])
 
 func _on_socket_opened() -> void:
 	print("Connected to server")
@@ -190,12 +203,20 @@ func _send_join() -> void:
 	var message = {
 		"type": "Join",
 		"data": {
-			"session_token": auth_context.auth_token
+			"session_token": auth_context.auth_token,
+			"mode": "Player"
 		}
 	}
 	var json_str = JSON.stringify(message)
 	game_socket.send_text(json_str)
 
+func _send_ready() -> void:
+	if game_socket.get_ready_state() != WebSocketPeer.STATE_OPEN:
+		return
+
+	# Unit variant: the server expects no data payload.
+	game_socket.send_text(JSON.stringify({ "type": "Ready" }))
+
 func _schedule_reconnect(reason: String) -> void:
 	# Only auto-reconnect in test mode to avoid surprising players.
 	if not game_manager.TEST_MODE:
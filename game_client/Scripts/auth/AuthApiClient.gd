@@ -13,16 +13,26 @@ func request_guest_init(display_name: String, callback: Callable) -> void:
 		callback
 	)
 
-func request_guest_login(guest_id: String, display_name: String, callback: Callable) -> void:
+func request_guest_login(guest_id: String, guest_secret: String, display_name: String, callback: Callable) -> void:
 	_request_json(
 		NetworkManager.HEAD_BASE_URL + "/guest/login",
 		{
 			"guest_id": guest_id,
+			"guest_secret": guest_secret,
 			"display_name": display_name
 		},
 		callback
 	)
 
+func request_guest_refresh(refresh_token: String, callback: Callable) -> void:
+	_request_json(
+		NetworkManager.HEAD_BASE_URL + "/guest/refresh",
+		{
+			"refresh_token": refresh_token
+		},
+		callback
+	)
+
 func _request_json(url: String, payload: Dictionary, callback: Callable) -> void:
 	var http := HTTPRequest.new()
 	add_child(http)
@@ -59,6 +69,7 @@ func _build_response(result: int, response_code: int, body: PackedByteArray) ->
 		return {
 			"ok": false,
 			"code": "http_error",
+			"status": response_code,
 			"detail": "HTTP error %s" % response_code
 		}
 
@@ -1,5 +1,11 @@
 extends AuthStateBase
 
+# Refresh this long before the session expires so a slow request still lands in time.
+const REFRESH_LEAD_SECONDS := 120
+const MIN_REFRESH_DELAY_SECONDS := 1.0
+
+var refresh_timer: Timer
+
 func enter(_ctx: Dictionary = {}) -> void:
 	super.enter(_ctx)
 	set_substate_name(&"ACTIVE")
@@ -9,9 +15,60 @@ func enter(_ctx: Dictionary = {}) -> void:
 		push_error(detail)
 		state_machine.set_error("missing_auth_token", detail)
 		# Clear stale session state before re-entering the login flow.
-		auth_context.auth_token = ""
-		auth_context.local_player_id = ""
+		auth_context.clear_session()
 		state_machine.transition_to(&"LoginState", "missing auth token in authenticated state")
 		return
 
+	_schedule_refresh()
 	state_machine.notify_authenticated(auth_context.auth_token)
+
+func exit() -> void:
+	if refresh_timer != null:
+		refresh_timer.stop()
+
+func _schedule_refresh() -> void:
+	# Without a refresh token the session simply runs until expiry.
+	if auth_context.refresh_token.is_empty() or auth_context.session_expires_at <= 0:
+		return
+
+	if refresh_timer == null:
+		refresh_timer = Timer.new()
+		refresh_timer.one_shot = true
+		refresh_timer.timeout.connect(_begin_refresh)
+		add_child(refresh_timer)
+
+	var now := int(Time.get_unix_time_from_system())
+	var delay: float = max(
+		float(auth_context.session_expires_at - REFRESH_LEAD_SECONDS - now),
+		MIN_REFRESH_DELAY_SECONDS
+	)
+	refresh_timer.start(delay)
+
+func _begin_refresh() -> void:
+	set_substate_name(&"REFRESHING")
+	auth_api_client.request_guest_refresh(
+		auth_context.refresh_token,
+		Callable(self, "_on_refresh_response")
+	)
+
+func _on_refresh_response(response: Dictionary) -> void:
+	if get_substate_name() != &"REFRESHING":
+		return
+
+	var json: Dictionary = response.get("json", {})
+	var session_token := str(json.get("session_token", "")).strip_edges()
+	if not response.get("ok", false) or session_token.is_empty():
+		# A rejected or failed refresh falls back to a normal guest login.
+		push_warning("Session refresh failed: %s (%s)" % [
+			str(response.get("code", "refresh_failed")),
+			str(response.get("detail", ""))
+		])
+		auth_context.clear_session()
+		state_machine.transition_to(&"LoginState", "session refresh failed")
+		return
+
+	# The live game connection keeps running; the new token is used on the next Join.
+	auth_context.auth_token = session_token
+	auth_context.store_session_renewal(json)
+	set_substate_name(&"ACTIVE")
+	_schedule_refresh()
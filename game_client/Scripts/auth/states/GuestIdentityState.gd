@@ -20,7 +20,7 @@ func _load_profile() -> void:
 	var data := auth_context.load_profile_data()
 	auth_context.apply_profile_data(data)
 
-	if auth_context.has_valid_guest_id():
+	if auth_context.has_guest_credentials():
 		auth_context.finish_profile_setup()
 		state_machine.clear_retry(&"guest_init")
 		set_substate_name(&"READY")
@@ -57,7 +57,13 @@ func _on_guest_id_response(response: Dictionary) -> void:
 		_handle_failure("invalid_guest_id", "Guest init returned an invalid guest_id")
 		return
 
+	var guest_secret := str(json.get("guest_secret", "")).strip_edges()
+	if guest_secret.is_empty():
+		_handle_failure("missing_guest_secret", "Guest init response did not include guest_secret")
+		return
+
 	auth_context.guest_id = resolved_guest_id
+	auth_context.guest_secret = guest_secret
 	auth_context.finish_profile_setup()
 	state_machine.clear_retry(&"guest_init")
 	state_machine.clear_error()
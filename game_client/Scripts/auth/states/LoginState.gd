@@ -16,8 +16,8 @@ func handle_event(event: StringName, _payload: Dictionary = {}) -> void:
 		_begin_login()
 
 func _begin_login() -> void:
-	if not auth_context.has_valid_guest_id():
-		state_machine.transition_to(&"GuestIdentityState", "guest id missing before login")
+	if not auth_context.has_guest_credentials():
+		state_machine.transition_to(&"GuestIdentityState", "guest credentials missing before login")
 		return
 
 	set_substate_name(&"REQUEST_LOGIN")
@@ -26,6 +26,7 @@ func _begin_login() -> void:
 
 	auth_api_client.request_guest_login(
 		auth_context.guest_id,
+		auth_context.guest_secret,
 		auth_context.local_username,
 		Callable(self, "_on_login_response")
 	)
@@ -35,6 +36,12 @@ func _on_login_response(response: Dictionary) -> void:
 		return
 
 	if not response.get("ok", false):
+		# Auth rejected the stored guest secret; retrying cannot help, so start a new identity.
+		if int(response.get("status", 0)) == 401:
+			push_warning("Stored guest credentials were rejected. Creating a new guest identity.")
+			auth_context.forget_guest_identity()
+			state_machine.transition_to(&"GuestIdentityState", "guest credentials rejected")
+			return
 		_handle_failure(str(response.get("code", "login_failed")), str(response.get("detail", "")))
 		return
 
@@ -49,6 +56,7 @@ func _on_login_response(response: Dictionary) -> void:
 		return
 
 	auth_context.auth_token = session_token
+	auth_context.store_session_renewal(json)
 	state_machine.clear_retry(&"login")
 	state_machine.clear_error()
 	set_substate_name(&"SUCCESS")
@@ -0,0 +1,185 @@
+use game_server::frameworks::config::EnvSource;
+use std::time::Duration;
+
+// Runtime settings for a single load-generation run.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadgenConfig {
+    // WebSocket endpoint of the game server (without query string).
+    pub ws_url: String,
+    // Lobby every simulated client joins.
+    pub lobby_id: String,
+    // Number of concurrent WebSocket connections to open.
+    pub connections: usize,
+    // Input messages sent per second by each connection.
+    pub input_rate_hz: u32,
+    // Total run time measured from the first connection attempt.
+    pub duration: Duration,
+    // Delay between successive connection attempts to avoid a connect storm.
+    pub ramp_interval: Duration,
+    // Interval between progress log lines.
+    pub report_interval: Duration,
+    // Where session tokens come from.
+    pub token_source: TokenSource,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenSource {
+    // Mint real guest sessions through `POST /auth/guest/init`.
+    Auth { base_url: String },
+    // Serve a local stub `POST /auth/verify-token` that accepts loadgen tokens.
+    Stub { bind_addr: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadgenConfigError {
+    InvalidEnvVar { key: &'static str, value: String },
+}
+
+pub fn load_config(env: &impl EnvSource) -> Result<LoadgenConfig, LoadgenConfigError> {
+    let token_source = match optional_string(env, "LOADGEN_AUTH_URL") {
+        Some(base_url) => TokenSource::Auth {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        },
+        None => TokenSource::Stub {
+            bind_addr: optional_string(env, "LOADGEN_STUB_AUTH_BIND")
+                .unwrap_or_else(|| "127.0.0.1:3102".to_string()),
+        },
+    };
+
+    let connections = parse_optional_u64(env, "LOADGEN_CONNECTIONS")?.unwrap_or(10);
+    let input_rate_hz = parse_optional_u64(env, "LOADGEN_INPUT_HZ")?.unwrap_or(30);
+    if connections == 0 {
+        return Err(LoadgenConfigError::InvalidEnvVar {
+            key: "LOADGEN_CONNECTIONS",
+            value: connections.to_string(),
+        });
+    }
+    if input_rate_hz == 0 || input_rate_hz > 1000 {
+        return Err(LoadgenConfigError::InvalidEnvVar {
+            key: "LOADGEN_INPUT_HZ",
+            value: input_rate_hz.to_string(),
+        });
+    }
+
+    Ok(LoadgenConfig {
+        ws_url: optional_string(env, "LOADGEN_WS_URL")
+            .unwrap_or_else(|| "ws://127.0.0.1:3001/ws".to_string()),
+        lobby_id: optional_string(env, "LOADGEN_LOBBY_ID").unwrap_or_else(|| "test".to_string()),
+        connections: connections as usize,
+        input_rate_hz: input_rate_hz as u32,
+        duration: Duration::from_secs(
+            parse_optional_u64(env, "LOADGEN_DURATION_SECS")?.unwrap_or(30),
+        ),
+        ramp_interval: Duration::from_millis(
+            parse_optional_u64(env, "LOADGEN_RAMP_MS")?.unwrap_or(10),
+        ),
+        report_interval: Duration::from_secs(
+            parse_optional_u64(env, "LOADGEN_REPORT_SECS")?
+                .unwrap_or(5)
+                .max(1),
+        ),
+        token_source,
+    })
+}
+
+fn optional_string(env: &impl EnvSource, key: &'static str) -> Option<String> {
+    env.get_var(key)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_optional_u64(
+    env: &impl EnvSource,
+    key: &'static str,
+) -> Result<Option<u64>, LoadgenConfigError> {
+    match optional_string(env, key) {
+        Some(value) => value
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|_| LoadgenConfigError::InvalidEnvVar { key, value }),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct TestEnv {
+        vars: HashMap<String, String>,
+    }
+
+    impl TestEnv {
+        fn from_pairs(pairs: &[(&str, &str)]) -> Self {
+            Self {
+                vars: pairs
+                    .iter()
+                    .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                    .collect(),
+            }
+        }
+    }
+
+    impl EnvSource for TestEnv {
+        fn get_var(&self, key: &str) -> Option<String> {
+            self.vars.get(key).cloned()
+        }
+    }
+
+    #[test]
+    fn load_config_defaults_to_stub_auth_and_test_lobby() {
+        let config = load_config(&TestEnv::default()).expect("defaults should load");
+
+        assert_eq!(config.ws_url, "ws://127.0.0.1:3001/ws");
+        assert_eq!(config.lobby_id, "test");
+        assert_eq!(config.connections, 10);
+        assert_eq!(config.input_rate_hz, 30);
+        assert_eq!(
+            config.token_source,
+            TokenSource::Stub {
+                bind_addr: "127.0.0.1:3102".into()
+            }
+        );
+    }
+
+    #[test]
+    fn load_config_uses_auth_when_url_is_set() {
+        let config = load_config(&TestEnv::from_pairs(&[
+            ("LOADGEN_AUTH_URL", "http://auth.internal:3002/"),
+            ("LOADGEN_CONNECTIONS", "250"),
+        ]))
+        .expect("config should load");
+
+        assert_eq!(config.connections, 250);
+        assert_eq!(
+            config.token_source,
+            TokenSource::Auth {
+                base_url: "http://auth.internal:3002".into()
+            }
+        );
+    }
+
+    #[test]
+    fn load_config_rejects_invalid_numbers() {
+        let zero_rate = load_config(&TestEnv::from_pairs(&[("LOADGEN_INPUT_HZ", "0")]));
+        assert!(matches!(
+            zero_rate,
+            Err(LoadgenConfigError::InvalidEnvVar {
+                key: "LOADGEN_INPUT_HZ",
+                ..
+            })
+        ));
+
+        let bad_count = load_config(&TestEnv::from_pairs(&[("LOADGEN_CONNECTIONS", "many")]));
+        assert!(matches!(
+            bad_count,
+            Err(LoadgenConfigError::InvalidEnvVar {
+                key: "LOADGEN_CONNECTIONS",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,404 @@
+// Headless load generator for the game server WebSocket.
+//
+// Opens many concurrent `/ws` connections, performs the Join handshake, streams
+// randomized input and reports throughput and latency so channel capacities can
+// be sized against real traffic instead of a room full of Godot clients.
+
+mod config;
+mod stats;
+mod tokens;
+
+use config::{LoadgenConfig, LoadgenConfigError, TokenSource};
+use futures_util::{SinkExt, StreamExt};
+use game_server::frameworks::config::ProcessEnv;
+use game_server::interface_adapters::protocol::{
+    ClientMessage, JoinModeDto, JoinPayload, PlayerInputDto,
+};
+use stats::{ConnStats, LiveCounters, RunSummary};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+
+// Upper bound for connect + Join + Identity before a connection counts as failed.
+const JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, serde::Deserialize)]
+struct InboundEnvelope {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct WorldUpdateEnvelope {
+    data: WorldTick,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct WorldTick {
+    tick: u64,
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .with_target(false)
+        .compact()
+        .init();
+    let _ = dotenvy::dotenv();
+
+    let config = match config::load_config(&ProcessEnv) {
+        Ok(config) => Arc::new(config),
+        Err(LoadgenConfigError::InvalidEnvVar { key, value }) => {
+            tracing::error!(env_var = key, value = %value, "invalid loadgen configuration");
+            std::process::exit(2);
+        }
+    };
+
+    let session_tokens = match &config.token_source {
+        TokenSource::Auth { base_url } => {
+            match tokens::mint_guest_tokens(base_url, config.connections).await {
+                Ok(tokens) => tokens,
+                Err(error) => {
+                    tracing::error!(error = %error, "failed to mint guest tokens");
+                    std::process::exit(3);
+                }
+            }
+        }
+        TokenSource::Stub { bind_addr } => {
+            if let Err(error) = tokens::spawn_stub_auth(bind_addr).await {
+                tracing::error!(bind_addr = %bind_addr, error = %error, "failed to bind stub auth");
+                std::process::exit(4);
+            }
+            tokens::stub_tokens(config.connections)
+        }
+    };
+
+    tracing::info!(
+        ws_url = %config.ws_url,
+        lobby_id = %config.lobby_id,
+        connections = config.connections,
+        input_rate_hz = config.input_rate_hz,
+        duration_secs = config.duration.as_secs(),
+        "starting load run"
+    );
+
+    let counters = Arc::new(LiveCounters::default());
+    let started_at = Instant::now();
+    let deadline = started_at + config.duration;
+    let reporter = tokio::spawn(report_progress(
+        counters.clone(),
+        config.report_interval,
+        deadline,
+    ));
+
+    let mut handles = Vec::with_capacity(session_tokens.len());
+    for (index, token) in session_tokens.into_iter().enumerate() {
+        if Instant::now() >= deadline {
+            break;
+        }
+        handles.push(tokio::spawn(run_connection(
+            index,
+            token,
+            config.clone(),
+            counters.clone(),
+            deadline,
+        )));
+        if !config.ramp_interval.is_zero() {
+            tokio::time::sleep(config.ramp_interval).await;
+        }
+    }
+
+    let mut summary = RunSummary::default();
+    for handle in handles {
+        match handle.await {
+            Ok(stats) => summary.add(stats),
+            Err(error) => tracing::error!(error = %error, "connection task failed"),
+        }
+    }
+    reporter.abort();
+
+    print_summary(&config, &mut summary, started_at.elapsed());
+}
+
+async fn run_connection(
+    index: usize,
+    token: String,
+    config: Arc<LoadgenConfig>,
+    counters: Arc<LiveCounters>,
+    deadline: Instant,
+) -> ConnStats {
+    let mut stats = ConnStats::default();
+    let url = format!("{}?lobby_id={}", config.ws_url, config.lobby_id);
+
+    let connect = tokio::time::timeout(JOIN_TIMEOUT, tokio_tungstenite::connect_async(url)).await;
+    let mut socket = match connect {
+        Ok(Ok((socket, _response))) => socket,
+        Ok(Err(error)) => {
+            tracing::warn!(conn = index, error = %error, "connect failed");
+            return stats;
+        }
+        Err(_) => {
+            tracing::warn!(conn = index, "connect timed out");
+            return stats;
+        }
+    };
+
+    let join = ClientMessage::Join(JoinPayload {
+        session_token: token,
+        mode: JoinModeDto::Player,
+    });
+    let join_sent_at = Instant::now();
+    if let Err(error) = send_json(&mut socket, &join, &mut stats, &counters).await {
+        tracing::warn!(conn = index, error = %error, "failed to send join");
+        return stats;
+    }
+
+    let mut rng = XorShift::seeded(index as u64);
+    let mut input_interval = tokio::time::interval(Duration::from_secs(1) / config.input_rate_hz);
+    input_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last_tick: Option<u64> = None;
+    let mut last_update_at: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => break,
+            _ = input_interval.tick(), if stats.joined => {
+                let input = ClientMessage::Input(rng.next_input());
+                if let Err(error) = send_json(&mut socket, &input, &mut stats, &counters).await {
+                    tracing::warn!(conn = index, error = %error, "failed to send input");
+                    break;
+                }
+            }
+            _ = tokio::time::sleep_until(join_sent_at + JOIN_TIMEOUT), if !stats.joined => {
+                tracing::warn!(conn = index, "join handshake timed out");
+                break;
+            }
+            incoming = socket.next() => {
+                let message = match incoming {
+                    Some(Ok(message)) => message,
+                    Some(Err(error)) => {
+                        tracing::warn!(conn = index, error = %error, "websocket error");
+                        break;
+                    }
+                    None => break,
+                };
+
+                match message {
+                    Message::Text(text) => {
+                        stats.msgs_in += 1;
+                        stats.bytes_in += text.len() as u64;
+                        counters.msgs_in.fetch_add(1, Ordering::Relaxed);
+                        counters.bytes_in.fetch_add(text.len() as u64, Ordering::Relaxed);
+
+                        let Ok(envelope) = serde_json::from_str::<InboundEnvelope>(&text) else {
+                            continue;
+                        };
+                        match envelope.kind.as_str() {
+                            "Identity" if !stats.joined => {
+                                stats.joined = true;
+                                stats.join_latency = Some(join_sent_at.elapsed());
+                                counters.connected.fetch_add(1, Ordering::Relaxed);
+                            }
+                            "WorldUpdate" => {
+                                let Ok(update) =
+                                    serde_json::from_str::<WorldUpdateEnvelope>(&text)
+                                else {
+                                    continue;
+                                };
+                                record_world_update(
+                                    &mut stats,
+                                    update.data.tick,
+                                    &mut last_tick,
+                                    &mut last_update_at,
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+                    Message::Close(frame) => {
+                        stats.close_reason = Some(
+                            frame
+                                .map(|frame| format!("{} {}", u16::from(frame.code), frame.reason))
+                                .unwrap_or_else(|| "closed".to_string()),
+                        );
+                        tracing::warn!(
+                            conn = index,
+                            reason = ?stats.close_reason,
+                            "server closed connection"
+                        );
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if stats.joined {
+        counters.connected.fetch_sub(1, Ordering::Relaxed);
+    }
+    let _ = socket.close(None).await;
+    stats
+}
+
+fn record_world_update(
+    stats: &mut ConnStats,
+    tick: u64,
+    last_tick: &mut Option<u64>,
+    last_update_at: &mut Option<Instant>,
+) {
+    stats.world_updates += 1;
+    let now = Instant::now();
+    if let Some(previous) = last_update_at.replace(now) {
+        stats
+            .update_gaps_us
+            .push(stats::duration_to_us(now - previous));
+    }
+
+    // The server skips straight to the latest snapshot when a client lags behind.
+    if let Some(previous) = last_tick.replace(tick)
+        && tick > previous + 1
+    {
+        stats.lag_recovery_events += 1;
+        stats.ticks_missed += tick - previous - 1;
+    }
+}
+
+async fn send_json<S>(
+    socket: &mut S,
+    msg: &ClientMessage,
+    stats: &mut ConnStats,
+    counters: &LiveCounters,
+) -> Result<(), String>
+where
+    S: SinkExt<Message> + Unpin,
+    S::Error: std::fmt::Display,
+{
+    let txt = serde_json::to_string(msg).map_err(|error| error.to_string())?;
+    let bytes = txt.len() as u64;
+    socket
+        .send(Message::Text(txt.into()))
+        .await
+        .map_err(|error| error.to_string())?;
+
+    stats.msgs_out += 1;
+    stats.bytes_out += bytes;
+    counters.msgs_out.fetch_add(1, Ordering::Relaxed);
+    counters.bytes_out.fetch_add(bytes, Ordering::Relaxed);
+    Ok(())
+}
+
+async fn report_progress(counters: Arc<LiveCounters>, every: Duration, deadline: Instant) {
+    let mut interval = tokio::time::interval(every);
+    // The first tick completes immediately; skip it so rates cover a full window.
+    interval.tick().await;
+    let mut previous = counters.snapshot();
+
+    while Instant::now() < deadline {
+        interval.tick().await;
+        let current = counters.snapshot();
+        let secs = every.as_secs_f64();
+        tracing::info!(
+            connected = current.connected,
+            msgs_in_per_sec = ((current.msgs_in - previous.msgs_in) as f64 / secs) as u64,
+            kib_in_per_sec = ((current.bytes_in - previous.bytes_in) as f64 / secs / 1024.0) as u64,
+            msgs_out_per_sec = ((current.msgs_out - previous.msgs_out) as f64 / secs) as u64,
+            kib_out_per_sec =
+                ((current.bytes_out - previous.bytes_out) as f64 / secs / 1024.0) as u64,
+            "progress"
+        );
+        previous = current;
+    }
+}
+
+fn print_summary(config: &LoadgenConfig, summary: &mut RunSummary, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+    let join = summary.join_latency();
+    let gaps = summary.update_gap();
+
+    println!();
+    println!("loadgen summary ({:.1}s)", secs);
+    println!(
+        "  connections      {} attempted, {} joined, {} closed by server",
+        summary.attempted, summary.joined, summary.closed_by_server
+    );
+    println!(
+        "  inbound          {} msgs ({:.0}/s), {:.1} KiB/s",
+        summary.msgs_in,
+        summary.msgs_in as f64 / secs,
+        summary.bytes_in as f64 / secs / 1024.0
+    );
+    println!(
+        "  outbound         {} msgs ({:.0}/s), {:.1} KiB/s at {} Hz per connection",
+        summary.msgs_out,
+        summary.msgs_out as f64 / secs,
+        summary.bytes_out as f64 / secs / 1024.0,
+        config.input_rate_hz
+    );
+    println!(
+        "  world updates    {} received, {} lag-recovery events, {} ticks skipped",
+        summary.world_updates, summary.lag_recovery_events, summary.ticks_missed
+    );
+    println!(
+        "  join latency     p50 {:.1}ms  p90 {:.1}ms  p99 {:.1}ms  max {:.1}ms  (n={})",
+        ms(join.p50_us),
+        ms(join.p90_us),
+        ms(join.p99_us),
+        ms(join.max_us),
+        join.count
+    );
+    println!(
+        "  update interval  p50 {:.1}ms  p90 {:.1}ms  p99 {:.1}ms  max {:.1}ms  (n={})",
+        ms(gaps.p50_us),
+        ms(gaps.p90_us),
+        ms(gaps.p99_us),
+        ms(gaps.max_us),
+        gaps.count
+    );
+}
+
+fn ms(us: u32) -> f64 {
+    us as f64 / 1000.0
+}
+
+// Small xorshift generator; input only needs to look random, not be secure.
+struct XorShift(u64);
+
+impl XorShift {
+    fn seeded(stream: u64) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        // Mix the stream index in so connections started together diverge.
+        Self((nanos ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn next_unit(&mut self) -> f32 {
+        // Map to [-1.0, 1.0].
+        (self.next_u64() >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn next_input(&mut self) -> PlayerInputDto {
+        PlayerInputDto {
+            thrust: self.next_unit(),
+            turn: self.next_unit(),
+            shoot: self.next_u64().is_multiple_of(4),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Counters and summaries collected across simulated connections.
+
+/// Process-wide counters sampled by the periodic progress log.
+#[derive(Debug, Default)]
+pub struct LiveCounters {
+    pub connected: AtomicU64,
+    pub msgs_in: AtomicU64,
+    pub bytes_in: AtomicU64,
+    pub msgs_out: AtomicU64,
+    pub bytes_out: AtomicU64,
+}
+
+impl LiveCounters {
+    pub fn snapshot(&self) -> CounterSnapshot {
+        CounterSnapshot {
+            connected: self.connected.load(Ordering::Relaxed),
+            msgs_in: self.msgs_in.load(Ordering::Relaxed),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            msgs_out: self.msgs_out.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CounterSnapshot {
+    pub connected: u64,
+    pub msgs_in: u64,
+    pub bytes_in: u64,
+    pub msgs_out: u64,
+    pub bytes_out: u64,
+}
+
+/// Per-connection results returned when a simulated client finishes.
+#[derive(Debug, Default)]
+pub struct ConnStats {
+    pub joined: bool,
+    pub close_reason: Option<String>,
+    pub join_latency: Option<Duration>,
+    pub msgs_in: u64,
+    pub bytes_in: u64,
+    pub msgs_out: u64,
+    pub bytes_out: u64,
+    pub world_updates: u64,
+    // Tick discontinuities observed by the client (server-side lag recovery skips ticks).
+    pub lag_recovery_events: u64,
+    pub ticks_missed: u64,
+    // Gaps between consecutive world updates, in microseconds.
+    pub update_gaps_us: Vec<u32>,
+}
+
+/// Aggregated results across every connection in a run.
+#[derive(Debug, Default)]
+pub struct RunSummary {
+    pub attempted: usize,
+    pub joined: usize,
+    pub closed_by_server: usize,
+    pub msgs_in: u64,
+    pub bytes_in: u64,
+    pub msgs_out: u64,
+    pub bytes_out: u64,
+    pub world_updates: u64,
+    pub lag_recovery_events: u64,
+    pub ticks_missed: u64,
+    join_latencies_us: Vec<u32>,
+    update_gaps_us: Vec<u32>,
+}
+
+impl RunSummary {
+    pub fn add(&mut self, stats: ConnStats) {
+        self.attempted += 1;
+        if stats.joined {
+            self.joined += 1;
+        }
+        if stats.close_reason.is_some() {
+            self.closed_by_server += 1;
+        }
+        if let Some(latency) = stats.join_latency {
+            self.join_latencies_us.push(duration_to_us(latency));
+        }
+        self.msgs_in += stats.msgs_in;
+        self.bytes_in += stats.bytes_in;
+        self.msgs_out += stats.msgs_out;
+        self.bytes_out += stats.bytes_out;
+        self.world_updates += stats.world_updates;
+        self.lag_recovery_events += stats.lag_recovery_events;
+        self.ticks_missed += stats.ticks_missed;
+        self.update_gaps_us.extend(stats.update_gaps_us);
+    }
+
+    pub fn join_latency(&mut self) -> Percentiles {
+        Percentiles::from_samples(&mut self.join_latencies_us)
+    }
+
+    pub fn update_gap(&mut self) -> Percentiles {
+        Percentiles::from_samples(&mut self.update_gaps_us)
+    }
+}
+
+pub fn duration_to_us(duration: Duration) -> u32 {
+    duration.as_micros().min(u32::MAX as u128) as u32
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Percentiles {
+    pub count: usize,
+    pub p50_us: u32,
+    pub p90_us: u32,
+    pub p99_us: u32,
+    pub max_us: u32,
+}
+
+impl Percentiles {
+    /// Nearest-rank percentiles; sorts the samples in place.
+    pub fn from_samples(samples: &mut [u32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+
+        let rank = |pct: usize| {
+            let index = (samples.len() * pct).div_ceil(100).max(1) - 1;
+            samples[index.min(samples.len() - 1)]
+        };
+        Self {
+            count: samples.len(),
+            p50_us: rank(50),
+            p90_us: rank(90),
+            p99_us: rank(99),
+            max_us: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let mut samples: Vec<u32> = (1..=100).rev().collect();
+
+        let result = Percentiles::from_samples(&mut samples);
+
+        assert_eq!(
+            result,
+            Percentiles {
+                count: 100,
+                p50_us: 50,
+                p90_us: 90,
+                p99_us: 99,
+                max_us: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn percentiles_of_empty_samples_are_zero() {
+        assert_eq!(Percentiles::from_samples(&mut []), Percentiles::default());
+    }
+}
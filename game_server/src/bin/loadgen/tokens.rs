@@ -0,0 +1,141 @@
+use axum::{
+    Json, Router,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Session token acquisition for simulated clients.
+
+const STUB_TOKEN_PREFIX: &str = "loadgen-";
+const STUB_SESSION_TTL_SECS: u64 = 3600;
+
+#[derive(Debug, Serialize)]
+struct GuestInitRequest<'a> {
+    display_name: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct GuestInitResponse {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyTokenRequest {
+    token: String,
+}
+
+#[derive(Debug, Serialize)]
+struct VerifyTokenResponse {
+    user_id: u64,
+    display_name: String,
+    session_id: String,
+    expires_at: u64,
+}
+
+// Auth's error envelope, so the game server handles stub rejections like real ones.
+// The game server's own `ErrorResponse` only carries its own codes, not auth's.
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    code: &'static str,
+    message: String,
+    retryable: bool,
+    details: Option<serde_json::Value>,
+}
+
+/// Mints one guest session per connection through the auth service.
+pub async fn mint_guest_tokens(auth_base_url: &str, count: usize) -> Result<Vec<String>, String> {
+    let http = reqwest::Client::new();
+    let url = format!("{auth_base_url}/auth/guest/init");
+    let mut tokens = Vec::with_capacity(count);
+
+    for index in 0..count {
+        let display_name = format!("loadgen{index}");
+        let response = http
+            .post(&url)
+            .json(&GuestInitRequest {
+                display_name: &display_name,
+            })
+            .send()
+            .await
+            .map_err(|error| format!("guest init request failed: {error}"))?;
+        if !response.status().is_success() {
+            return Err(format!("guest init returned {}", response.status()));
+        }
+
+        let body = response
+            .json::<GuestInitResponse>()
+            .await
+            .map_err(|error| format!("guest init response was invalid: {error}"))?;
+        tokens.push(body.token);
+    }
+
+    Ok(tokens)
+}
+
+/// Deterministic tokens accepted by the stub auth server.
+pub fn stub_tokens(count: usize) -> Vec<String> {
+    (1..=count as u64)
+        .map(|user_id| format!("{STUB_TOKEN_PREFIX}{user_id}"))
+        .collect()
+}
+
+/// Serves a minimal `/auth/verify-token` and an empty `/auth/revocations` so the
+/// game server can run without auth.
+///
+/// Point the game server's `AUTH_SERVICE_URL` at this listener.
+pub async fn spawn_stub_auth(bind_addr: &str) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(bind_addr).await?;
+    let address = listener.local_addr()?;
+    let app = Router::new()
+        .route("/auth/verify-token", post(stub_verify_token))
+        .route("/auth/revocations", get(stub_revocations));
+
+    tokio::spawn(async move {
+        if let Err(error) = axum::serve(listener, app).await {
+            tracing::error!(error = %error, "stub auth server error");
+        }
+    });
+    tracing::info!(%address, "stub auth listening");
+    Ok(())
+}
+
+async fn stub_verify_token(Json(payload): Json<VerifyTokenRequest>) -> impl IntoResponse {
+    let user_id = payload
+        .token
+        .strip_prefix(STUB_TOKEN_PREFIX)
+        .and_then(|id| id.parse::<u64>().ok())
+        .filter(|id| *id > 0);
+
+    let Some(user_id) = user_id else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                code: "invalid_token",
+                message: "invalid token".to_string(),
+                retryable: false,
+                details: None,
+            }),
+        )
+            .into_response();
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Json(VerifyTokenResponse {
+        user_id,
+        display_name: format!("loadgen{user_id}"),
+        session_id: payload.token,
+        expires_at: now + STUB_SESSION_TTL_SECS,
+    })
+    .into_response()
+}
+
+// Loadgen sessions are never revoked.
+async fn stub_revocations() -> impl IntoResponse {
+    Json(serde_json::json!({ "revocations": [], "cursor": 0 }))
+}
@@ -40,6 +40,9 @@ pub struct SimEntity {
     pub throttle: f32,           // 0.0..=1.0
     pub last_input: PlayerInput, // last received input for this entity
     pub shoot_cooldown: f32,     // seconds until next allowed shot
+
+    // Connection state: seconds left before a disconnected ship is removed.
+    pub reconnect_grace: Option<f32>,
 }
 
 pub struct SimProjectile {
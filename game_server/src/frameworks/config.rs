@@ -8,6 +8,41 @@ pub struct GameServerRuntimeConfig {
     pub http_port: u16,
     pub auth_service_url: String,
     pub auth_verify_timeout: Duration,
+    // Head service base URL for lobby lifecycle reports; unset disables reporting.
+    pub head_service_url: Option<String>,
+    // Bearer token guarding the admin lobby API; unset disables the admin routes.
+    pub admin_token: Option<String>,
+    // Shared HMAC secret for service-to-service requests with head.
+    pub internal_api_secret: String,
+    // Optional separate port for internal routes; unset serves them on `http_port`.
+    pub internal_http_port: Option<u16>,
+    // Admission limits protecting the process from runaway lobby or socket counts.
+    pub max_lobbies: usize,
+    pub max_players_per_lobby: usize,
+    pub max_spectators_per_lobby: usize,
+    pub max_connections: usize,
+    // How long a drain waits for running matches before shutting down anyway.
+    pub drain_timeout: Duration,
+    // How long a lobby may sit without any player before it is reaped (0 disables).
+    pub lobby_join_deadline: Duration,
+    // How often auth's revocation list is polled to drop revoked sessions (0 disables).
+    pub revocation_poll_interval: Duration,
+    // Close connections whose session expired when their lobby changes match state.
+    pub enforce_session_expiry: bool,
+    // Head registration; set together with `head_service_url` to join head's directory.
+    pub registration: Option<RegistrationConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistrationConfig {
+    // Stable id for heartbeats; defaults to the internal URL so restarts replace the entry.
+    pub server_id: String,
+    // Matchmaking region key this server hosts matches for.
+    pub region: String,
+    // WebSocket URL head hands to matched clients.
+    pub public_ws_url: String,
+    // Base URL head uses for lobby creation.
+    pub internal_base_url: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -39,9 +74,62 @@ pub fn load_runtime_config(
         http_port: parse_optional_u16(env, "GAME_SERVER_PORT")?.unwrap_or(3001),
         auth_service_url: required_env_var(env, "AUTH_SERVICE_URL")?,
         auth_verify_timeout: Duration::from_millis(auth_verify_timeout_millis),
+        head_service_url: optional_env_var(env, "HEAD_SERVICE_URL"),
+        admin_token: env
+            .get_var("GAME_SERVER_ADMIN_TOKEN")
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty()),
+        internal_api_secret: required_env_var(env, "INTERNAL_API_SECRET")?,
+        internal_http_port: parse_optional_u16(env, "GAME_SERVER_INTERNAL_PORT")?,
+        max_lobbies: parse_optional_limit(env, "GAME_SERVER_MAX_LOBBIES")?
+            .unwrap_or(DEFAULT_MAX_LOBBIES),
+        max_players_per_lobby: parse_optional_limit(env, "GAME_SERVER_MAX_PLAYERS_PER_LOBBY")?
+            .unwrap_or(DEFAULT_MAX_PLAYERS_PER_LOBBY),
+        max_spectators_per_lobby: parse_optional_limit(
+            env,
+            "GAME_SERVER_MAX_SPECTATORS_PER_LOBBY",
+        )?
+        .unwrap_or(DEFAULT_MAX_SPECTATORS_PER_LOBBY),
+        max_connections: parse_optional_limit(env, "GAME_SERVER_MAX_CONNECTIONS")?
+            .unwrap_or(DEFAULT_MAX_CONNECTIONS),
+        drain_timeout: parse_optional_u64(env, "GAME_SERVER_DRAIN_TIMEOUT_SECS")?
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_DRAIN_TIMEOUT),
+        lobby_join_deadline: parse_optional_u64(env, "GAME_SERVER_LOBBY_JOIN_DEADLINE_SECS")?
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_LOBBY_JOIN_DEADLINE),
+        revocation_poll_interval: parse_optional_u64(env, "GAME_SERVER_REVOCATION_POLL_SECS")?
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_REVOCATION_POLL_INTERVAL),
+        enforce_session_expiry: parse_optional_bool(env, "GAME_SERVER_ENFORCE_SESSION_EXPIRY")?
+            .unwrap_or(false),
+        registration: load_registration_config(env)?,
     })
 }
 
+// A region opts the server into registration, after which both URLs are required.
+fn load_registration_config(
+    env: &impl EnvSource,
+) -> Result<Option<RegistrationConfig>, GameServerConfigError> {
+    let Some(region) = optional_env_var(env, "GAME_SERVER_REGION") else {
+        return Ok(None);
+    };
+    let public_ws_url = optional_env_var(env, "GAME_SERVER_PUBLIC_WS_URL").ok_or(
+        GameServerConfigError::MissingEnvVar("GAME_SERVER_PUBLIC_WS_URL"),
+    )?;
+    let internal_base_url = optional_env_var(env, "GAME_SERVER_INTERNAL_URL").ok_or(
+        GameServerConfigError::MissingEnvVar("GAME_SERVER_INTERNAL_URL"),
+    )?;
+
+    Ok(Some(RegistrationConfig {
+        server_id: optional_env_var(env, "GAME_SERVER_ID")
+            .unwrap_or_else(|| internal_base_url.clone()),
+        region,
+        public_ws_url,
+        internal_base_url,
+    }))
+}
+
 pub fn http_port() -> u16 {
     env::var("GAME_SERVER_PORT")
         .ok()
@@ -53,6 +141,18 @@ pub fn auth_service_url() -> String {
     env::var("AUTH_SERVICE_URL").unwrap_or_else(|_| "http://127.0.0.1:3002".to_string())
 }
 
+pub fn head_service_url() -> Option<String> {
+    env::var("HEAD_SERVICE_URL")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+pub fn admin_token() -> Option<String> {
+    env::var("GAME_SERVER_ADMIN_TOKEN")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
 pub fn auth_verify_timeout() -> Duration {
     let millis = env::var("AUTH_VERIFY_TIMEOUT_MS")
         .ok()
@@ -71,6 +171,12 @@ fn required_env_var(
         .ok_or(GameServerConfigError::MissingEnvVar(key))
 }
 
+fn optional_env_var(env: &impl EnvSource, key: &'static str) -> Option<String> {
+    env.get_var(key)
+        .map(|value| value.trim().trim_end_matches('/').to_string())
+        .filter(|value| !value.is_empty())
+}
+
 fn parse_optional_u16(
     env: &impl EnvSource,
     key: &'static str,
@@ -114,12 +220,84 @@ fn parse_optional_u64(
         None => Ok(None),
     }
 }
+fn parse_optional_bool(
+    env: &impl EnvSource,
+    key: &'static str,
+) -> Result<Option<bool>, GameServerConfigError> {
+    match env.get_var(key) {
+        Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+            "" => Ok(None),
+            "true" | "1" => Ok(Some(true)),
+            "false" | "0" => Ok(Some(false)),
+            _ => Err(GameServerConfigError::InvalidEnvVar {
+                key,
+                value: value.to_string(),
+            }),
+        },
+        None => Ok(None),
+    }
+}
+
+// Capacity limits must be positive; zero would refuse every lobby or socket.
+fn parse_optional_limit(
+    env: &impl EnvSource,
+    key: &'static str,
+) -> Result<Option<usize>, GameServerConfigError> {
+    match env.get_var(key) {
+        Some(value) => {
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                return Ok(None);
+            }
+            match trimmed.parse::<usize>() {
+                Ok(limit) if limit > 0 => Ok(Some(limit)),
+                _ => Err(GameServerConfigError::InvalidEnvVar {
+                    key,
+                    value: value.to_string(),
+                }),
+            }
+        }
+        None => Ok(None),
+    }
+}
+
 pub const INPUT_CHANNEL_CAPACITY: usize = 1024;
 pub const WORLD_BROADCAST_CAPACITY: usize = 128;
 
 pub const TICK_INTERVAL: Duration = Duration::from_millis(1000 / 60);
 // Default time limit for non-test lobbies (0 disables match end).
 pub const DEFAULT_MATCH_TIME_LIMIT: Duration = Duration::from_secs(600);
+// Unpinned lobbies per process.
+pub const DEFAULT_MAX_LOBBIES: usize = 64;
+// Player connections per lobby; also the largest roster head may send.
+pub const DEFAULT_MAX_PLAYERS_PER_LOBBY: usize = 16;
+// Spectators per lobby; keeps observers from crowding out the match itself.
+pub const DEFAULT_MAX_SPECTATORS_PER_LOBBY: usize = 8;
+// WebSocket connections per process across all lobbies.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 1024;
+// Drain deadline on SIGTERM; long enough for a default-length match to finish most of the way.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(120);
+// Matched players who have not connected within this window are not coming.
+// Kept under `READY_TIMEOUT` so an empty lobby is reaped before its ready check fails.
+pub const DEFAULT_LOBBY_JOIN_DEADLINE: Duration = Duration::from_secs(20);
+// Revoked sessions are disconnected within roughly this long of a logout.
+pub const DEFAULT_REVOCATION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// How often the reaper looks for lobbies past their join deadline.
+pub const LOBBY_REAPER_INTERVAL: Duration = Duration::from_secs(5);
+// Spectator feed lag so watching cannot be used to relay live positions.
+pub const SPECTATOR_FEED_DELAY: Duration = Duration::from_secs(3);
+// How long a dropped player's ship idles in the world waiting for a rejoin.
+pub const RECONNECT_GRACE: Duration = Duration::from_secs(20);
+// Rostered lobbies wait this long for every player to join and ready up.
+pub const READY_TIMEOUT: Duration = Duration::from_secs(30);
+// After the ready timeout, start if at least this fraction of the roster is present.
+pub const READY_QUORUM: f32 = 0.5;
+// Countdown between the ready check passing and the match running.
+pub const MATCH_COUNTDOWN: Duration = Duration::from_secs(3);
+// Upper bound on lifecycle reports to head; they are fire-and-forget.
+pub const HEAD_REPORT_TIMEOUT: Duration = Duration::from_secs(2);
+// How often a registered server reports its load to head.
+pub const HEAD_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
 #[cfg(test)]
 mod tests {
@@ -180,6 +358,16 @@ mod tests {
             ("GAME_SERVER_PORT", "5001"),
             ("AUTH_SERVICE_URL", "http://auth.internal:9000"),
             ("AUTH_VERIFY_TIMEOUT_MS", "3200"),
+            ("HEAD_SERVICE_URL", "http://head.internal:3000/"),
+            ("GAME_SERVER_ADMIN_TOKEN", " admin-secret "),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("GAME_SERVER_INTERNAL_PORT", "5101"),
+            ("GAME_SERVER_MAX_LOBBIES", "12"),
+            ("GAME_SERVER_MAX_CONNECTIONS", "300"),
+            ("GAME_SERVER_DRAIN_TIMEOUT_SECS", "45"),
+            ("GAME_SERVER_LOBBY_JOIN_DEADLINE_SECS", "0"),
+            ("GAME_SERVER_REVOCATION_POLL_SECS", "2"),
+            ("GAME_SERVER_ENFORCE_SESSION_EXPIRY", "true"),
         ]))
         .expect("runtime config should load");
 
@@ -187,6 +375,76 @@ mod tests {
         assert_eq!(config.http_port, 5001);
         assert_eq!(config.auth_service_url, "http://auth.internal:9000");
         assert_eq!(config.auth_verify_timeout, Duration::from_millis(3200));
+        assert_eq!(
+            config.head_service_url.as_deref(),
+            Some("http://head.internal:3000")
+        );
+        assert_eq!(config.admin_token.as_deref(), Some("admin-secret"));
+        assert_eq!(config.internal_api_secret, "shared-secret");
+        assert_eq!(config.internal_http_port, Some(5101));
+        assert_eq!(config.max_lobbies, 12);
+        assert_eq!(config.max_players_per_lobby, DEFAULT_MAX_PLAYERS_PER_LOBBY);
+        assert_eq!(
+            config.max_spectators_per_lobby,
+            DEFAULT_MAX_SPECTATORS_PER_LOBBY
+        );
+        assert_eq!(config.max_connections, 300);
+        assert_eq!(config.drain_timeout, Duration::from_secs(45));
+        assert_eq!(config.lobby_join_deadline, Duration::ZERO);
+        assert_eq!(config.revocation_poll_interval, Duration::from_secs(2));
+        assert!(config.enforce_session_expiry);
+        assert_eq!(config.registration, None);
+    }
+
+    #[test]
+    fn load_runtime_config_reads_registration_when_region_is_set() {
+        let base = [
+            ("GAME_SERVER_BIND_HOST", "127.0.0.1"),
+            ("AUTH_SERVICE_URL", "http://auth.internal:9000"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("GAME_SERVER_REGION", "eu-west"),
+        ];
+        let missing_ws = load_runtime_config(&TestEnv::from_pairs(&base));
+        assert!(matches!(
+            missing_ws,
+            Err(GameServerConfigError::MissingEnvVar(
+                "GAME_SERVER_PUBLIC_WS_URL"
+            ))
+        ));
+
+        let mut pairs = base.to_vec();
+        pairs.extend([
+            ("GAME_SERVER_PUBLIC_WS_URL", "wss://eu.example.com/ws"),
+            (
+                "GAME_SERVER_INTERNAL_URL",
+                "http://game-eu-1.internal:3101/",
+            ),
+        ]);
+        let config =
+            load_runtime_config(&TestEnv::from_pairs(&pairs)).expect("runtime config should load");
+
+        assert_eq!(
+            config.registration,
+            Some(RegistrationConfig {
+                server_id: "http://game-eu-1.internal:3101".into(),
+                region: "eu-west".into(),
+                public_ws_url: "wss://eu.example.com/ws".into(),
+                internal_base_url: "http://game-eu-1.internal:3101".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn load_runtime_config_requires_internal_api_secret() {
+        let config = load_runtime_config(&TestEnv::from_pairs(&[
+            ("GAME_SERVER_BIND_HOST", "127.0.0.1"),
+            ("AUTH_SERVICE_URL", "http://auth.internal:9000"),
+        ]));
+
+        assert!(matches!(
+            config,
+            Err(GameServerConfigError::MissingEnvVar("INTERNAL_API_SECRET"))
+        ));
     }
 
     #[test]
@@ -214,5 +472,33 @@ mod tests {
                 ..
             })
         ));
+
+        let zero_limit = load_runtime_config(&TestEnv::from_pairs(&[
+            ("GAME_SERVER_BIND_HOST", "127.0.0.1"),
+            ("AUTH_SERVICE_URL", "http://auth.internal:9000"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("GAME_SERVER_MAX_LOBBIES", "0"),
+        ]));
+        assert!(matches!(
+            zero_limit,
+            Err(GameServerConfigError::InvalidEnvVar {
+                key: "GAME_SERVER_MAX_LOBBIES",
+                ..
+            })
+        ));
+
+        let invalid_flag = load_runtime_config(&TestEnv::from_pairs(&[
+            ("GAME_SERVER_BIND_HOST", "127.0.0.1"),
+            ("AUTH_SERVICE_URL", "http://auth.internal:9000"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("GAME_SERVER_ENFORCE_SESSION_EXPIRY", "sometimes"),
+        ]));
+        assert!(matches!(
+            invalid_flag,
+            Err(GameServerConfigError::InvalidEnvVar {
+                key: "GAME_SERVER_ENFORCE_SESSION_EXPIRY",
+                ..
+            })
+        ));
     }
 }
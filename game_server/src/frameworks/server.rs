@@ -1,19 +1,27 @@
 // Framework bootstrap for the game server runtime.
 
 use crate::frameworks::config;
-use crate::frameworks::config::{GameServerConfigError, ProcessEnv};
+use crate::frameworks::config::{GameServerConfigError, GameServerRuntimeConfig, ProcessEnv};
 use crate::interface_adapters::clients::auth::AuthClient;
+use crate::interface_adapters::clients::head::{HeadClient, ServerRegistration};
 use crate::interface_adapters::http::health;
-use crate::interface_adapters::net::{create_lobby_handler, spawn_lobby_serializer, ws_handler};
+use crate::interface_adapters::net::{
+    NetMetrics, create_lobby_handler, delete_lobby_handler, end_match_handler, get_lobby_handler,
+    kick_player_handler, list_lobbies_handler, load_handler, metrics_handler, require_admin_token,
+    require_internal_signature, spawn_lobby_serializer, ws_handler,
+};
 use crate::interface_adapters::state::AppState;
-use crate::use_cases::{LobbyRegistry, LobbySettings};
+use crate::use_cases::{LobbyRegistry, LobbyReporter, LobbySettings, NoopLobbyReporter};
 
 use axum::{
-    Router,
+    Router, middleware,
     routing::{get, post},
 };
 use std::net::SocketAddr;
-use std::{collections::HashSet, io::Result as IoResult, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet, future::Future, io::Result as IoResult, sync::Arc, time::Duration,
+};
+use tokio::sync::watch;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum StartupFailure {
@@ -75,41 +83,193 @@ pub async fn run(listener: tokio::net::TcpListener) -> IoResult<()> {
             format!("invalid environment variable {key}={value}"),
         ),
     })?;
-    let state = build_state_with_auth_config(
-        runtime_config.auth_service_url,
-        runtime_config.auth_verify_timeout,
-    )
-    .await?;
-    run_with_state(listener, state).await
+    let drain_timeout = runtime_config.drain_timeout;
+    let state = build_state(runtime_config).await?;
+    let shutdown = drain_on_signal(state.lobby_registry.clone(), drain_timeout);
+    run_with_state(listener, None, state, shutdown).await
 }
 
 /// Test-only compatibility entrypoint that bypasses runtime-config validation.
 ///
 /// This preserves existing integration tests that spawn an ephemeral listener
-/// and only need the server loop plus default auth client settings.
-pub async fn run_for_tests(listener: tokio::net::TcpListener) -> IoResult<()> {
-    let state =
-        build_state_with_auth_config(config::auth_service_url(), config::auth_verify_timeout())
-            .await?;
-    run_with_state(listener, state).await
+/// and only need the server loop plus default auth client settings. Tests pass
+/// the secret they sign internal requests with and the admin token they use.
+pub async fn run_for_tests(
+    listener: tokio::net::TcpListener,
+    internal_api_secret: String,
+    admin_token: String,
+) -> IoResult<()> {
+    let address = listener.local_addr()?;
+    let state = build_state(GameServerRuntimeConfig {
+        bind_host: address.ip().to_string(),
+        http_port: address.port(),
+        auth_service_url: config::auth_service_url(),
+        auth_verify_timeout: config::auth_verify_timeout(),
+        head_service_url: config::head_service_url(),
+        admin_token: Some(admin_token),
+        internal_api_secret,
+        internal_http_port: None,
+        max_lobbies: config::DEFAULT_MAX_LOBBIES,
+        max_players_per_lobby: config::DEFAULT_MAX_PLAYERS_PER_LOBBY,
+        max_spectators_per_lobby: config::DEFAULT_MAX_SPECTATORS_PER_LOBBY,
+        max_connections: config::DEFAULT_MAX_CONNECTIONS,
+        drain_timeout: config::DEFAULT_DRAIN_TIMEOUT,
+        lobby_join_deadline: config::DEFAULT_LOBBY_JOIN_DEADLINE,
+        // Tests run without an auth service to poll.
+        revocation_poll_interval: Duration::ZERO,
+        enforce_session_expiry: false,
+        registration: None,
+    })
+    .await?;
+    run_with_state(listener, None, state, std::future::pending()).await
 }
 
-async fn run_with_state(listener: tokio::net::TcpListener, state: Arc<AppState>) -> IoResult<()> {
+/// Serves public routes on `listener` and internal routes on `internal_listener`.
+///
+/// Without a separate internal listener, both route sets share the public one.
+/// Both listeners stop accepting and the call returns once `shutdown` resolves.
+async fn run_with_state(
+    listener: tokio::net::TcpListener,
+    internal_listener: Option<tokio::net::TcpListener>,
+    state: Arc<AppState>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> IoResult<()> {
+    // Fan the single shutdown future out to every listener.
+    let (stop_tx, stop_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown.await;
+        stop_tx.send_replace(true);
+    });
+    let stopped = move || {
+        let mut stop_rx = stop_rx.clone();
+        async move {
+            let _ = stop_rx.wait_for(|stopped| *stopped).await;
+        }
+    };
+
     let address = listener.local_addr()?;
-    let app = Router::new()
+    let public = Router::new()
         .route("/health", get(health))
-        .route("/ws", get(ws_handler))
-        .route("/lobbies", post(create_lobby_handler))
-        .with_state(state);
+        .route("/ws", get(ws_handler));
+    let internal = internal_routes(&state);
+
+    let Some(internal_listener) = internal_listener else {
+        tracing::info!(%address, "listening");
+        // Sharing the public port, metrics are for operators only.
+        let metrics = Router::new()
+            .route("/metrics", get(metrics_handler))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_admin_token,
+            ));
+        let app = public.merge(metrics).merge(internal).with_state(state);
+        // Serve app and report errors rather than panicking
+        return axum::serve(listener, app)
+            .with_graceful_shutdown(stopped())
+            .await
+            .inspect_err(|e| {
+                tracing::error!(error = %e, "server error");
+            });
+    };
 
-    tracing::info!(%address, "listening");
+    let internal_address = internal_listener.local_addr()?;
+    let internal = internal
+        .route("/health", get(health))
+        .route("/metrics", get(metrics_handler))
+        .with_state(state.clone());
+    let public = public.with_state(state);
+    tracing::info!(%address, %internal_address, "listening");
 
-    // Serve app and report errors rather than panicking
-    axum::serve(listener, app).await.inspect_err(|e| {
+    tokio::try_join!(
+        async {
+            axum::serve(listener, public)
+                .with_graceful_shutdown(stopped())
+                .await
+        },
+        async {
+            axum::serve(internal_listener, internal)
+                .with_graceful_shutdown(stopped())
+                .await
+        },
+    )
+    .map(|_| ())
+    .inspect_err(|e| {
         tracing::error!(error = %e, "server error");
     })
 }
 
+// Resolves once a termination signal has arrived and the lobbies have drained.
+async fn drain_on_signal(lobby_registry: Arc<LobbyRegistry>, drain_timeout: Duration) {
+    shutdown_signal().await;
+    lobby_registry.drain(drain_timeout).await;
+    tracing::info!("drain complete; shutting down");
+}
+
+// Ctrl+C locally, SIGTERM from container runtimes.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %e, "failed to listen for ctrl-c");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("shutdown signal received; draining");
+}
+
+// Head-facing and operator routes; never needed by game clients.
+fn internal_routes(state: &Arc<AppState>) -> Router<Arc<AppState>> {
+    let mut internal = Router::new()
+        .route("/lobbies", post(create_lobby_handler))
+        .route("/load", get(load_handler))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_internal_signature,
+        ));
+
+    // Admin routes only exist when an operator token is configured.
+    if state.admin_token.is_some() {
+        let admin = Router::new()
+            .route("/lobbies", get(list_lobbies_handler))
+            .route(
+                "/lobbies/{lobby_id}",
+                get(get_lobby_handler).delete(delete_lobby_handler),
+            )
+            .route(
+                "/lobbies/{lobby_id}/kick/{player_id}",
+                post(kick_player_handler),
+            )
+            .route("/lobbies/{lobby_id}/end", post(end_match_handler))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_admin_token,
+            ));
+        internal = internal.merge(admin);
+    } else {
+        tracing::info!("GAME_SERVER_ADMIN_TOKEN not set; admin lobby API disabled");
+    }
+    internal
+}
+
 pub async fn run_with_config() -> std::result::Result<(), StartupFailure> {
     init_runtime();
 
@@ -148,42 +308,141 @@ pub async fn run_with_config() -> std::result::Result<(), StartupFailure> {
         })
         .map_err(|_| StartupFailure::Bind)?;
 
-    let state = build_state_with_auth_config(
-        runtime_config.auth_service_url,
-        runtime_config.auth_verify_timeout,
-    )
-    .await
-    .map_err(|error| {
+    // Internal routes move to their own listener when a port is configured.
+    let internal_listener = match runtime_config.internal_http_port {
+        Some(port) => {
+            let internal_address = SocketAddr::new(address.ip(), port);
+            let listener = tokio::net::TcpListener::bind(internal_address)
+                .await
+                .inspect_err(|e| {
+                    tracing::error!(%internal_address, error = %e, "failed to bind internal listener");
+                })
+                .map_err(|_| StartupFailure::Bind)?;
+            Some(listener)
+        }
+        None => None,
+    };
+
+    let drain_timeout = runtime_config.drain_timeout;
+    let state = build_state(runtime_config).await.map_err(|error| {
         tracing::error!(error = %error, "failed to initialize game server state");
         StartupFailure::Initialization
     })?;
+    let shutdown = drain_on_signal(state.lobby_registry.clone(), drain_timeout);
 
-    run_with_state(listener, state).await.map_err(|error| {
-        tracing::error!(error = %error, "server error");
-        StartupFailure::Serve
-    })
+    run_with_state(listener, internal_listener, state, shutdown)
+        .await
+        .map_err(|error| {
+            tracing::error!(error = %error, "server error");
+            StartupFailure::Serve
+        })
 }
 
-async fn build_state_with_auth_config(
-    auth_base_url: String,
-    auth_verify_timeout: Duration,
-) -> IoResult<Arc<AppState>> {
-    let auth_client = AuthClient::new(auth_base_url.clone(), auth_verify_timeout)
-        .map_err(|e| std::io::Error::other(format!("failed to initialize auth client: {e}")))?;
+async fn build_state(runtime_config: GameServerRuntimeConfig) -> IoResult<Arc<AppState>> {
+    let GameServerRuntimeConfig {
+        auth_service_url: auth_base_url,
+        auth_verify_timeout,
+        head_service_url: head_base_url,
+        admin_token,
+        internal_api_secret,
+        max_lobbies,
+        max_players_per_lobby,
+        max_spectators_per_lobby,
+        max_connections,
+        lobby_join_deadline,
+        revocation_poll_interval,
+        enforce_session_expiry,
+        registration,
+        ..
+    } = runtime_config;
+
+    let auth_client = AuthClient::new(
+        auth_base_url.clone(),
+        auth_verify_timeout,
+        internal_api_secret.clone(),
+    )
+    .map_err(|e| std::io::Error::other(format!("failed to initialize auth client: {e}")))?;
     tracing::debug!(
         auth_base_url = %auth_base_url,
         auth_verify_timeout_ms = auth_verify_timeout.as_millis(),
         "auth client configured"
     );
 
+    let head_client = match head_base_url {
+        Some(head_base_url) => {
+            let head_client = HeadClient::new(
+                head_base_url.clone(),
+                config::HEAD_REPORT_TIMEOUT,
+                internal_api_secret.clone(),
+            )
+            .map_err(|e| std::io::Error::other(format!("failed to initialize head client: {e}")))?;
+            tracing::debug!(head_base_url = %head_base_url, "head reporting configured");
+            Some(head_client)
+        }
+        None => {
+            tracing::warn!("HEAD_SERVICE_URL not set; lobby aborts will not be reported");
+            None
+        }
+    };
+    // Aborted lobbies are reported to head so it can re-queue their tickets.
+    let reporter: Arc<dyn LobbyReporter> = match &head_client {
+        Some(head_client) => Arc::new(head_client.clone()),
+        None => Arc::new(NoopLobbyReporter),
+    };
+
     // Setup Lobby Registry
     // This owns the set of active lobby world tasks.
-    let lobby_registry = Arc::new(LobbyRegistry::new(LobbySettings {
-        input_channel_capacity: config::INPUT_CHANNEL_CAPACITY,
-        world_broadcast_capacity: config::WORLD_BROADCAST_CAPACITY,
-        tick_interval: config::TICK_INTERVAL,
-        default_match_time_limit: config::DEFAULT_MATCH_TIME_LIMIT,
-    }));
+    let lobby_registry = Arc::new(
+        LobbyRegistry::new(LobbySettings {
+            input_channel_capacity: config::INPUT_CHANNEL_CAPACITY,
+            world_broadcast_capacity: config::WORLD_BROADCAST_CAPACITY,
+            tick_interval: config::TICK_INTERVAL,
+            default_match_time_limit: config::DEFAULT_MATCH_TIME_LIMIT,
+            max_lobbies,
+            max_players_per_lobby,
+            max_spectators_per_lobby,
+            max_connections,
+            spectator_feed_delay: config::SPECTATOR_FEED_DELAY,
+            reconnect_grace: config::RECONNECT_GRACE,
+            ready_timeout: config::READY_TIMEOUT,
+            ready_quorum: config::READY_QUORUM,
+            match_countdown: config::MATCH_COUNTDOWN,
+            join_deadline: lobby_join_deadline,
+        })
+        .with_reporter(reporter),
+    );
+    lobby_registry
+        .clone()
+        .spawn_lobby_reaper(config::LOBBY_REAPER_INTERVAL);
+
+    // Sessions logged out through auth are dropped mid-match.
+    if revocation_poll_interval.is_zero() {
+        tracing::info!(
+            "GAME_SERVER_REVOCATION_POLL_SECS is 0; revoked sessions stay connected and signed tokens are checked by auth"
+        );
+    } else {
+        auth_client
+            .clone()
+            .spawn_revocation_poller(lobby_registry.clone(), revocation_poll_interval);
+    }
+
+    // Join head's directory so matches are placed here by load.
+    match (head_client, registration) {
+        (Some(head_client), Some(registration)) => head_client.spawn_heartbeat(
+            ServerRegistration {
+                server_id: registration.server_id,
+                region: registration.region,
+                ws_url: registration.public_ws_url,
+                base_url: registration.internal_base_url,
+            },
+            lobby_registry.clone(),
+            config::HEAD_HEARTBEAT_INTERVAL,
+        ),
+        (None, Some(_)) => {
+            tracing::warn!("GAME_SERVER_REGION set without HEAD_SERVICE_URL; not registering");
+        }
+        _ => {}
+    }
 
     // Create the default test lobby and spawn its world task.
     let test_lobby_id = "test".to_string();
@@ -211,6 +470,10 @@ async fn build_state_with_auth_config(
         lobby_registry,
         default_lobby_id: Arc::from(test_lobby_id.as_str()),
         auth_client: Arc::new(auth_client),
+        internal_secret: Arc::from(internal_api_secret),
+        admin_token: admin_token.map(Arc::from),
+        net_metrics: Arc::new(NetMetrics::default()),
+        enforce_session_expiry,
     }))
 }
 
@@ -1,6 +1,28 @@
-use reqwest::StatusCode;
+use crate::interface_adapters::clients::auth_cache::{
+    CircuitBreaker, IdentityCache, TokenKey, VerifyResult, token_key,
+};
+use crate::interface_adapters::utils::signing::{
+    SIGNATURE_HEADER, TIMESTAMP_HEADER, sign, unix_now,
+};
+use crate::use_cases::LobbyRegistry;
+
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use session_tokens::{KeySetResponse, LocalVerifier, Rejection, SignedToken};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
+
+// Verified identities are reused for this long (capped by the session's expiry).
+const VERIFY_CACHE_TTL: Duration = Duration::from_secs(60);
+// Invalid and expired tokens are remembered briefly so retries do not reach auth.
+const VERIFY_NEGATIVE_TTL: Duration = Duration::from_secs(10);
+const VERIFY_CACHE_CAPACITY: usize = 10_000;
+// Consecutive upstream failures before joins fail fast without calling auth.
+const BREAKER_FAILURE_THRESHOLD: u32 = 5;
+// How long the breaker stays open before probing auth again.
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(10);
 
 // Auth verification response consumed by the game server join path.
 #[derive(Debug, Clone, Deserialize)]
@@ -16,35 +38,227 @@ struct VerifyTokenRequest<'a> {
     token: &'a str,
 }
 
+// Error envelope returned by auth; only the fields the join path branches on.
 #[derive(Debug, Deserialize)]
 struct ErrorResponse {
-    message: String,
+    code: AuthErrorCode,
+    retryable: bool,
+}
+
+// Auth error codes the join path tells apart; the registry is in ERROR_CODES.md.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AuthErrorCode {
+    InvalidToken,
+    SessionExpired,
+    #[serde(other)]
+    Other,
+}
+
+// One session auth revoked before it expired.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RevokedSession {
+    pub session_id: String,
+    pub expires_at: u64,
+}
+
+// Revocations since the polled cursor and the cursor for the next poll.
+#[derive(Debug, Deserialize)]
+pub struct RevocationPage {
+    pub revocations: Vec<RevokedSession>,
+    pub cursor: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum VerifyTokenError {
     InvalidToken,
     SessionExpired,
     UpstreamUnavailable,
 }
 
-// Thin reqwest client for auth token verification.
+// Reqwest client for auth token verification, shielded by a cache and a circuit breaker.
 #[derive(Clone)]
 pub struct AuthClient {
     http: reqwest::Client,
     base_url: String,
+    // Shared secret used to sign revocation polls, which auth only serves to services.
+    secret: Arc<str>,
+    // Shared by clones so the join path and the revocation poller see one cache.
+    verify: Arc<VerifyState>,
+}
+
+struct VerifyState {
+    cache: Mutex<IdentityCache>,
+    breaker: Mutex<CircuitBreaker>,
+    // One upstream lookup per token; concurrent joins wait for its result.
+    in_flight: Mutex<HashMap<TokenKey, watch::Sender<Option<VerifyResult>>>>,
+    // Keys and revocations for verifying signed tokens without calling auth.
+    local: LocalVerifier,
+}
+
+// Frees the in-flight slot even when the leading join is cancelled mid-request,
+// so waiting joins retry instead of hanging.
+struct InFlightSlot<'a> {
+    in_flight: &'a Mutex<HashMap<TokenKey, watch::Sender<Option<VerifyResult>>>>,
+    key: TokenKey,
+}
+
+impl Drop for InFlightSlot<'_> {
+    fn drop(&mut self) {
+        lock(self.in_flight).remove(&self.key);
+    }
+}
+
+// Cache state stays consistent across a panicking holder, so poisoning is ignored.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 impl AuthClient {
-    pub fn new(base_url: impl Into<String>, timeout: Duration) -> Result<Self, reqwest::Error> {
+    pub fn new(
+        base_url: impl Into<String>,
+        timeout: Duration,
+        secret: impl Into<Arc<str>>,
+    ) -> Result<Self, reqwest::Error> {
         let http = reqwest::Client::builder().timeout(timeout).build()?;
         Ok(Self {
             http,
             base_url: base_url.into(),
+            secret: secret.into(),
+            verify: Arc::new(VerifyState {
+                cache: Mutex::new(IdentityCache::new(
+                    VERIFY_CACHE_CAPACITY,
+                    VERIFY_CACHE_TTL,
+                    VERIFY_NEGATIVE_TTL,
+                )),
+                breaker: Mutex::new(CircuitBreaker::new(
+                    BREAKER_FAILURE_THRESHOLD,
+                    BREAKER_COOLDOWN,
+                )),
+                in_flight: Mutex::new(HashMap::new()),
+                local: LocalVerifier::default(),
+            }),
         })
     }
 
+    /// Resolves a session token, locally or from cache when possible.
+    ///
+    /// Signed tokens are checked against auth's published keys and revocation
+    /// list while the revocation poller keeps up. Other tokens go to auth:
+    /// concurrent calls for the same token share one upstream request, and
+    /// while auth keeps failing calls return `UpstreamUnavailable` without a
+    /// request.
     pub async fn verify_token(&self, token: &str) -> Result<VerifiedIdentity, VerifyTokenError> {
+        if let Some(signed) = SignedToken::parse(token)
+            && let Some(result) = self.verify_locally(&signed).await
+        {
+            return result;
+        }
+
+        let key = token_key(token);
+        let slot = loop {
+            if let Some(cached) = lock(&self.verify.cache).get(&key, Instant::now()) {
+                return cached;
+            }
+
+            let waiting = {
+                let mut in_flight = lock(&self.verify.in_flight);
+                match in_flight.get(&key) {
+                    Some(tx) => Some(tx.subscribe()),
+                    None => {
+                        in_flight.insert(key, watch::channel(None).0);
+                        None
+                    }
+                }
+            };
+            let Some(mut rx) = waiting else {
+                break InFlightSlot {
+                    in_flight: &self.verify.in_flight,
+                    key,
+                };
+            };
+            if let Ok(result) = rx.wait_for(Option::is_some).await
+                && let Some(result) = result.clone()
+            {
+                return result;
+            }
+            // The leading join was cancelled; look again and maybe lead.
+        };
+
+        let result = self.verify_upstream(token).await;
+        lock(&self.verify.cache).insert(key, &result, Instant::now(), unix_now());
+        if let Some(tx) = lock(&self.verify.in_flight).get(&key) {
+            tx.send_replace(Some(result.clone()));
+        }
+        drop(slot);
+        result
+    }
+
+    // `None` when auth has to decide; forged and revoked tokens are `InvalidToken`.
+    async fn verify_locally(&self, token: &SignedToken<'_>) -> Option<VerifyResult> {
+        let fetch_keys = || async {
+            self.fetch_keys()
+                .await
+                .inspect_err(
+                    |error| tracing::warn!(error = %error, "failed to fetch auth token keys"),
+                )
+                .ok()
+        };
+        let result = self.verify.local.verify(token, fetch_keys).await?;
+        Some(
+            result
+                .map(|claims| VerifiedIdentity {
+                    user_id: claims.user_id,
+                    display_name: claims.display_name,
+                    session_id: claims.session_id,
+                    expires_at: claims.expires_at,
+                })
+                .map_err(|rejection| match rejection {
+                    Rejection::Invalid => VerifyTokenError::InvalidToken,
+                    Rejection::Expired => VerifyTokenError::SessionExpired,
+                }),
+        )
+    }
+
+    async fn fetch_keys(&self) -> Result<KeySetResponse, reqwest::Error> {
+        let url = format!("{}/auth/keys", self.base_url);
+        self.http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<KeySetResponse>()
+            .await
+    }
+
+    async fn verify_upstream(&self, token: &str) -> VerifyResult {
+        if !lock(&self.verify.breaker).try_acquire(Instant::now()) {
+            return Err(VerifyTokenError::UpstreamUnavailable);
+        }
+
+        let result = self.request_verify(token).await;
+        let mut breaker = lock(&self.verify.breaker);
+        match result {
+            Err(VerifyTokenError::UpstreamUnavailable) => {
+                if breaker.record_failure(Instant::now()) {
+                    tracing::warn!(
+                        cooldown_secs = BREAKER_COOLDOWN.as_secs(),
+                        "auth unavailable; failing joins fast"
+                    );
+                }
+            }
+            _ => {
+                if breaker.record_success() {
+                    tracing::info!("auth reachable again; circuit closed");
+                }
+            }
+        }
+        result
+    }
+
+    async fn request_verify(&self, token: &str) -> VerifyResult {
         let url = format!("{}/auth/verify-token", self.base_url);
         let response = self
             .http
@@ -61,19 +275,364 @@ impl AuthClient {
                 .map_err(|_| VerifyTokenError::UpstreamUnavailable);
         }
 
-        if response.status() == StatusCode::UNAUTHORIZED {
-            let error = response
-                .json::<ErrorResponse>()
-                .await
-                .map_err(|_| VerifyTokenError::UpstreamUnavailable)?;
+        // Server errors say nothing about the token, whatever their body claims.
+        if response.status().is_server_error() {
+            return Err(VerifyTokenError::UpstreamUnavailable);
+        }
 
-            // TODO: switch to stable machine-readable error codes from auth.
-            if error.message == "session expired" {
-                return Err(VerifyTokenError::SessionExpired);
+        let error = response
+            .json::<ErrorResponse>()
+            .await
+            .map_err(|_| VerifyTokenError::UpstreamUnavailable)?;
+        match error.code {
+            AuthErrorCode::SessionExpired => Err(VerifyTokenError::SessionExpired),
+            AuthErrorCode::InvalidToken => Err(VerifyTokenError::InvalidToken),
+            // A refusal auth calls final will not verify this token later either.
+            AuthErrorCode::Other if !error.retryable => Err(VerifyTokenError::InvalidToken),
+            AuthErrorCode::Other => Err(VerifyTokenError::UpstreamUnavailable),
+        }
+    }
+
+    pub async fn fetch_revocations(&self, since: u64) -> Result<RevocationPage, reqwest::Error> {
+        let target = format!("/auth/revocations?since={since}");
+        let timestamp = unix_now();
+        let signature = sign(self.secret.as_bytes(), timestamp, "GET", &target, b"");
+        self.http
+            .get(format!("{}{target}", self.base_url))
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RevocationPage>()
+            .await
+    }
+
+    /// Polls auth's revocation list every `interval` and disconnects revoked sessions.
+    ///
+    /// The first poll replays every revocation auth still holds, so a restart
+    /// does not let revoked sessions keep playing. Signed tokens are verified
+    /// locally only while polls keep succeeding.
+    pub fn spawn_revocation_poller(self, registry: Arc<LobbyRegistry>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut cursor = 0;
+            loop {
+                ticker.tick().await;
+                match self.fetch_revocations(cursor).await {
+                    Ok(page) => {
+                        cursor = page.cursor;
+                        self.verify.local.record_revocations(
+                            page.revocations
+                                .iter()
+                                .map(|revoked| (revoked.session_id.clone(), revoked.expires_at)),
+                            interval,
+                        );
+                        let session_ids: Vec<String> = page
+                            .revocations
+                            .into_iter()
+                            .map(|revoked| revoked.session_id)
+                            .collect();
+                        // A revoked token must not rejoin from a cached identity.
+                        lock(&self.verify.cache).forget_sessions(&session_ids);
+                        registry.revoke_sessions(session_ids);
+                    }
+                    Err(error) => {
+                        tracing::warn!(error = %error, "failed to poll auth revocations")
+                    }
+                }
             }
-            return Err(VerifyTokenError::InvalidToken);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface_adapters::utils::signing::verify;
+    use axum::http::{HeaderMap, Uri};
+    use axum::{
+        Json, Router,
+        http::StatusCode as HttpStatus,
+        routing::{get, post},
+    };
+    use session_tokens::KEY_SET_MAX_AGE;
+    use session_tokens::test_support::{claims, key_set_json, sign_token};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TEST_SECRET: &str = "test-internal-secret";
+
+    // Serves `/auth/verify-token` with `status` and error `code`, counting requests and
+    // answering slowly enough for concurrent joins to overlap.
+    async fn spawn_auth(status: HttpStatus, code: &'static str) -> (String, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new().route(
+            "/auth/verify-token",
+            post(move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    let body = serde_json::json!({
+                        "user_id": 7,
+                        "display_name": "Pilot",
+                        "session_id": "session-7",
+                        "expires_at": unix_now() + 3600,
+                        "code": code,
+                        "message": "rejected by test auth",
+                        "retryable": false,
+                        "details": null,
+                    });
+                    (status, Json(body))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{address}"), calls)
+    }
+
+    #[tokio::test]
+    async fn concurrent_joins_share_one_lookup_and_later_ones_hit_the_cache() {
+        let (base_url, calls) = spawn_auth(HttpStatus::OK, "invalid_token").await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+
+        let joins: Vec<_> = (0..8)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.verify_token("token-7").await })
+            })
+            .collect();
+        for join in joins {
+            assert_eq!(join.await.unwrap().unwrap().user_id, 7);
+        }
+        assert_eq!(client.verify_token("token-7").await.unwrap().user_id, 7);
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn invalid_tokens_are_negatively_cached() {
+        let (base_url, calls) = spawn_auth(HttpStatus::UNAUTHORIZED, "invalid_token").await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                client.verify_token("bad-token").await,
+                Err(VerifyTokenError::InvalidToken)
+            ));
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_sessions_are_told_apart_by_error_code() {
+        let (base_url, _) = spawn_auth(HttpStatus::UNAUTHORIZED, "session_expired").await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+
+        assert!(matches!(
+            client.verify_token("old-token").await,
+            Err(VerifyTokenError::SessionExpired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn breaker_fails_fast_once_auth_keeps_failing() {
+        let (base_url, calls) =
+            spawn_auth(HttpStatus::INTERNAL_SERVER_ERROR, "invalid_token").await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+
+        for attempt in 0..BREAKER_FAILURE_THRESHOLD + 3 {
+            let result = client.verify_token(&format!("token-{attempt}")).await;
+            assert!(matches!(result, Err(VerifyTokenError::UpstreamUnavailable)));
+        }
+
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            BREAKER_FAILURE_THRESHOLD as usize
+        );
+    }
+
+    // Publishes the key for seed 1 as `key-1` and answers verify calls with 401.
+    async fn spawn_signing_auth() -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let verify_calls = Arc::new(AtomicUsize::new(0));
+        let key_fetches = Arc::new(AtomicUsize::new(0));
+        let (verify_counter, key_counter) = (verify_calls.clone(), key_fetches.clone());
+        let app = Router::new()
+            .route(
+                "/auth/verify-token",
+                post(move || {
+                    verify_counter.fetch_add(1, Ordering::SeqCst);
+                    async {
+                        (
+                            HttpStatus::UNAUTHORIZED,
+                            Json(serde_json::json!({
+                                "code": "invalid_token",
+                                "message": "invalid session token",
+                                "retryable": false,
+                                "details": null,
+                            })),
+                        )
+                    }
+                }),
+            )
+            .route(
+                "/auth/keys",
+                get(move || {
+                    key_counter.fetch_add(1, Ordering::SeqCst);
+                    async { Json(key_set_json(1, "key-1")) }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{address}"), verify_calls, key_fetches)
+    }
+
+    // Stands in for a revocation poll that just succeeded.
+    fn mark_revocations_polled(client: &AuthClient) {
+        client
+            .verify
+            .local
+            .record_revocations([], Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn signed_tokens_are_verified_locally_with_keys_fetched_once() {
+        let (base_url, verify_calls, key_fetches) = spawn_signing_auth().await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+        mark_revocations_polled(&client);
+        let token = sign_token(1, "key-1", claims(unix_now() + 3600));
+        let forged = sign_token(9, "key-1", claims(unix_now() + 3600));
+
+        for _ in 0..3 {
+            assert_eq!(client.verify_token(&token).await.unwrap().user_id, 42);
         }
+        assert!(matches!(
+            client.verify_token(&forged).await,
+            Err(VerifyTokenError::InvalidToken)
+        ));
+
+        assert_eq!(key_fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(verify_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn revoked_signed_tokens_are_refused_locally() {
+        let (base_url, verify_calls, _) = spawn_signing_auth().await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+        mark_revocations_polled(&client);
+        let token = sign_token(1, "key-1", claims(unix_now() + 3600));
+
+        client.verify.local.record_revocations(
+            [("session-1".to_string(), unix_now() + 3600)],
+            Duration::from_secs(60),
+        );
+
+        assert!(matches!(
+            client.verify_token(&token).await,
+            Err(VerifyTokenError::InvalidToken)
+        ));
+        assert_eq!(verify_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn tokens_under_unknown_keys_fall_back_to_auth_without_refetching_keys() {
+        let (base_url, verify_calls, key_fetches) = spawn_signing_auth().await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+        mark_revocations_polled(&client);
+        let first = sign_token(2, "key-2", claims(unix_now() + 3600));
+        let second = sign_token(2, "key-2", claims(unix_now() + 1800));
+
+        for token in [first, second] {
+            assert!(matches!(
+                client.verify_token(&token).await,
+                Err(VerifyTokenError::InvalidToken)
+            ));
+        }
+
+        assert_eq!(key_fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(verify_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn signed_tokens_go_to_auth_while_revocations_are_not_polled() {
+        let (base_url, verify_calls, key_fetches) = spawn_signing_auth().await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+        let token = sign_token(1, "key-1", claims(unix_now() + 3600));
+
+        // Auth refuses it, as it would a session revoked since the last poll.
+        assert!(matches!(
+            client.verify_token(&token).await,
+            Err(VerifyTokenError::InvalidToken)
+        ));
+
+        assert_eq!(verify_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(key_fetches.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn stale_key_sets_are_refetched_and_retired_keys_dropped() {
+        let (base_url, verify_calls, key_fetches) = spawn_signing_auth().await;
+        let client = AuthClient::new(base_url, Duration::from_secs(1), TEST_SECRET).unwrap();
+        mark_revocations_polled(&client);
+        let fetched_long_ago = Instant::now()
+            .checked_sub(KEY_SET_MAX_AGE + Duration::from_secs(1))
+            .unwrap();
+        client.verify.local.install_keys(
+            serde_json::from_value(key_set_json(2, "key-2")).unwrap(),
+            fetched_long_ago,
+        );
+        let retired = sign_token(2, "key-2", claims(unix_now() + 3600));
+
+        assert!(matches!(
+            client.verify_token(&retired).await,
+            Err(VerifyTokenError::InvalidToken)
+        ));
+
+        assert_eq!(key_fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(verify_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn revocation_polls_are_signed_with_the_service_secret() {
+        // Answers only polls signed with `TEST_SECRET`, as auth does.
+        let app = Router::new().route(
+            "/auth/revocations",
+            get(|uri: Uri, headers: HeaderMap| async move {
+                let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+                let signed = match (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER)) {
+                    (Some(timestamp), Some(signature)) => verify(
+                        TEST_SECRET.as_bytes(),
+                        timestamp,
+                        signature,
+                        "GET",
+                        &format!("/auth/revocations?{}", uri.query().unwrap_or_default()),
+                        b"",
+                        unix_now(),
+                    )
+                    .is_ok(),
+                    _ => false,
+                };
+                if !signed {
+                    return Err(HttpStatus::UNAUTHORIZED);
+                }
+                Ok(Json(serde_json::json!({ "revocations": [], "cursor": 3 })))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client =
+            AuthClient::new(base_url.clone(), Duration::from_secs(1), TEST_SECRET).unwrap();
+        assert_eq!(client.fetch_revocations(0).await.unwrap().cursor, 3);
 
-        Err(VerifyTokenError::UpstreamUnavailable)
+        let wrong_secret = AuthClient::new(base_url, Duration::from_secs(1), "other").unwrap();
+        assert!(wrong_secret.fetch_revocations(0).await.is_err());
     }
 }
@@ -0,0 +1,328 @@
+use crate::interface_adapters::clients::auth::{VerifiedIdentity, VerifyTokenError};
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Building blocks that keep join storms from turning into auth storms:
+// a bounded cache of verify results and a circuit breaker for auth outages.
+
+// Cache key; raw session tokens are never held in memory longer than a lookup.
+pub type TokenKey = [u8; 32];
+
+pub fn token_key(token: &str) -> TokenKey {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+pub type VerifyResult = Result<VerifiedIdentity, VerifyTokenError>;
+
+#[derive(Debug)]
+struct CacheEntry {
+    result: VerifyResult,
+    valid_until: Instant,
+}
+
+/// Bounded TTL cache of verify results keyed by token hash.
+///
+/// Identities live for `ttl` but never past the session's `expires_at`;
+/// rejections live for `negative_ttl`. Upstream failures are never cached.
+#[derive(Debug)]
+pub struct IdentityCache {
+    capacity: usize,
+    ttl: Duration,
+    negative_ttl: Duration,
+    entries: HashMap<TokenKey, CacheEntry>,
+}
+
+impl IdentityCache {
+    pub fn new(capacity: usize, ttl: Duration, negative_ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            negative_ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, key: &TokenKey, now: Instant) -> Option<VerifyResult> {
+        match self.entries.get(key) {
+            Some(entry) if entry.valid_until > now => Some(entry.result.clone()),
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches `result`; `unix_now` converts the session's `expires_at` into a deadline.
+    pub fn insert(&mut self, key: TokenKey, result: &VerifyResult, now: Instant, unix_now: u64) {
+        let lifetime = match result {
+            Ok(identity) => self.ttl.min(Duration::from_secs(
+                identity.expires_at.saturating_sub(unix_now),
+            )),
+            Err(VerifyTokenError::InvalidToken | VerifyTokenError::SessionExpired) => {
+                self.negative_ttl
+            }
+            Err(VerifyTokenError::UpstreamUnavailable) => return,
+        };
+        if lifetime.is_zero() || self.capacity == 0 {
+            return;
+        }
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.make_room(now);
+        }
+        self.entries.insert(
+            key,
+            CacheEntry {
+                result: result.clone(),
+                valid_until: now + lifetime,
+            },
+        );
+    }
+
+    /// Drops cached identities for sessions auth has revoked.
+    pub fn forget_sessions(&mut self, session_ids: &[String]) {
+        self.entries.retain(|_, entry| match &entry.result {
+            Ok(identity) => !session_ids.contains(&identity.session_id),
+            Err(_) => true,
+        });
+    }
+
+    // Expired entries go first; if none have, evict whichever expires soonest.
+    fn make_room(&mut self, now: Instant) {
+        self.entries.retain(|_, entry| entry.valid_until > now);
+        if self.entries.len() < self.capacity {
+            return;
+        }
+        if let Some(key) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.valid_until)
+            .map(|(key, _)| *key)
+        {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreakerState {
+    Closed { failures: u32 },
+    // Calls fail fast until `until`.
+    Open { until: Instant },
+    // One probe is in flight; another is allowed if it has not resolved by `retry_at`.
+    HalfOpen { retry_at: Instant },
+}
+
+/// Fails auth calls fast after repeated upstream failures.
+///
+/// After `failure_threshold` consecutive failures the breaker opens for
+/// `cooldown`, then lets a single probe through; its outcome closes or reopens it.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: BreakerState,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: BreakerState::Closed { failures: 0 },
+        }
+    }
+
+    /// Returns whether a call may go upstream at `now`.
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        match self.state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } | BreakerState::HalfOpen { retry_at: until }
+                if now >= until =>
+            {
+                self.state = BreakerState::HalfOpen {
+                    retry_at: now + self.cooldown,
+                };
+                true
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Returns true when this success closed an open breaker.
+    pub fn record_success(&mut self) -> bool {
+        let recovered = !matches!(self.state, BreakerState::Closed { .. });
+        self.state = BreakerState::Closed { failures: 0 };
+        recovered
+    }
+
+    /// Returns true when this failure opened the breaker.
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        let failures = match self.state {
+            BreakerState::Closed { failures } => failures + 1,
+            // A failed probe reopens immediately.
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => self.failure_threshold,
+        };
+        if failures >= self.failure_threshold {
+            let was_open = matches!(self.state, BreakerState::Open { .. });
+            self.state = BreakerState::Open {
+                until: now + self.cooldown,
+            };
+            !was_open
+        } else {
+            self.state = BreakerState::Closed { failures };
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIX_NOW: u64 = 1_700_000_000;
+
+    fn identity(session_id: &str, expires_at: u64) -> VerifyResult {
+        Ok(VerifiedIdentity {
+            user_id: 7,
+            display_name: "Pilot".into(),
+            session_id: session_id.into(),
+            expires_at,
+        })
+    }
+
+    fn cache() -> IdentityCache {
+        IdentityCache::new(2, Duration::from_secs(60), Duration::from_secs(10))
+    }
+
+    #[test]
+    fn identities_are_cached_until_the_ttl_or_session_expiry() {
+        let now = Instant::now();
+        let mut cache = cache();
+        let long = token_key("long");
+        let short = token_key("short");
+        cache.insert(long, &identity("s-long", UNIX_NOW + 3600), now, UNIX_NOW);
+        cache.insert(short, &identity("s-short", UNIX_NOW + 5), now, UNIX_NOW);
+
+        let later = now + Duration::from_secs(6);
+        assert!(matches!(cache.get(&long, later), Some(Ok(_))));
+        assert!(cache.get(&short, later).is_none());
+        assert!(cache.get(&long, now + Duration::from_secs(61)).is_none());
+    }
+
+    #[test]
+    fn rejections_are_cached_briefly_and_outages_not_at_all() {
+        let now = Instant::now();
+        let mut cache = cache();
+        let invalid = token_key("invalid");
+        let outage = token_key("outage");
+        cache.insert(invalid, &Err(VerifyTokenError::InvalidToken), now, UNIX_NOW);
+        cache.insert(
+            outage,
+            &Err(VerifyTokenError::UpstreamUnavailable),
+            now,
+            UNIX_NOW,
+        );
+
+        assert!(matches!(
+            cache.get(&invalid, now + Duration::from_secs(9)),
+            Some(Err(VerifyTokenError::InvalidToken))
+        ));
+        assert!(cache.get(&invalid, now + Duration::from_secs(10)).is_none());
+        assert!(cache.get(&outage, now).is_none());
+    }
+
+    #[test]
+    fn full_cache_evicts_the_entry_expiring_soonest() {
+        let now = Instant::now();
+        let mut cache = cache();
+        cache.insert(
+            token_key("a"),
+            &identity("s-a", UNIX_NOW + 3600),
+            now,
+            UNIX_NOW,
+        );
+        cache.insert(
+            token_key("b"),
+            &Err(VerifyTokenError::InvalidToken),
+            now,
+            UNIX_NOW,
+        );
+        cache.insert(
+            token_key("c"),
+            &identity("s-c", UNIX_NOW + 3600),
+            now,
+            UNIX_NOW,
+        );
+
+        assert!(cache.get(&token_key("b"), now).is_none());
+        assert!(cache.get(&token_key("a"), now).is_some());
+        assert!(cache.get(&token_key("c"), now).is_some());
+    }
+
+    #[test]
+    fn revoked_sessions_are_forgotten() {
+        let now = Instant::now();
+        let mut cache = cache();
+        cache.insert(
+            token_key("a"),
+            &identity("s-a", UNIX_NOW + 3600),
+            now,
+            UNIX_NOW,
+        );
+
+        cache.forget_sessions(&["s-a".to_string()]);
+
+        assert!(cache.get(&token_key("a"), now).is_none());
+    }
+
+    #[test]
+    fn breaker_opens_after_repeated_failures_and_probes_after_cooldown() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(3, Duration::from_secs(10));
+
+        assert!(!breaker.record_failure(now));
+        assert!(!breaker.record_failure(now));
+        assert!(breaker.record_failure(now));
+        assert!(!breaker.try_acquire(now + Duration::from_secs(9)));
+
+        // One probe after the cooldown; others keep failing fast meanwhile.
+        let probe_at = now + Duration::from_secs(10);
+        assert!(breaker.try_acquire(probe_at));
+        assert!(!breaker.try_acquire(probe_at));
+
+        // A failed probe reopens for another cooldown.
+        assert!(breaker.record_failure(probe_at));
+        assert!(!breaker.try_acquire(probe_at + Duration::from_secs(5)));
+
+        let second_probe = probe_at + Duration::from_secs(10);
+        assert!(breaker.try_acquire(second_probe));
+        assert!(breaker.record_success());
+        assert!(breaker.try_acquire(second_probe));
+    }
+
+    #[test]
+    fn breaker_allows_another_probe_if_the_first_never_resolves() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        breaker.record_failure(now);
+
+        let probe_at = now + Duration::from_secs(10);
+        assert!(breaker.try_acquire(probe_at));
+        assert!(breaker.try_acquire(probe_at + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn successes_reset_the_failure_count() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+
+        breaker.record_failure(now);
+        assert!(!breaker.record_success());
+        assert!(!breaker.record_failure(now));
+        assert!(breaker.try_acquire(now));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::interface_adapters::utils::signing::{
+    SIGNATURE_HEADER, TIMESTAMP_HEADER, sign, unix_now,
+};
+use crate::use_cases::{AbortReason, LobbyRegistry, LobbyReporter, ServerLoad};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+// Lobby lifecycle report sent to head when a lobby ends without a match.
+#[derive(Debug, Serialize)]
+struct LobbyAbortedRequest {
+    reason: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    joined_players: Option<Vec<u64>>,
+}
+
+// Occupancy fields shared by registration and heartbeat bodies.
+#[derive(Debug, Serialize)]
+struct LoadBody {
+    lobbies: usize,
+    players: usize,
+    connections: usize,
+    draining: bool,
+}
+
+impl From<&ServerLoad> for LoadBody {
+    fn from(load: &ServerLoad) -> Self {
+        Self {
+            lobbies: load.lobbies,
+            players: load.players,
+            connections: load.connections,
+            draining: load.draining,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HeartbeatRequest<'a> {
+    server_id: &'a str,
+    #[serde(flatten)]
+    load: LoadBody,
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterRequest<'a> {
+    server_id: &'a str,
+    region: &'a str,
+    ws_url: &'a str,
+    base_url: &'a str,
+    max_lobbies: usize,
+    max_connections: usize,
+    #[serde(flatten)]
+    load: LoadBody,
+}
+
+/// How this server identifies itself in head's game-server directory.
+#[derive(Clone, Debug)]
+pub struct ServerRegistration {
+    pub server_id: String,
+    pub region: String,
+    pub ws_url: String,
+    pub base_url: String,
+}
+
+// Thin reqwest client for reporting lobby lifecycle events to head.
+#[derive(Clone, Debug)]
+pub struct HeadClient {
+    http: reqwest::Client,
+    base_url: String,
+    // Shared secret used to sign requests to head's internal routes.
+    secret: Arc<str>,
+}
+
+impl HeadClient {
+    pub fn new(
+        base_url: impl Into<String>,
+        timeout: Duration,
+        secret: impl Into<Arc<str>>,
+    ) -> Result<Self, reqwest::Error> {
+        let http = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.into(),
+            secret: secret.into(),
+        })
+    }
+
+    pub async fn report_lobby_aborted(
+        &self,
+        lobby_id: &str,
+        reason: &AbortReason,
+    ) -> Result<(), reqwest::Error> {
+        let path = format!("/internal/lobbies/{lobby_id}/aborted");
+        let body = match reason {
+            AbortReason::RosterNoShow { present, required } => LobbyAbortedRequest {
+                reason: "roster_no_show",
+                required: Some(*required),
+                joined_players: Some(present.clone()),
+            },
+            AbortReason::ClosedByAdmin => LobbyAbortedRequest {
+                reason: "closed_by_admin",
+                required: None,
+                joined_players: None,
+            },
+            AbortReason::ServerDraining => LobbyAbortedRequest {
+                reason: "server_draining",
+                required: None,
+                joined_players: None,
+            },
+            AbortReason::WorldCrashed => LobbyAbortedRequest {
+                reason: "world_crashed",
+                required: None,
+                joined_players: None,
+            },
+            AbortReason::Abandoned => LobbyAbortedRequest {
+                reason: "abandoned",
+                required: None,
+                joined_players: None,
+            },
+        };
+
+        self.post_signed(&path, &body).await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Announces this server and its limits to head's directory.
+    pub async fn register(
+        &self,
+        registration: &ServerRegistration,
+        load: &ServerLoad,
+    ) -> Result<(), reqwest::Error> {
+        let body = RegisterRequest {
+            server_id: &registration.server_id,
+            region: &registration.region,
+            ws_url: &registration.ws_url,
+            base_url: &registration.base_url,
+            max_lobbies: load.max_lobbies,
+            max_connections: load.max_connections,
+            load: load.into(),
+        };
+        self.post_signed("/internal/game-servers", &body)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Reports current load; returns false when head no longer knows this server.
+    pub async fn heartbeat(
+        &self,
+        server_id: &str,
+        load: &ServerLoad,
+    ) -> Result<bool, reqwest::Error> {
+        let body = HeartbeatRequest {
+            server_id,
+            load: load.into(),
+        };
+        let response = self
+            .post_signed("/internal/game-servers/heartbeat", &body)
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+
+    /// Registers with head, then heartbeats every `interval` for the life of the process.
+    ///
+    /// Registration is retried on failure and repeated whenever head forgets this server.
+    pub fn spawn_heartbeat(
+        self,
+        registration: ServerRegistration,
+        registry: Arc<LobbyRegistry>,
+        interval: Duration,
+    ) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut registered = false;
+            loop {
+                ticker.tick().await;
+                let load = registry.load().await;
+                if !registered {
+                    match self.register(&registration, &load).await {
+                        Ok(()) => {
+                            tracing::info!(
+                                server_id = %registration.server_id,
+                                region = %registration.region,
+                                "registered with head"
+                            );
+                            registered = true;
+                        }
+                        Err(error) => {
+                            tracing::warn!(error = %error, "failed to register with head");
+                        }
+                    }
+                    continue;
+                }
+
+                match self.heartbeat(&registration.server_id, &load).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::warn!("head does not know this server; re-registering");
+                        registered = false;
+                    }
+                    Err(error) => {
+                        tracing::warn!(error = %error, "failed to send heartbeat to head")
+                    }
+                }
+            }
+        });
+    }
+
+    // Sign the exact bytes we send so head can verify them before parsing.
+    async fn post_signed(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let body = serde_json::to_vec(body).expect("head request body serializes");
+        let timestamp = unix_now();
+        let signature = sign(self.secret.as_bytes(), timestamp, "POST", path, &body);
+
+        self.http
+            .post(format!("{}{path}", self.base_url))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+    }
+}
+
+impl LobbyReporter for HeadClient {
+    fn lobby_aborted(&self, lobby_id: &str, reason: &AbortReason) {
+        // Reports must not stall lobby teardown; head tolerates missed reports.
+        let client = self.clone();
+        let lobby_id = lobby_id.to_string();
+        let reason = reason.clone();
+        tokio::spawn(async move {
+            if let Err(error) = client.report_lobby_aborted(&lobby_id, &reason).await {
+                tracing::warn!(lobby_id = %lobby_id, error = %error, "failed to report lobby abort");
+            }
+        });
+    }
+}
@@ -1,3 +1,5 @@
 // Outbound service clients used by interface adapters.
 
 pub mod auth;
+pub mod auth_cache;
+pub mod head;
@@ -1,11 +1,76 @@
-use axum::Json;
+use crate::interface_adapters::state::AppState;
+
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header::RETRY_AFTER},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
 
 // Shared HTTP response types for consistent API error payloads.
 
+// Seconds callers should wait before retrying a request refused for capacity.
+const CAPACITY_RETRY_AFTER_SECS: u64 = 5;
+
+// Error envelope shared by every service; codes are listed in ERROR_CODES.md.
 #[derive(Debug, serde::Serialize)]
 pub struct ErrorResponse {
-    // Human-readable error string for consistent JSON error responses.
-    pub error: String,
+    pub code: ErrorCode,
+    // Human-readable error string; clients branch on `code` instead.
+    pub message: String,
+    pub retryable: bool,
+    pub details: Option<serde_json::Value>,
+}
+
+// Stable error codes returned by the game server's HTTP endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    Unauthorized,
+    LobbyNotFound,
+    PlayerNotConnected,
+    LobbyExists,
+    LobbyPinned,
+    LobbyStopped,
+    RosterTooLarge,
+    LobbyFull,
+    ServerFull,
+    ServerDraining,
+}
+
+impl ErrorCode {
+    // Whether the same request may succeed when sent again after a short wait.
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::LobbyFull | ErrorCode::ServerFull | ErrorCode::ServerDraining
+        )
+    }
+}
+
+pub fn error_response(status: StatusCode, code: ErrorCode, message: &str) -> Response {
+    (status, Json(error_body(code, message))).into_response()
+}
+
+// 503 with a `Retry-After` hint for requests refused because the server is full.
+pub fn capacity_response(code: ErrorCode, message: &str) -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(RETRY_AFTER, CAPACITY_RETRY_AFTER_SECS.to_string())],
+        Json(error_body(code, message)),
+    )
+        .into_response()
+}
+
+fn error_body(code: ErrorCode, message: &str) -> ErrorResponse {
+    ErrorResponse {
+        code,
+        message: message.to_string(),
+        retryable: code.retryable(),
+        details: None,
+    }
 }
 
 #[derive(Debug, serde::Serialize, PartialEq, Eq)]
@@ -14,17 +79,50 @@ pub struct HealthResponse {
 }
 
 // Lightweight liveness endpoint for container smoke checks.
-pub async fn health() -> Json<HealthResponse> {
-    Json(HealthResponse { status: "ok" })
+//
+// Stays 200 while draining so orchestrators do not kill the process mid-drain.
+pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+    Json(health_status(state.lobby_registry.is_draining()))
+}
+
+fn health_status(draining: bool) -> HealthResponse {
+    HealthResponse {
+        status: if draining { "draining" } else { "ok" },
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn health_returns_ok_status() {
-        let response = health().await;
-        assert_eq!(response.0, HealthResponse { status: "ok" });
+    #[test]
+    fn health_returns_ok_status() {
+        assert_eq!(health_status(false), HealthResponse { status: "ok" });
+        assert_eq!(health_status(true), HealthResponse { status: "draining" });
+    }
+
+    #[test]
+    fn capacity_response_carries_retry_hint() {
+        let response = capacity_response(ErrorCode::ServerFull, "server at capacity");
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(RETRY_AFTER).unwrap(),
+            &CAPACITY_RETRY_AFTER_SECS.to_string()
+        );
+    }
+
+    #[test]
+    fn error_body_serializes_the_shared_envelope() {
+        let body = serde_json::to_value(error_body(ErrorCode::ServerDraining, "draining")).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "code": "server_draining",
+                "message": "draining",
+                "retryable": true,
+                "details": null,
+            })
+        );
+        assert!(!ErrorCode::LobbyNotFound.retryable());
     }
 }
@@ -0,0 +1,208 @@
+use crate::interface_adapters::http::{ErrorCode, capacity_response, error_response};
+use crate::interface_adapters::protocol::ServerStateDto;
+use crate::interface_adapters::state::AppState;
+use crate::use_cases::{LobbyError, LobbySummary};
+
+use axum::{
+    extract::{Json, Path, Request, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+// Operator endpoints for inspecting and controlling lobbies.
+
+#[derive(Debug, serde::Serialize)]
+pub struct LobbySummaryResponse {
+    lobby_id: String,
+    state: ServerStateDto,
+    // Latest simulated tick.
+    tick: u64,
+    pinned: bool,
+    // Players allowed to spawn (empty means open lobby).
+    roster: Vec<u64>,
+    // Players with a live connection.
+    connected_players: Vec<u64>,
+    // All sockets, players and spectators.
+    connections: usize,
+    spectators: usize,
+}
+
+impl From<LobbySummary> for LobbySummaryResponse {
+    fn from(summary: LobbySummary) -> Self {
+        Self {
+            lobby_id: summary.lobby_id.to_string(),
+            state: summary.state.into(),
+            tick: summary.tick,
+            pinned: summary.is_pinned,
+            roster: summary.roster,
+            connected_players: summary.connected_players,
+            connections: summary.connections,
+            spectators: summary.spectators,
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct LobbyListResponse {
+    lobbies: Vec<LobbySummaryResponse>,
+}
+
+/// Rejects admin requests that do not carry the configured bearer token.
+pub async fn require_admin_token(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = state
+        .admin_token
+        .as_deref()
+        .is_some_and(|expected| bearer_matches(request.headers(), expected));
+    if !authorized {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "admin token required",
+        );
+    }
+    next.run(request).await
+}
+
+pub async fn list_lobbies_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let lobbies = state.lobby_registry.list_lobbies().await;
+    Json(LobbyListResponse {
+        lobbies: lobbies.into_iter().map(Into::into).collect(),
+    })
+}
+
+pub async fn get_lobby_handler(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<String>,
+) -> Response {
+    match state.lobby_registry.get_lobby(&lobby_id).await {
+        Some(lobby) => Json(LobbySummaryResponse::from(lobby.summary().await)).into_response(),
+        None => lobby_error_response(LobbyError::NotFound),
+    }
+}
+
+pub async fn delete_lobby_handler(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<String>,
+) -> Response {
+    match state.lobby_registry.close_lobby(&lobby_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(error) => lobby_error_response(error),
+    }
+}
+
+pub async fn kick_player_handler(
+    State(state): State<Arc<AppState>>,
+    Path((lobby_id, player_id)): Path<(String, u64)>,
+) -> Response {
+    let Some(lobby) = state.lobby_registry.get_lobby(&lobby_id).await else {
+        return lobby_error_response(LobbyError::NotFound);
+    };
+    match lobby.kick_player(player_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(error) => lobby_error_response(error),
+    }
+}
+
+pub async fn end_match_handler(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<String>,
+) -> Response {
+    let Some(lobby) = state.lobby_registry.get_lobby(&lobby_id).await else {
+        return lobby_error_response(LobbyError::NotFound);
+    };
+    match lobby.end_match().await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(error) => lobby_error_response(error),
+    }
+}
+
+/// Maps registry errors to the shared JSON error schema.
+pub(crate) fn lobby_error_response(error: LobbyError) -> Response {
+    let (status, code, message) = match error {
+        LobbyError::AtCapacity => {
+            return capacity_response(ErrorCode::ServerFull, "server at lobby capacity");
+        }
+        LobbyError::Draining => {
+            return capacity_response(ErrorCode::ServerDraining, "server is draining");
+        }
+        LobbyError::AlreadyExists => (
+            StatusCode::CONFLICT,
+            ErrorCode::LobbyExists,
+            "lobby already exists",
+        ),
+        LobbyError::RosterTooLarge => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::RosterTooLarge,
+            "roster exceeds max players per lobby",
+        ),
+        LobbyError::NotFound => (
+            StatusCode::NOT_FOUND,
+            ErrorCode::LobbyNotFound,
+            "lobby not found",
+        ),
+        LobbyError::Pinned => (
+            StatusCode::CONFLICT,
+            ErrorCode::LobbyPinned,
+            "lobby is pinned",
+        ),
+        LobbyError::PlayerNotConnected => (
+            StatusCode::NOT_FOUND,
+            ErrorCode::PlayerNotConnected,
+            "player not connected",
+        ),
+        LobbyError::WorldStopped => (
+            StatusCode::CONFLICT,
+            ErrorCode::LobbyStopped,
+            "lobby world has stopped",
+        ),
+    };
+    error_response(status, code, message)
+}
+
+// Constant-time comparison so response timing does not leak the token prefix.
+fn bearer_matches(headers: &HeaderMap, expected: &str) -> bool {
+    let Some(provided) = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+
+    let (provided, expected) = (provided.trim().as_bytes(), expected.as_bytes());
+    if provided.len() != expected.len() {
+        return false;
+    }
+    provided
+        .iter()
+        .zip(expected)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers_with(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn bearer_matches_only_the_exact_token() {
+        assert!(bearer_matches(&headers_with("Bearer s3cret"), "s3cret"));
+        assert!(!bearer_matches(&headers_with("Bearer s3cre"), "s3cret"));
+        assert!(!bearer_matches(&headers_with("Bearer s3creT"), "s3cret"));
+        assert!(!bearer_matches(&headers_with("s3cret"), "s3cret"));
+        assert!(!bearer_matches(&HeaderMap::new(), "s3cret"));
+    }
+}
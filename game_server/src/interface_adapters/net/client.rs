@@ -1,15 +1,21 @@
 use crate::domain::PlayerInput;
 use crate::interface_adapters::clients::auth::{AuthClient, VerifyTokenError};
-use crate::interface_adapters::http::ErrorResponse;
+use crate::interface_adapters::http::{ErrorCode, capacity_response, error_response};
+use crate::interface_adapters::net::metrics::{NetMetrics, SocketOffense};
 use crate::interface_adapters::protocol::{
-    ClientMessage, PlayerInputDto, ServerMessage, WorldUpdateDto,
+    ClientMessage, FollowPayload, PlayerInputDto, ReconnectHintDto, ServerMessage, WorldUpdateDto,
 };
 use crate::interface_adapters::state::AppState;
+use crate::interface_adapters::utils::rate_limit::{RateDecision, RateLimitPolicy, RateLimiter};
 use crate::interface_adapters::utils::rng::rand_id;
-use crate::use_cases::{GameEvent, LobbyHandle, LobbyRegistry, ServerState, WorldUpdate};
+use crate::interface_adapters::utils::signing::unix_now;
+use crate::use_cases::{
+    AbortReason, ConnectionPermit, GameEvent, JoinMode, JoinRejection, LobbyHandle, LobbyRegistry,
+    ServerState, ShutdownReason, WorldFrame, WorldUpdate,
+};
 
 use axum::{
-    Error, Json,
+    Error,
     extract::{
         Query, State,
         ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
@@ -19,6 +25,7 @@ use axum::{
 };
 use futures::SinkExt;
 use std::{
+    collections::VecDeque,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -40,6 +47,7 @@ enum NetError {
     JoinRequired,
     JoinTimeout,
     AuthVerify,
+    JoinRejected(JoinRejection),
     ClosedBeforeJoin,
 }
 
@@ -52,14 +60,14 @@ pub struct LobbyQuery {
 
 pub async fn world_update_serializer(
     mut world_rx: broadcast::Receiver<WorldUpdate>,
-    world_bytes_tx: broadcast::Sender<String>,
+    world_bytes_tx: broadcast::Sender<WorldFrame>,
     world_latest_tx: watch::Sender<String>,
 ) {
     // Serialize each world update once and broadcast the shared bytes.
     loop {
         match world_rx.recv().await {
             Ok(update) => {
-                let msg = ServerMessage::WorldUpdate(WorldUpdateDto::from(update));
+                let msg = ServerMessage::WorldUpdate(WorldUpdateDto::from(&update));
                 let txt = match serde_json::to_string(&msg) {
                     Ok(txt) => txt,
                     Err(e) => {
@@ -70,7 +78,10 @@ pub async fn world_update_serializer(
 
                 // Store the latest payload for lag recovery and broadcast to clients.
                 let _ = world_latest_tx.send(txt.clone());
-                let _ = world_bytes_tx.send(txt);
+                let _ = world_bytes_tx.send(WorldFrame {
+                    update: Arc::new(update),
+                    bytes: txt,
+                });
             }
             Err(broadcast::error::RecvError::Lagged(n)) => {
                 warn!(
@@ -86,6 +97,47 @@ pub async fn world_update_serializer(
     }
 }
 
+pub async fn spectator_feed_relay<T: Clone>(
+    mut world_bytes_rx: broadcast::Receiver<T>,
+    spectator_bytes_tx: broadcast::Sender<T>,
+    delay: Duration,
+) {
+    // Hold each serialized update until it is `delay` old, then release it to spectators.
+    let mut pending: VecDeque<(tokio::time::Instant, T)> = VecDeque::new();
+    loop {
+        let next_release = pending.front().map(|(release_at, _)| *release_at);
+        tokio::select! {
+            received = world_bytes_rx.recv() => match received {
+                Ok(bytes) => {
+                    // Skip buffering entirely while nobody is spectating.
+                    if spectator_bytes_tx.receiver_count() == 0 {
+                        pending.clear();
+                        continue;
+                    }
+                    pending.push_back((tokio::time::Instant::now() + delay, bytes));
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(missed = n, "spectator relay lagged; feed has a gap");
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    debug!("world bytes channel closed; spectator relay exiting");
+                    break;
+                }
+            },
+            _ = tokio::time::sleep_until(next_release.unwrap_or_else(tokio::time::Instant::now)),
+                if next_release.is_some() =>
+            {
+                let now = tokio::time::Instant::now();
+                while pending.front().is_some_and(|(release_at, _)| *release_at <= now) {
+                    if let Some((_, bytes)) = pending.pop_front() {
+                        let _ = spectator_bytes_tx.send(bytes);
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub fn spawn_lobby_serializer(lobby: &LobbyHandle) {
     // Spawn a task that serializes world updates for this lobby.
     tokio::spawn(world_update_serializer(
@@ -93,6 +145,12 @@ pub fn spawn_lobby_serializer(lobby: &LobbyHandle) {
         lobby.world_bytes_tx.clone(),
         lobby.world_latest_tx.clone(),
     ));
+    // Spectators read a delayed copy of the serialized stream.
+    tokio::spawn(spectator_feed_relay(
+        lobby.world_bytes_tx.subscribe(),
+        lobby.spectator_bytes_tx.clone(),
+        lobby.spectator_feed_delay,
+    ));
 }
 
 impl From<axum::Error> for NetError {
@@ -114,19 +172,42 @@ pub async fn ws_handler(
         Some(lobby) => lobby,
         None => {
             // Keep not-found responses consistent with the JSON error schema.
-            return (
+            return error_response(
                 StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: "lobby not found".to_string(),
-                }),
-            )
-                .into_response();
+                ErrorCode::LobbyNotFound,
+                "lobby not found",
+            );
         }
     };
 
+    // Refuse before upgrading so a full server never spends a socket on the handshake.
+    if !lobby.has_connection_capacity() {
+        warn!(lobby_id = %lobby_id, "lobby full; refusing upgrade");
+        return capacity_response(ErrorCode::LobbyFull, "lobby is full");
+    }
+    let Some(permit) = state.lobby_registry.try_acquire_connection() else {
+        warn!(lobby_id = %lobby_id, "connection cap reached; refusing upgrade");
+        return capacity_response(ErrorCode::ServerFull, "server at connection capacity");
+    };
+
     let lobby_registry = state.lobby_registry.clone();
     let auth_client = state.auth_client.clone();
-    ws.on_upgrade(move |socket| handle_socket(socket, lobby, lobby_registry, auth_client))
+    let net_metrics = state.net_metrics.clone();
+    let enforce_session_expiry = state.enforce_session_expiry;
+    // Frames beyond the transport cap are never buffered; anything between the
+    // application cap and this one is refused with a close frame instead.
+    ws.max_message_size(MAX_TRANSPORT_MESSAGE_BYTES)
+        .on_upgrade(move |socket| {
+            handle_socket(
+                socket,
+                lobby,
+                lobby_registry,
+                auth_client,
+                net_metrics,
+                enforce_session_expiry,
+                permit,
+            )
+        })
 }
 
 async fn handle_socket(
@@ -134,6 +215,10 @@ async fn handle_socket(
     lobby: LobbyHandle,
     lobby_registry: Arc<LobbyRegistry>,
     auth_client: Arc<AuthClient>,
+    net_metrics: Arc<NetMetrics>,
+    enforce_session_expiry: bool,
+    // Held for the socket's lifetime; dropping it frees the server-wide slot.
+    _permit: ConnectionPermit,
 ) {
     // Separate connection id for correlating logs before/after a player_id exists.
     let conn_id = rand_id();
@@ -145,6 +230,8 @@ async fn handle_socket(
         &lobby,
         lobby_registry.clone(),
         auth_client,
+        net_metrics,
+        enforce_session_expiry,
     )
     .await
     {
@@ -153,6 +240,11 @@ async fn handle_socket(
             info!("client disconnected before join handshake");
             return;
         }
+        Err(NetError::JoinRejected(reason)) => {
+            // The client was already told why and the socket is closed.
+            info!(?reason, "join rejected");
+            return;
+        }
         Err(e) => {
             error!(error = ?e, "failed to bootstrap connection");
             let _ = socket
@@ -176,6 +268,9 @@ async fn handle_socket(
         ctx.lobby
             .unregister_player_connection_if_owner(ctx.player_id, ctx.player_conn_token)
             .await;
+        if !ctx.can_spawn {
+            ctx.lobby.release_spectator_slot();
+        }
         // The lobby can be removed between lookup and registration during shutdown.
         warn!(lobby_id = %ctx.lobby_id, "lobby missing during connection registration");
         // Best-effort cleanup in case the lobby was removed after bootstrap.
@@ -203,6 +298,7 @@ async fn handle_socket(
         player_id = ctx.player_id,
         session_id = %ctx.session_id,
         display_name = %ctx.display_name,
+        spectator = !ctx.can_spawn,
         "client connected"
     );
 
@@ -241,10 +337,21 @@ struct ConnCtx {
     // Whether the connection has been registered in the lobby counter.
     pub registered: bool,
     pub input_tx: mpsc::Sender<GameEvent>,
-    pub world_bytes_rx: broadcast::Receiver<String>,
+    pub world_bytes_rx: broadcast::Receiver<WorldFrame>,
     pub world_latest_rx: watch::Receiver<String>,
     pub server_state_rx: watch::Receiver<ServerState>,
+    // Process-wide shutdown notice published when a drain completes.
+    pub server_shutdown_rx: watch::Receiver<Option<ShutdownReason>>,
+    // Session ids revoked by auth; the connection closes if its own is among them.
+    pub session_revocations_rx: broadcast::Receiver<Arc<[String]>>,
+    // Auth session expiry, checked at match state changes when enforcement is on.
+    pub session_expires_at: Option<u64>,
+    // Players spawn ships; spectators read the delayed feed instead.
     pub can_spawn: bool,
+    // Player the spectator asked to follow, if any.
+    pub follow_target: Option<u64>,
+    // When a spectator is closed after the match ends (once the delayed feed drains).
+    pub spectator_close_at: Option<tokio::time::Instant>,
     // Count lag recovery snapshots sent to this client.
     pub lag_recovery_count: u64,
 
@@ -254,6 +361,8 @@ struct ConnCtx {
     pub bytes_out: u64,
 
     pub invalid_json: u32,
+    // Inbound rate and size limits applied before messages are parsed.
+    pub inbound_guard: InboundGuard,
 
     pub last_input_full_log: Instant,
     pub last_world_lag_log: Instant,
@@ -267,6 +376,8 @@ struct JoinHandshake {
     player_id: u64,
     session_id: String,
     display_name: String,
+    mode: JoinMode,
+    expires_at: u64,
     bytes_in: u64,
     msgs_in: u64,
 }
@@ -276,16 +387,21 @@ async fn bootstrap_connection(
     lobby: &LobbyHandle,
     lobby_registry: Arc<LobbyRegistry>,
     auth_client: Arc<AuthClient>,
+    net_metrics: Arc<NetMetrics>,
+    enforce_session_expiry: bool,
 ) -> Result<ConnCtx, NetError> {
     // Subscribe to updates *before* doing anything else (awaits) to not miss packets.
-    let world_bytes_rx = lobby.world_bytes_tx.subscribe();
+    // The role is unknown until Join, so subscribe to both feeds and keep one.
+    let live_bytes_rx = lobby.world_bytes_tx.subscribe();
+    let spectator_bytes_rx = lobby.spectator_bytes_tx.subscribe();
     let world_latest_rx = lobby.world_latest_tx.subscribe();
     let server_state_rx = lobby.server_state_tx.subscribe();
+    let session_revocations_rx = lobby_registry.subscribe_session_revocations();
 
     // Authenticate the very first meaningful client message before assigning player ownership.
     let join = match timeout(
         JOIN_HANDSHAKE_TIMEOUT,
-        read_join_handshake(socket, auth_client.as_ref()),
+        read_join_handshake(socket, auth_client.as_ref(), &net_metrics),
     )
     .await
     {
@@ -297,30 +413,46 @@ async fn bootstrap_connection(
     };
     let player_id = join.player_id;
 
+    // Honor the explicit join intent; refuse instead of silently downgrading.
+    if let Err(rejection) = lobby.admit(player_id, join.mode) {
+        return Err(reject_join(socket, rejection).await);
+    }
+    let can_spawn = join.mode == JoinMode::Player;
+    let world_bytes_rx = if can_spawn {
+        live_bytes_rx
+    } else {
+        spectator_bytes_rx
+    };
+
     // Handshake & ID Assignment
     // Player identity is the canonical verified user id from auth.
     // Track this connection with a unique token so newer connections can replace it.
+    // Spectators never own a player slot, so watching cannot kick a live player.
     let player_conn_token = rand_id();
-    let player_conn_shutdown = lobby
-        .register_or_replace_player_connection(player_id, player_conn_token)
-        .await;
+    let player_conn_shutdown = if can_spawn {
+        match lobby
+            .register_or_replace_player_connection(player_id, player_conn_token)
+            .await
+        {
+            Ok(shutdown) => shutdown,
+            Err(rejection) => return Err(reject_join(socket, rejection).await),
+        }
+    } else {
+        Arc::new(Notify::new())
+    };
 
     // Send Identity Packet
     // Tell the client "This is who you are".
     let identity_msg = ServerMessage::Identity {
         player_id: player_id.to_string(),
+        mode: join.mode.into(),
     };
     if let Err(err) = send_message(socket, &identity_msg).await {
         // Ensure the player slot is freed if we fail the handshake early.
-        lobby
-            .unregister_player_connection_if_owner(player_id, player_conn_token)
-            .await;
+        release_join_slot(lobby, player_id, player_conn_token, can_spawn).await;
         return Err(err);
     }
 
-    // Only allow spawning if the lobby explicitly allows this player id.
-    let can_spawn = lobby.is_player_allowed(player_id);
-
     if can_spawn {
         // Notify World Task
         // Tell the game loop to spawn a ship for this ID.
@@ -332,9 +464,7 @@ async fn bootstrap_connection(
             .await
             .map_err(|_| NetError::InputClosed)
         {
-            lobby
-                .unregister_player_connection_if_owner(player_id, player_conn_token)
-                .await;
+            release_join_slot(lobby, player_id, player_conn_token, can_spawn).await;
             return Err(err);
         }
     }
@@ -343,20 +473,27 @@ async fn bootstrap_connection(
     // Keep in mind that we clone as soon as we borrow to avoid holding the lock. (especially
     // during an await)
     let initial_state = server_state_rx.borrow().clone();
-    let state_msg = ServerMessage::GameState(initial_state.into());
+    let state_msg = ServerMessage::GameState(initial_state.clone().into());
     if let Err(e) = send_message(socket, &state_msg).await {
-        if can_spawn {
+        // Hold the ship for a rejoin rather than despawning it on a failed handshake.
+        if release_join_slot(lobby, player_id, player_conn_token, can_spawn).await {
             lobby
                 .input_tx
-                .send(GameEvent::Leave { player_id })
+                .send(GameEvent::Disconnect { player_id })
                 .await
                 .map_err(|_| NetError::InputClosed)?; // InputClosed takes precedence
         }
-        lobby
-            .unregister_player_connection_if_owner(player_id, player_conn_token)
-            .await;
         return Err(e);
     }
+    // A spectator joining an already-ended match only sees the tail of the delayed feed.
+    let spectator_close_at = (!can_spawn && matches!(initial_state, ServerState::MatchEnded))
+        .then(|| tokio::time::Instant::now() + lobby.spectator_feed_delay);
+
+    // A connection that slips in after the notice still has to see it.
+    let mut server_shutdown_rx = lobby_registry.subscribe_shutdown();
+    if server_shutdown_rx.borrow().is_some() {
+        server_shutdown_rx.mark_changed();
+    }
 
     let now = Instant::now() - LOG_THROTTLE;
     Ok(ConnCtx {
@@ -372,8 +509,13 @@ async fn bootstrap_connection(
         world_bytes_rx,
         world_latest_rx,
         server_state_rx,
+        server_shutdown_rx,
+        session_revocations_rx,
+        session_expires_at: enforce_session_expiry.then_some(join.expires_at),
         input_tx: lobby.input_tx.clone(),
         can_spawn,
+        follow_target: None,
+        spectator_close_at,
         lag_recovery_count: 0,
 
         msgs_in: join.msgs_in,
@@ -382,6 +524,7 @@ async fn bootstrap_connection(
         bytes_out: 0,
 
         invalid_json: 0,
+        inbound_guard: InboundGuard::new(net_metrics, now),
 
         last_input_full_log: now,
         last_world_lag_log: now,
@@ -391,6 +534,42 @@ async fn bootstrap_connection(
     })
 }
 
+// Frees whichever lobby slot the join reserved.
+// Returns true only if this connection still owned its player slot.
+async fn release_join_slot(
+    lobby: &LobbyHandle,
+    player_id: u64,
+    token: u64,
+    can_spawn: bool,
+) -> bool {
+    if can_spawn {
+        lobby
+            .unregister_player_connection_if_owner(player_id, token)
+            .await
+    } else {
+        lobby.release_spectator_slot();
+        false
+    }
+}
+
+// Tells the client why its join was refused, then closes the socket.
+async fn reject_join(socket: &mut WebSocket, rejection: JoinRejection) -> NetError {
+    let rejected = ServerMessage::JoinRejected {
+        reason: rejection.into(),
+    };
+    let _ = send_message(socket, &rejected).await;
+    let _ = send_close_with_reason(socket, close_code::POLICY, rejection_reason(rejection)).await;
+    NetError::JoinRejected(rejection)
+}
+
+fn rejection_reason(rejection: JoinRejection) -> &'static str {
+    match rejection {
+        JoinRejection::NotInRoster => "not in lobby roster",
+        JoinRejection::SpectatorLimitReached => "spectator limit reached",
+        JoinRejection::LobbyFull => "lobby full",
+    }
+}
+
 enum LoopControl {
     Continue,
     Disconnect,
@@ -400,6 +579,78 @@ const LOG_THROTTLE: Duration = Duration::from_secs(2);
 const MAX_INVALID_JSON: u32 = 10;
 const MAX_SESSION_TOKEN_LEN: usize = 4096;
 const JOIN_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+// Largest text message a client may send; the join (with its session token) is the biggest.
+const MAX_CLIENT_MESSAGE_BYTES: usize = 8 * 1024;
+// Hard cap enforced by the websocket layer so oversized frames are never buffered.
+const MAX_TRANSPORT_MESSAGE_BYTES: usize = 4 * MAX_CLIENT_MESSAGE_BYTES;
+// Clients send one input per physics frame (60 Hz); leave headroom for catch-up bursts.
+const INBOUND_RATE_LIMIT: RateLimitPolicy = RateLimitPolicy {
+    per_second: 120,
+    burst: 120,
+    warn_strikes: 30,
+    disconnect_strikes: 300,
+};
+
+// Per-connection abuse limits applied to every inbound message before it is parsed.
+struct InboundGuard {
+    limiter: RateLimiter,
+    metrics: Arc<NetMetrics>,
+    last_log: Instant,
+}
+
+enum Screened {
+    Handle,
+    Drop,
+    Close(CloseFrame),
+}
+
+impl InboundGuard {
+    fn new(metrics: Arc<NetMetrics>, now: Instant) -> Self {
+        Self {
+            limiter: RateLimiter::new(INBOUND_RATE_LIMIT, now),
+            metrics,
+            last_log: now,
+        }
+    }
+
+    // Escalates from a warning to dropped messages to a policy close.
+    fn screen(&mut self, player_id: u64, bytes: usize) -> Screened {
+        if bytes > MAX_CLIENT_MESSAGE_BYTES {
+            self.metrics.record(SocketOffense::OversizedMessage);
+            warn!(player_id, bytes, "client message too large; closing");
+            return Screened::Close(CloseFrame {
+                code: close_code::SIZE,
+                reason: "message too large".into(),
+            });
+        }
+
+        match self.limiter.check(Instant::now()) {
+            RateDecision::Allow => Screened::Handle,
+            RateDecision::Warn => {
+                self.metrics.record(SocketOffense::RateLimitWarning);
+                if should_log(&mut self.last_log) {
+                    warn!(player_id, "client exceeding message rate");
+                }
+                Screened::Handle
+            }
+            RateDecision::Drop => {
+                self.metrics.record(SocketOffense::RateLimitDrop);
+                if should_log(&mut self.last_log) {
+                    warn!(player_id, "client over message rate; dropping messages");
+                }
+                Screened::Drop
+            }
+            RateDecision::Disconnect => {
+                self.metrics.record(SocketOffense::RateLimitDisconnect);
+                warn!(player_id, "client persistently over message rate; closing");
+                Screened::Close(CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "rate limit exceeded".into(),
+                })
+            }
+        }
+    }
+}
 
 async fn send_close_with_reason(
     socket: &mut WebSocket,
@@ -419,6 +670,7 @@ async fn send_close_with_reason(
 async fn read_join_handshake(
     socket: &mut WebSocket,
     auth_client: &AuthClient,
+    net_metrics: &NetMetrics,
 ) -> Result<JoinHandshake, NetError> {
     loop {
         let Some(incoming) = socket.recv().await else {
@@ -428,11 +680,19 @@ async fn read_join_handshake(
         let message = incoming.map_err(NetError::Ws)?;
         match message {
             Message::Text(text) => {
+                if text.len() > MAX_CLIENT_MESSAGE_BYTES {
+                    net_metrics.record(SocketOffense::OversizedMessage);
+                    let _ =
+                        send_close_with_reason(socket, close_code::SIZE, "message too large").await;
+                    return Err(NetError::JoinRequired);
+                }
                 let bytes_in = text.len() as u64;
                 let parsed = serde_json::from_str::<ClientMessage>(&text);
                 let payload = match parsed {
                     Ok(ClientMessage::Join(payload)) => payload,
-                    Ok(ClientMessage::Input(_)) => {
+                    Ok(
+                        ClientMessage::Input(_) | ClientMessage::Follow(_) | ClientMessage::Ready,
+                    ) => {
                         let _ = send_close_with_reason(socket, close_code::POLICY, "join required")
                             .await;
                         return Err(NetError::JoinRequired);
@@ -448,6 +708,7 @@ async fn read_join_handshake(
                     }
                 };
 
+                let mode = JoinMode::from(payload.mode);
                 let session_token = payload.session_token.trim();
                 if session_token.is_empty() || session_token.len() > MAX_SESSION_TOKEN_LEN {
                     let _ =
@@ -480,13 +741,14 @@ async fn read_join_handshake(
                         return Err(NetError::AuthVerify);
                     }
                 };
-                let _token_expires_at = identity.expires_at;
 
                 return Ok(JoinHandshake {
                     player_id: identity.user_id,
                     session_id: identity.session_id,
                     display_name: identity.display_name,
-                    // Token expiry is enforced only at join to avoid mid-round disconnects.
+                    mode,
+                    // Expiry is never checked mid-round; see `session_expires_at`.
+                    expires_at: identity.expires_at,
                     bytes_in,
                     msgs_in: 1,
                 });
@@ -558,6 +820,7 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
 
     // Split borrows so `tokio::select!` can hold them concurrently.
     let ConnCtx {
+        session_id,
         lobby_id,
         lobby_registry,
         lobby,
@@ -568,13 +831,19 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
         world_bytes_rx,
         world_latest_rx,
         server_state_rx,
+        server_shutdown_rx,
+        session_revocations_rx,
+        session_expires_at,
         can_spawn,
+        follow_target,
+        spectator_close_at,
         lag_recovery_count,
         msgs_in,
         msgs_out,
         bytes_in,
         bytes_out,
         invalid_json,
+        inbound_guard,
         last_input_full_log,
         last_world_lag_log,
         last_invalid_input_log,
@@ -593,11 +862,16 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
                     socket,
                     incoming,
                     player_id,
+                    lobby,
                     input_tx,
                     *can_spawn,
+                    follow_target,
+                    msgs_out,
+                    bytes_out,
                     msgs_in,
                     bytes_in,
                     invalid_json,
+                    inbound_guard,
                     last_input_full_log,
                     last_invalid_input_log,
                     close_frame,
@@ -614,10 +888,24 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
             // Outgoing World Update
             world_msg = world_bytes_rx.recv() => {
                 match world_msg {
-                    Ok(bytes) => match forward_world_bytes(bytes, socket, msgs_out, bytes_out).await {
-                        LoopControl::Continue => false,
-                        LoopControl::Disconnect => true,
-                    },
+                    Ok(frame) => {
+                        // Only spectators have a follow target; everyone else gets the shared bytes.
+                        let bytes = match *follow_target {
+                            Some(target) => focus_world_bytes(frame, target),
+                            None => frame.bytes,
+                        };
+                        match forward_world_bytes(bytes, socket, msgs_out, bytes_out).await {
+                            LoopControl::Continue => false,
+                            LoopControl::Disconnect => true,
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) if !*can_spawn => {
+                        // The latest snapshot is live data; spectators just resume the delayed feed.
+                        if should_log(last_world_lag_log) {
+                            warn!(missed = n, "spectator feed lagged; skipping ahead");
+                        }
+                        false
+                    }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         if should_log(last_world_lag_log) {
                             warn!(missed = n, "world updates lagged; sending snapshot");
@@ -663,7 +951,47 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
             changed_state = server_state_rx.changed() => {
                 match changed_state {
                     Ok(()) => match forward_server_state(server_state_rx, socket, msgs_out, bytes_out).await {
-                        LoopControl::Continue => false,
+                        LoopControl::Continue => {
+                            // Aborted lobbies are torn down immediately; nothing left to watch.
+                            let aborted = match &*server_state_rx.borrow() {
+                                ServerState::MatchAborted { reason: AbortReason::ClosedByAdmin } => {
+                                    Some("lobby closed")
+                                }
+                                ServerState::MatchAborted { reason: AbortReason::ServerDraining } => {
+                                    Some("server draining")
+                                }
+                                ServerState::MatchAborted { reason: AbortReason::WorldCrashed } => {
+                                    Some("lobby crashed")
+                                }
+                                ServerState::MatchAborted { .. } => Some("match aborted"),
+                                _ => None,
+                            };
+                            if let Some(reason) = aborted {
+                                *close_frame = Some(CloseFrame {
+                                    code: close_code::NORMAL,
+                                    reason: reason.into(),
+                                });
+                                true
+                            } else if session_expires_at.is_some_and(|expires_at| expires_at <= unix_now()) {
+                                // Match boundaries are the only place expiry can end a session.
+                                *close_frame = Some(CloseFrame {
+                                    code: close_code::POLICY,
+                                    reason: "session expired".into(),
+                                });
+                                info!(player_id, "closing connection with expired session");
+                                true
+                            } else {
+                                // Spectators leave once the delayed feed has shown the end of the match.
+                                if !*can_spawn
+                                    && spectator_close_at.is_none()
+                                    && matches!(*server_state_rx.borrow(), ServerState::MatchEnded)
+                                {
+                                    *spectator_close_at =
+                                        Some(tokio::time::Instant::now() + lobby.spectator_feed_delay);
+                                }
+                                false
+                            }
+                        }
                         LoopControl::Disconnect => true,
                     },
                     Err(_) => {
@@ -674,14 +1002,83 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
                 }
             }
 
+            // The server finished draining and is about to exit.
+            changed = server_shutdown_rx.changed() => {
+                let reason = match changed {
+                    Ok(()) => *server_shutdown_rx.borrow_and_update(),
+                    // The registry outlives every connection, so this is unexpected.
+                    Err(_) => Some(ShutdownReason::DrainTimeout),
+                };
+                match reason {
+                    Some(reason) => {
+                        send_shutdown_notice(socket, reason, msgs_out, bytes_out).await;
+                        *close_frame = Some(CloseFrame {
+                            code: close_code::AWAY,
+                            reason: "server shutting down".into(),
+                        });
+                        info!(player_id, ?reason, "closing connection for server shutdown");
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            // Auth revoked a session; close if it is the one this socket joined with.
+            revoked = session_revocations_rx.recv() => {
+                match revoked {
+                    Ok(session_ids) if session_ids.iter().any(|id| id == session_id) => {
+                        *close_frame = Some(CloseFrame {
+                            code: close_code::POLICY,
+                            reason: "session revoked".into(),
+                        });
+                        info!(player_id, "closing connection for revoked session");
+                        true
+                    }
+                    Ok(_) => false,
+                    // A skipped batch may have held this session; rejoining verifies it again.
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        *close_frame = Some(CloseFrame {
+                            code: close_code::AWAY,
+                            reason: "session revocations missed; rejoin".into(),
+                        });
+                        warn!(player_id, skipped, "missed session revocation batches; disconnecting");
+                        true
+                    }
+                    // The registry outlives every connection, so this is unexpected.
+                    Err(broadcast::error::RecvError::Closed) => {
+                        warn!(player_id, "session revocation channel closed; disconnecting");
+                        true
+                    }
+                }
+            }
+
             // Connection replacement signal for duplicate player ids.
             _ = player_conn_shutdown.notified() => {
-                // Ask the client to close; a newer connection took ownership.
+                // A replacement keeps the slot under a new token; a kick removes it.
+                if lobby.is_player_connected(player_id).await {
+                    *close_frame = Some(CloseFrame {
+                        code: close_code::POLICY,
+                        reason: "connection replaced".into(),
+                    });
+                    info!(player_id, "connection replaced by newer session");
+                } else {
+                    *close_frame = Some(CloseFrame {
+                        code: close_code::POLICY,
+                        reason: "kicked".into(),
+                    });
+                    info!(player_id, "connection kicked by operator");
+                }
+                true
+            }
+
+            // Spectators do not keep an ended lobby alive.
+            _ = tokio::time::sleep_until(spectator_close_at.unwrap_or_else(tokio::time::Instant::now)),
+                if spectator_close_at.is_some() =>
+            {
                 *close_frame = Some(CloseFrame {
-                    code: close_code::POLICY,
-                    reason: "connection replaced".into(),
+                    code: close_code::NORMAL,
+                    reason: "match ended".into(),
                 });
-                info!(player_id, "connection replaced by newer session");
                 true
             }
         };
@@ -730,14 +1127,19 @@ async fn run_client_loop(socket: &mut WebSocket, ctx: &mut ConnCtx) -> Result<()
 
 #[allow(clippy::too_many_arguments)]
 async fn handle_incoming_ws(
-    _socket: &mut WebSocket,
+    socket: &mut WebSocket,
     incoming: Option<Result<Message, Error>>,
     player_id: u64,
+    lobby: &LobbyHandle,
     input_tx: &mpsc::Sender<GameEvent>,
     can_spawn: bool,
+    follow_target: &mut Option<u64>,
+    msgs_out: &mut u64,
+    bytes_out: &mut u64,
     msgs_in: &mut u64,
     bytes_in: &mut u64,
     invalid_json: &mut u32,
+    inbound_guard: &mut InboundGuard,
     last_input_full_log: &mut Instant,
     last_invalid_input_log: &mut Instant,
     close_frame: &mut Option<CloseFrame>,
@@ -748,6 +1150,15 @@ async fn handle_incoming_ws(
                 *msgs_in += 1;
                 *bytes_in += text.len() as u64;
 
+                match inbound_guard.screen(player_id, text.len()) {
+                    Screened::Handle => {}
+                    Screened::Drop => return Ok(LoopControl::Continue),
+                    Screened::Close(frame) => {
+                        *close_frame = Some(frame);
+                        return Ok(LoopControl::Disconnect);
+                    }
+                }
+
                 match serde_json::from_str::<ClientMessage>(&text) {
                     Ok(ClientMessage::Join(_)) => {
                         // Ignore repeated Join packets after bootstrap to keep the session stable.
@@ -774,6 +1185,54 @@ async fn handle_incoming_ws(
                             last_invalid_input_log,
                         )
                     }
+                    Ok(ClientMessage::Ready) => {
+                        if !can_spawn {
+                            // Spectators are not part of the ready check.
+                            if should_log(last_invalid_input_log) {
+                                warn!(player_id, "spectator ready ignored");
+                            }
+                            return Ok(LoopControl::Continue);
+                        }
+
+                        match input_tx.try_send(GameEvent::Ready { player_id }) {
+                            Ok(()) => Ok(LoopControl::Continue),
+                            Err(tokio::sync::mpsc::error::TrySendError::Full(_evt)) => {
+                                // Ready is idempotent; clients resend it if the countdown never starts.
+                                if should_log(last_input_full_log) {
+                                    warn!(player_id, "input channel full; dropping ready");
+                                }
+                                Ok(LoopControl::Continue)
+                            }
+                            Err(tokio::sync::mpsc::error::TrySendError::Closed(_evt)) => {
+                                Err(NetError::InputClosed)
+                            }
+                        }
+                    }
+                    Ok(ClientMessage::Follow(payload)) => {
+                        if can_spawn {
+                            // Players always follow their own ship.
+                            if should_log(last_invalid_input_log) {
+                                warn!(player_id, "follow ignored for player connection");
+                            }
+                            return Ok(LoopControl::Continue);
+                        }
+
+                        *follow_target = resolve_follow_target(lobby, payload).await;
+                        let reply = ServerMessage::Following {
+                            player_id: follow_target.map(|id| id.to_string()),
+                        };
+                        match send_message(socket, &reply).await {
+                            Ok(bytes) => {
+                                *msgs_out += 1;
+                                *bytes_out += bytes as u64;
+                                Ok(LoopControl::Continue)
+                            }
+                            Err(err) => {
+                                warn!(error = ?err, "failed to send follow reply");
+                                Ok(LoopControl::Disconnect)
+                            }
+                        }
+                    }
                     Err(parse_err) => {
                         // Legacy client fallback: accept raw PlayerInput messages.
                         match serde_json::from_str::<PlayerInputDto>(&text) {
@@ -806,6 +1265,9 @@ async fn handle_incoming_ws(
                                 }
 
                                 if *invalid_json > MAX_INVALID_JSON {
+                                    inbound_guard
+                                        .metrics
+                                        .record(SocketOffense::InvalidMessageDisconnect);
                                     *close_frame = Some(CloseFrame {
                                         code: close_code::POLICY,
                                         reason: "too many invalid messages".into(),
@@ -826,7 +1288,14 @@ async fn handle_incoming_ws(
                 });
                 Ok(LoopControl::Disconnect)
             }
-            Message::Ping(_) | Message::Pong(_) => Ok(LoopControl::Continue),
+            // Pings are answered by the socket layer, so they still spend rate tokens.
+            Message::Ping(_) | Message::Pong(_) => match inbound_guard.screen(player_id, 0) {
+                Screened::Handle | Screened::Drop => Ok(LoopControl::Continue),
+                Screened::Close(frame) => {
+                    *close_frame = Some(frame);
+                    Ok(LoopControl::Disconnect)
+                }
+            },
             Message::Close(_) => Ok(LoopControl::Disconnect),
         },
         Some(Err(e)) => {
@@ -840,6 +1309,27 @@ async fn handle_incoming_ws(
     }
 }
 
+// Spectators may only follow rostered players who are currently connected.
+async fn resolve_follow_target(lobby: &LobbyHandle, payload: FollowPayload) -> Option<u64> {
+    let target = payload.player_id?.trim().parse::<u64>().ok()?;
+    (lobby.is_player_allowed(target) && lobby.is_player_connected(target).await).then_some(target)
+}
+
+// Centres a spectator's snapshot on the followed ship, serializing the typed update once.
+// Snapshots without that ship, such as after it was destroyed, go out as the shared bytes.
+fn focus_world_bytes(frame: WorldFrame, target: u64) -> String {
+    let Some(focused) = WorldUpdateDto::focused_on(&frame.update, target) else {
+        return frame.bytes;
+    };
+    match serde_json::to_string(&ServerMessage::WorldUpdate(focused)) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!(error = ?e, "failed to serialize focused world update");
+            frame.bytes
+        }
+    }
+}
+
 async fn forward_world_bytes(
     world_msg: String,
     socket: &mut WebSocket,
@@ -887,6 +1377,28 @@ async fn forward_server_state(
     }
 }
 
+async fn send_shutdown_notice(
+    socket: &mut WebSocket,
+    reason: ShutdownReason,
+    msgs_out: &mut u64,
+    bytes_out: &mut u64,
+) {
+    let msg = ServerMessage::ServerShutdown {
+        reason: reason.into(),
+        reconnect_hint: ReconnectHintDto::Requeue,
+    };
+    match send_message(socket, &msg).await {
+        Ok(bytes) => {
+            *msgs_out += 1;
+            *bytes_out += bytes as u64;
+        }
+        Err(err) => {
+            // The socket is closing either way; the client falls back to its close handling.
+            debug!(error = ?err, "failed to send shutdown notice");
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn disconnect_cleanup(
     player_id: u64,
@@ -904,24 +1416,21 @@ async fn disconnect_cleanup(
     invalid_json: u32,
     lag_recovery_count: u64,
 ) -> Result<(), NetError> {
-    if can_spawn {
-        // Only despawn players that were allowed to join the lobby.
+    // Release the player or spectator slot this connection holds.
+    if release_join_slot(lobby, player_id, player_conn_token, can_spawn).await {
+        // Idle the ship for the reconnect grace window instead of despawning it.
+        // Replaced connections skip this; the newer connection already reclaimed the ship.
         input_tx
-            .send(GameEvent::Leave { player_id })
+            .send(GameEvent::Disconnect { player_id })
             .await
             .map_err(|_| NetError::InputClosed)?;
     }
 
     if registered {
-        // Spectators keep lobbies alive by policy, so count every socket.
+        // Every socket is counted; spectators are closed once the match ends.
         lobby_registry.register_disconnect(lobby_id).await;
     }
 
-    // Release the player connection slot if this connection still owns it.
-    lobby
-        .unregister_player_connection_if_owner(player_id, player_conn_token)
-        .await;
-
     debug!(
         player_id,
         msgs_in,
@@ -935,3 +1444,80 @@ async fn disconnect_cleanup(
     info!(player_id, "client disconnected");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::EntitySnapshot;
+
+    fn world_frame(tick: u64, ids: &[&str]) -> WorldFrame {
+        let update = WorldUpdate {
+            tick,
+            entities: ids
+                .iter()
+                .map(|id| EntitySnapshot {
+                    id: id.to_string(),
+                    x: 0.0,
+                    y: 0.0,
+                    rot: 0.0,
+                    hp: 100,
+                })
+                .collect(),
+            projectiles: Vec::new(),
+        };
+        let bytes =
+            serde_json::to_string(&ServerMessage::WorldUpdate(WorldUpdateDto::from(&update)))
+                .unwrap();
+        WorldFrame {
+            update: Arc::new(update),
+            bytes,
+        }
+    }
+
+    #[test]
+    fn followed_snapshots_list_the_followed_ship_first_and_name_it() {
+        let focused: serde_json::Value =
+            serde_json::from_str(&focus_world_bytes(world_frame(7, &["1", "2", "3"]), 2)).unwrap();
+
+        assert_eq!(focused["type"], "WorldUpdate");
+        assert_eq!(focused["data"]["tick"], 7);
+        assert_eq!(focused["data"]["focus"], "2");
+        let ids: Vec<&str> = focused["data"]["entities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entity| entity["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["2", "1", "3"]);
+
+        // A ship missing from the snapshot leaves it untouched.
+        let frame = world_frame(8, &["1", "3"]);
+        assert_eq!(focus_world_bytes(frame.clone(), 2), frame.bytes);
+        assert!(!frame.bytes.contains("focus"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spectator_feed_is_released_only_once_it_is_delay_old() {
+        let (world_bytes_tx, _) = broadcast::channel(16);
+        let (spectator_bytes_tx, mut spectator_bytes_rx) = broadcast::channel(16);
+        tokio::spawn(spectator_feed_relay(
+            world_bytes_tx.subscribe(),
+            spectator_bytes_tx,
+            Duration::from_secs(2),
+        ));
+        tokio::task::yield_now().await;
+
+        world_bytes_tx.send("first".to_string()).unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        world_bytes_tx.send("second".to_string()).unwrap();
+        tokio::time::sleep(Duration::from_millis(900)).await;
+        assert!(spectator_bytes_rx.try_recv().is_err());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(spectator_bytes_rx.try_recv().unwrap(), "first");
+        assert!(spectator_bytes_rx.try_recv().is_err());
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(spectator_bytes_rx.try_recv().unwrap(), "second");
+    }
+}
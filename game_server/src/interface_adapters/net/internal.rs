@@ -1,13 +1,23 @@
-use crate::interface_adapters::http::ErrorResponse;
+use crate::interface_adapters::http::{ErrorCode, error_response};
+use crate::interface_adapters::net::admin::lobby_error_response;
 use crate::interface_adapters::net::client::spawn_lobby_serializer;
 use crate::interface_adapters::state::AppState;
+use crate::interface_adapters::utils::signing::{
+    SIGNATURE_HEADER, SignatureError, TIMESTAMP_HEADER, unix_now, verify,
+};
 
 use axum::{
-    extract::{Json, State},
-    http::StatusCode,
-    response::IntoResponse,
+    body::{Body, to_bytes},
+    extract::{Json, Request, State},
+    http::{StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
 };
 use std::{collections::HashSet, sync::Arc};
+use tracing::warn;
+
+// Internal request bodies are small JSON documents; cap what we buffer for signing.
+const MAX_SIGNED_BODY_BYTES: usize = 64 * 1024;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct LobbyInitRequest {
@@ -24,6 +34,75 @@ struct LobbyInitResponse {
     lobby_id: String,
 }
 
+#[derive(Debug, serde::Serialize)]
+struct LoadResponse {
+    // Unpinned lobbies hosted against `max_lobbies`.
+    lobbies: usize,
+    max_lobbies: usize,
+    players: usize,
+    connections: usize,
+    max_connections: usize,
+}
+
+/// Rejects internal requests that are not signed with the shared service secret.
+pub async fn require_internal_signature(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_SIGNED_BODY_BYTES).await else {
+        return unauthorized("request body too large to verify");
+    };
+
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let result = match (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER)) {
+        (Some(timestamp), Some(signature)) => verify(
+            state.internal_secret.as_bytes(),
+            timestamp,
+            signature,
+            parts.method.as_str(),
+            request_target(&parts.uri),
+            &body,
+            unix_now(),
+        ),
+        _ => Err(SignatureError::Missing),
+    };
+
+    if let Err(error) = result {
+        warn!(path = %parts.uri.path(), ?error, "rejected unsigned internal request");
+        return unauthorized("invalid internal request signature");
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+// The path and query a request was signed over, so a query cannot be swapped in transit.
+fn request_target(uri: &Uri) -> &str {
+    uri.path_and_query()
+        .map_or_else(|| uri.path(), |target| target.as_str())
+}
+
+fn unauthorized(message: &str) -> Response {
+    error_response(StatusCode::UNAUTHORIZED, ErrorCode::Unauthorized, message)
+}
+
+// Current occupancy so head can place new matches on the least-loaded server.
+pub async fn load_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let load = state.lobby_registry.load().await;
+    Json(LoadResponse {
+        lobbies: load.lobbies,
+        max_lobbies: load.max_lobbies,
+        players: load.players,
+        connections: load.connections,
+        max_connections: load.max_connections,
+    })
+}
+
 pub async fn create_lobby_handler(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<LobbyInitRequest>,
@@ -32,13 +111,11 @@ pub async fn create_lobby_handler(
     let lobby_id = payload.lobby_id.trim().to_string();
     if lobby_id.is_empty() {
         // Return a JSON error even for head-only routes to keep responses consistent.
-        return (
+        return error_response(
             StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "lobby_id is required".to_string(),
-            }),
-        )
-            .into_response();
+            ErrorCode::InvalidRequest,
+            "lobby_id is required",
+        );
     }
 
     let allowed_players: HashSet<u64> = payload.allowed_player_ids.into_iter().collect();
@@ -64,15 +141,7 @@ pub async fn create_lobby_handler(
                 .spawn_match_end_watcher(lobby.lobby_id.clone(), lobby.server_state_tx.subscribe());
             (StatusCode::CREATED, Json(LobbyInitResponse { lobby_id })).into_response()
         }
-        Err(crate::use_cases::lobby::LobbyError::AlreadyExists) => {
-            // Match the JSON error schema used for other create failures.
-            (
-                StatusCode::CONFLICT,
-                Json(ErrorResponse {
-                    error: "lobby already exists".to_string(),
-                }),
-            )
-                .into_response()
-        }
+        // Match the JSON error schema used for other create failures.
+        Err(error) => lobby_error_response(error),
     }
 }
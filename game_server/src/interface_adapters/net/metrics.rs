@@ -0,0 +1,103 @@
+use crate::interface_adapters::state::AppState;
+
+use axum::{extract::State, http::header::CONTENT_TYPE, response::IntoResponse};
+use std::fmt::Write;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+// Process-wide counters for client socket abuse, exposed in Prometheus text format.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketOffense {
+    // Over the inbound rate but still within the grace strikes.
+    RateLimitWarning,
+    // Over the inbound rate; the message was discarded.
+    RateLimitDrop,
+    // Closed for persistently exceeding the inbound rate.
+    RateLimitDisconnect,
+    // Closed for sending a message above the size cap.
+    OversizedMessage,
+    // Closed for sending too many unparseable messages.
+    InvalidMessageDisconnect,
+}
+
+impl SocketOffense {
+    const ALL: [SocketOffense; 5] = [
+        SocketOffense::RateLimitWarning,
+        SocketOffense::RateLimitDrop,
+        SocketOffense::RateLimitDisconnect,
+        SocketOffense::OversizedMessage,
+        SocketOffense::InvalidMessageDisconnect,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SocketOffense::RateLimitWarning => "rate_limit_warning",
+            SocketOffense::RateLimitDrop => "rate_limit_drop",
+            SocketOffense::RateLimitDisconnect => "rate_limit_disconnect",
+            SocketOffense::OversizedMessage => "oversized_message",
+            SocketOffense::InvalidMessageDisconnect => "invalid_message_disconnect",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NetMetrics {
+    offenses: [AtomicU64; SocketOffense::ALL.len()],
+}
+
+impl NetMetrics {
+    pub fn record(&self, offense: SocketOffense) {
+        self.offenses[offense as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn offenses(&self, offense: SocketOffense) -> u64 {
+        self.offenses[offense as usize].load(Ordering::Relaxed)
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::from(
+            "# HELP game_server_socket_offenses_total Client socket policy violations by kind.\n\
+             # TYPE game_server_socket_offenses_total counter\n",
+        );
+        for offense in SocketOffense::ALL {
+            let _ = writeln!(
+                out,
+                "game_server_socket_offenses_total{{kind=\"{}\"}} {}",
+                offense.label(),
+                self.offenses(offense)
+            );
+        }
+        out
+    }
+}
+
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.net_metrics.render(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_reports_every_offense_kind() {
+        let metrics = NetMetrics::default();
+        metrics.record(SocketOffense::RateLimitDrop);
+        metrics.record(SocketOffense::RateLimitDrop);
+        metrics.record(SocketOffense::OversizedMessage);
+
+        let text = metrics.render();
+
+        assert!(text.contains("game_server_socket_offenses_total{kind=\"rate_limit_drop\"} 2\n"));
+        assert!(text.contains("game_server_socket_offenses_total{kind=\"oversized_message\"} 1\n"));
+        assert!(
+            text.contains("game_server_socket_offenses_total{kind=\"rate_limit_warning\"} 0\n")
+        );
+    }
+}
@@ -1,7 +1,14 @@
 // Network adapter modules split by external client sockets vs internal HTTP routes.
 
+pub mod admin;
 pub mod client;
 pub mod internal;
+pub mod metrics;
 
+pub use admin::{
+    delete_lobby_handler, end_match_handler, get_lobby_handler, kick_player_handler,
+    list_lobbies_handler, require_admin_token,
+};
 pub use client::{spawn_lobby_serializer, ws_handler};
-pub use internal::create_lobby_handler;
+pub use internal::{create_lobby_handler, load_handler, require_internal_signature};
+pub use metrics::{NetMetrics, metrics_handler};
@@ -2,7 +2,9 @@
 // Internal service-to-service DTOs should live outside this module.
 
 use crate::domain::{EntitySnapshot, PlayerInput, ProjectileSnapshot};
-use crate::use_cases::{ServerState, WorldUpdate};
+use crate::use_cases::{
+    AbortReason, JoinMode, JoinRejection, ServerState, ShutdownReason, WorldUpdate,
+};
 use serde::{Deserialize, Serialize};
 
 /// Messages the server sends to connected clients over the WebSocket.
@@ -10,31 +12,130 @@ use serde::{Deserialize, Serialize};
 #[serde(tag = "type", content = "data")]
 pub enum ServerMessage {
     // Assigned identity for the connection after Join is accepted.
-    Identity { player_id: String },
+    Identity {
+        player_id: String,
+        mode: JoinModeDto,
+    },
+    // Join was refused; the server closes the socket right after this message.
+    JoinRejected {
+        reason: JoinRejectionDto,
+    },
+    // Spectator follow target after a Follow request (None when not following).
+    Following {
+        player_id: Option<String>,
+    },
     // Snapshot of the world for a given tick.
     WorldUpdate(WorldUpdateDto),
     // High-level server state transitions (lobby, match start/end).
     GameState(ServerStateDto),
+    // The server is shutting down; the socket closes right after this message.
+    ServerShutdown {
+        reason: ShutdownReasonDto,
+        reconnect_hint: ReconnectHintDto,
+    },
 }
 
 /// Messages the client sends to the server over the WebSocket.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
 pub enum ClientMessage {
     // Initial handshake message with identity metadata.
     Join(JoinPayload),
     // Input messages sent after a successful Join.
     Input(PlayerInputDto),
+    // Spectator request to follow a player (None clears the target).
+    Follow(FollowPayload),
+    // Player signals readiness during the pre-match ready check.
+    Ready,
 }
 
 /// Payload for the Join handshake with a session token.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JoinPayload {
     pub session_token: String,
+    // Explicit join intent; older clients omit it and join as players.
+    #[serde(default)]
+    pub mode: JoinModeDto,
+}
+
+/// Join intent sent by the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JoinModeDto {
+    #[default]
+    Player,
+    Spectator,
+}
+
+impl From<JoinModeDto> for JoinMode {
+    fn from(mode: JoinModeDto) -> Self {
+        match mode {
+            JoinModeDto::Player => JoinMode::Player,
+            JoinModeDto::Spectator => JoinMode::Spectator,
+        }
+    }
+}
+
+impl From<JoinMode> for JoinModeDto {
+    fn from(mode: JoinMode) -> Self {
+        match mode {
+            JoinMode::Player => JoinModeDto::Player,
+            JoinMode::Spectator => JoinModeDto::Spectator,
+        }
+    }
+}
+
+/// Reason sent to clients whose join was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum JoinRejectionDto {
+    NotInRoster,
+    SpectatorLimitReached,
+    LobbyFull,
+}
+
+impl From<JoinRejection> for JoinRejectionDto {
+    fn from(rejection: JoinRejection) -> Self {
+        match rejection {
+            JoinRejection::NotInRoster => JoinRejectionDto::NotInRoster,
+            JoinRejection::SpectatorLimitReached => JoinRejectionDto::SpectatorLimitReached,
+            JoinRejection::LobbyFull => JoinRejectionDto::LobbyFull,
+        }
+    }
+}
+
+/// Why the server is shutting down.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum ShutdownReasonDto {
+    // Every match finished before the drain deadline.
+    Drained,
+    // The drain deadline cut running matches short.
+    DrainTimeout,
+}
+
+impl From<ShutdownReason> for ShutdownReasonDto {
+    fn from(reason: ShutdownReason) -> Self {
+        match reason {
+            ShutdownReason::Drained => ShutdownReasonDto::Drained,
+            ShutdownReason::DrainTimeout => ShutdownReasonDto::DrainTimeout,
+        }
+    }
+}
+
+/// What a client should do after a server shutdown.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum ReconnectHintDto {
+    // Go back through head matchmaking; this server will not host the match again.
+    Requeue,
+}
+
+/// Spectator follow request; ids use the same string form as `Identity`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowPayload {
+    #[serde(default)]
+    pub player_id: Option<String>,
 }
 
 /// Per-tick input payload sent by the client after joining.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerInputDto {
     #[serde(default)]
     pub thrust: f32,
@@ -61,10 +162,30 @@ pub struct WorldUpdateDto {
     pub entities: Vec<EntityStateDto>,
     #[serde(default)]
     pub projectiles: Vec<ProjectileStateDto>,
+    // Player a following spectator's copy is centred on; omitted everywhere else.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<String>,
 }
 
-impl From<WorldUpdate> for WorldUpdateDto {
-    fn from(update: WorldUpdate) -> Self {
+impl WorldUpdateDto {
+    /// A following spectator's copy: the followed ship is listed first and named in
+    /// `focus`. `None` when the snapshot has no such ship, such as after it was destroyed.
+    pub fn focused_on(update: &WorldUpdate, player_id: u64) -> Option<Self> {
+        let focus = player_id.to_string();
+        let index = update
+            .entities
+            .iter()
+            .position(|entity| entity.id == focus)?;
+        let mut dto = Self::from(update);
+        let followed = dto.entities.remove(index);
+        dto.entities.insert(0, followed);
+        dto.focus = Some(focus);
+        Some(dto)
+    }
+}
+
+impl From<&WorldUpdate> for WorldUpdateDto {
+    fn from(update: &WorldUpdate) -> Self {
         Self {
             tick: update.tick,
             entities: update.entities.iter().map(EntityStateDto::from).collect(),
@@ -73,6 +194,7 @@ impl From<WorldUpdate> for WorldUpdateDto {
                 .iter()
                 .map(ProjectileStateDto::from)
                 .collect(),
+            focus: None,
         }
     }
 }
@@ -128,6 +250,38 @@ pub enum ServerStateDto {
     MatchStarting { in_seconds: u32 },
     MatchRunning,
     MatchEnded,
+    MatchAborted { reason: AbortReasonDto },
+}
+
+/// Why a lobby was aborted before its match started.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum AbortReasonDto {
+    // Too few rostered players showed up before the ready timeout.
+    RosterNoShow { joined: usize, required: usize },
+    // An operator closed the lobby.
+    ClosedByAdmin,
+    // The server began draining before the match started.
+    ServerDraining,
+    // The lobby crashed on the server.
+    WorldCrashed,
+    // No player joined the lobby in time.
+    Abandoned,
+}
+
+impl From<AbortReason> for AbortReasonDto {
+    fn from(reason: AbortReason) -> Self {
+        match reason {
+            AbortReason::RosterNoShow { present, required } => AbortReasonDto::RosterNoShow {
+                joined: present.len(),
+                required,
+            },
+            AbortReason::ClosedByAdmin => AbortReasonDto::ClosedByAdmin,
+            AbortReason::ServerDraining => AbortReasonDto::ServerDraining,
+            AbortReason::WorldCrashed => AbortReasonDto::WorldCrashed,
+            AbortReason::Abandoned => AbortReasonDto::Abandoned,
+        }
+    }
 }
 
 impl From<ServerState> for ServerStateDto {
@@ -139,6 +293,9 @@ impl From<ServerState> for ServerStateDto {
             }
             ServerState::MatchRunning => ServerStateDto::MatchRunning,
             ServerState::MatchEnded => ServerStateDto::MatchEnded,
+            ServerState::MatchAborted { reason } => ServerStateDto::MatchAborted {
+                reason: reason.into(),
+            },
         }
     }
 }
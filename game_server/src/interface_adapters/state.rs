@@ -1,4 +1,5 @@
 use crate::interface_adapters::clients::auth::AuthClient;
+use crate::interface_adapters::net::NetMetrics;
 use crate::use_cases::LobbyRegistry;
 use std::sync::Arc;
 
@@ -10,4 +11,12 @@ pub struct AppState {
     pub default_lobby_id: Arc<str>,
     // Outbound auth service client used to verify join session tokens.
     pub auth_client: Arc<AuthClient>,
+    // Shared secret head uses to sign internal requests (and that signs our reports to head).
+    pub internal_secret: Arc<str>,
+    // Bearer token for the admin lobby API; None keeps the admin routes unmounted.
+    pub admin_token: Option<Arc<str>>,
+    // Counters for client socket policy violations, served on `/metrics`.
+    pub net_metrics: Arc<NetMetrics>,
+    // Close connections whose auth session expired at the next match state change.
+    pub enforce_session_expiry: bool,
 }
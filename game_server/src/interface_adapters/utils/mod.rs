@@ -1 +1,3 @@
+pub mod rate_limit;
 pub mod rng;
+pub mod signing;
@@ -0,0 +1,143 @@
+use std::time::Instant;
+
+// Token-bucket limiter for inbound socket messages with escalating penalties.
+//
+// Each message spends one token; tokens refill continuously up to the burst size.
+// Messages arriving with an empty bucket are strikes: the first few only warn,
+// later ones are dropped, and past the last threshold the connection is cut.
+// Strikes are forgiven once the bucket has refilled completely.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    // Sustained messages per second.
+    pub per_second: u32,
+    // Messages that may arrive back to back before the rate applies.
+    pub burst: u32,
+    // Strikes tolerated (message still handled) before dropping starts.
+    pub warn_strikes: u32,
+    // Strikes after which the connection is closed.
+    pub disconnect_strikes: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    Allow,
+    // Over the limit but still within the grace strikes; handle the message.
+    Warn,
+    // Over the limit; discard the message.
+    Drop,
+    // Persistently over the limit; close the connection.
+    Disconnect,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    policy: RateLimitPolicy,
+    tokens: f64,
+    last_refill: Instant,
+    strikes: u32,
+}
+
+impl RateLimiter {
+    pub fn new(policy: RateLimitPolicy, now: Instant) -> Self {
+        Self {
+            policy,
+            tokens: f64::from(policy.burst),
+            last_refill: now,
+            strikes: 0,
+        }
+    }
+
+    /// Spends a token for a message arriving at `now` and returns how to treat it.
+    pub fn check(&mut self, now: Instant) -> RateDecision {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return RateDecision::Allow;
+        }
+
+        self.strikes = self.strikes.saturating_add(1);
+        if self.strikes > self.policy.disconnect_strikes {
+            RateDecision::Disconnect
+        } else if self.strikes > self.policy.warn_strikes {
+            RateDecision::Drop
+        } else {
+            RateDecision::Warn
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.last_refill = now;
+
+        let burst = f64::from(self.policy.burst);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * f64::from(self.policy.per_second)).min(burst);
+        if self.tokens >= burst {
+            self.strikes = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const POLICY: RateLimitPolicy = RateLimitPolicy {
+        per_second: 10,
+        burst: 5,
+        warn_strikes: 2,
+        disconnect_strikes: 4,
+    };
+
+    #[test]
+    fn burst_is_allowed_then_penalties_escalate() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(POLICY, start);
+
+        for _ in 0..5 {
+            assert_eq!(limiter.check(start), RateDecision::Allow);
+        }
+        assert_eq!(limiter.check(start), RateDecision::Warn);
+        assert_eq!(limiter.check(start), RateDecision::Warn);
+        assert_eq!(limiter.check(start), RateDecision::Drop);
+        assert_eq!(limiter.check(start), RateDecision::Drop);
+        assert_eq!(limiter.check(start), RateDecision::Disconnect);
+    }
+
+    #[test]
+    fn tokens_refill_at_the_sustained_rate() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(POLICY, start);
+        for _ in 0..5 {
+            limiter.check(start);
+        }
+        assert_eq!(limiter.check(start), RateDecision::Warn);
+
+        // 10 per second refills one token every 100ms.
+        let later = start + Duration::from_millis(100);
+        assert_eq!(limiter.check(later), RateDecision::Allow);
+        assert_eq!(limiter.check(later), RateDecision::Warn);
+    }
+
+    #[test]
+    fn strikes_are_forgiven_once_the_bucket_is_full_again() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(POLICY, start);
+        for _ in 0..5 {
+            limiter.check(start);
+        }
+        for _ in 0..4 {
+            limiter.check(start);
+        }
+
+        // Five tokens at 10 per second.
+        let later = start + Duration::from_millis(500);
+        for _ in 0..5 {
+            assert_eq!(limiter.check(later), RateDecision::Allow);
+        }
+        assert_eq!(limiter.check(later), RateDecision::Warn);
+    }
+}
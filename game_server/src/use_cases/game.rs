@@ -1,29 +1,77 @@
-use super::types::{GameEvent, ServerState, WorldUpdate};
+use super::types::{AbortReason, GameEvent, ServerState, WorldUpdate};
 use crate::domain::systems::{projectiles, ship_movement};
 use crate::domain::tuning::player::PlayerTuning;
 use crate::domain::tuning::projectile::ProjectileTuning;
 use crate::domain::{EntitySnapshot, PlayerInput, ProjectileSnapshot, SimEntity, SimProjectile};
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::{broadcast, mpsc, watch};
-use tracing::info;
+use tracing::{info, warn};
+
+/// Per-lobby parameters for the world loop.
+#[derive(Debug, Clone)]
+pub struct WorldSettings {
+    /// Fixed tick interval for the game loop.
+    pub tick_interval: Duration,
+    /// Match duration once running (0 disables match end).
+    pub match_time_limit: Duration,
+    /// How long a disconnected player's ship waits for a rejoin.
+    pub reconnect_grace: Duration,
+    /// Players expected in the match; empty means an open lobby that starts right away.
+    pub roster: Arc<HashSet<u64>>,
+    /// How long to wait for the full roster before falling back to the quorum rule.
+    pub ready_timeout: Duration,
+    /// Fraction of the roster that must be present to start after the timeout.
+    pub ready_quorum: f32,
+    /// Countdown between the lobby becoming ready and the match running.
+    pub countdown: Duration,
+    /// Latest tick, published for lobby inspection.
+    pub current_tick: Arc<AtomicU64>,
+}
+
+// Match progression driven by the world tick.
+enum MatchPhase {
+    WaitingForRoster { waited: Duration },
+    Countdown { remaining: Duration, announced: u32 },
+    Running { elapsed: Duration },
+    Ended,
+}
 
 pub async fn world_task(
     mut input_rx: mpsc::Receiver<GameEvent>,
     world_tx: broadcast::Sender<WorldUpdate>,
     server_state_tx: watch::Sender<ServerState>,
-    tick_interval: Duration,
     shutdown: Arc<tokio::sync::Notify>,
-    match_time_limit: Duration,
+    settings: WorldSettings,
 ) {
+    let WorldSettings {
+        tick_interval,
+        match_time_limit,
+        reconnect_grace,
+        roster,
+        ready_timeout,
+        ready_quorum,
+        countdown,
+        current_tick,
+    } = settings;
+
     let mut tick: u64 = 0;
     let mut entities: Vec<SimEntity> = Vec::new();
     let mut projectiles: Vec<SimProjectile> = Vec::new();
     let mut next_projectile_id: u64 = 1;
 
-    let _ = server_state_tx.send(ServerState::MatchStarting { in_seconds: 3 });
-    tokio::time::sleep(Duration::from_secs(3)).await;
-    let _ = server_state_tx.send(ServerState::MatchRunning);
+    // Rostered lobbies wait in `Lobby` for their players; open lobbies count down immediately.
+    let mut ready: HashSet<u64> = HashSet::new();
+    let required_players = quorum_size(roster.len(), ready_quorum);
+    let mut phase = if roster.is_empty() {
+        start_countdown(&server_state_tx, countdown)
+    } else {
+        MatchPhase::WaitingForRoster {
+            waited: Duration::from_secs(0),
+        }
+    };
 
     // Drive the fixed-step game loop at the configured tick rate.
     let mut interval = tokio::time::interval(tick_interval);
@@ -45,31 +93,26 @@ pub async fn world_task(
     let player_max_hp: i32 = player_tuning.max_hp;
     let respawn_delay: f32 = player_tuning.respawn_seconds;
 
-    // Track match duration for time-limit based end conditions.
-    let mut match_elapsed = Duration::from_secs(0);
-    let mut match_ended = false;
-
     loop {
         tokio::select! {
             _ = shutdown.notified() => {
                 // Exit cleanly when the lobby is removed.
                 break;
             }
-            _ = interval.tick() => {
-                if !match_ended && match_time_limit != Duration::from_secs(0) {
-                    // Time limit is the current win condition; extend with other checks later.
-                    match_elapsed += tick_interval;
-                    if match_elapsed >= match_time_limit {
-                        let _ = server_state_tx.send(ServerState::MatchEnded);
-                        match_ended = true;
-                    }
-                }
-            }
+            _ = interval.tick() => {}
         }
 
         while let Ok(ev) = input_rx.try_recv() {
             match ev {
                 GameEvent::Join { player_id } => {
+                    if let Some(e) = entities.iter_mut().find(|e| e.id == player_id) {
+                        // Rejoin (or replacement connection) reclaims the existing ship as-is.
+                        info!(player_id, "player reclaimed ship");
+                        e.reconnect_grace = None;
+                        e.last_input = idle_input();
+                        continue;
+                    }
+
                     info!(player_id, "player joined");
                     let now = SystemTime::now()
                         .duration_since(UNIX_EPOCH)
@@ -86,21 +129,46 @@ pub async fn world_task(
                         alive: true,
                         respawn_timer: 0.0,
                         throttle: 0.0,
-                        last_input: PlayerInput {
-                            thrust: 0.0,
-                            turn: 0.0,
-                            shoot: false,
-                        },
+                        last_input: idle_input(),
                         shoot_cooldown: 0.0,
+                        reconnect_grace: None,
                     });
                 }
                 GameEvent::Leave { player_id } => {
                     info!(player_id, "player left");
+                    ready.remove(&player_id);
                     entities.retain(|e| e.id != player_id);
                     projectiles.retain(|p| p.owner_id != player_id);
                 }
-                GameEvent::Input { player_id, input } => {
+                GameEvent::Disconnect { player_id } => {
                     if let Some(e) = entities.iter_mut().find(|e| e.id == player_id) {
+                        // Keep the ship idle so a quick rejoin restores it intact.
+                        info!(player_id, "player disconnected; holding ship for rejoin");
+                        ready.remove(&player_id);
+                        e.reconnect_grace = Some(reconnect_grace.as_secs_f32());
+                        e.last_input = idle_input();
+                    }
+                }
+                GameEvent::Ready { player_id } => {
+                    let present = entities
+                        .iter()
+                        .any(|e| e.id == player_id && e.reconnect_grace.is_none());
+                    if present && ready.insert(player_id) {
+                        info!(player_id, "player ready");
+                    }
+                }
+                GameEvent::EndMatch => {
+                    if !matches!(phase, MatchPhase::Ended) {
+                        info!("match ended by operator");
+                        let _ = server_state_tx.send(ServerState::MatchEnded);
+                        phase = MatchPhase::Ended;
+                    }
+                }
+                GameEvent::Input { player_id, input } => {
+                    if let Some(e) = entities
+                        .iter_mut()
+                        .find(|e| e.id == player_id && e.reconnect_grace.is_none())
+                    {
                         e.last_input = input;
                     }
                 }
@@ -108,64 +176,154 @@ pub async fn world_task(
         }
 
         let dt = tick_interval.as_secs_f32();
-        let cfg = ship_movement::MovementConfig {
-            max_speed: player_tuning.max_speed,
-            turn_rate: player_tuning.turn_rate,
-            throttle_rate: player_tuning.throttle_rate,
-            min_x,
-            max_x,
-            min_y,
-            max_y,
-        };
 
+        // Remove ships whose players did not come back within the grace window.
+        let mut expired: Vec<u64> = Vec::new();
         for e in &mut entities {
-            // Respawn logic.
-            if !e.alive {
-                e.respawn_timer -= dt;
-                if e.respawn_timer <= 0.0 {
-                    let now = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_micros();
-                    e.x = ((now % 800) as f32) - 400.0;
-                    e.y = ((now % 460) as f32) - 230.0;
-                    e.rot = 0.0;
-                    e.hp = player_max_hp;
-                    e.alive = true;
-                    e.respawn_timer = 0.0;
-                    e.throttle = 0.0;
-                    e.shoot_cooldown = 0.0;
-                    e.last_input = PlayerInput {
-                        thrust: 0.0,
-                        turn: 0.0,
-                        shoot: false,
-                    };
+            if let Some(grace) = e.reconnect_grace.as_mut() {
+                *grace -= dt;
+                if *grace <= 0.0 {
+                    expired.push(e.id);
                 }
-                continue;
             }
+        }
+        for player_id in expired {
+            info!(player_id, "reconnect grace expired; removing ship");
+            entities.retain(|e| e.id != player_id);
+            projectiles.retain(|p| p.owner_id != player_id);
+        }
+
+        // Advance the match lifecycle.
+        match &mut phase {
+            MatchPhase::WaitingForRoster { waited } => {
+                *waited += tick_interval;
+                let present: Vec<u64> = roster
+                    .iter()
+                    .copied()
+                    .filter(|id| {
+                        entities
+                            .iter()
+                            .any(|e| e.id == *id && e.reconnect_grace.is_none())
+                    })
+                    .collect();
+                let all_ready = roster.iter().all(|id| ready.contains(id));
 
-            // Ship movement.
-            ship_movement::tick_entity(e, dt, cfg);
+                if all_ready {
+                    info!(players = present.len(), "roster ready; starting countdown");
+                    phase = start_countdown(&server_state_tx, countdown);
+                } else if *waited >= ready_timeout {
+                    if present.len() >= required_players {
+                        info!(
+                            players = present.len(),
+                            required = required_players,
+                            "ready timeout reached with quorum; starting countdown"
+                        );
+                        phase = start_countdown(&server_state_tx, countdown);
+                    } else {
+                        warn!(
+                            players = present.len(),
+                            required = required_players,
+                            "roster did not show up; aborting match"
+                        );
+                        let _ = server_state_tx.send(ServerState::MatchAborted {
+                            reason: AbortReason::RosterNoShow {
+                                present,
+                                required: required_players,
+                            },
+                        });
+                        break;
+                    }
+                }
+            }
+            MatchPhase::Countdown {
+                remaining,
+                announced,
+            } => {
+                *remaining = remaining.saturating_sub(tick_interval);
+                if remaining.is_zero() {
+                    let _ = server_state_tx.send(ServerState::MatchRunning);
+                    phase = MatchPhase::Running {
+                        elapsed: Duration::from_secs(0),
+                    };
+                } else {
+                    let in_seconds = whole_seconds_left(*remaining);
+                    if in_seconds != *announced {
+                        *announced = in_seconds;
+                        let _ = server_state_tx.send(ServerState::MatchStarting { in_seconds });
+                    }
+                }
+            }
+            MatchPhase::Running { elapsed } => {
+                if match_time_limit != Duration::from_secs(0) {
+                    // Time limit is the current win condition; extend with other checks later.
+                    *elapsed += tick_interval;
+                    if *elapsed >= match_time_limit {
+                        let _ = server_state_tx.send(ServerState::MatchEnded);
+                        phase = MatchPhase::Ended;
+                    }
+                }
+            }
+            MatchPhase::Ended => {}
         }
 
-        // Projectile simulation and collision resolution.
-        projectiles::tick_projectiles(
-            &mut entities,
-            &mut projectiles,
-            &mut next_projectile_id,
-            dt,
-            projectiles::ProjectileConfig {
-                speed: projectile_speed,
-                ttl: projectile_ttl,
-                radius: projectile_radius,
-                damage: projectile_damage,
-                cooldown: projectile_cooldown,
-                player_radius,
-                respawn_delay,
-            },
-        );
+        // Ships hold position until the countdown finishes.
+        if matches!(phase, MatchPhase::Running { .. } | MatchPhase::Ended) {
+            let cfg = ship_movement::MovementConfig {
+                max_speed: player_tuning.max_speed,
+                turn_rate: player_tuning.turn_rate,
+                throttle_rate: player_tuning.throttle_rate,
+                min_x,
+                max_x,
+                min_y,
+                max_y,
+            };
+
+            for e in &mut entities {
+                // Respawn logic.
+                if !e.alive {
+                    e.respawn_timer -= dt;
+                    if e.respawn_timer <= 0.0 {
+                        let now = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap()
+                            .as_micros();
+                        e.x = ((now % 800) as f32) - 400.0;
+                        e.y = ((now % 460) as f32) - 230.0;
+                        e.rot = 0.0;
+                        e.hp = player_max_hp;
+                        e.alive = true;
+                        e.respawn_timer = 0.0;
+                        e.throttle = 0.0;
+                        e.shoot_cooldown = 0.0;
+                        e.last_input = idle_input();
+                    }
+                    continue;
+                }
+
+                // Ship movement.
+                ship_movement::tick_entity(e, dt, cfg);
+            }
+
+            // Projectile simulation and collision resolution.
+            projectiles::tick_projectiles(
+                &mut entities,
+                &mut projectiles,
+                &mut next_projectile_id,
+                dt,
+                projectiles::ProjectileConfig {
+                    speed: projectile_speed,
+                    ttl: projectile_ttl,
+                    radius: projectile_radius,
+                    damage: projectile_damage,
+                    cooldown: projectile_cooldown,
+                    player_radius,
+                    respawn_delay,
+                },
+            );
+        }
 
         tick += 1;
+        current_tick.store(tick, Ordering::Relaxed);
         let entities_snapshot: Vec<EntitySnapshot> = entities
             .iter()
             .filter(|e| e.alive)
@@ -181,3 +339,213 @@ pub async fn world_task(
         });
     }
 }
+
+fn start_countdown(
+    server_state_tx: &watch::Sender<ServerState>,
+    countdown: Duration,
+) -> MatchPhase {
+    let in_seconds = whole_seconds_left(countdown);
+    let _ = server_state_tx.send(ServerState::MatchStarting { in_seconds });
+    MatchPhase::Countdown {
+        remaining: countdown,
+        announced: in_seconds,
+    }
+}
+
+// Rounds up so clients see 3, 2, 1 rather than 2, 1, 0.
+fn whole_seconds_left(remaining: Duration) -> u32 {
+    remaining.as_millis().div_ceil(1000) as u32
+}
+
+// Minimum rostered players needed to start once the ready timeout passes.
+fn quorum_size(roster_len: usize, quorum: f32) -> usize {
+    if roster_len == 0 {
+        return 0;
+    }
+    ((roster_len as f32 * quorum.clamp(0.0, 1.0)).ceil() as usize).clamp(1, roster_len)
+}
+
+fn idle_input() -> PlayerInput {
+    PlayerInput {
+        thrust: 0.0,
+        turn: 0.0,
+        shoot: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Notify;
+
+    const TICK: Duration = Duration::from_millis(100);
+    const GRACE: Duration = Duration::from_secs(1);
+
+    struct World {
+        input_tx: mpsc::Sender<GameEvent>,
+        world_rx: broadcast::Receiver<WorldUpdate>,
+        server_state_rx: watch::Receiver<ServerState>,
+    }
+
+    fn settings(roster: &[u64]) -> WorldSettings {
+        WorldSettings {
+            tick_interval: TICK,
+            match_time_limit: Duration::from_secs(0),
+            reconnect_grace: GRACE,
+            roster: Arc::new(roster.iter().copied().collect()),
+            ready_timeout: Duration::from_secs(2),
+            ready_quorum: 0.5,
+            countdown: Duration::from_secs(1),
+            current_tick: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn spawn_world() -> World {
+        spawn_world_with(settings(&[]))
+    }
+
+    fn spawn_world_with(settings: WorldSettings) -> World {
+        let (input_tx, input_rx) = mpsc::channel(16);
+        let (world_tx, world_rx) = broadcast::channel(1024);
+        let (server_state_tx, server_state_rx) = watch::channel(ServerState::Lobby);
+        tokio::spawn(world_task(
+            input_rx,
+            world_tx,
+            server_state_tx,
+            Arc::new(Notify::new()),
+            settings,
+        ));
+        World {
+            input_tx,
+            world_rx,
+            server_state_rx,
+        }
+    }
+
+    async fn wait_for_state(world: &mut World, expected: fn(&ServerState) -> bool) -> ServerState {
+        loop {
+            let state = world.server_state_rx.borrow_and_update().clone();
+            if expected(&state) {
+                return state;
+            }
+            world
+                .server_state_rx
+                .changed()
+                .await
+                .expect("world should still be running");
+        }
+    }
+
+    async fn next_has_player(world: &mut World, player_id: u64) -> bool {
+        let update = world.world_rx.recv().await.expect("world update");
+        update
+            .entities
+            .iter()
+            .any(|e| e.id == player_id.to_string())
+    }
+
+    async fn join_and_wait(world: &mut World, player_id: u64) {
+        world
+            .input_tx
+            .send(GameEvent::Join { player_id })
+            .await
+            .expect("join should send");
+        while !next_has_player(world, player_id).await {}
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disconnected_ship_is_removed_only_after_grace_expires() {
+        let mut world = spawn_world();
+        join_and_wait(&mut world, 7).await;
+
+        world
+            .input_tx
+            .send(GameEvent::Disconnect { player_id: 7 })
+            .await
+            .expect("disconnect should send");
+        for _ in 0..5 {
+            assert!(next_has_player(&mut world, 7).await);
+        }
+
+        let mut removed = false;
+        for _ in 0..10 {
+            if !next_has_player(&mut world, 7).await {
+                removed = true;
+                break;
+            }
+        }
+        assert!(removed, "ship should be removed once grace expires");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejoin_within_grace_reclaims_the_same_ship() {
+        let mut world = spawn_world();
+        join_and_wait(&mut world, 7).await;
+
+        world
+            .input_tx
+            .send(GameEvent::Disconnect { player_id: 7 })
+            .await
+            .expect("disconnect should send");
+        world
+            .input_tx
+            .send(GameEvent::Join { player_id: 7 })
+            .await
+            .expect("rejoin should send");
+
+        for _ in 0..20 {
+            let update = world.world_rx.recv().await.expect("world update");
+            let ships: Vec<_> = update.entities.iter().filter(|e| e.id == "7").collect();
+            assert_eq!(ships.len(), 1, "rejoin must not spawn a second ship");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn full_roster_ready_starts_countdown_before_timeout() {
+        let mut world = spawn_world_with(settings(&[1, 2]));
+        join_and_wait(&mut world, 1).await;
+        join_and_wait(&mut world, 2).await;
+        assert_eq!(*world.server_state_rx.borrow(), ServerState::Lobby);
+
+        for player_id in [1, 2] {
+            world
+                .input_tx
+                .send(GameEvent::Ready { player_id })
+                .await
+                .expect("ready should send");
+        }
+
+        let started = tokio::time::Instant::now();
+        wait_for_state(&mut world, |s| matches!(s, ServerState::MatchRunning)).await;
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn roster_below_quorum_aborts_after_ready_timeout() {
+        let mut world = spawn_world_with(settings(&[1, 2, 3]));
+        join_and_wait(&mut world, 1).await;
+
+        let state = wait_for_state(&mut world, |s| {
+            matches!(s, ServerState::MatchAborted { .. })
+        })
+        .await;
+        assert_eq!(
+            state,
+            ServerState::MatchAborted {
+                reason: AbortReason::RosterNoShow {
+                    present: vec![1],
+                    required: 2,
+                },
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn roster_at_quorum_starts_after_ready_timeout() {
+        let mut world = spawn_world_with(settings(&[1, 2, 3]));
+        join_and_wait(&mut world, 1).await;
+        join_and_wait(&mut world, 2).await;
+
+        wait_for_state(&mut world, |s| matches!(s, ServerState::MatchRunning)).await;
+    }
+}
@@ -1,13 +1,24 @@
 // Lobby orchestration for spawning and managing game worlds.
 
-use crate::use_cases::game::world_task;
-use crate::use_cases::{GameEvent, ServerState, WorldUpdate};
+use crate::use_cases::game::{WorldSettings, world_task};
+use crate::use_cases::{
+    AbortReason, GameEvent, ServerState, ShutdownReason, WorldFrame, WorldUpdate,
+};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::sync::{Mutex, Notify, RwLock, broadcast, mpsc, watch};
-use tracing::{debug, info, warn};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
+
+// How often a drain re-checks whether the remaining matches have finished.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// How long connections get to deliver the shutdown notice and close.
+const SHUTDOWN_NOTICE_GRACE: Duration = Duration::from_secs(2);
+// Revocation batches buffered per connection; one batch arrives per auth poll.
+const SESSION_REVOCATION_CAPACITY: usize = 16;
 
 /// Shared configuration for spawning lobby worlds.
 #[derive(Debug, Clone)]
@@ -20,13 +31,127 @@ pub struct LobbySettings {
     pub tick_interval: Duration,
     /// Default match duration for non-pinned lobbies.
     pub default_match_time_limit: Duration,
+    /// Maximum unpinned lobbies hosted by this process.
+    pub max_lobbies: usize,
+    /// Maximum concurrent player connections per lobby (and roster size).
+    pub max_players_per_lobby: usize,
+    /// Maximum concurrent spectator connections per lobby.
+    pub max_spectators_per_lobby: usize,
+    /// Maximum WebSocket connections across all lobbies.
+    pub max_connections: usize,
+    /// How far the spectator feed trails the live world updates.
+    pub spectator_feed_delay: Duration,
+    /// How long a disconnected player's ship waits for the player to rejoin.
+    pub reconnect_grace: Duration,
+    /// How long a rostered lobby waits for its players before the quorum rule applies.
+    pub ready_timeout: Duration,
+    /// Fraction of the roster that must be present to start after the ready timeout.
+    pub ready_quorum: f32,
+    /// Countdown between the lobby becoming ready and the match running.
+    pub match_countdown: Duration,
+    /// How long an unpinned lobby waits for its first player before it is reaped (0 disables).
+    pub join_deadline: Duration,
+}
+
+/// Outbound notifications about lobbies that end abnormally.
+///
+/// Implementations must not block; the registry calls them from lifecycle tasks.
+pub trait LobbyReporter: Send + Sync + std::fmt::Debug {
+    /// Called once a lobby has been aborted and removed from the registry.
+    fn lobby_aborted(&self, lobby_id: &str, reason: &AbortReason);
+}
+
+/// Reporter used when no upstream is configured; aborts are only logged.
+#[derive(Debug, Default)]
+pub struct NoopLobbyReporter;
+
+impl LobbyReporter for NoopLobbyReporter {
+    fn lobby_aborted(&self, _lobby_id: &str, _reason: &AbortReason) {}
 }
 
 /// Errors returned by lobby registry operations.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum LobbyError {
     /// Lobby already exists and cannot be re-created.
     AlreadyExists,
+    /// The process already hosts its maximum number of lobbies.
+    AtCapacity,
+    /// The process is draining and accepts no new lobbies.
+    Draining,
+    /// The roster has more players than a lobby can hold.
+    RosterTooLarge,
+    /// No lobby with the requested id is registered.
+    NotFound,
+    /// Pinned lobbies cannot be closed.
+    Pinned,
+    /// The player has no live connection in the lobby.
+    PlayerNotConnected,
+    /// The lobby world task has already stopped.
+    WorldStopped,
+}
+
+/// Point-in-time view of a lobby for operators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LobbySummary {
+    pub lobby_id: Arc<str>,
+    pub state: ServerState,
+    pub tick: u64,
+    pub is_pinned: bool,
+    /// Players allowed to spawn (empty means open lobby).
+    pub roster: Vec<u64>,
+    /// Players with a live connection.
+    pub connected_players: Vec<u64>,
+    /// All sockets, players and spectators.
+    pub connections: usize,
+    pub spectators: usize,
+}
+
+/// Current occupancy of this process, used for placement decisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerLoad {
+    /// Unpinned lobbies; pinned lobbies do not count against `max_lobbies`.
+    pub lobbies: usize,
+    pub max_lobbies: usize,
+    /// Live player connections across all lobbies.
+    pub players: usize,
+    /// All WebSocket connections, including ones still in the join handshake.
+    pub connections: usize,
+    pub max_connections: usize,
+    /// True once the process has started draining and refuses new lobbies.
+    pub draining: bool,
+}
+
+/// Reservation of one server-wide connection slot, released on drop.
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    connections: Arc<AtomicUsize>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Role a connection asks for when joining a lobby.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinMode {
+    /// Spawn and control a ship; requires a roster slot.
+    #[default]
+    Player,
+    /// Watch the delayed feed without spawning.
+    Spectator,
+}
+
+/// Reasons a join request is refused instead of silently downgraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinRejection {
+    /// The player is not on the lobby roster and cannot spawn.
+    NotInRoster,
+    /// The lobby already has the maximum number of spectators.
+    SpectatorLimitReached,
+    /// The lobby already has the maximum number of players.
+    LobbyFull,
 }
 
 /// Per-lobby channels and access rules.
@@ -39,19 +164,35 @@ pub struct LobbyHandle {
     /// Broadcast sender for raw world updates.
     pub world_tx: broadcast::Sender<WorldUpdate>,
     /// Broadcast sender for serialized world updates.
-    pub world_bytes_tx: broadcast::Sender<String>,
+    pub world_bytes_tx: broadcast::Sender<WorldFrame>,
     /// Watch sender holding the latest serialized world update.
     pub world_latest_tx: watch::Sender<String>,
+    /// Broadcast sender for the delayed spectator feed.
+    pub spectator_bytes_tx: broadcast::Sender<WorldFrame>,
+    /// Delay applied to the spectator feed.
+    pub spectator_feed_delay: Duration,
     /// Watch sender for high-level server state changes.
     pub server_state_tx: watch::Sender<ServerState>,
     /// Active connections for this lobby (players + spectators).
     pub active_connections: Arc<AtomicUsize>,
+    /// Active spectator connections, bounded by `max_spectators`.
+    pub active_spectators: Arc<AtomicUsize>,
+    /// Maximum concurrent spectators for this lobby.
+    pub max_spectators: usize,
+    /// Maximum concurrent player connections for this lobby.
+    pub max_players: usize,
     /// True if the lobby should never be deleted.
     pub is_pinned: bool,
     /// Shutdown signal for the world task.
     pub shutdown_tx: Arc<Notify>,
     /// Active player connections for duplicate-id handling.
     pub active_player_connections: Arc<Mutex<HashMap<u64, PlayerConnectionSlot>>>,
+    /// Latest tick published by the world task.
+    pub current_tick: Arc<AtomicU64>,
+    /// When the lobby was created, for the join deadline.
+    pub created_at: Instant,
+    /// Set once any player connection registers; spectators do not count.
+    pub player_joined: Arc<AtomicBool>,
     /// Players allowed to spawn into the lobby (empty means open lobby).
     allowed_players: Arc<HashSet<u64>>,
 }
@@ -70,16 +211,63 @@ impl LobbyHandle {
         self.allowed_players.is_empty() || self.allowed_players.contains(&player_id)
     }
 
+    /// Checks a join request against the roster and spectator cap.
+    ///
+    /// Admitted spectators hold a slot until `release_spectator_slot` is called.
+    pub fn admit(&self, player_id: u64, mode: JoinMode) -> Result<(), JoinRejection> {
+        match mode {
+            JoinMode::Player if self.is_player_allowed(player_id) => Ok(()),
+            JoinMode::Player => Err(JoinRejection::NotInRoster),
+            JoinMode::Spectator => {
+                let reserved = self.active_spectators.fetch_update(
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                    |current| (current < self.max_spectators).then_some(current + 1),
+                );
+                reserved
+                    .map(|_| ())
+                    .map_err(|_| JoinRejection::SpectatorLimitReached)
+            }
+        }
+    }
+
+    /// Frees a spectator slot reserved by `admit`.
+    pub fn release_spectator_slot(&self) {
+        let _ =
+            self.active_spectators
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                    current.checked_sub(1)
+                });
+    }
+
+    /// Returns true if the player currently has a live player connection.
+    pub async fn is_player_connected(&self, player_id: u64) -> bool {
+        self.active_player_connections
+            .lock()
+            .await
+            .contains_key(&player_id)
+    }
+
+    /// Returns true if another socket could still join without exceeding the lobby caps.
+    pub fn has_connection_capacity(&self) -> bool {
+        self.active_connections.load(Ordering::SeqCst) < self.max_players + self.max_spectators
+    }
+
     /// Registers a player connection, replacing any existing one.
+    ///
+    /// New players are refused once the lobby holds `max_players` connections;
+    /// replacing an existing connection is always allowed.
     pub async fn register_or_replace_player_connection(
         &self,
         player_id: u64,
         token: u64,
-    ) -> Arc<Notify> {
+    ) -> Result<Arc<Notify>, JoinRejection> {
         let mut map = self.active_player_connections.lock().await;
         if let Some(existing) = map.get(&player_id) {
             // Replace the previous connection to recover from stale sessions.
             existing.shutdown.notify_waiters();
+        } else if map.len() >= self.max_players {
+            return Err(JoinRejection::LobbyFull);
         }
 
         let shutdown = Arc::new(Notify::new());
@@ -90,16 +278,73 @@ impl LobbyHandle {
                 shutdown: shutdown.clone(),
             },
         );
-        shutdown
+        self.player_joined.store(true, Ordering::SeqCst);
+        Ok(shutdown)
+    }
+
+    /// Builds an operator-facing snapshot of this lobby.
+    pub async fn summary(&self) -> LobbySummary {
+        let mut connected_players: Vec<u64> = self
+            .active_player_connections
+            .lock()
+            .await
+            .keys()
+            .copied()
+            .collect();
+        connected_players.sort_unstable();
+        let mut roster: Vec<u64> = self.allowed_players.iter().copied().collect();
+        roster.sort_unstable();
+
+        LobbySummary {
+            lobby_id: self.lobby_id.clone(),
+            state: self.server_state_tx.borrow().clone(),
+            tick: self.current_tick.load(Ordering::Relaxed),
+            is_pinned: self.is_pinned,
+            roster,
+            connected_players,
+            connections: self.active_connections.load(Ordering::SeqCst),
+            spectators: self.active_spectators.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Disconnects a player and removes their ship without a reconnect grace.
+    ///
+    /// The connection sees its slot vanish and closes with a kick reason.
+    pub async fn kick_player(&self, player_id: u64) -> Result<(), LobbyError> {
+        let slot = self
+            .active_player_connections
+            .lock()
+            .await
+            .remove(&player_id)
+            .ok_or(LobbyError::PlayerNotConnected)?;
+        slot.shutdown.notify_waiters();
+
+        info!(lobby_id = %self.lobby_id, player_id, "player kicked");
+        self.input_tx
+            .send(GameEvent::Leave { player_id })
+            .await
+            .map_err(|_| LobbyError::WorldStopped)
+    }
+
+    /// Asks the world task to end the match immediately.
+    pub async fn end_match(&self) -> Result<(), LobbyError> {
+        info!(lobby_id = %self.lobby_id, "match end requested");
+        self.input_tx
+            .send(GameEvent::EndMatch)
+            .await
+            .map_err(|_| LobbyError::WorldStopped)
     }
 
     /// Removes the player connection only if this connection still owns it.
-    pub async fn unregister_player_connection_if_owner(&self, player_id: u64, token: u64) {
+    ///
+    /// Returns false when a newer connection has taken over the slot.
+    pub async fn unregister_player_connection_if_owner(&self, player_id: u64, token: u64) -> bool {
         let mut map = self.active_player_connections.lock().await;
         let is_owner = map.get(&player_id).is_some_and(|slot| slot.token == token);
         if is_owner {
             map.remove(&player_id);
         }
+        is_owner
     }
 }
 
@@ -108,17 +353,24 @@ impl LobbyHandle {
 pub struct LobbyRegistry {
     /// Global settings applied to newly created lobbies.
     settings: LobbySettings,
-    /// Map of lobby id to active handle.
-    lobbies: RwLock<HashMap<String, LobbyEntry>>,
+    /// Map of lobby id to active handle, shared with the world task supervisors.
+    lobbies: Arc<RwLock<HashMap<String, LobbyEntry>>>,
+    /// Upstream notified when lobbies are aborted.
+    reporter: Arc<dyn LobbyReporter>,
+    /// WebSocket connections across all lobbies, bounded by `max_connections`.
+    connections: Arc<AtomicUsize>,
+    /// Set once a drain starts; new unpinned lobbies are refused from then on.
+    draining: AtomicBool,
+    /// Process-wide shutdown notice for connected clients, published at the end of a drain.
+    server_shutdown_tx: watch::Sender<Option<ShutdownReason>>,
+    /// Batches of session ids revoked by auth; connections holding one of them close.
+    session_revocations_tx: broadcast::Sender<Arc<[String]>>,
 }
 
 #[derive(Debug)]
 struct LobbyEntry {
     // The externally shared handle for this lobby.
     handle: LobbyHandle,
-    // Track the world task for debugging/visibility.
-    #[allow(dead_code)]
-    world_task: tokio::task::JoinHandle<()>,
 }
 
 impl LobbyRegistry {
@@ -126,16 +378,161 @@ impl LobbyRegistry {
     pub fn new(settings: LobbySettings) -> Self {
         Self {
             settings,
-            lobbies: RwLock::new(HashMap::new()),
+            lobbies: Arc::new(RwLock::new(HashMap::new())),
+            reporter: Arc::new(NoopLobbyReporter),
+            connections: Arc::new(AtomicUsize::new(0)),
+            draining: AtomicBool::new(false),
+            server_shutdown_tx: watch::channel(None).0,
+            session_revocations_tx: broadcast::channel(SESSION_REVOCATION_CAPACITY).0,
         }
     }
 
+    /// Replaces the reporter used for lobby abort notifications.
+    pub fn with_reporter(mut self, reporter: Arc<dyn LobbyReporter>) -> Self {
+        self.reporter = reporter;
+        self
+    }
+
     /// Returns the default match time limit for non-pinned lobbies.
     pub fn default_match_time_limit(&self) -> Duration {
         self.settings.default_match_time_limit
     }
 
+    /// Reserves a server-wide connection slot, or returns None when the server is full.
+    pub fn try_acquire_connection(&self) -> Option<ConnectionPermit> {
+        self.connections
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                (current < self.settings.max_connections).then_some(current + 1)
+            })
+            .ok()
+            .map(|_| ConnectionPermit {
+                connections: self.connections.clone(),
+            })
+    }
+
+    /// Reports lobby, player and connection counts against the configured limits.
+    pub async fn load(&self) -> ServerLoad {
+        let handles: Vec<LobbyHandle> = {
+            let lobbies = self.lobbies.read().await;
+            lobbies.values().map(|entry| entry.handle.clone()).collect()
+        };
+
+        let mut players = 0;
+        for handle in &handles {
+            players += handle.active_player_connections.lock().await.len();
+        }
+        ServerLoad {
+            lobbies: handles.iter().filter(|handle| !handle.is_pinned).count(),
+            max_lobbies: self.settings.max_lobbies,
+            players,
+            connections: self.connections.load(Ordering::SeqCst),
+            max_connections: self.settings.max_connections,
+            draining: self.is_draining(),
+        }
+    }
+
+    /// Returns true once a drain has started.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Subscribes to the shutdown notice published when a drain completes.
+    pub fn subscribe_shutdown(&self) -> watch::Receiver<Option<ShutdownReason>> {
+        self.server_shutdown_tx.subscribe()
+    }
+
+    /// Subscribes to batches of session ids revoked by auth.
+    pub fn subscribe_session_revocations(&self) -> broadcast::Receiver<Arc<[String]>> {
+        self.session_revocations_tx.subscribe()
+    }
+
+    /// Disconnects every connection authenticated with one of `session_ids`.
+    pub fn revoke_sessions(&self, session_ids: Vec<String>) {
+        if session_ids.is_empty() {
+            return;
+        }
+        info!(count = session_ids.len(), "sessions revoked by auth");
+        // No receivers just means none of this server's sockets are affected.
+        let _ = self.session_revocations_tx.send(Arc::from(session_ids));
+    }
+
+    /// Stops accepting lobbies and waits for running matches to finish.
+    ///
+    /// Lobbies still waiting for their roster are aborted so head can re-queue
+    /// the tickets. Once every match has ended, or `deadline` passes, clients
+    /// are sent the shutdown notice and given a short grace to disconnect.
+    pub async fn drain(&self, deadline: Duration) -> ShutdownReason {
+        self.draining.store(true, Ordering::SeqCst);
+        info!(
+            deadline_secs = deadline.as_secs(),
+            "draining; refusing new lobbies"
+        );
+
+        let waiting: Vec<String> = {
+            let lobbies = self.lobbies.read().await;
+            lobbies
+                .iter()
+                .filter(|(_, entry)| {
+                    !entry.handle.is_pinned
+                        && matches!(*entry.handle.server_state_tx.borrow(), ServerState::Lobby)
+                })
+                .map(|(lobby_id, _)| lobby_id.clone())
+                .collect()
+        };
+        for lobby_id in waiting {
+            // The lobby may have started or vanished since the snapshot; either is fine.
+            let _ = self
+                .abort_lobby(&lobby_id, AbortReason::ServerDraining)
+                .await;
+        }
+
+        let reason = match tokio::time::timeout(deadline, self.wait_for_matches()).await {
+            Ok(()) => ShutdownReason::Drained,
+            Err(_) => {
+                warn!("drain deadline passed with matches still running");
+                ShutdownReason::DrainTimeout
+            }
+        };
+        info!(?reason, "notifying clients of shutdown");
+        // Replace rather than send so the notice sticks even with no subscribers.
+        self.server_shutdown_tx.send_replace(Some(reason));
+
+        let _ = tokio::time::timeout(SHUTDOWN_NOTICE_GRACE, async {
+            while self.connections.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await;
+        reason
+    }
+
+    // Resolves once no unpinned lobby has a match in progress.
+    async fn wait_for_matches(&self) {
+        loop {
+            let active = {
+                let lobbies = self.lobbies.read().await;
+                lobbies
+                    .values()
+                    .filter(|entry| {
+                        !entry.handle.is_pinned
+                            && !matches!(
+                                *entry.handle.server_state_tx.borrow(),
+                                ServerState::MatchEnded | ServerState::MatchAborted { .. }
+                            )
+                    })
+                    .count()
+            };
+            if active == 0 {
+                return;
+            }
+            debug!(active, "waiting for matches to finish");
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    }
+
     /// Creates a new lobby and spawns its world task.
+    ///
+    /// Pinned lobbies bypass the lobby cap so the default lobby always exists.
     pub async fn create_lobby(
         &self,
         lobby_id: String,
@@ -149,27 +546,64 @@ impl LobbyRegistry {
             warn!(lobby_id = %lobby_id, "lobby already exists");
             return Err(LobbyError::AlreadyExists);
         }
+        if allowed_players.len() > self.settings.max_players_per_lobby {
+            warn!(
+                lobby_id = %lobby_id,
+                roster = allowed_players.len(),
+                max_players = self.settings.max_players_per_lobby,
+                "lobby roster exceeds player cap"
+            );
+            return Err(LobbyError::RosterTooLarge);
+        }
+        if !is_pinned && self.is_draining() {
+            warn!(lobby_id = %lobby_id, "server draining; refusing lobby");
+            return Err(LobbyError::Draining);
+        }
+        if !is_pinned {
+            let hosted = lobbies
+                .values()
+                .filter(|entry| !entry.handle.is_pinned)
+                .count();
+            if hosted >= self.settings.max_lobbies {
+                warn!(lobby_id = %lobby_id, hosted, "lobby cap reached; refusing lobby");
+                return Err(LobbyError::AtCapacity);
+            }
+        }
 
         // Channel wiring for the lobby world loop.
         let (input_tx, input_rx) = mpsc::channel::<GameEvent>(self.settings.input_channel_capacity);
         let (world_tx, _world_rx) =
             broadcast::channel::<WorldUpdate>(self.settings.world_broadcast_capacity);
         let (world_bytes_tx, _world_bytes_rx) =
-            broadcast::channel::<String>(self.settings.world_broadcast_capacity);
+            broadcast::channel::<WorldFrame>(self.settings.world_broadcast_capacity);
         let (world_latest_tx, _world_latest_rx) = watch::channel::<String>(String::new());
+        let (spectator_bytes_tx, _spectator_bytes_rx) =
+            broadcast::channel::<WorldFrame>(self.settings.world_broadcast_capacity);
         let (server_state_tx, _server_state_rx) = watch::channel::<ServerState>(ServerState::Lobby);
 
         // Shutdown signal for the world task.
         let shutdown_tx = Arc::new(Notify::new());
 
+        // The roster gates both spawning and the pre-match ready check.
+        let allowed_players = Arc::new(allowed_players);
+        let current_tick = Arc::new(AtomicU64::new(0));
+
         // Spawn the authoritative world loop for this lobby.
         let world_task = tokio::spawn(world_task(
             input_rx,
             world_tx.clone(),
             server_state_tx.clone(),
-            self.settings.tick_interval,
             shutdown_tx.clone(),
-            match_time_limit,
+            WorldSettings {
+                tick_interval: self.settings.tick_interval,
+                match_time_limit,
+                reconnect_grace: self.settings.reconnect_grace,
+                roster: allowed_players.clone(),
+                ready_timeout: self.settings.ready_timeout,
+                ready_quorum: self.settings.ready_quorum,
+                countdown: self.settings.match_countdown,
+                current_tick: current_tick.clone(),
+            },
         ));
 
         let lobby = LobbyHandle {
@@ -178,21 +612,34 @@ impl LobbyRegistry {
             world_tx,
             world_bytes_tx,
             world_latest_tx,
+            spectator_bytes_tx,
+            spectator_feed_delay: self.settings.spectator_feed_delay,
             server_state_tx,
             active_connections: Arc::new(AtomicUsize::new(0)),
+            active_spectators: Arc::new(AtomicUsize::new(0)),
+            max_spectators: self.settings.max_spectators_per_lobby,
+            max_players: self.settings.max_players_per_lobby,
             is_pinned,
             shutdown_tx,
             active_player_connections: Arc::new(Mutex::new(HashMap::new())),
-            allowed_players: Arc::new(allowed_players),
+            current_tick,
+            created_at: Instant::now(),
+            player_joined: Arc::new(AtomicBool::new(false)),
+            allowed_players,
         };
 
         lobbies.insert(
             lobby_id,
             LobbyEntry {
                 handle: lobby.clone(),
-                world_task,
             },
         );
+        tokio::spawn(supervise_world_task(
+            world_task,
+            lobby.clone(),
+            self.lobbies.clone(),
+            self.reporter.clone(),
+        ));
         // Log lobby creation for lifecycle visibility.
         info!(
             lobby_id = %lobby.lobby_id,
@@ -204,6 +651,8 @@ impl LobbyRegistry {
     }
 
     /// Spawns a watcher that removes empty lobbies once the match ends.
+    ///
+    /// Aborted lobbies are removed immediately and reported upstream.
     pub fn spawn_match_end_watcher(
         self: Arc<Self>,
         lobby_id: Arc<str>,
@@ -218,6 +667,14 @@ impl LobbyRegistry {
                 }
 
                 let state = server_state_rx.borrow().clone();
+                if let ServerState::MatchAborted { reason } = state {
+                    // Whoever removes the lobby reports it, so head hears about it once.
+                    if self.remove_lobby(&lobby_id).await {
+                        warn!(lobby_id = %lobby_id, ?reason, "match aborted; removed lobby");
+                        self.reporter.lobby_aborted(&lobby_id, &reason);
+                    }
+                    break;
+                }
                 if matches!(state, ServerState::MatchEnded) {
                     // If the match ends while empty, clean up immediately.
                     info!(lobby_id = %lobby_id, "match ended; checking for cleanup");
@@ -228,12 +685,115 @@ impl LobbyRegistry {
         });
     }
 
+    /// Spawns the periodic reaper for lobbies nobody joined before the deadline.
+    pub fn spawn_lobby_reaper(self: Arc<Self>, interval: Duration) {
+        if self.settings.join_deadline.is_zero() {
+            info!("lobby join deadline disabled; reaper not started");
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.reap_abandoned_lobbies(Instant::now()).await;
+            }
+        });
+    }
+
+    /// Aborts unpinned lobbies whose join deadline passed before any player connected.
+    ///
+    /// Returns the reaped lobby ids; each is reported to head as abandoned.
+    pub async fn reap_abandoned_lobbies(&self, now: Instant) -> Vec<String> {
+        let deadline = self.settings.join_deadline;
+        if deadline.is_zero() {
+            return Vec::new();
+        }
+
+        let abandoned: Vec<String> = {
+            let lobbies = self.lobbies.read().await;
+            lobbies
+                .iter()
+                .filter(|(_, entry)| {
+                    let handle = &entry.handle;
+                    !handle.is_pinned
+                        && !handle.player_joined.load(Ordering::SeqCst)
+                        && now.saturating_duration_since(handle.created_at) >= deadline
+                })
+                .map(|(lobby_id, _)| lobby_id.clone())
+                .collect()
+        };
+
+        let mut reaped = Vec::with_capacity(abandoned.len());
+        for lobby_id in abandoned {
+            if self
+                .abort_lobby(&lobby_id, AbortReason::Abandoned)
+                .await
+                .is_ok()
+            {
+                warn!(
+                    lobby_id = %lobby_id,
+                    deadline_secs = deadline.as_secs(),
+                    "no player joined before the deadline; lobby reaped"
+                );
+                reaped.push(lobby_id);
+            }
+        }
+        reaped
+    }
+
     /// Returns a lobby handle for the provided id, if it exists.
     pub async fn get_lobby(&self, lobby_id: &str) -> Option<LobbyHandle> {
         let lobbies = self.lobbies.read().await;
         lobbies.get(lobby_id).map(|entry| entry.handle.clone())
     }
 
+    /// Lists every registered lobby, ordered by id.
+    pub async fn list_lobbies(&self) -> Vec<LobbySummary> {
+        let handles: Vec<LobbyHandle> = {
+            let lobbies = self.lobbies.read().await;
+            lobbies.values().map(|entry| entry.handle.clone()).collect()
+        };
+
+        let mut summaries = Vec::with_capacity(handles.len());
+        for handle in handles {
+            summaries.push(handle.summary().await);
+        }
+        summaries.sort_by(|a, b| a.lobby_id.cmp(&b.lobby_id));
+        summaries
+    }
+
+    /// Closes a lobby on operator request, disconnecting everyone in it.
+    ///
+    /// Connections see `MatchAborted` and close; head is told via the abort watcher.
+    pub async fn close_lobby(&self, lobby_id: &str) -> Result<(), LobbyError> {
+        self.abort_lobby(lobby_id, AbortReason::ClosedByAdmin).await
+    }
+
+    // Removes an unpinned lobby and publishes `MatchAborted` with the given reason.
+    async fn abort_lobby(&self, lobby_id: &str, reason: AbortReason) -> Result<(), LobbyError> {
+        let handle = {
+            let mut lobbies = self.lobbies.write().await;
+            let entry = lobbies.get(lobby_id).ok_or(LobbyError::NotFound)?;
+            if entry.handle.is_pinned {
+                return Err(LobbyError::Pinned);
+            }
+            let entry = lobbies.remove(lobby_id).ok_or(LobbyError::NotFound)?;
+            entry.handle
+        };
+
+        info!(lobby_id = %lobby_id, ?reason, "closing lobby");
+        handle.shutdown_tx.notify_waiters();
+        // Replace rather than send so the terminal state sticks even with no subscribers.
+        handle
+            .server_state_tx
+            .send_replace(ServerState::MatchAborted {
+                reason: reason.clone(),
+            });
+        self.reporter.lobby_aborted(lobby_id, &reason);
+        Ok(())
+    }
+
     /// Record a new connection for the lobby.
     pub async fn register_connection(&self, lobby_id: &str) -> Option<LobbyHandle> {
         let lobbies = self.lobbies.read().await;
@@ -276,7 +836,7 @@ impl LobbyRegistry {
             }
         };
 
-        // Spectators keep the lobby alive by design.
+        // Spectators are closed at match end, so only players can hold an ended lobby open.
         if remaining == 0
             && !entry.handle.is_pinned
             && matches!(
@@ -291,6 +851,20 @@ impl LobbyRegistry {
         }
     }
 
+    /// Stops the world task and removes the lobby regardless of connections.
+    ///
+    /// Returns false when the lobby was already gone.
+    async fn remove_lobby(&self, lobby_id: &str) -> bool {
+        let mut lobbies = self.lobbies.write().await;
+        match lobbies.remove(lobby_id) {
+            Some(entry) => {
+                entry.handle.shutdown_tx.notify_waiters();
+                true
+            }
+            None => false,
+        }
+    }
+
     async fn cleanup_if_empty_on_match_end(&self, lobby_id: &str) {
         let mut lobbies = self.lobbies.write().await;
         let Some(entry) = lobbies.get(lobby_id) else {
@@ -311,3 +885,462 @@ impl LobbyRegistry {
         }
     }
 }
+
+/// Awaits a lobby's world task so a panic tears the lobby down instead of
+/// leaving its clients on a silent socket.
+///
+/// The panic stays inside the task, so other lobbies keep running.
+async fn supervise_world_task(
+    world_task: JoinHandle<()>,
+    lobby: LobbyHandle,
+    lobbies: Arc<RwLock<HashMap<String, LobbyEntry>>>,
+    reporter: Arc<dyn LobbyReporter>,
+) {
+    let panic = match world_task.await {
+        Ok(()) => {
+            debug!(lobby_id = %lobby.lobby_id, "world task exited");
+            return;
+        }
+        Err(join_error) if join_error.is_panic() => join_error,
+        Err(join_error) => {
+            debug!(lobby_id = %lobby.lobby_id, error = %join_error, "world task cancelled");
+            return;
+        }
+    };
+
+    let tick = lobby.current_tick.load(Ordering::Relaxed);
+    error!(
+        lobby_id = %lobby.lobby_id,
+        tick,
+        error = %panic,
+        "world task panicked; tearing down lobby"
+    );
+
+    // Lobby ids can be reused, so only remove the entry if it is still this lobby.
+    let removed = {
+        let mut lobbies = lobbies.write().await;
+        let owned = lobbies
+            .get(&*lobby.lobby_id)
+            .is_some_and(|entry| Arc::ptr_eq(&entry.handle.lobby_id, &lobby.lobby_id));
+        owned && lobbies.remove(&*lobby.lobby_id).is_some()
+    };
+    // Remove before publishing so the match-end watcher does not report it a second time.
+    lobby
+        .server_state_tx
+        .send_replace(ServerState::MatchAborted {
+            reason: AbortReason::WorldCrashed,
+        });
+    if removed {
+        reporter.lobby_aborted(&lobby.lobby_id, &AbortReason::WorldCrashed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct RecordingReporter {
+        aborted: std::sync::Mutex<Vec<(String, AbortReason)>>,
+    }
+
+    impl LobbyReporter for RecordingReporter {
+        fn lobby_aborted(&self, lobby_id: &str, reason: &AbortReason) {
+            self.aborted
+                .lock()
+                .unwrap()
+                .push((lobby_id.to_string(), reason.clone()));
+        }
+    }
+
+    fn test_settings() -> LobbySettings {
+        LobbySettings {
+            input_channel_capacity: 16,
+            world_broadcast_capacity: 16,
+            tick_interval: Duration::from_millis(16),
+            default_match_time_limit: Duration::from_secs(0),
+            max_lobbies: 1,
+            max_players_per_lobby: 2,
+            max_spectators_per_lobby: 1,
+            max_connections: 2,
+            spectator_feed_delay: Duration::from_millis(50),
+            reconnect_grace: Duration::from_secs(1),
+            ready_timeout: Duration::from_secs(1),
+            ready_quorum: 0.5,
+            match_countdown: Duration::from_secs(1),
+            join_deadline: Duration::from_secs(10),
+        }
+    }
+
+    async fn wait_for_state(lobby: &LobbyHandle, expected: fn(&ServerState) -> bool) {
+        let mut state_rx = lobby.server_state_tx.subscribe();
+        while !expected(&state_rx.borrow_and_update()) {
+            state_rx.changed().await.expect("state channel open");
+        }
+    }
+
+    async fn rostered_lobby(registry: &LobbyRegistry, allowed: &[u64]) -> LobbyHandle {
+        registry
+            .create_lobby(
+                "lobby".into(),
+                allowed.iter().copied().collect(),
+                false,
+                Duration::from_secs(0),
+            )
+            .await
+            .expect("lobby should be created")
+    }
+
+    #[tokio::test]
+    async fn admit_rejects_players_outside_the_roster() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[1, 2]).await;
+
+        assert_eq!(lobby.admit(1, JoinMode::Player), Ok(()));
+        assert_eq!(
+            lobby.admit(3, JoinMode::Player),
+            Err(JoinRejection::NotInRoster)
+        );
+    }
+
+    #[tokio::test]
+    async fn admit_caps_spectators_until_a_slot_is_released() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[1]).await;
+
+        assert_eq!(lobby.admit(7, JoinMode::Spectator), Ok(()));
+        assert_eq!(
+            lobby.admit(8, JoinMode::Spectator),
+            Err(JoinRejection::SpectatorLimitReached)
+        );
+
+        lobby.release_spectator_slot();
+
+        assert_eq!(lobby.admit(8, JoinMode::Spectator), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn kick_requires_a_live_player_connection() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[1]).await;
+        assert_eq!(
+            lobby.kick_player(1).await,
+            Err(LobbyError::PlayerNotConnected)
+        );
+
+        let shutdown = lobby
+            .register_or_replace_player_connection(1, 10)
+            .await
+            .expect("player slot should be free");
+        let kicked = shutdown.notified();
+        tokio::pin!(kicked);
+        kicked.as_mut().enable();
+
+        assert_eq!(lobby.kick_player(1).await, Ok(()));
+        kicked.await;
+        assert!(!lobby.is_player_connected(1).await);
+        assert!(lobby.summary().await.connected_players.is_empty());
+    }
+
+    #[tokio::test]
+    async fn player_cap_refuses_new_players_but_allows_replacement() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[]).await;
+
+        for (player_id, token) in [(1, 10), (2, 20), (1, 11)] {
+            assert!(
+                lobby
+                    .register_or_replace_player_connection(player_id, token)
+                    .await
+                    .is_ok()
+            );
+        }
+        assert_eq!(
+            lobby
+                .register_or_replace_player_connection(3, 30)
+                .await
+                .err(),
+            Some(JoinRejection::LobbyFull)
+        );
+    }
+
+    #[tokio::test]
+    async fn registry_enforces_lobby_roster_and_connection_caps() {
+        let registry = LobbyRegistry::new(test_settings());
+        assert_eq!(
+            registry
+                .create_lobby(
+                    "crowded".into(),
+                    [1, 2, 3].into_iter().collect(),
+                    false,
+                    Duration::from_secs(0),
+                )
+                .await
+                .err(),
+            Some(LobbyError::RosterTooLarge)
+        );
+        rostered_lobby(&registry, &[1]).await;
+        assert_eq!(
+            registry
+                .create_lobby(
+                    "second".into(),
+                    HashSet::new(),
+                    false,
+                    Duration::from_secs(0)
+                )
+                .await
+                .err(),
+            Some(LobbyError::AtCapacity)
+        );
+        // Pinned lobbies are exempt from the lobby cap.
+        assert!(
+            registry
+                .create_lobby(
+                    "pinned".into(),
+                    HashSet::new(),
+                    true,
+                    Duration::from_secs(0)
+                )
+                .await
+                .is_ok()
+        );
+
+        let first = registry.try_acquire_connection();
+        let second = registry.try_acquire_connection();
+        assert!(first.is_some() && second.is_some());
+        assert!(registry.try_acquire_connection().is_none());
+        drop(first);
+
+        let load = registry.load().await;
+        assert_eq!((load.lobbies, load.connections), (1, 1));
+        assert!(registry.try_acquire_connection().is_some());
+    }
+
+    #[tokio::test]
+    async fn end_match_moves_lobby_to_match_ended() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[1]).await;
+
+        lobby.end_match().await.expect("world should accept end");
+
+        wait_for_state(&lobby, |s| matches!(s, ServerState::MatchEnded)).await;
+    }
+
+    #[tokio::test]
+    async fn close_lobby_removes_unpinned_lobbies_only() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[1]).await;
+        registry
+            .create_lobby(
+                "pinned".into(),
+                HashSet::new(),
+                true,
+                Duration::from_secs(0),
+            )
+            .await
+            .expect("pinned lobby should be created");
+
+        assert_eq!(
+            registry.close_lobby("pinned").await,
+            Err(LobbyError::Pinned)
+        );
+        assert_eq!(registry.close_lobby("lobby").await, Ok(()));
+        assert_eq!(
+            registry.close_lobby("lobby").await,
+            Err(LobbyError::NotFound)
+        );
+
+        assert_eq!(
+            *lobby.server_state_tx.borrow(),
+            ServerState::MatchAborted {
+                reason: AbortReason::ClosedByAdmin
+            }
+        );
+        let ids: Vec<_> = registry
+            .list_lobbies()
+            .await
+            .into_iter()
+            .map(|summary| summary.lobby_id.to_string())
+            .collect();
+        assert_eq!(ids, vec!["pinned".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn revoked_session_batches_reach_every_subscriber() {
+        let registry = LobbyRegistry::new(test_settings());
+        let mut first = registry.subscribe_session_revocations();
+        let mut second = registry.subscribe_session_revocations();
+
+        // Empty polls are not broadcast.
+        registry.revoke_sessions(Vec::new());
+        registry.revoke_sessions(vec!["session-1".into(), "session-2".into()]);
+
+        for rx in [&mut first, &mut second] {
+            let batch = rx.recv().await.expect("batch should arrive");
+            assert_eq!(&*batch, ["session-1".to_string(), "session-2".to_string()]);
+            assert!(rx.try_recv().is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn drain_refuses_lobbies_and_aborts_ones_still_waiting() {
+        let registry = LobbyRegistry::new(test_settings());
+        let lobby = rostered_lobby(&registry, &[1]).await;
+        let mut shutdown_rx = registry.subscribe_shutdown();
+
+        let reason = registry.drain(Duration::from_secs(5)).await;
+
+        assert_eq!(reason, ShutdownReason::Drained);
+        assert_eq!(
+            *shutdown_rx.borrow_and_update(),
+            Some(ShutdownReason::Drained)
+        );
+        assert_eq!(
+            *lobby.server_state_tx.borrow(),
+            ServerState::MatchAborted {
+                reason: AbortReason::ServerDraining
+            }
+        );
+        assert!(registry.load().await.draining);
+        assert_eq!(
+            registry
+                .create_lobby("late".into(), HashSet::new(), false, Duration::from_secs(0))
+                .await
+                .err(),
+            Some(LobbyError::Draining)
+        );
+    }
+
+    #[tokio::test]
+    async fn world_panic_aborts_and_reports_only_the_crashed_lobby() {
+        let reporter = Arc::new(RecordingReporter::default());
+        let registry = LobbyRegistry::new(LobbySettings {
+            max_lobbies: 2,
+            ..test_settings()
+        })
+        .with_reporter(reporter.clone());
+        let crashed = rostered_lobby(&registry, &[1]).await;
+        let healthy = registry
+            .create_lobby(
+                "healthy".into(),
+                [2].into_iter().collect(),
+                false,
+                Duration::from_secs(0),
+            )
+            .await
+            .expect("second lobby should be created");
+        crashed.current_tick.store(42, Ordering::Relaxed);
+
+        let panicking = tokio::spawn(async { panic!("simulated world bug") });
+        supervise_world_task(
+            panicking,
+            crashed.clone(),
+            registry.lobbies.clone(),
+            reporter.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            *crashed.server_state_tx.borrow(),
+            ServerState::MatchAborted {
+                reason: AbortReason::WorldCrashed
+            }
+        );
+        assert!(registry.get_lobby("lobby").await.is_none());
+        assert_eq!(
+            *reporter.aborted.lock().unwrap(),
+            vec![("lobby".to_string(), AbortReason::WorldCrashed)]
+        );
+        assert_eq!(*healthy.server_state_tx.borrow(), ServerState::Lobby);
+        assert!(registry.get_lobby("healthy").await.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reaper_aborts_running_lobbies_no_player_joined_before_the_deadline() {
+        let reporter = Arc::new(RecordingReporter::default());
+        let registry = Arc::new(
+            LobbyRegistry::new(LobbySettings {
+                max_lobbies: 2,
+                ready_timeout: Duration::from_secs(30),
+                ..test_settings()
+            })
+            .with_reporter(reporter.clone()),
+        );
+        let empty = rostered_lobby(&registry, &[1]).await;
+        let joined = registry
+            .create_lobby(
+                "joined".into(),
+                HashSet::new(),
+                false,
+                Duration::from_secs(0),
+            )
+            .await
+            .expect("second lobby should be created");
+        joined
+            .register_or_replace_player_connection(2, 20)
+            .await
+            .expect("player slot should be free");
+        registry.clone().spawn_lobby_reaper(Duration::from_secs(1));
+
+        tokio::time::sleep(Duration::from_secs(9)).await;
+        assert_eq!(*empty.server_state_tx.borrow(), ServerState::Lobby);
+
+        // The world task is still waiting on its ready check when the deadline passes.
+        wait_for_state(&empty, |state| {
+            matches!(state, ServerState::MatchAborted { .. })
+        })
+        .await;
+        assert_eq!(
+            *empty.server_state_tx.borrow(),
+            ServerState::MatchAborted {
+                reason: AbortReason::Abandoned
+            }
+        );
+        assert!(registry.get_lobby("lobby").await.is_none());
+        assert!(registry.get_lobby("joined").await.is_some());
+        assert_eq!(
+            *reporter.aborted.lock().unwrap(),
+            vec![("lobby".to_string(), AbortReason::Abandoned)]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unjoined_roster_past_the_ready_timeout_is_reported_with_nobody_joined() {
+        let reporter = Arc::new(RecordingReporter::default());
+        let registry = Arc::new(
+            LobbyRegistry::new(LobbySettings {
+                ready_timeout: Duration::from_secs(1),
+                join_deadline: Duration::from_secs(10),
+                ..test_settings()
+            })
+            .with_reporter(reporter.clone()),
+        );
+        let empty = rostered_lobby(&registry, &[1, 2]).await;
+        registry
+            .clone()
+            .spawn_match_end_watcher(empty.lobby_id.clone(), empty.server_state_tx.subscribe());
+        registry.clone().spawn_lobby_reaper(Duration::from_secs(1));
+
+        wait_for_state(&empty, |state| {
+            matches!(state, ServerState::MatchAborted { .. })
+        })
+        .await;
+
+        let no_show = AbortReason::RosterNoShow {
+            present: Vec::new(),
+            required: 1,
+        };
+        assert_eq!(
+            *empty.server_state_tx.borrow(),
+            ServerState::MatchAborted {
+                reason: no_show.clone()
+            }
+        );
+        // Let the match-end watcher report it.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(
+            *reporter.aborted.lock().unwrap(),
+            vec![("lobby".to_string(), no_show)]
+        );
+    }
+}
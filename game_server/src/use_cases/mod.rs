@@ -4,5 +4,8 @@ pub mod game;
 pub mod lobby;
 pub mod types;
 
-pub use lobby::{LobbyHandle, LobbyRegistry, LobbySettings};
-pub use types::{GameEvent, ServerState, WorldUpdate};
+pub use lobby::{
+    ConnectionPermit, JoinMode, JoinRejection, LobbyError, LobbyHandle, LobbyRegistry,
+    LobbyReporter, LobbySettings, LobbySummary, NoopLobbyReporter, ServerLoad,
+};
+pub use types::{AbortReason, GameEvent, ServerState, ShutdownReason, WorldFrame, WorldUpdate};
@@ -1,20 +1,53 @@
 // Use-case level inputs/outputs for the game loop.
 
 use crate::domain::{EntitySnapshot, PlayerInput, ProjectileSnapshot};
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub enum GameEvent {
     Join { player_id: u64 },
     Leave { player_id: u64 },
+    // Socket dropped; keep the ship idle for the reconnect grace window.
+    Disconnect { player_id: u64 },
     Input { player_id: u64, input: PlayerInput },
+    // Player signals they are ready for the pre-match countdown.
+    Ready { player_id: u64 },
+    // Operator request to end the match now, whatever phase it is in.
+    EndMatch,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ServerState {
     Lobby,
     MatchStarting { in_seconds: u32 },
     MatchRunning,
     MatchEnded,
+    // Terminal state: the match will not run and the lobby is being torn down.
+    MatchAborted { reason: AbortReason },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbortReason {
+    // Too few rostered players joined before the ready timeout. `present` lists the
+    // rostered players connected at the abort, so head requeues only them.
+    RosterNoShow { present: Vec<u64>, required: usize },
+    // An operator closed the lobby through the admin API.
+    ClosedByAdmin,
+    // The server started draining before the match got underway.
+    ServerDraining,
+    // The lobby's world task panicked; the match cannot continue.
+    WorldCrashed,
+    // No player connected before the lobby's join deadline.
+    Abandoned,
+}
+
+// Why connections are told the whole server is going away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+    // Every active match finished before the drain deadline.
+    Drained,
+    // The drain deadline passed with matches still running.
+    DrainTimeout,
 }
 
 #[derive(Debug, Clone)]
@@ -23,3 +56,11 @@ pub struct WorldUpdate {
     pub entities: Vec<EntitySnapshot>,
     pub projectiles: Vec<ProjectileSnapshot>,
 }
+
+// A world update serialized once per tick; the typed update stays alongside so
+// connections that need their own copy (following spectators) skip re-parsing.
+#[derive(Debug, Clone)]
+pub struct WorldFrame {
+    pub update: Arc<WorldUpdate>,
+    pub bytes: String,
+}
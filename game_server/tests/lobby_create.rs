@@ -1,23 +1,60 @@
 mod support;
 
+use game_server::interface_adapters::utils::signing::{
+    SIGNATURE_HEADER, TIMESTAMP_HEADER, sign, unix_now,
+};
+use support::TEST_INTERNAL_API_SECRET;
+
 // TODO: test the full response of the lobby creation request
 
+fn lobby_payload() -> Vec<u8> {
+    let lobby_id = format!("test-{}", uuid::Uuid::new_v4());
+    serde_json::to_vec(&serde_json::json!({
+        "lobby_id": lobby_id,
+        "allowed_player_ids": []
+    }))
+    .expect("payload should serialize")
+}
+
 #[tokio::test]
 async fn test_lobby_creation() {
     let base_url = support::ensure_server();
     let client = reqwest::Client::new();
-    let lobby_id = format!("test-{}", uuid::Uuid::new_v4());
-    let payload = serde_json::json!({
-        "lobby_id": lobby_id,
-        "allowed_player_ids": []
-    });
+    let payload = lobby_payload();
+    let timestamp = unix_now();
+    let signature = sign(
+        TEST_INTERNAL_API_SECRET.as_bytes(),
+        timestamp,
+        "POST",
+        "/lobbies",
+        &payload,
+    );
 
     let res = client
         .post(format!("{base_url}/lobbies"))
-        .json(&payload)
+        .header("content-type", "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, signature)
+        .body(payload)
         .send()
         .await
         .expect("request should succeed");
 
     assert_eq!(res.status(), reqwest::StatusCode::CREATED)
 }
+
+#[tokio::test]
+async fn test_lobby_creation_requires_signature() {
+    let base_url = support::ensure_server();
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{base_url}/lobbies"))
+        .header("content-type", "application/json")
+        .body(lobby_payload())
+        .send()
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED)
+}
@@ -0,0 +1,49 @@
+mod support;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::{Message, protocol::frame::coding::CloseCode};
+
+#[tokio::test]
+async fn oversized_message_is_closed_and_counted() {
+    let base_url = support::ensure_server();
+    let ws_url = format!("{}/ws", base_url.replacen("http://", "ws://", 1));
+    let (mut socket, _) = tokio_tungstenite::connect_async(ws_url)
+        .await
+        .expect("websocket should connect");
+
+    socket
+        .send(Message::Text("x".repeat(9 * 1024).into()))
+        .await
+        .expect("send should succeed");
+
+    let close = loop {
+        match socket.next().await {
+            Some(Ok(Message::Close(frame))) => break frame,
+            Some(Ok(_)) => continue,
+            other => panic!("expected a close frame, got {other:?}"),
+        }
+    };
+    let close = close.expect("close should carry a code");
+    assert_eq!(close.code, CloseCode::Size);
+    assert_eq!(close.reason.as_str(), "message too large");
+
+    // On the shared public port, metrics need the admin token.
+    let client = reqwest::Client::new();
+    let anonymous = client
+        .get(format!("{base_url}/metrics"))
+        .send()
+        .await
+        .expect("metrics request should succeed");
+    assert_eq!(anonymous.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let metrics = client
+        .get(format!("{base_url}/metrics"))
+        .bearer_auth(support::TEST_ADMIN_TOKEN)
+        .send()
+        .await
+        .expect("metrics request should succeed")
+        .text()
+        .await
+        .expect("metrics body should be text");
+    assert!(metrics.contains("game_server_socket_offenses_total{kind=\"oversized_message\"} 1\n"));
+}
@@ -6,6 +6,14 @@ use std::{
     time::Duration,
 };
 
+// Secret the test server verifies internal requests with; only some tests sign requests.
+#[allow(dead_code)]
+pub const TEST_INTERNAL_API_SECRET: &str = "test-internal-secret";
+
+// Admin token the test server accepts; only some tests call admin routes.
+#[allow(dead_code)]
+pub const TEST_ADMIN_TOKEN: &str = "test-admin-token";
+
 // Global base URL used by all tests after the server publishes its bound address.
 static SERVER_URL: OnceLock<String> = OnceLock::new();
 // One-time guard that ensures the server bootstrap path runs only once.
@@ -34,9 +42,13 @@ pub fn ensure_server() -> &'static str {
                 // Publish the final base URL so test code can target the right server.
                 let _ = published_url_thread.set(format!("http://{}", addr));
                 // Start serving requests until the test process exits.
-                game_server::run_for_tests(listener)
-                    .await
-                    .expect("server failed");
+                game_server::run_for_tests(
+                    listener,
+                    TEST_INTERNAL_API_SECRET.to_string(),
+                    TEST_ADMIN_TOKEN.to_string(),
+                )
+                .await
+                .expect("server failed");
             });
         });
         // Block until URL is published and the bound port starts accepting connections.
@@ -1,17 +1,28 @@
+use crate::interface_adapters::request_signing::{
+    SIGNATURE_HEADER, TIMESTAMP_HEADER, sign, unix_now,
+};
 use crate::use_cases::{
     AuthProvider, AuthProviderError, GuestInit, GuestInitResult, GuestLogin, GuestLoginResult,
-    VerifySession, VerifySessionResult,
+    RefreshSession, RefreshSessionResult, VerifySession, VerifySessionResult,
 };
 use async_trait::async_trait;
-use reqwest::{Client, Response, StatusCode, Url};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
 use serde::{Deserialize, Serialize};
+use session_tokens::{KeySetResponse, LocalVerifier, Rejection, SignedToken};
 use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::time::MissedTickBehavior;
 
 #[derive(Clone)]
 pub struct AuthClient {
     http: Client,
     pub base_url: Url,
+    // Shared secret used to sign revocation polls, which auth only serves to services.
+    secret: Arc<str>,
+    // Shared by clones so session checks and the revocation poller see the same state.
+    local: Arc<LocalVerifier>,
 }
 
 #[derive(Debug)]
@@ -35,13 +46,17 @@ struct AuthGuestInitRequest {
 #[derive(Debug, Deserialize)]
 struct AuthGuestInitResponse {
     guest_id: u64,
+    guest_secret: String,
     token: String,
     expires_at: u64,
+    refresh_token: String,
+    refresh_expires_at: u64,
 }
 
 #[derive(Debug, Serialize)]
 struct AuthGuestLoginRequest {
     guest_id: u64,
+    guest_secret: String,
     display_name: String,
 }
 
@@ -49,6 +64,21 @@ struct AuthGuestLoginRequest {
 struct AuthGuestLoginResponse {
     token: String,
     expires_at: u64,
+    refresh_token: String,
+    refresh_expires_at: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct AuthRefreshRequest {
+    refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthRefreshResponse {
+    token: String,
+    expires_at: u64,
+    refresh_token: String,
+    refresh_expires_at: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -64,8 +94,46 @@ struct AuthVerifyTokenResponse {
     expires_at: u64,
 }
 
+// Error envelope returned by auth; only the code decides the error.
+#[derive(Debug, Deserialize)]
+struct AuthErrorResponse {
+    code: AuthErrorCode,
+}
+
+// Auth error codes head tells apart; the registry is in ERROR_CODES.md.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AuthErrorCode {
+    InvalidRequest,
+    InvalidDisplayName,
+    InvalidGuestId,
+    InvalidGuestCredentials,
+    InvalidToken,
+    SessionExpired,
+    InvalidRefreshToken,
+    RefreshTokenExpired,
+    RefreshTokenReused,
+    RateLimited,
+    StorageUnavailable,
+    IdentityProviderUnavailable,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthRevokedSession {
+    session_id: String,
+    expires_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthRevocationsResponse {
+    revocations: Vec<AuthRevokedSession>,
+    cursor: u64,
+}
+
 impl AuthClient {
-    pub fn new(base_url: &str) -> Result<Self, AuthClientConfigError> {
+    pub fn new(base_url: &str, secret: impl Into<Arc<str>>) -> Result<Self, AuthClientConfigError> {
         let mut base_url = Url::parse(base_url).map_err(|error| AuthClientConfigError {
             message: format!("invalid auth base URL: {error}"),
         })?;
@@ -82,7 +150,115 @@ impl AuthClient {
                 message: format!("failed to build auth HTTP client: {error}"),
             })?;
 
-        Ok(Self { http, base_url })
+        Ok(Self {
+            http,
+            base_url,
+            secret: secret.into(),
+            local: Arc::default(),
+        })
+    }
+
+    // Polls auth's revocation list so locally verified tokens stop working once revoked.
+    // Signed tokens are verified locally only while polls keep succeeding.
+    pub fn spawn_revocation_poller(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // The first poll replays every revocation auth still holds.
+            let mut cursor = 0;
+            loop {
+                ticker.tick().await;
+                match self.fetch_revocations(cursor).await {
+                    Ok(page) => {
+                        cursor = page.cursor;
+                        self.local.record_revocations(
+                            page.revocations
+                                .into_iter()
+                                .map(|session| (session.session_id, session.expires_at)),
+                            interval,
+                        );
+                    }
+                    Err(error) => {
+                        tracing::warn!(error = %error, "failed to poll auth revocations")
+                    }
+                }
+            }
+        });
+    }
+
+    async fn fetch_revocations(
+        &self,
+        since: u64,
+    ) -> Result<AuthRevocationsResponse, AuthProviderError> {
+        let target = format!("auth/revocations?since={since}");
+        let url = self.endpoint(&target)?;
+        // Signed as auth routes it, since a path prefix belongs to whatever fronts auth.
+        let timestamp = unix_now();
+        let signature = sign(
+            self.secret.as_bytes(),
+            timestamp,
+            "GET",
+            &format!("/{target}"),
+            b"",
+        );
+        let response = self
+            .http
+            .get(url)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .send()
+            .await
+            .map_err(|_| AuthProviderError::UpstreamUnavailable)?;
+        ensure_success_response(response)
+            .await?
+            .json::<AuthRevocationsResponse>()
+            .await
+            .map_err(|_| AuthProviderError::Unexpected)
+    }
+
+    // `None` when auth has to decide; forged and revoked tokens are `Unauthorized` and
+    // expired ones `SessionExpired`, as auth would answer.
+    async fn verify_locally(
+        &self,
+        token: &SignedToken<'_>,
+    ) -> Option<Result<VerifySessionResult, AuthProviderError>> {
+        let fetch_keys = || async {
+            self.fetch_keys()
+                .await
+                .inspect_err(
+                    |error| tracing::warn!(error = %error, "failed to fetch auth token keys"),
+                )
+                .ok()
+        };
+        let result = self.local.verify(token, fetch_keys).await?;
+        Some(
+            result
+                .map(|claims| VerifySessionResult {
+                    user_id: claims.user_id,
+                    display_name: claims.display_name,
+                    session_id: claims.session_id,
+                    expires_at: claims.expires_at,
+                })
+                .map_err(|rejection| match rejection {
+                    Rejection::Invalid => AuthProviderError::Unauthorized,
+                    Rejection::Expired => AuthProviderError::SessionExpired,
+                }),
+        )
+    }
+
+    async fn fetch_keys(&self) -> Result<KeySetResponse, AuthProviderError> {
+        let url = self.endpoint("auth/keys")?;
+        let response = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .map_err(|_| AuthProviderError::UpstreamUnavailable)?;
+        ensure_success_response(response)
+            .await?
+            .json::<KeySetResponse>()
+            .await
+            .map_err(|_| AuthProviderError::Unexpected)
     }
 
     fn endpoint(&self, path: &str) -> Result<Url, AuthProviderError> {
@@ -99,9 +275,7 @@ impl AuthProvider for AuthClient {
         req: GuestInit,
     ) -> Result<GuestInitResult, AuthProviderError> {
         let url = self.endpoint("auth/guest/init")?;
-        let response = self
-            .http
-            .post(url)
+        let response = forward_client_ip(self.http.post(url), req.client_ip)
             .json(&AuthGuestInitRequest {
                 display_name: req.display_name,
             })
@@ -117,8 +291,11 @@ impl AuthProvider for AuthClient {
 
         Ok(GuestInitResult {
             guest_id: payload.guest_id,
+            guest_secret: payload.guest_secret,
             session_token: payload.token,
             expires_at: payload.expires_at,
+            refresh_token: payload.refresh_token,
+            refresh_expires_at: payload.refresh_expires_at,
         })
     }
 
@@ -127,11 +304,10 @@ impl AuthProvider for AuthClient {
         req: GuestLogin,
     ) -> Result<GuestLoginResult, AuthProviderError> {
         let url = self.endpoint("auth/guest")?;
-        let response = self
-            .http
-            .post(url)
+        let response = forward_client_ip(self.http.post(url), req.client_ip)
             .json(&AuthGuestLoginRequest {
                 guest_id: req.guest_id,
+                guest_secret: req.guest_secret,
                 display_name: req.display_name,
             })
             .send()
@@ -147,6 +323,35 @@ impl AuthProvider for AuthClient {
         Ok(GuestLoginResult {
             session_token: payload.token,
             expires_at: payload.expires_at,
+            refresh_token: payload.refresh_token,
+            refresh_expires_at: payload.refresh_expires_at,
+        })
+    }
+
+    async fn refresh_session(
+        &self,
+        req: RefreshSession,
+    ) -> Result<RefreshSessionResult, AuthProviderError> {
+        let url = self.endpoint("auth/refresh")?;
+        let response = forward_client_ip(self.http.post(url), req.client_ip)
+            .json(&AuthRefreshRequest {
+                refresh_token: req.refresh_token,
+            })
+            .send()
+            .await
+            .map_err(|_| AuthProviderError::UpstreamUnavailable)?;
+        let response = ensure_success_response(response).await?;
+
+        let payload = response
+            .json::<AuthRefreshResponse>()
+            .await
+            .map_err(|_| AuthProviderError::Unexpected)?;
+
+        Ok(RefreshSessionResult {
+            session_token: payload.token,
+            expires_at: payload.expires_at,
+            refresh_token: payload.refresh_token,
+            refresh_expires_at: payload.refresh_expires_at,
         })
     }
 
@@ -154,6 +359,12 @@ impl AuthProvider for AuthClient {
         &self,
         req: VerifySession,
     ) -> Result<VerifySessionResult, AuthProviderError> {
+        if let Some(signed) = SignedToken::parse(&req.session_token)
+            && let Some(result) = self.verify_locally(&signed).await
+        {
+            return result;
+        }
+
         let url = self.endpoint("auth/verify-token")?;
         let response = self
             .http
@@ -180,15 +391,51 @@ impl AuthProvider for AuthClient {
     }
 }
 
+// Auth trusts this header from head and rate limits the named player instead of head.
+fn forward_client_ip(request: RequestBuilder, client_ip: Option<IpAddr>) -> RequestBuilder {
+    match client_ip {
+        Some(ip) => request.header("x-forwarded-for", ip.to_string()),
+        None => request,
+    }
+}
+
 async fn ensure_success_response(response: Response) -> Result<Response, AuthProviderError> {
     let status = response.status();
     if status.is_success() {
         return Ok(response);
     }
 
-    // Drain the response body so the underlying connection can be reused.
-    let _ = response.bytes().await;
-    Err(map_status_to_error(status))
+    // Reading the body also lets the underlying connection be reused.
+    let code = response
+        .json::<AuthErrorResponse>()
+        .await
+        .ok()
+        .map(|error| error.code);
+    Err(code
+        .and_then(map_code_to_error)
+        .unwrap_or_else(|| map_status_to_error(status)))
+}
+
+// `None` for codes head does not know; the status decides those.
+fn map_code_to_error(code: AuthErrorCode) -> Option<AuthProviderError> {
+    let error = match code {
+        AuthErrorCode::InvalidRequest
+        | AuthErrorCode::InvalidDisplayName
+        | AuthErrorCode::InvalidGuestId => AuthProviderError::BadRequest,
+        // An expired refresh token cannot be refreshed; the client must log in again.
+        AuthErrorCode::InvalidGuestCredentials
+        | AuthErrorCode::InvalidToken
+        | AuthErrorCode::InvalidRefreshToken
+        | AuthErrorCode::RefreshTokenExpired
+        | AuthErrorCode::RefreshTokenReused => AuthProviderError::Unauthorized,
+        AuthErrorCode::SessionExpired => AuthProviderError::SessionExpired,
+        AuthErrorCode::RateLimited => AuthProviderError::RateLimited,
+        AuthErrorCode::StorageUnavailable | AuthErrorCode::IdentityProviderUnavailable => {
+            AuthProviderError::UpstreamUnavailable
+        }
+        AuthErrorCode::Other => return None,
+    };
+    Some(error)
 }
 
 fn map_status_to_error(status: StatusCode) -> AuthProviderError {
@@ -206,11 +453,21 @@ fn map_status_to_error(status: StatusCode) -> AuthProviderError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::{Json, Router, extract::State, http::StatusCode as AxumStatusCode, routing::post};
+    use crate::interface_adapters::request_signing::verify;
+    use axum::{
+        Json, Router,
+        extract::State,
+        http::{HeaderMap, StatusCode as AxumStatusCode, Uri},
+        routing::{get, post},
+    };
     use serde_json::json;
+    use session_tokens::KEY_SET_MAX_AGE;
+    use session_tokens::test_support::{claims, key_set_json, sign_token};
     use std::sync::{Arc, Mutex};
     use tokio::net::TcpListener;
 
+    const TEST_SECRET: &str = "test-internal-secret";
+
     #[derive(Clone, Default)]
     struct RequestLog {
         paths: Arc<Mutex<Vec<String>>>,
@@ -229,7 +486,9 @@ mod tests {
             AxumStatusCode::OK,
             Json(json!({
                 "token": "token",
-                "expires_at": 123
+                "expires_at": 123,
+                "refresh_token": "refresh",
+                "refresh_expires_at": 1234
             })),
         )
     }
@@ -271,7 +530,7 @@ mod tests {
 
     #[test]
     fn new_rejects_invalid_base_url() {
-        match AuthClient::new("not a url") {
+        match AuthClient::new("not a url", TEST_SECRET) {
             Ok(_) => panic!("invalid URLs should fail"),
             Err(error) => assert!(error.to_string().contains("invalid auth base URL")),
         }
@@ -279,10 +538,10 @@ mod tests {
 
     #[test]
     fn endpoint_normalizes_base_urls_with_and_without_trailing_slashes() {
-        let without_slash =
-            AuthClient::new("http://localhost:3002/prefix").expect("client should build");
-        let with_slash =
-            AuthClient::new("http://localhost:3002/prefix/").expect("client should build");
+        let without_slash = AuthClient::new("http://localhost:3002/prefix", TEST_SECRET)
+            .expect("client should build");
+        let with_slash = AuthClient::new("http://localhost:3002/prefix/", TEST_SECRET)
+            .expect("client should build");
 
         assert_eq!(
             without_slash
@@ -307,12 +566,15 @@ mod tests {
             .route("/prefix/auth/guest", post(capture_guest_login_request_path))
             .with_state(log.clone());
         let base_url = spawn_test_server(router).await;
-        let client = AuthClient::new(&format!("{base_url}/prefix")).expect("client should build");
+        let client = AuthClient::new(&format!("{base_url}/prefix"), TEST_SECRET)
+            .expect("client should build");
 
         let result = client
             .create_guest_session(GuestLogin {
                 guest_id: 42,
+                guest_secret: "secret".into(),
                 display_name: "Pilot".into(),
+                client_ip: None,
             })
             .await
             .expect("request should succeed");
@@ -322,6 +584,8 @@ mod tests {
             GuestLoginResult {
                 session_token: "token".into(),
                 expires_at: 123,
+                refresh_token: "refresh".into(),
+                refresh_expires_at: 1234,
             }
         );
         assert_eq!(
@@ -343,7 +607,8 @@ mod tests {
             )
             .with_state(log.clone());
         let base_url = spawn_test_server(router).await;
-        let client = AuthClient::new(&format!("{base_url}/prefix")).expect("client should build");
+        let client = AuthClient::new(&format!("{base_url}/prefix"), TEST_SECRET)
+            .expect("client should build");
 
         let result = client
             .verify_session(VerifySession {
@@ -369,6 +634,166 @@ mod tests {
         );
     }
 
+    async fn rotate_refresh_token(
+        headers: HeaderMap,
+        Json(body): Json<serde_json::Value>,
+    ) -> (AxumStatusCode, Json<serde_json::Value>) {
+        // Refreshes are rate limited per player too.
+        assert_eq!(
+            headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok()),
+            Some("203.0.113.7")
+        );
+        if body["refresh_token"] != "refresh-1" {
+            return (
+                AxumStatusCode::UNAUTHORIZED,
+                Json(json!({
+                    "code": "refresh_token_reused",
+                    "message": "refresh token reused",
+                    "retryable": false,
+                    "details": null
+                })),
+            );
+        }
+        (
+            AxumStatusCode::OK,
+            Json(json!({
+                "token": "token-2",
+                "expires_at": 456,
+                "refresh_token": "refresh-2",
+                "refresh_expires_at": 4567
+            })),
+        )
+    }
+
+    #[tokio::test]
+    async fn refresh_session_rotates_tokens_and_maps_rejections_to_unauthorized() {
+        let router = Router::new().route("/auth/refresh", post(rotate_refresh_token));
+        let base_url = spawn_test_server(router).await;
+        let client = AuthClient::new(&base_url, TEST_SECRET).expect("client should build");
+
+        let rotated = client
+            .refresh_session(RefreshSession {
+                refresh_token: "refresh-1".into(),
+                client_ip: Some("203.0.113.7".parse().unwrap()),
+            })
+            .await;
+        let replayed = client
+            .refresh_session(RefreshSession {
+                refresh_token: "refresh-0".into(),
+                client_ip: Some("203.0.113.7".parse().unwrap()),
+            })
+            .await;
+
+        assert_eq!(
+            rotated,
+            Ok(RefreshSessionResult {
+                session_token: "token-2".into(),
+                expires_at: 456,
+                refresh_token: "refresh-2".into(),
+                refresh_expires_at: 4567,
+            })
+        );
+        assert_eq!(replayed, Err(AuthProviderError::Unauthorized));
+    }
+
+    async fn check_guest_secret(
+        headers: HeaderMap,
+        Json(body): Json<serde_json::Value>,
+    ) -> (AxumStatusCode, Json<serde_json::Value>) {
+        assert_eq!(
+            headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok()),
+            Some("203.0.113.7")
+        );
+        if body["guest_secret"] != "secret" {
+            return (
+                AxumStatusCode::UNAUTHORIZED,
+                Json(json!({
+                    "code": "invalid_guest_credentials",
+                    "message": "invalid guest credentials",
+                    "retryable": false,
+                    "details": null
+                })),
+            );
+        }
+
+        (
+            AxumStatusCode::OK,
+            Json(json!({
+                "token": "token",
+                "expires_at": 123,
+                "refresh_token": "refresh",
+                "refresh_expires_at": 1234
+            })),
+        )
+    }
+
+    #[tokio::test]
+    async fn create_guest_session_forwards_the_guest_secret_and_player_address() {
+        let router = Router::new().route("/auth/guest", post(check_guest_secret));
+        let base_url = spawn_test_server(router).await;
+        let client = AuthClient::new(&base_url, TEST_SECRET).expect("client should build");
+        let login = |guest_secret: &str| GuestLogin {
+            guest_id: 42,
+            guest_secret: guest_secret.into(),
+            display_name: "Pilot".into(),
+            client_ip: Some("203.0.113.7".parse().unwrap()),
+        };
+
+        let accepted = client.create_guest_session(login("secret")).await;
+        let rejected = client.create_guest_session(login("wrong")).await;
+
+        assert!(accepted.is_ok());
+        assert_eq!(rejected, Err(AuthProviderError::Unauthorized));
+    }
+
+    async fn reject_expired_session() -> (AxumStatusCode, Json<serde_json::Value>) {
+        (
+            AxumStatusCode::UNAUTHORIZED,
+            Json(json!({
+                "code": "session_expired",
+                "message": "session expired",
+                "retryable": false,
+                "details": null
+            })),
+        )
+    }
+
+    #[tokio::test]
+    async fn error_codes_take_precedence_over_statuses() {
+        let router = Router::new()
+            .route("/auth/verify-token", post(reject_expired_session))
+            .route(
+                "/auth/guest/init",
+                post(|| async {
+                    (
+                        AxumStatusCode::BAD_GATEWAY,
+                        Json(json!({ "code": "something_new" })),
+                    )
+                }),
+            );
+        let base_url = spawn_test_server(router).await;
+        let client = AuthClient::new(&base_url, TEST_SECRET).expect("client should build");
+
+        let expired = client
+            .verify_session(VerifySession {
+                session_token: "opaque-token".into(),
+            })
+            .await;
+        let unknown = client
+            .create_guest_identity(GuestInit {
+                display_name: "Pilot".into(),
+                client_ip: None,
+            })
+            .await;
+
+        assert_eq!(expired, Err(AuthProviderError::SessionExpired));
+        assert_eq!(unknown, Err(AuthProviderError::UpstreamUnavailable));
+    }
+
     #[test]
     fn known_and_unknown_statuses_map_as_expected() {
         assert_eq!(
@@ -404,4 +829,156 @@ mod tests {
             AuthProviderError::UpstreamUnavailable
         );
     }
+
+    // Stands in for a revocation poll that just succeeded.
+    fn mark_revocations_polled(client: &AuthClient) {
+        client.local.record_revocations([], Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn signed_sessions_are_verified_locally_and_refused_once_revoked() {
+        let log = RequestLog::default();
+        let router = Router::new()
+            .route("/auth/verify-token", post(capture_verify_request_path))
+            .route(
+                "/auth/keys",
+                get(|| async { Json(key_set_json(1, "key-1")) }),
+            )
+            .with_state(log.clone());
+        let base_url = spawn_test_server(router).await;
+        let client = AuthClient::new(&base_url, TEST_SECRET).expect("client should build");
+        mark_revocations_polled(&client);
+        let token = sign_token(1, "key-1", claims(unix_now() + 3600));
+
+        let session = client
+            .verify_session(VerifySession {
+                session_token: token.clone(),
+            })
+            .await
+            .expect("signed token should verify locally");
+        assert_eq!(session.user_id, 42);
+
+        client.local.record_revocations(
+            [("session-1".to_string(), unix_now() + 3600)],
+            Duration::from_secs(60),
+        );
+        let revoked = client
+            .verify_session(VerifySession {
+                session_token: token,
+            })
+            .await;
+        assert_eq!(revoked, Err(AuthProviderError::Unauthorized));
+
+        assert!(
+            log.paths
+                .lock()
+                .expect("lock should not be poisoned")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn signed_sessions_go_to_auth_while_revocations_are_not_polled() {
+        let log = RequestLog::default();
+        let router = Router::new()
+            .route("/auth/verify-token", post(capture_verify_request_path))
+            .route(
+                "/auth/keys",
+                get(|| async { Json(key_set_json(1, "key-1")) }),
+            )
+            .with_state(log.clone());
+        let base_url = spawn_test_server(router).await;
+        let client = AuthClient::new(&base_url, TEST_SECRET).expect("client should build");
+
+        client
+            .verify_session(VerifySession {
+                session_token: sign_token(1, "key-1", claims(unix_now() + 3600)),
+            })
+            .await
+            .expect("auth should verify the session");
+
+        assert_eq!(
+            *log.paths.lock().expect("lock should not be poisoned"),
+            vec!["/auth/verify-token".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_key_sets_are_refetched_and_retired_keys_dropped() {
+        let log = RequestLog::default();
+        let router = Router::new()
+            .route("/auth/verify-token", post(capture_verify_request_path))
+            .route(
+                "/auth/keys",
+                get(|| async { Json(key_set_json(1, "key-1")) }),
+            )
+            .with_state(log.clone());
+        let base_url = spawn_test_server(router).await;
+        let client = AuthClient::new(&base_url, TEST_SECRET).expect("client should build");
+        mark_revocations_polled(&client);
+        let fetched_long_ago = std::time::Instant::now()
+            .checked_sub(KEY_SET_MAX_AGE + Duration::from_secs(1))
+            .expect("clock should be past the key set age");
+        client.local.install_keys(
+            serde_json::from_value(key_set_json(2, "key-2")).expect("key set should parse"),
+            fetched_long_ago,
+        );
+
+        client
+            .verify_session(VerifySession {
+                session_token: sign_token(2, "key-2", claims(unix_now() + 3600)),
+            })
+            .await
+            .expect("auth should verify the session");
+
+        // The retired key no longer verifies locally, so auth was asked.
+        assert_eq!(
+            *log.paths.lock().expect("lock should not be poisoned"),
+            vec!["/auth/verify-token".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn revocation_polls_are_signed_with_the_service_secret() {
+        // Answers only polls signed with `TEST_SECRET`, as auth does.
+        let router = Router::new().route(
+            "/prefix/auth/revocations",
+            get(|uri: Uri, headers: HeaderMap| async move {
+                let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+                let signed = match (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER)) {
+                    (Some(timestamp), Some(signature)) => verify(
+                        TEST_SECRET.as_bytes(),
+                        timestamp,
+                        signature,
+                        "GET",
+                        &format!("/auth/revocations?{}", uri.query().unwrap_or_default()),
+                        b"",
+                        unix_now(),
+                    )
+                    .is_ok(),
+                    _ => false,
+                };
+                if !signed {
+                    return Err(AxumStatusCode::UNAUTHORIZED);
+                }
+                Ok(Json(json!({ "revocations": [], "cursor": 3 })))
+            }),
+        );
+        let base_url = spawn_test_server(router).await;
+
+        let client = AuthClient::new(&format!("{base_url}/prefix"), TEST_SECRET)
+            .expect("client should build");
+        assert_eq!(
+            client
+                .fetch_revocations(0)
+                .await
+                .expect("signed poll should succeed")
+                .cursor,
+            3
+        );
+
+        let wrong_secret =
+            AuthClient::new(&format!("{base_url}/prefix"), "other").expect("client should build");
+        assert!(wrong_secret.fetch_revocations(0).await.is_err());
+    }
 }
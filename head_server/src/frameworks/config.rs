@@ -3,6 +3,11 @@ use serde::Deserialize;
 use std::collections::HashSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Three missed heartbeats at the game server's 5 second interval.
+const DEFAULT_GAME_SERVER_HEARTBEAT_TTL_SECS: u64 = 15;
+const DEFAULT_REVOCATION_POLL_SECS: u64 = 5;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HeadServerConfig {
@@ -11,6 +16,12 @@ pub struct HeadServerConfig {
     pub auth_service_url: String,
     pub matchmaking_service_url: String,
     pub region_config_path: PathBuf,
+    // Shared HMAC secret for signed requests between head and game servers.
+    pub internal_api_secret: String,
+    // Registered game servers that stay silent for longer are evicted.
+    pub game_server_heartbeat_ttl: Duration,
+    // How often auth's revocation list is polled for locally verified tokens; zero disables.
+    pub revocation_poll_interval: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -206,9 +217,38 @@ pub fn load_head_server_config(
             .filter(|value| !value.trim().is_empty())
             .map(PathBuf::from)
             .ok_or(HeadServerConfigError::MissingEnvVar("REGION_CONFIG_PATH"))?,
+        internal_api_secret: required_env_var(env, "INTERNAL_API_SECRET")?
+            .trim()
+            .to_string(),
+        game_server_heartbeat_ttl: Duration::from_secs(parse_heartbeat_ttl_secs(env)?),
+        revocation_poll_interval: Duration::from_secs(parse_revocation_poll_secs(env)?),
     })
 }
 
+fn parse_revocation_poll_secs(env: &impl EnvSource) -> Result<u64, HeadServerConfigError> {
+    const KEY: &str = "HEAD_REVOCATION_POLL_SECS";
+    match env.get_var(KEY) {
+        Some(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| HeadServerConfigError::InvalidEnvVar { key: KEY, value }),
+        _ => Ok(DEFAULT_REVOCATION_POLL_SECS),
+    }
+}
+
+fn parse_heartbeat_ttl_secs(env: &impl EnvSource) -> Result<u64, HeadServerConfigError> {
+    const KEY: &str = "GAME_SERVER_HEARTBEAT_TTL_SECS";
+    match env.get_var(KEY) {
+        Some(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or(HeadServerConfigError::InvalidEnvVar { key: KEY, value }),
+        _ => Ok(DEFAULT_GAME_SERVER_HEARTBEAT_TTL_SECS),
+    }
+}
+
 fn parse_shared_region_config(raw: &str) -> Result<SharedRegionConfig, SharedRegionConfigError> {
     let parsed: RawSharedRegionConfig =
         toml::from_str(raw).map_err(SharedRegionConfigError::ParseFailed)?;
@@ -681,6 +721,9 @@ game_server_ws_url = "http://localhost:3001/ws"
                 "http://matchmaking.internal:9001",
             ),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("GAME_SERVER_HEARTBEAT_TTL_SECS", "30"),
+            ("HEAD_REVOCATION_POLL_SECS", "0"),
         ]);
 
         let config = load_head_server_config(&env).expect("config should load");
@@ -696,6 +739,9 @@ game_server_ws_url = "http://localhost:3001/ws"
             config.region_config_path,
             PathBuf::from("/tmp/regions.custom.toml")
         );
+        assert_eq!(config.internal_api_secret, "shared-secret");
+        assert_eq!(config.game_server_heartbeat_ttl, Duration::from_secs(30));
+        assert_eq!(config.revocation_poll_interval, Duration::ZERO);
     }
 
     #[test]
@@ -712,6 +758,23 @@ game_server_ws_url = "http://localhost:3001/ws"
         ));
     }
 
+    #[test]
+    fn load_head_server_config_requires_internal_api_secret() {
+        for secret in [None, Some("  ")] {
+            let mut pairs = vec![
+                ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
+                ("HEAD_SERVER_PORT", "3000"),
+                ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ];
+            pairs.extend(secret.map(|secret| ("INTERNAL_API_SECRET", secret)));
+
+            assert!(matches!(
+                load_head_server_config(&TestEnv::from_pairs(&pairs)),
+                Err(HeadServerConfigError::MissingEnvVar("INTERNAL_API_SECRET"))
+            ));
+        }
+    }
+
     #[test]
     fn load_head_server_config_reads_port_from_shared_catalog() {
         let path = write_temp_config(
@@ -724,6 +787,7 @@ head_server = 3200
         let env = TestEnv::from_pairs(&[
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("BACKEND_PORTS_CONFIG_PATH", path.to_string_lossy().as_ref()),
         ]);
 
@@ -732,6 +796,33 @@ head_server = 3200
         assert_eq!(config.port, 3200);
     }
 
+    #[test]
+    fn load_head_server_config_polls_revocations_by_default_and_rejects_bad_intervals() {
+        let config = load_head_server_config(&TestEnv::from_pairs(&[
+            ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
+            ("HEAD_SERVER_PORT", "3000"),
+            ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+        ]))
+        .expect("config should load");
+        assert_eq!(config.revocation_poll_interval, Duration::from_secs(5));
+
+        let config = load_head_server_config(&TestEnv::from_pairs(&[
+            ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
+            ("HEAD_SERVER_PORT", "3000"),
+            ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
+            ("HEAD_REVOCATION_POLL_SECS", "soon"),
+        ]));
+        assert!(matches!(
+            config,
+            Err(HeadServerConfigError::InvalidEnvVar {
+                key: "HEAD_REVOCATION_POLL_SECS",
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn load_head_server_config_treats_empty_port_override_as_unset() {
         let path = write_temp_config(
@@ -745,6 +836,7 @@ head_server = 3300
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("HEAD_SERVER_PORT", ""),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("BACKEND_PORTS_CONFIG_PATH", path.to_string_lossy().as_ref()),
         ]);
 
@@ -759,6 +851,7 @@ head_server = 3300
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("HEAD_SERVER_PORT", "not-a-port"),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
         ]);
 
         let config = load_head_server_config(&env);
@@ -778,6 +871,7 @@ head_server = 3300
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("HEAD_SERVER_PORT", "0"),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
         ]);
 
         let config = load_head_server_config(&env);
@@ -803,6 +897,7 @@ head_server = 3000
         let env = TestEnv::from_pairs(&[
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("BACKEND_PORTS_CONFIG_PATH", path.to_string_lossy().as_ref()),
         ]);
 
@@ -823,6 +918,7 @@ auth_server = 3002
         let env = TestEnv::from_pairs(&[
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("BACKEND_PORTS_CONFIG_PATH", path.to_string_lossy().as_ref()),
         ]);
 
@@ -848,6 +944,7 @@ head_server = 0
         let env = TestEnv::from_pairs(&[
             ("HEAD_SERVER_BIND_HOST", "127.0.0.1"),
             ("REGION_CONFIG_PATH", "/tmp/regions.custom.toml"),
+            ("INTERNAL_API_SECRET", "shared-secret"),
             ("BACKEND_PORTS_CONFIG_PATH", path.to_string_lossy().as_ref()),
         ]);
 
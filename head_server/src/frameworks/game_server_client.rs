@@ -1,14 +1,19 @@
+use crate::interface_adapters::request_signing::{
+    SIGNATURE_HEADER, TIMESTAMP_HEADER, sign, unix_now,
+};
 use crate::use_cases::{
     CreateGameLobby, CreateGameLobbyResult, GameServerError, GameServerProvisioner,
 };
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, StatusCode, header::CONTENT_TYPE};
 use serde::Serialize;
 use std::time::Duration;
 
 #[derive(Clone)]
 pub struct GameServerClient {
     http: Client,
+    // Shared secret for signing internal game-server requests.
+    secret: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -18,14 +23,14 @@ struct CreateLobbyHttpRequest {
 }
 
 impl GameServerClient {
-    pub fn new() -> Result<Self, GameServerError> {
+    pub fn new(secret: String) -> Result<Self, GameServerError> {
         let http = Client::builder()
             .connect_timeout(Duration::from_secs(5))
             .timeout(Duration::from_secs(30))
             .build()
             .map_err(|_| GameServerError::Unexpected)?;
 
-        Ok(Self { http })
+        Ok(Self { http, secret })
     }
 }
 
@@ -37,14 +42,24 @@ impl GameServerProvisioner for GameServerClient {
     ) -> Result<CreateGameLobbyResult, GameServerError> {
         let base_url = request.base_url.trim_end_matches('/');
         let url = format!("{base_url}/lobbies");
-
+        let body = serde_json::to_vec(&CreateLobbyHttpRequest {
+            lobby_id: request.lobby_id,
+            allowed_player_ids: request.allowed_player_ids,
+        })
+        .map_err(|_| GameServerError::Unexpected)?;
+
+        // The game server verifies these exact body bytes before creating the lobby.
+        let timestamp = unix_now();
         let response = self
             .http
             .post(url)
-            .json(&CreateLobbyHttpRequest {
-                lobby_id: request.lobby_id,
-                allowed_player_ids: request.allowed_player_ids,
-            })
+            .header(CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(self.secret.as_bytes(), timestamp, "POST", "/lobbies", &body),
+            )
+            .body(body)
             .send()
             .await
             .map_err(|_| GameServerError::UpstreamUnavailable)?;
@@ -101,7 +116,7 @@ mod tests {
     async fn create_lobby_treats_created_as_success() {
         let base_url =
             spawn_test_server(Router::new().route("/lobbies", post(created_handler))).await;
-        let client = GameServerClient::new().expect("client should build");
+        let client = GameServerClient::new("test-secret".into()).expect("client should build");
 
         let result = client
             .create_lobby(CreateGameLobby {
@@ -119,7 +134,7 @@ mod tests {
     async fn create_lobby_treats_conflict_as_retry_safe_success() {
         let base_url =
             spawn_test_server(Router::new().route("/lobbies", post(conflict_handler))).await;
-        let client = GameServerClient::new().expect("client should build");
+        let client = GameServerClient::new("test-secret".into()).expect("client should build");
 
         let result = client
             .create_lobby(CreateGameLobby {
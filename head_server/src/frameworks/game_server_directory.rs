@@ -1,7 +1,12 @@
 use crate::frameworks::config::SharedRegionConfig;
-use crate::use_cases::{GameServerDirectory, GameServerError, ResolvedGameServer};
+use crate::use_cases::{
+    GameServerDirectory, GameServerError, GameServerLoad, GameServerRegistration,
+    GameServerRegistry, GameServerRegistryError, ResolvedGameServer,
+};
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StaticGameServerDirectory {
@@ -22,11 +27,12 @@ impl StaticGameServerDirectory {
 
         Self::new(regional_targets)
     }
-}
 
-#[async_trait]
-impl GameServerDirectory for StaticGameServerDirectory {
-    async fn resolve(&self, region: &str) -> Result<ResolvedGameServer, GameServerError> {
+    pub fn has_region(&self, region: &str) -> bool {
+        self.regional_targets.contains_key(region)
+    }
+
+    fn resolve_static(&self, region: &str) -> Result<ResolvedGameServer, GameServerError> {
         self.regional_targets
             .get(region)
             .cloned()
@@ -36,6 +42,179 @@ impl GameServerDirectory for StaticGameServerDirectory {
     }
 }
 
+#[async_trait]
+impl GameServerDirectory for StaticGameServerDirectory {
+    async fn resolve(&self, region: &str) -> Result<ResolvedGameServer, GameServerError> {
+        self.resolve_static(region)
+    }
+}
+
+// Directory fed by game-server registrations and heartbeats.
+//
+// Regions come from the shared region config; a region with no registered
+// servers falls back to its static entry so single-node setups keep working.
+pub struct DynamicGameServerDirectory {
+    fallback: StaticGameServerDirectory,
+    // Servers that miss heartbeats for this long are evicted.
+    heartbeat_ttl: Duration,
+    servers: Mutex<HashMap<String, RegisteredGameServer>>,
+}
+
+#[derive(Debug)]
+struct RegisteredGameServer {
+    region: String,
+    server: ResolvedGameServer,
+    max_lobbies: usize,
+    max_connections: usize,
+    load: GameServerLoad,
+    last_seen: Instant,
+}
+
+impl RegisteredGameServer {
+    fn has_capacity(&self) -> bool {
+        !self.load.draining
+            && self.load.lobbies < self.max_lobbies
+            && self.load.connections < self.max_connections
+    }
+
+    // Utilization in permille of whichever limit is closer to being hit.
+    fn load_permille(&self) -> usize {
+        let lobbies = self.load.lobbies * 1000 / self.max_lobbies.max(1);
+        let connections = self.load.connections * 1000 / self.max_connections.max(1);
+        lobbies.max(connections)
+    }
+}
+
+impl DynamicGameServerDirectory {
+    pub fn new(fallback: StaticGameServerDirectory, heartbeat_ttl: Duration) -> Self {
+        Self {
+            fallback,
+            heartbeat_ttl,
+            servers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn register_at(
+        &self,
+        registration: GameServerRegistration,
+        now: Instant,
+    ) -> Result<(), GameServerRegistryError> {
+        if !self.fallback.has_region(&registration.region) {
+            return Err(GameServerRegistryError::UnknownRegion {
+                region: registration.region,
+            });
+        }
+
+        tracing::info!(
+            server_id = %registration.server_id,
+            region = %registration.region,
+            base_url = %registration.server.base_url,
+            "game server registered"
+        );
+        // Re-registering replaces the entry, e.g. after a game-server restart.
+        self.servers.lock().unwrap().insert(
+            registration.server_id,
+            RegisteredGameServer {
+                region: registration.region,
+                server: registration.server,
+                max_lobbies: registration.max_lobbies,
+                max_connections: registration.max_connections,
+                load: registration.load,
+                last_seen: now,
+            },
+        );
+        Ok(())
+    }
+
+    fn heartbeat_at(
+        &self,
+        server_id: &str,
+        load: GameServerLoad,
+        now: Instant,
+    ) -> Result<(), GameServerRegistryError> {
+        let mut servers = self.servers.lock().unwrap();
+        self.evict_stale(&mut servers, now);
+        let server = servers
+            .get_mut(server_id)
+            .ok_or(GameServerRegistryError::UnknownServer)?;
+        server.load = load;
+        server.last_seen = now;
+        Ok(())
+    }
+
+    fn resolve_at(
+        &self,
+        region: &str,
+        now: Instant,
+    ) -> Result<ResolvedGameServer, GameServerError> {
+        let mut servers = self.servers.lock().unwrap();
+        self.evict_stale(&mut servers, now);
+
+        let mut in_region = servers
+            .iter_mut()
+            .filter(|(_, server)| server.region == region)
+            .peekable();
+        if in_region.peek().is_none() {
+            drop(servers);
+            return self.fallback.resolve_static(region);
+        }
+
+        let (server_id, chosen) = in_region
+            .filter(|(_, server)| server.has_capacity())
+            .min_by(|(a_id, a), (b_id, b)| {
+                a.load_permille()
+                    .cmp(&b.load_permille())
+                    .then_with(|| a_id.cmp(b_id))
+            })
+            .ok_or_else(|| GameServerError::NoCapacity {
+                region: region.to_string(),
+            })?;
+        // Count the lobby now so placements between heartbeats spread out.
+        chosen.load.lobbies += 1;
+        tracing::debug!(%server_id, region, lobbies = chosen.load.lobbies, "placed lobby");
+        Ok(chosen.server.clone())
+    }
+
+    fn evict_stale(&self, servers: &mut HashMap<String, RegisteredGameServer>, now: Instant) {
+        servers.retain(|server_id, server| {
+            let alive = now.saturating_duration_since(server.last_seen) <= self.heartbeat_ttl;
+            if !alive {
+                tracing::warn!(
+                    %server_id,
+                    region = %server.region,
+                    "game server missed heartbeats; evicting"
+                );
+            }
+            alive
+        });
+    }
+}
+
+#[async_trait]
+impl GameServerDirectory for DynamicGameServerDirectory {
+    async fn resolve(&self, region: &str) -> Result<ResolvedGameServer, GameServerError> {
+        self.resolve_at(region, Instant::now())
+    }
+}
+
+#[async_trait]
+impl GameServerRegistry for DynamicGameServerDirectory {
+    async fn register(
+        &self,
+        registration: GameServerRegistration,
+    ) -> Result<(), GameServerRegistryError> {
+        self.register_at(registration, Instant::now())
+    }
+
+    async fn heartbeat(
+        &self,
+        server_id: &str,
+        load: GameServerLoad,
+    ) -> Result<(), GameServerRegistryError> {
+        self.heartbeat_at(server_id, load, Instant::now())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +283,147 @@ mod tests {
             })
         );
     }
+
+    fn dynamic_directory() -> DynamicGameServerDirectory {
+        DynamicGameServerDirectory::new(
+            StaticGameServerDirectory::new(HashMap::from([(
+                "eu-west".to_string(),
+                target("http://eu.static", "ws://eu.static/ws"),
+            )])),
+            Duration::from_secs(15),
+        )
+    }
+
+    fn registration(server_id: &str, lobbies: usize) -> GameServerRegistration {
+        GameServerRegistration {
+            server_id: server_id.into(),
+            region: "eu-west".into(),
+            server: target(
+                &format!("http://{server_id}.internal"),
+                &format!("ws://{server_id}/ws"),
+            ),
+            max_lobbies: 4,
+            max_connections: 100,
+            load: GameServerLoad {
+                lobbies,
+                ..GameServerLoad::default()
+            },
+        }
+    }
+
+    #[test]
+    fn dynamic_directory_falls_back_to_static_entry_until_a_server_registers() {
+        let directory = dynamic_directory();
+        let now = Instant::now();
+
+        assert_eq!(
+            directory.resolve_at("eu-west", now),
+            Ok(target("http://eu.static", "ws://eu.static/ws"))
+        );
+        assert_eq!(
+            directory.register_at(
+                GameServerRegistration {
+                    region: "us-east".into(),
+                    ..registration("gs-1", 0)
+                },
+                now
+            ),
+            Err(GameServerRegistryError::UnknownRegion {
+                region: "us-east".into()
+            })
+        );
+
+        directory
+            .register_at(registration("gs-1", 0), now)
+            .expect("registration should succeed");
+        assert_eq!(
+            directory.resolve_at("eu-west", now),
+            Ok(target("http://gs-1.internal", "ws://gs-1/ws"))
+        );
+    }
+
+    #[test]
+    fn dynamic_directory_picks_least_loaded_server_with_capacity() {
+        let directory = dynamic_directory();
+        let now = Instant::now();
+        directory.register_at(registration("gs-1", 1), now).unwrap();
+        directory.register_at(registration("gs-2", 3), now).unwrap();
+
+        // gs-1 absorbs placements until it is full; ties go to the lower id.
+        let placed: Vec<String> = (0..4)
+            .map(|_| directory.resolve_at("eu-west", now).unwrap().base_url)
+            .collect();
+        assert_eq!(
+            placed,
+            vec![
+                "http://gs-1.internal",
+                "http://gs-1.internal",
+                "http://gs-1.internal",
+                "http://gs-2.internal",
+            ]
+        );
+
+        assert_eq!(
+            directory.resolve_at("eu-west", now),
+            Err(GameServerError::NoCapacity {
+                region: "eu-west".into()
+            })
+        );
+    }
+
+    #[test]
+    fn dynamic_directory_evicts_servers_that_stop_heartbeating() {
+        let directory = dynamic_directory();
+        let start = Instant::now();
+        directory
+            .register_at(registration("gs-1", 0), start)
+            .unwrap();
+        directory
+            .register_at(registration("gs-2", 2), start)
+            .unwrap();
+
+        let later = start + Duration::from_secs(10);
+        directory
+            .heartbeat_at("gs-2", GameServerLoad::default(), later)
+            .expect("known server heartbeat should succeed");
+
+        let after_ttl = start + Duration::from_secs(20);
+        assert_eq!(
+            directory.resolve_at("eu-west", after_ttl),
+            Ok(target("http://gs-2.internal", "ws://gs-2/ws"))
+        );
+        assert_eq!(
+            directory.heartbeat_at("gs-1", GameServerLoad::default(), after_ttl),
+            Err(GameServerRegistryError::UnknownServer)
+        );
+    }
+    #[test]
+    fn dynamic_directory_skips_draining_servers() {
+        let directory = dynamic_directory();
+        let now = Instant::now();
+        directory.register_at(registration("gs-1", 0), now).unwrap();
+        directory.register_at(registration("gs-2", 3), now).unwrap();
+
+        directory
+            .heartbeat_at(
+                "gs-1",
+                GameServerLoad {
+                    draining: true,
+                    ..GameServerLoad::default()
+                },
+                now,
+            )
+            .unwrap();
+
+        assert_eq!(
+            directory.resolve_at("eu-west", now),
+            Ok(target("http://gs-2.internal", "ws://gs-2/ws"))
+        );
+        assert_eq!(
+            directory.resolve_at("eu-west", now),
+            Err(GameServerError::NoCapacity {
+                region: "eu-west".into()
+            })
+        );
+    }
 }
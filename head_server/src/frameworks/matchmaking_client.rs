@@ -34,6 +34,13 @@ struct MatchmakingQueueHttpRequest {
     region: String,
 }
 
+// Players to requeue; matchmaking requeues the whole roster when absent.
+#[derive(Debug, Serialize)]
+struct RequeueMatchHttpRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    player_ids: Option<Vec<u64>>,
+}
+
 #[derive(Debug, Deserialize)]
 struct MatchmakingHttpResponse {
     status: MatchmakingHttpStatus,
@@ -136,6 +143,45 @@ impl MatchmakingProvider for MatchmakingClient {
 
         parse_lifecycle_response(response).await
     }
+
+    async fn requeue_match(
+        &self,
+        match_id: String,
+        player_ids: Option<Vec<u64>>,
+    ) -> Result<(), MatchmakingProviderError> {
+        let url = self.endpoint(&format!("matchmaking/matches/{match_id}/requeue"))?;
+        let response = self
+            .http
+            .post(url)
+            .json(&RequeueMatchHttpRequest { player_ids })
+            .send()
+            .await
+            .map_err(|_| MatchmakingProviderError::UpstreamUnavailable)?;
+
+        // The requeued ticket states are only informational for head.
+        let response = ensure_success_response(response).await?;
+        let _ = response.bytes().await;
+        Ok(())
+    }
+}
+
+// Error envelope returned by matchmaking; only the code decides the error.
+#[derive(Debug, Deserialize)]
+struct MatchmakingErrorResponse {
+    code: MatchmakingErrorCode,
+}
+
+// Matchmaking error codes; the registry is in ERROR_CODES.md.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MatchmakingErrorCode {
+    InvalidRequest,
+    TicketNotOwned,
+    TicketNotFound,
+    TicketMatched,
+    MatchNotFound,
+    #[serde(other)]
+    Other,
 }
 
 async fn parse_lifecycle_response(
@@ -175,9 +221,29 @@ async fn ensure_success_response(response: Response) -> Result<Response, Matchma
         return Ok(response);
     }
 
-    // Drain the response body so the underlying connection can be reused.
-    let _ = response.bytes().await;
-    Err(map_status_to_error(status))
+    // Reading the body also lets the underlying connection be reused.
+    let code = response
+        .json::<MatchmakingErrorResponse>()
+        .await
+        .ok()
+        .map(|error| error.code);
+    Err(code
+        .and_then(map_code_to_error)
+        .unwrap_or_else(|| map_status_to_error(status)))
+}
+
+// `None` for codes head does not know; the status decides those.
+fn map_code_to_error(code: MatchmakingErrorCode) -> Option<MatchmakingProviderError> {
+    let error = match code {
+        MatchmakingErrorCode::InvalidRequest => MatchmakingProviderError::BadRequest,
+        MatchmakingErrorCode::TicketNotOwned => MatchmakingProviderError::Unauthorized,
+        MatchmakingErrorCode::TicketNotFound | MatchmakingErrorCode::MatchNotFound => {
+            MatchmakingProviderError::NotFound
+        }
+        MatchmakingErrorCode::TicketMatched => MatchmakingProviderError::Conflict,
+        MatchmakingErrorCode::Other => return None,
+    };
+    Some(error)
 }
 
 fn map_status_to_error(status: StatusCode) -> MatchmakingProviderError {
@@ -379,6 +445,30 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn cancel_branches_on_the_error_code() {
+        let router = Router::new().route(
+            "/matchmaking/queue/{ticket_id}",
+            delete(|| async {
+                (
+                    AxumStatusCode::CONFLICT,
+                    Json(json!({
+                        "code": "ticket_matched",
+                        "message": "ticket_id ticket-123 is already matched",
+                        "retryable": false,
+                        "details": { "ticket_id": "ticket-123" }
+                    })),
+                )
+            }),
+        );
+        let base_url = spawn_test_server(router).await;
+        let client = MatchmakingClient::new(&base_url).expect("client should build");
+
+        let result = client.cancel(42, "ticket-123".into()).await;
+
+        assert_eq!(result, Err(MatchmakingProviderError::Conflict));
+    }
+
     #[test]
     fn conflict_maps_to_matchmaking_conflict_error() {
         assert_eq!(
@@ -394,4 +484,43 @@ mod tests {
             MatchmakingProviderError::NotFound
         );
     }
+
+    #[tokio::test]
+    async fn requeue_match_posts_the_players_to_requeue() {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::new()
+            .route(
+                "/matchmaking/matches/match-123/requeue",
+                post(
+                    |State(bodies): State<Arc<Mutex<Vec<serde_json::Value>>>>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        bodies
+                            .lock()
+                            .expect("lock should not be poisoned")
+                            .push(body);
+                        Json(json!({ "tickets": [] }))
+                    },
+                ),
+            )
+            .with_state(bodies.clone());
+        let base_url = spawn_test_server(router).await;
+        let client = MatchmakingClient::new(&base_url).expect("client should build");
+
+        client
+            .requeue_match("match-123".into(), Some(vec![7]))
+            .await
+            .expect("request should succeed");
+        client
+            .requeue_match("match-123".into(), None)
+            .await
+            .expect("request should succeed");
+
+        assert_eq!(
+            bodies
+                .lock()
+                .expect("lock should not be poisoned")
+                .as_slice(),
+            &[json!({ "player_ids": [7] }), json!({})]
+        );
+    }
 }
@@ -3,7 +3,9 @@ use crate::frameworks::config::{
     HeadServerConfigError, ProcessEnv, load_head_server_config, load_shared_region_config,
 };
 use crate::frameworks::game_server_client::GameServerClient;
-use crate::frameworks::game_server_directory::StaticGameServerDirectory;
+use crate::frameworks::game_server_directory::{
+    DynamicGameServerDirectory, StaticGameServerDirectory,
+};
 use crate::frameworks::matchmaking_client::MatchmakingClient;
 use crate::interface_adapters::routes;
 use crate::interface_adapters::state::AppState;
@@ -120,7 +122,10 @@ pub async fn run() -> Result<(), StartupFailure> {
         auth_base_url = %config.auth_service_url,
         "auth client configured."
     );
-    let auth = match AuthClient::new(&config.auth_service_url) {
+    let auth = match AuthClient::new(
+        &config.auth_service_url,
+        config.internal_api_secret.as_str(),
+    ) {
         Ok(client) => Arc::new(client),
         Err(error) => {
             tracing::error!(
@@ -132,6 +137,14 @@ pub async fn run() -> Result<(), StartupFailure> {
         }
     };
 
+    if config.revocation_poll_interval.is_zero() {
+        tracing::info!("revocation polling disabled; signed tokens are verified by auth");
+    } else {
+        auth.as_ref()
+            .clone()
+            .spawn_revocation_poller(config.revocation_poll_interval);
+    }
+
     let guest_sessions = Arc::new(GuestSessionService::new(auth.clone()));
 
     tracing::debug!(
@@ -165,11 +178,13 @@ pub async fn run() -> Result<(), StartupFailure> {
             return Err(StartupFailure::InvalidConfiguration);
         }
     };
-    let game_servers = Arc::new(StaticGameServerDirectory::from_shared_region_config(
-        shared_region_config,
+    // Registered game servers take over placement; regions.toml is the fallback.
+    let game_servers = Arc::new(DynamicGameServerDirectory::new(
+        StaticGameServerDirectory::from_shared_region_config(shared_region_config),
+        config.game_server_heartbeat_ttl,
     ));
 
-    let provisioner = match GameServerClient::new() {
+    let provisioner = match GameServerClient::new(config.internal_api_secret.clone()) {
         Ok(client) => Arc::new(client),
         Err(error) => {
             tracing::error!(?error, "failed to build game server client");
@@ -180,13 +195,15 @@ pub async fn run() -> Result<(), StartupFailure> {
     let matchmaking = Arc::new(MatchmakingService::new(
         auth.clone(),
         matchmaking,
-        game_servers,
+        game_servers.clone(),
         provisioner,
     ));
 
     let state = Arc::new(AppState {
         guest_sessions,
         matchmaking,
+        game_server_registry: game_servers,
+        internal_secret: Arc::from(config.internal_api_secret.as_str()),
     });
 
     // Start the web server with the HTTP routes wired up.
@@ -213,7 +230,13 @@ pub async fn run() -> Result<(), StartupFailure> {
         }
     };
 
-    if let Err(error) = axum::serve(listener, app).await {
+    // Peer addresses are forwarded to auth, which rate limits guest calls per player.
+    if let Err(error) = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    {
         tracing::error!(error = %error, "server error");
         return Err(StartupFailure::Serve);
     }
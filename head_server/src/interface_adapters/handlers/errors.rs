@@ -0,0 +1,46 @@
+use crate::interface_adapters::protocol::{ErrorCode, ErrorResponse};
+use axum::{Json, http::StatusCode};
+
+// JSON error reply shared by head's handlers.
+pub type ErrorReply = (StatusCode, Json<ErrorResponse>);
+
+pub fn error_response(status: StatusCode, code: ErrorCode, message: &str) -> ErrorReply {
+    (
+        status,
+        Json(ErrorResponse {
+            code,
+            message: message.to_string(),
+            retryable: code.retryable(),
+            details: None,
+        }),
+    )
+}
+
+pub fn bad_request(message: &str) -> ErrorReply {
+    error_response(StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest, message)
+}
+
+pub fn unauthorized(message: &str) -> ErrorReply {
+    error_response(StatusCode::UNAUTHORIZED, ErrorCode::Unauthorized, message)
+}
+
+// The client should refresh its session and retry.
+pub fn session_expired() -> ErrorReply {
+    error_response(
+        StatusCode::UNAUTHORIZED,
+        ErrorCode::SessionExpired,
+        "session expired",
+    )
+}
+
+pub fn upstream_unavailable(message: &str) -> ErrorReply {
+    error_response(
+        StatusCode::BAD_GATEWAY,
+        ErrorCode::UpstreamUnavailable,
+        message,
+    )
+}
+
+pub fn upstream_error(message: &str) -> ErrorReply {
+    error_response(StatusCode::BAD_GATEWAY, ErrorCode::UpstreamError, message)
+}
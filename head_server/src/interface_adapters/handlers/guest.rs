@@ -1,19 +1,48 @@
+use crate::interface_adapters::handlers::errors::{
+    ErrorReply, bad_request, error_response, session_expired, unauthorized, upstream_error,
+    upstream_unavailable,
+};
 use crate::interface_adapters::protocol::{
-    HeadGuestInitRequest, HeadGuestInitResponse, HeadGuestLoginRequest, HeadGuestLoginResponse,
+    ErrorCode, HeadGuestInitRequest, HeadGuestInitResponse, HeadGuestLoginRequest,
+    HeadGuestLoginResponse, HeadGuestRefreshRequest, HeadGuestRefreshResponse,
 };
 use crate::interface_adapters::state::AppState;
-use crate::use_cases::{AuthProviderError, GuestInit, GuestLogin};
-use axum::{Json, extract::State, http::StatusCode};
+use crate::use_cases::{AuthProviderError, GuestInit, GuestLogin, RefreshSession};
+use axum::{
+    Json,
+    extract::{ConnectInfo, FromRequestParts, State},
+    http::{StatusCode, request::Parts},
+};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
+// Player's address; absent when the router is served without connect info, as in tests.
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+        ))
+    }
+}
+
 #[tracing::instrument(name = "guest_init", skip_all)]
 pub async fn guest_init(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
     Json(body): Json<HeadGuestInitRequest>,
-) -> Result<Json<HeadGuestInitResponse>, StatusCode> {
+) -> Result<Json<HeadGuestInitResponse>, ErrorReply> {
     // Convert the HTTP request into an application command.
     let request = GuestInit {
         display_name: body.display_name,
+        client_ip,
     };
 
     // Delegate workflow orchestration to the use-case layer.
@@ -34,8 +63,11 @@ pub async fn guest_init(
     Ok(Json(HeadGuestInitResponse {
         // Keep guest_id stringly-typed on the client boundary to avoid JSON number precision loss.
         guest_id: result.guest_id.to_string(),
+        guest_secret: result.guest_secret,
         session_token: result.session_token,
         expires_at: result.expires_at,
+        refresh_token: result.refresh_token,
+        refresh_expires_at: result.refresh_expires_at,
     }))
 }
 
@@ -46,19 +78,22 @@ pub async fn guest_init(
 )]
 pub async fn guest_login(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
     Json(body): Json<HeadGuestLoginRequest>,
-) -> Result<Json<HeadGuestLoginResponse>, StatusCode> {
+) -> Result<Json<HeadGuestLoginResponse>, ErrorReply> {
     // Parse guest_id at the adapter boundary; application paths keep numeric IDs.
     let guest_id = body
         .guest_id
         .trim()
         .parse::<u64>()
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|_| bad_request("guest_id must be a number"))?;
 
     // Convert the HTTP request into an application command.
     let request = GuestLogin {
         guest_id,
+        guest_secret: body.guest_secret,
         display_name: body.display_name,
+        client_ip,
     };
 
     // Delegate workflow orchestration to the use-case layer.
@@ -67,7 +102,12 @@ pub async fn guest_login(
         .guest_login(request)
         .await
         .map_err(|error| {
-            tracing::error!(?error, "failed to create guest session.");
+            match error {
+                AuthProviderError::Unauthorized => {
+                    tracing::warn!(?error, "guest login rejected.")
+                }
+                _ => tracing::error!(?error, "failed to create guest session."),
+            }
             map_guest_session_error(&error)
         })?;
 
@@ -77,19 +117,72 @@ pub async fn guest_login(
     Ok(Json(HeadGuestLoginResponse {
         session_token: result.session_token,
         expires_at: result.expires_at,
+        refresh_token: result.refresh_token,
+        refresh_expires_at: result.refresh_expires_at,
+    }))
+}
+
+#[tracing::instrument(name = "guest_refresh", skip_all)]
+pub async fn guest_refresh(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    Json(body): Json<HeadGuestRefreshRequest>,
+) -> Result<Json<HeadGuestRefreshResponse>, ErrorReply> {
+    let request = RefreshSession {
+        refresh_token: body.refresh_token,
+        client_ip,
+    };
+
+    // Auth rotates both tokens; a rejected refresh means the client must log in again.
+    let result = state
+        .guest_sessions
+        .refresh_session(request)
+        .await
+        .map_err(|error| {
+            match error {
+                AuthProviderError::Unauthorized => {
+                    tracing::warn!(?error, "guest session refresh rejected.")
+                }
+                _ => tracing::error!(?error, "failed to refresh guest session."),
+            }
+            map_guest_session_error(&error)
+        })?;
+
+    tracing::info!("guest session refreshed successfully.");
+
+    Ok(Json(HeadGuestRefreshResponse {
+        session_token: result.session_token,
+        expires_at: result.expires_at,
+        refresh_token: result.refresh_token,
+        refresh_expires_at: result.refresh_expires_at,
     }))
 }
 
-fn map_guest_session_error(error: &AuthProviderError) -> StatusCode {
+fn map_guest_session_error(error: &AuthProviderError) -> ErrorReply {
     match error {
-        AuthProviderError::BadRequest => StatusCode::BAD_REQUEST,
-        AuthProviderError::Unauthorized => StatusCode::UNAUTHORIZED,
-        AuthProviderError::Forbidden => StatusCode::FORBIDDEN,
-        AuthProviderError::NotFound => StatusCode::NOT_FOUND,
-        AuthProviderError::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
-        AuthProviderError::UnexpectedClientError
-        | AuthProviderError::UpstreamUnavailable
-        | AuthProviderError::Unexpected => StatusCode::BAD_GATEWAY,
+        AuthProviderError::BadRequest => bad_request("rejected by auth"),
+        AuthProviderError::Unauthorized => unauthorized("invalid credentials"),
+        AuthProviderError::SessionExpired => session_expired(),
+        AuthProviderError::Forbidden => {
+            error_response(StatusCode::FORBIDDEN, ErrorCode::Forbidden, "forbidden")
+        }
+        AuthProviderError::NotFound => {
+            error_response(StatusCode::NOT_FOUND, ErrorCode::NotFound, "not found")
+        }
+        AuthProviderError::UnprocessableEntity => error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::InvalidRequest,
+            "rejected by auth",
+        ),
+        AuthProviderError::RateLimited => error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::RateLimited,
+            "too many requests",
+        ),
+        AuthProviderError::UpstreamUnavailable => upstream_unavailable("auth unavailable"),
+        AuthProviderError::UnexpectedClientError | AuthProviderError::Unexpected => {
+            upstream_error("unexpected auth response")
+        }
     }
 }
 
@@ -98,9 +191,10 @@ mod tests {
     use super::*;
     use crate::use_cases::{
         AuthProvider, CreateGameLobby, CreateGameLobbyResult, GameServerDirectory, GameServerError,
-        GameServerProvisioner, GuestInitResult, GuestLoginResult, GuestSessionService,
+        GameServerLoad, GameServerProvisioner, GameServerRegistration, GameServerRegistry,
+        GameServerRegistryError, GuestInitResult, GuestLoginResult, GuestSessionService,
         MatchmakingLifecycleState, MatchmakingProvider, MatchmakingProviderError,
-        MatchmakingService, ResolvedGameServer,
+        MatchmakingService, RefreshSessionResult, ResolvedGameServer,
     };
     use async_trait::async_trait;
     use std::sync::Mutex;
@@ -109,6 +203,7 @@ mod tests {
     struct MockAuthProvider {
         init_response: Mutex<Option<Result<GuestInitResult, AuthProviderError>>>,
         login_response: Mutex<Option<Result<GuestLoginResult, AuthProviderError>>>,
+        refresh_response: Mutex<Option<Result<RefreshSessionResult, AuthProviderError>>>,
     }
 
     #[async_trait]
@@ -141,6 +236,17 @@ mod tests {
         ) -> Result<crate::use_cases::VerifySessionResult, AuthProviderError> {
             panic!("verify session should not be called");
         }
+
+        async fn refresh_session(
+            &self,
+            _req: RefreshSession,
+        ) -> Result<RefreshSessionResult, AuthProviderError> {
+            self.refresh_response
+                .lock()
+                .expect("lock should not be poisoned")
+                .take()
+                .expect("refresh response should be configured")
+        }
     }
 
     #[derive(Default)]
@@ -170,6 +276,14 @@ mod tests {
         ) -> Result<MatchmakingLifecycleState, MatchmakingProviderError> {
             panic!("matchmaking should not be called");
         }
+
+        async fn requeue_match(
+            &self,
+            _match_id: String,
+            _player_ids: Option<Vec<u64>>,
+        ) -> Result<(), MatchmakingProviderError> {
+            panic!("matchmaking should not be called");
+        }
     }
 
     #[derive(Default)]
@@ -195,6 +309,27 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct UnusedGameServerRegistry;
+
+    #[async_trait]
+    impl GameServerRegistry for UnusedGameServerRegistry {
+        async fn register(
+            &self,
+            _registration: GameServerRegistration,
+        ) -> Result<(), GameServerRegistryError> {
+            panic!("game server registry should not be called");
+        }
+
+        async fn heartbeat(
+            &self,
+            _server_id: &str,
+            _load: GameServerLoad,
+        ) -> Result<(), GameServerRegistryError> {
+            panic!("game server registry should not be called");
+        }
+    }
+
     fn app_state(auth: Arc<dyn AuthProvider>) -> Arc<AppState> {
         Arc::new(AppState {
             guest_sessions: Arc::new(GuestSessionService::new(auth)),
@@ -204,6 +339,8 @@ mod tests {
                 Arc::new(UnusedGameServerDirectory),
                 Arc::new(UnusedGameServerProvisioner),
             )),
+            game_server_registry: Arc::new(UnusedGameServerRegistry),
+            internal_secret: Arc::from("test-secret"),
         })
     }
 
@@ -212,8 +349,10 @@ mod tests {
         let state = app_state(Arc::new(MockAuthProvider::default()));
         let result = guest_login(
             State(state),
+            ClientIp(None),
             Json(HeadGuestLoginRequest {
                 guest_id: "abc".into(),
+                guest_secret: "secret".into(),
                 display_name: "Pilot".into(),
             }),
         )
@@ -221,7 +360,10 @@ mod tests {
 
         match result {
             Ok(_) => panic!("invalid guest ids should fail"),
-            Err(error) => assert_eq!(error, StatusCode::BAD_REQUEST),
+            Err((status, Json(error))) => {
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(error.code, ErrorCode::InvalidRequest);
+            }
         }
     }
 
@@ -255,8 +397,10 @@ mod tests {
 
             let result = guest_login(
                 State(state),
+                ClientIp(None),
                 Json(HeadGuestLoginRequest {
                     guest_id: "42".into(),
+                    guest_secret: "secret".into(),
                     display_name: "Pilot".into(),
                 }),
             )
@@ -264,7 +408,7 @@ mod tests {
 
             match result {
                 Ok(_) => panic!("provider errors should fail"),
-                Err(status) => assert_eq!(status, expected_status),
+                Err((status, _)) => assert_eq!(status, expected_status),
             }
         }
     }
@@ -299,6 +443,7 @@ mod tests {
 
             let result = guest_init(
                 State(state),
+                ClientIp(None),
                 Json(HeadGuestInitRequest {
                     display_name: "Pilot".into(),
                 }),
@@ -307,44 +452,121 @@ mod tests {
 
             match result {
                 Ok(_) => panic!("provider errors should fail"),
-                Err(status) => assert_eq!(status, expected_status),
+                Err((status, _)) => assert_eq!(status, expected_status),
             }
         }
     }
 
+    #[tokio::test]
+    async fn guest_refresh_returns_rotated_tokens() {
+        let state = app_state(Arc::new(MockAuthProvider {
+            refresh_response: Mutex::new(Some(Ok(RefreshSessionResult {
+                session_token: "token-2".into(),
+                expires_at: 456,
+                refresh_token: "refresh-2".into(),
+                refresh_expires_at: 4567,
+            }))),
+            ..Default::default()
+        }));
+
+        let Json(response) = guest_refresh(
+            State(state),
+            ClientIp(None),
+            Json(HeadGuestRefreshRequest {
+                refresh_token: "refresh-1".into(),
+            }),
+        )
+        .await
+        .unwrap_or_else(|_| panic!("refresh should succeed"));
+
+        assert_eq!(response.session_token, "token-2");
+        assert_eq!(response.expires_at, 456);
+        assert_eq!(response.refresh_token, "refresh-2");
+        assert_eq!(response.refresh_expires_at, 4567);
+    }
+
+    #[tokio::test]
+    async fn guest_refresh_passes_rejections_through_as_unauthorized() {
+        let state = app_state(Arc::new(MockAuthProvider {
+            refresh_response: Mutex::new(Some(Err(AuthProviderError::Unauthorized))),
+            ..Default::default()
+        }));
+
+        let result = guest_refresh(
+            State(state),
+            ClientIp(None),
+            Json(HeadGuestRefreshRequest {
+                refresh_token: "refresh-1".into(),
+            }),
+        )
+        .await;
+
+        match result {
+            Ok(_) => panic!("rejected refreshes should fail"),
+            Err((status, _)) => assert_eq!(status, StatusCode::UNAUTHORIZED),
+        }
+    }
+
     #[test]
     fn auth_provider_errors_map_to_expected_http_status_codes() {
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::BadRequest),
-            StatusCode::BAD_REQUEST
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::Unauthorized),
-            StatusCode::UNAUTHORIZED
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::Forbidden),
-            StatusCode::FORBIDDEN
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::NotFound),
-            StatusCode::NOT_FOUND
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::UnprocessableEntity),
-            StatusCode::UNPROCESSABLE_ENTITY
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::UnexpectedClientError),
-            StatusCode::BAD_GATEWAY
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::UpstreamUnavailable),
-            StatusCode::BAD_GATEWAY
-        );
-        assert_eq!(
-            map_guest_session_error(&AuthProviderError::Unexpected),
-            StatusCode::BAD_GATEWAY
-        );
+        let cases = [
+            (
+                AuthProviderError::BadRequest,
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidRequest,
+            ),
+            (
+                AuthProviderError::Unauthorized,
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::Unauthorized,
+            ),
+            (
+                AuthProviderError::SessionExpired,
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::SessionExpired,
+            ),
+            (
+                AuthProviderError::Forbidden,
+                StatusCode::FORBIDDEN,
+                ErrorCode::Forbidden,
+            ),
+            (
+                AuthProviderError::NotFound,
+                StatusCode::NOT_FOUND,
+                ErrorCode::NotFound,
+            ),
+            (
+                AuthProviderError::UnprocessableEntity,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::InvalidRequest,
+            ),
+            (
+                AuthProviderError::RateLimited,
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorCode::RateLimited,
+            ),
+            (
+                AuthProviderError::UnexpectedClientError,
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::UpstreamError,
+            ),
+            (
+                AuthProviderError::UpstreamUnavailable,
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::UpstreamUnavailable,
+            ),
+            (
+                AuthProviderError::Unexpected,
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::UpstreamError,
+            ),
+        ];
+
+        for (provider_error, expected_status, expected_code) in cases {
+            let (status, Json(body)) = map_guest_session_error(&provider_error);
+            assert_eq!(status, expected_status);
+            assert_eq!(body.code, expected_code);
+            assert_eq!(body.retryable, expected_code.retryable());
+        }
     }
 }
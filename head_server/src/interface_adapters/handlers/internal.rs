@@ -0,0 +1,323 @@
+use crate::interface_adapters::handlers::errors::{
+    ErrorReply, bad_request, error_response, unauthorized,
+};
+use crate::interface_adapters::protocol::{
+    ErrorCode, GameServerHeartbeatRequest, GameServerLoadDto, GameServerRegistrationRequest,
+    LobbyAbortReasonDto, LobbyAbortedRequest,
+};
+use crate::interface_adapters::request_signing::{
+    SIGNATURE_HEADER, SignatureError, TIMESTAMP_HEADER, unix_now, verify,
+};
+use crate::interface_adapters::state::AppState;
+use crate::use_cases::{
+    GameServerLoad, GameServerRegistration, GameServerRegistryError, LobbyAbortReason,
+    LobbyAborted, ResolvedGameServer,
+};
+use axum::{
+    Json,
+    body::{Body, to_bytes},
+    extract::{Path, Request, State},
+    http::{StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+// Game-server callbacks; these are not part of the public client API.
+
+// Callback bodies are small JSON documents; cap what we buffer for signing.
+const MAX_SIGNED_BODY_BYTES: usize = 64 * 1024;
+
+/// Rejects callbacks that are not signed with the shared service secret.
+pub async fn require_internal_signature(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let secret = state.internal_secret.clone();
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_SIGNED_BODY_BYTES).await else {
+        return unauthorized("request body too large to verify").into_response();
+    };
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let result = match (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER)) {
+        (Some(timestamp), Some(signature)) => verify(
+            secret.as_bytes(),
+            timestamp,
+            signature,
+            parts.method.as_str(),
+            request_target(&parts.uri),
+            &body,
+            unix_now(),
+        ),
+        _ => Err(SignatureError::Missing),
+    };
+
+    if let Err(error) = result {
+        tracing::warn!(path = %parts.uri.path(), ?error, "rejected unsigned internal request");
+        return unauthorized("invalid internal request signature").into_response();
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+// The path and query a request was signed over, so a query cannot be swapped in transit.
+fn request_target(uri: &Uri) -> &str {
+    uri.path_and_query()
+        .map_or_else(|| uri.path(), |target| target.as_str())
+}
+
+#[tracing::instrument(name = "lobby_aborted", skip_all, fields(lobby_id = %lobby_id))]
+pub async fn lobby_aborted(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<String>,
+    Json(body): Json<LobbyAbortedRequest>,
+) -> Result<StatusCode, ErrorReply> {
+    if lobby_id.trim().is_empty() {
+        return Err(bad_request("lobby_id is required"));
+    }
+
+    let reason = match (body.reason, body.joined_players, body.required) {
+        (LobbyAbortReasonDto::RosterNoShow, Some(joined_players), Some(required)) => {
+            LobbyAbortReason::RosterNoShow {
+                joined_players,
+                required,
+            }
+        }
+        (LobbyAbortReasonDto::RosterNoShow, _, _) => {
+            return Err(bad_request(
+                "roster_no_show needs joined_players and required",
+            ));
+        }
+        (LobbyAbortReasonDto::ClosedByAdmin, _, _) => LobbyAbortReason::ClosedByAdmin,
+        (LobbyAbortReasonDto::ServerDraining, _, _) => LobbyAbortReason::ServerDraining,
+        (LobbyAbortReasonDto::WorldCrashed, _, _) => LobbyAbortReason::WorldCrashed,
+        (LobbyAbortReasonDto::Abandoned, _, _) => LobbyAbortReason::Abandoned,
+    };
+    state
+        .matchmaking
+        .record_lobby_aborted(LobbyAborted { lobby_id, reason })
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[tracing::instrument(
+    name = "register_game_server",
+    skip_all,
+    fields(server_id = %body.server_id, region = %body.region)
+)]
+pub async fn register_game_server(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<GameServerRegistrationRequest>,
+) -> Result<StatusCode, ErrorReply> {
+    if body.server_id.trim().is_empty()
+        || body.max_lobbies == 0
+        || body.max_connections == 0
+        || !has_scheme(&body.base_url, &["http", "https"])
+        || !has_scheme(&body.ws_url, &["ws", "wss"])
+    {
+        return Err(bad_request("invalid game server registration"));
+    }
+
+    let registration = GameServerRegistration {
+        server_id: body.server_id,
+        region: body.region,
+        server: ResolvedGameServer {
+            base_url: body.base_url,
+            ws_url: body.ws_url,
+        },
+        max_lobbies: body.max_lobbies,
+        max_connections: body.max_connections,
+        load: body.load.into(),
+    };
+    match state.game_server_registry.register(registration).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(error) => {
+            tracing::warn!(?error, "rejected game server registration");
+            Err(map_registry_error(&error))
+        }
+    }
+}
+
+#[tracing::instrument(name = "game_server_heartbeat", skip_all, fields(server_id = %body.server_id))]
+pub async fn game_server_heartbeat(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<GameServerHeartbeatRequest>,
+) -> Result<StatusCode, ErrorReply> {
+    // 404 tells the game server to register again, e.g. after head restarted.
+    match state
+        .game_server_registry
+        .heartbeat(&body.server_id, body.load.into())
+        .await
+    {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(error) => Err(map_registry_error(&error)),
+    }
+}
+
+impl From<GameServerLoadDto> for GameServerLoad {
+    fn from(load: GameServerLoadDto) -> Self {
+        GameServerLoad {
+            lobbies: load.lobbies,
+            players: load.players,
+            connections: load.connections,
+            draining: load.draining,
+        }
+    }
+}
+
+fn map_registry_error(error: &GameServerRegistryError) -> ErrorReply {
+    match error {
+        GameServerRegistryError::UnknownRegion { .. } => bad_request("unknown region"),
+        GameServerRegistryError::UnknownServer => error_response(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            "unknown game server",
+        ),
+    }
+}
+
+fn has_scheme(raw: &str, schemes: &[&str]) -> bool {
+    url::Url::parse(raw).is_ok_and(|url| schemes.contains(&url.scheme()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frameworks::auth_client::AuthClient;
+    use crate::frameworks::game_server_client::GameServerClient;
+    use crate::frameworks::game_server_directory::{
+        DynamicGameServerDirectory, StaticGameServerDirectory,
+    };
+    use crate::frameworks::matchmaking_client::MatchmakingClient;
+    use crate::interface_adapters::request_signing::{
+        SIGNATURE_HEADER, TIMESTAMP_HEADER, sign, unix_now,
+    };
+    use crate::interface_adapters::routes;
+    use crate::interface_adapters::state::AppState;
+    use crate::use_cases::{
+        GameServerDirectory, GuestSessionService, MatchmakingService, ResolvedGameServer,
+    };
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+
+    const SECRET: &str = "test-secret";
+
+    // Serves head's routes; auth and matchmaking are never reached by internal routes.
+    async fn spawn_head(directory: Arc<DynamicGameServerDirectory>) -> String {
+        let auth =
+            Arc::new(AuthClient::new("http://127.0.0.1:9", "shared-secret").expect("auth client"));
+        let state = Arc::new(AppState {
+            guest_sessions: Arc::new(GuestSessionService::new(auth.clone())),
+            matchmaking: Arc::new(MatchmakingService::new(
+                auth,
+                Arc::new(MatchmakingClient::new("http://127.0.0.1:9").expect("client")),
+                directory.clone(),
+                Arc::new(GameServerClient::new(SECRET.into()).expect("client")),
+            )),
+            game_server_registry: directory,
+            internal_secret: Arc::from(SECRET),
+        });
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let address = listener.local_addr().expect("address should be available");
+        tokio::spawn(async move {
+            axum::serve(listener, routes::app(state))
+                .await
+                .expect("test server should run");
+        });
+        format!("http://{address}")
+    }
+
+    fn registration() -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "server_id": "rogue",
+            "region": "eu-west",
+            "ws_url": "ws://rogue.example/ws",
+            "base_url": "http://rogue.example",
+            "max_lobbies": 10,
+            "max_connections": 100,
+        }))
+        .expect("registration should serialize")
+    }
+
+    #[tokio::test]
+    async fn unsigned_game_server_registration_is_rejected_and_not_placed() {
+        let directory = Arc::new(DynamicGameServerDirectory::new(
+            StaticGameServerDirectory::new(HashMap::from([(
+                "eu-west".to_string(),
+                ResolvedGameServer {
+                    base_url: "http://game.internal".into(),
+                    ws_url: "ws://game.public/ws".into(),
+                },
+            )])),
+            Duration::from_secs(15),
+        ));
+        let base_url = spawn_head(directory.clone()).await;
+        let client = reqwest::Client::new();
+        let url = format!("{base_url}/internal/game-servers");
+        let timestamp = unix_now();
+
+        let unsigned = client
+            .post(&url)
+            .header("content-type", "application/json")
+            .body(registration())
+            .send()
+            .await
+            .expect("request should send");
+        let wrong_secret = client
+            .post(&url)
+            .header("content-type", "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(
+                    b"guessed-secret",
+                    timestamp,
+                    "POST",
+                    "/internal/game-servers",
+                    &registration(),
+                ),
+            )
+            .body(registration())
+            .send()
+            .await
+            .expect("request should send");
+
+        assert_eq!(unsigned.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(wrong_secret.status(), reqwest::StatusCode::UNAUTHORIZED);
+        let placed = directory.resolve("eu-west").await.expect("region resolves");
+        assert_eq!(placed.ws_url, "ws://game.public/ws");
+
+        let signed = client
+            .post(&url)
+            .header("content-type", "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(
+                    SECRET.as_bytes(),
+                    timestamp,
+                    "POST",
+                    "/internal/game-servers",
+                    &registration(),
+                ),
+            )
+            .body(registration())
+            .send()
+            .await
+            .expect("request should send");
+
+        assert_eq!(signed.status(), reqwest::StatusCode::NO_CONTENT);
+        let placed = directory.resolve("eu-west").await.expect("region resolves");
+        assert_eq!(placed.ws_url, "ws://rogue.example/ws");
+    }
+}
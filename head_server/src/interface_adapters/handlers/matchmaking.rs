@@ -1,5 +1,9 @@
+use crate::interface_adapters::handlers::errors::{
+    ErrorReply, bad_request, error_response, session_expired, unauthorized, upstream_error,
+    upstream_unavailable,
+};
 use crate::interface_adapters::protocol::{
-    HeadEnterMatchmakingRequest, HeadMatchmakingResponse, HeadMatchmakingStatus,
+    ErrorCode, HeadEnterMatchmakingRequest, HeadMatchmakingResponse, HeadMatchmakingStatus,
 };
 use crate::interface_adapters::state::AppState;
 use crate::use_cases::{
@@ -21,9 +25,9 @@ use std::sync::Arc;
 pub async fn enter_matchmaking(
     State(state): State<Arc<AppState>>,
     Json(body): Json<HeadEnterMatchmakingRequest>,
-) -> Result<Json<HeadMatchmakingResponse>, StatusCode> {
+) -> Result<Json<HeadMatchmakingResponse>, ErrorReply> {
     if body.session_token.trim().is_empty() || body.region.trim().is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(bad_request("session_token and region are required"));
     }
 
     let request = EnterMatchmaking {
@@ -49,10 +53,10 @@ pub async fn poll_matchmaking(
     State(state): State<Arc<AppState>>,
     Path(ticket_id): Path<String>,
     headers: HeaderMap,
-) -> Result<Json<HeadMatchmakingResponse>, StatusCode> {
+) -> Result<Json<HeadMatchmakingResponse>, ErrorReply> {
     let session_token = extract_bearer_token(&headers)?;
     if ticket_id.trim().is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(bad_request("ticket_id is required"));
     }
 
     let result = state
@@ -75,10 +79,10 @@ pub async fn cancel_matchmaking(
     State(state): State<Arc<AppState>>,
     Path(ticket_id): Path<String>,
     headers: HeaderMap,
-) -> Result<Json<HeadMatchmakingResponse>, StatusCode> {
+) -> Result<Json<HeadMatchmakingResponse>, ErrorReply> {
     let session_token = extract_bearer_token(&headers)?;
     if ticket_id.trim().is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(bad_request("ticket_id is required"));
     }
 
     let result = state
@@ -131,64 +135,84 @@ fn map_head_result(result: HeadMatchmakingResult) -> HeadMatchmakingResponse {
     }
 }
 
-fn extract_bearer_token(headers: &HeaderMap) -> Result<String, StatusCode> {
+fn extract_bearer_token(headers: &HeaderMap) -> Result<String, ErrorReply> {
+    let missing = || bad_request("missing bearer token");
     let raw_value = headers
         .get(AUTHORIZATION)
-        .ok_or(StatusCode::BAD_REQUEST)?
+        .ok_or_else(missing)?
         .to_str()
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|_| missing())?;
 
     let token = raw_value
         .strip_prefix("Bearer ")
-        .ok_or(StatusCode::BAD_REQUEST)?
+        .ok_or_else(missing)?
         .trim();
 
     if token.is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(missing());
     }
 
     Ok(token.to_string())
 }
 
-fn map_matchmaking_error(error: &EnterMatchmakingError) -> StatusCode {
+fn map_matchmaking_error(error: &EnterMatchmakingError) -> ErrorReply {
     match error {
-        EnterMatchmakingError::Unauthorized => StatusCode::UNAUTHORIZED,
-        EnterMatchmakingError::BadRequest => StatusCode::BAD_REQUEST,
-        EnterMatchmakingError::UnexpectedClientError
-        | EnterMatchmakingError::UpstreamUnavailable
-        | EnterMatchmakingError::Unexpected => StatusCode::BAD_GATEWAY,
+        EnterMatchmakingError::Unauthorized => unauthorized("invalid session token"),
+        EnterMatchmakingError::SessionExpired => session_expired(),
+        EnterMatchmakingError::BadRequest => bad_request("rejected by matchmaking"),
+        EnterMatchmakingError::UpstreamUnavailable => upstream_unavailable("upstream unavailable"),
+        EnterMatchmakingError::UnexpectedClientError | EnterMatchmakingError::Unexpected => {
+            upstream_error("unexpected upstream response")
+        }
     }
 }
 
-fn map_poll_matchmaking_error(error: &PollMatchmakingError) -> StatusCode {
+fn map_poll_matchmaking_error(error: &PollMatchmakingError) -> ErrorReply {
     match error {
-        PollMatchmakingError::Unauthorized => StatusCode::UNAUTHORIZED,
-        PollMatchmakingError::BadRequest => StatusCode::BAD_REQUEST,
-        PollMatchmakingError::NotFound => StatusCode::NOT_FOUND,
-        PollMatchmakingError::UnexpectedClientError
-        | PollMatchmakingError::UpstreamUnavailable
-        | PollMatchmakingError::Unexpected => StatusCode::BAD_GATEWAY,
+        PollMatchmakingError::Unauthorized => unauthorized("not allowed to see this ticket"),
+        PollMatchmakingError::SessionExpired => session_expired(),
+        PollMatchmakingError::BadRequest => bad_request("rejected by matchmaking"),
+        PollMatchmakingError::NotFound => ticket_not_found(),
+        PollMatchmakingError::UpstreamUnavailable => upstream_unavailable("upstream unavailable"),
+        PollMatchmakingError::UnexpectedClientError | PollMatchmakingError::Unexpected => {
+            upstream_error("unexpected upstream response")
+        }
     }
 }
 
-fn map_cancel_matchmaking_error(error: &CancelMatchmakingError) -> StatusCode {
+fn map_cancel_matchmaking_error(error: &CancelMatchmakingError) -> ErrorReply {
     match error {
-        CancelMatchmakingError::Unauthorized => StatusCode::UNAUTHORIZED,
-        CancelMatchmakingError::BadRequest => StatusCode::BAD_REQUEST,
-        CancelMatchmakingError::Conflict => StatusCode::CONFLICT,
-        CancelMatchmakingError::NotFound => StatusCode::NOT_FOUND,
-        CancelMatchmakingError::UnexpectedClientError
-        | CancelMatchmakingError::UpstreamUnavailable
-        | CancelMatchmakingError::Unexpected => StatusCode::BAD_GATEWAY,
+        CancelMatchmakingError::Unauthorized => unauthorized("not allowed to cancel this ticket"),
+        CancelMatchmakingError::SessionExpired => session_expired(),
+        CancelMatchmakingError::BadRequest => bad_request("rejected by matchmaking"),
+        CancelMatchmakingError::Conflict => error_response(
+            StatusCode::CONFLICT,
+            ErrorCode::Conflict,
+            "ticket is already matched",
+        ),
+        CancelMatchmakingError::NotFound => ticket_not_found(),
+        CancelMatchmakingError::UpstreamUnavailable => upstream_unavailable("upstream unavailable"),
+        CancelMatchmakingError::UnexpectedClientError | CancelMatchmakingError::Unexpected => {
+            upstream_error("unexpected upstream response")
+        }
     }
 }
 
+fn ticket_not_found() -> ErrorReply {
+    error_response(
+        StatusCode::NOT_FOUND,
+        ErrorCode::NotFound,
+        "ticket not found",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::use_cases::{
         AuthProvider, CreateGameLobby, CreateGameLobbyResult, GameServerDirectory, GameServerError,
-        GameServerProvisioner, GuestInit, GuestInitResult, GuestLogin, GuestLoginResult,
+        GameServerLoad, GameServerProvisioner, GameServerRegistration, GameServerRegistry,
+        GameServerRegistryError, GuestInit, GuestInitResult, GuestLogin, GuestLoginResult,
         MatchmakingLifecycleState, MatchmakingProvider, MatchmakingProviderError,
         MatchmakingQueueRequest, MatchmakingService, ResolvedGameServer, VerifySession,
         VerifySessionResult,
@@ -219,6 +243,14 @@ mod tests {
             panic!("guest login should not be called");
         }
 
+        async fn refresh_session(
+            &self,
+            _req: crate::use_cases::RefreshSession,
+        ) -> Result<crate::use_cases::RefreshSessionResult, crate::use_cases::AuthProviderError>
+        {
+            panic!("refresh session should not be called");
+        }
+
         async fn verify_session(
             &self,
             _req: VerifySession,
@@ -275,6 +307,14 @@ mod tests {
                 .take()
                 .expect("cancel response should be configured")
         }
+
+        async fn requeue_match(
+            &self,
+            _match_id: String,
+            _player_ids: Option<Vec<u64>>,
+        ) -> Result<(), MatchmakingProviderError> {
+            panic!("requeue should not be called");
+        }
     }
 
     #[derive(Default)]
@@ -312,6 +352,27 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct UnusedGameServerRegistry;
+
+    #[async_trait]
+    impl GameServerRegistry for UnusedGameServerRegistry {
+        async fn register(
+            &self,
+            _registration: GameServerRegistration,
+        ) -> Result<(), GameServerRegistryError> {
+            panic!("game server registry should not be called");
+        }
+
+        async fn heartbeat(
+            &self,
+            _server_id: &str,
+            _load: GameServerLoad,
+        ) -> Result<(), GameServerRegistryError> {
+            panic!("game server registry should not be called");
+        }
+    }
+
     fn app_state(
         auth: Arc<dyn AuthProvider>,
         matchmaking: Arc<dyn MatchmakingProvider>,
@@ -326,6 +387,8 @@ mod tests {
                 directory,
                 provisioner,
             )),
+            game_server_registry: Arc::new(UnusedGameServerRegistry),
+            internal_secret: Arc::from("test-secret"),
         })
     }
 
@@ -548,7 +611,10 @@ mod tests {
 
         match result {
             Ok(_) => panic!("cancel conflict should fail"),
-            Err(status) => assert_eq!(status, StatusCode::CONFLICT),
+            Err((status, Json(error))) => {
+                assert_eq!(status, StatusCode::CONFLICT);
+                assert_eq!(error.code, ErrorCode::Conflict);
+            }
         }
     }
 
@@ -573,7 +639,10 @@ mod tests {
 
         match result {
             Ok(_) => panic!("missing ticket cancel should fail"),
-            Err(status) => assert_eq!(status, StatusCode::NOT_FOUND),
+            Err((status, Json(error))) => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(error.code, ErrorCode::NotFound);
+            }
         }
     }
 }
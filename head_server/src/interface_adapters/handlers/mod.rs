@@ -1,3 +1,5 @@
+pub mod errors;
 pub mod guest;
 pub mod health;
+pub mod internal;
 pub mod matchmaking;
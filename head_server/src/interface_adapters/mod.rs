@@ -1,4 +1,5 @@
 pub mod handlers;
 pub mod protocol;
+pub mod request_signing;
 pub mod routes;
 pub mod state;
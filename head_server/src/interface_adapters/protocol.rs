@@ -10,16 +10,24 @@ pub struct HeadGuestInitRequest {
 pub struct HeadGuestInitResponse {
     // Guest identifier returned as a string for JSON precision safety in clients.
     pub guest_id: String,
+    // Secret the client must store with guest_id and send on every /guest/login.
+    pub guest_secret: String,
     // Session token returned by auth.
     pub session_token: String,
     // Token expiration timestamp from auth.
     pub expires_at: u64,
+    // Long-lived token the client trades for a new session via /guest/refresh.
+    pub refresh_token: String,
+    // Refresh token expiration timestamp from auth.
+    pub refresh_expires_at: u64,
 }
 
 #[derive(Deserialize)]
 pub struct HeadGuestLoginRequest {
     // Guest ID supplied by the client as a string for JSON precision safety.
     pub guest_id: String,
+    // Secret returned by /guest/init for this guest.
+    pub guest_secret: String,
     // Display name chosen by the client.
     pub display_name: String,
 }
@@ -30,6 +38,28 @@ pub struct HeadGuestLoginResponse {
     pub session_token: String,
     // Token expiration timestamp from auth.
     pub expires_at: u64,
+    // Long-lived token the client trades for a new session via /guest/refresh.
+    pub refresh_token: String,
+    // Refresh token expiration timestamp from auth.
+    pub refresh_expires_at: u64,
+}
+
+#[derive(Deserialize)]
+pub struct HeadGuestRefreshRequest {
+    // Refresh token from the latest login or refresh; it is spent by this call.
+    pub refresh_token: String,
+}
+
+#[derive(Serialize)]
+pub struct HeadGuestRefreshResponse {
+    // New session token returned by auth.
+    pub session_token: String,
+    // Token expiration timestamp from auth.
+    pub expires_at: u64,
+    // Replacement refresh token; the previous one must not be sent again.
+    pub refresh_token: String,
+    // Refresh token expiration timestamp from auth.
+    pub refresh_expires_at: u64,
 }
 
 #[derive(Deserialize)]
@@ -69,3 +99,97 @@ pub struct HeadMatchmakingResponse {
     // Region that the lifecycle response applies to.
     pub region: String,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LobbyAbortReasonDto {
+    RosterNoShow,
+    ClosedByAdmin,
+    ServerDraining,
+    WorldCrashed,
+    Abandoned,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LobbyAbortedRequest {
+    // Machine-readable abort reason reported by the game server.
+    pub reason: LobbyAbortReasonDto,
+    // Rostered players connected when the lobby was aborted (roster no-shows only).
+    #[serde(default)]
+    pub joined_players: Option<Vec<u64>>,
+    // Rostered players the ready check required to start (roster no-shows only).
+    #[serde(default)]
+    pub required: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GameServerRegistrationRequest {
+    // Stable id the game server uses for its heartbeats.
+    pub server_id: String,
+    // Matchmaking region key the server hosts matches for.
+    pub region: String,
+    // Public WebSocket URL handed to matched clients.
+    pub ws_url: String,
+    // Internal HTTP base URL head uses for lobby creation.
+    pub base_url: String,
+    pub max_lobbies: usize,
+    pub max_connections: usize,
+    // Load at registration time, same shape as heartbeats.
+    #[serde(flatten)]
+    pub load: GameServerLoadDto,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GameServerHeartbeatRequest {
+    // Id the server registered with; carried in the body since ids may contain slashes.
+    pub server_id: String,
+    #[serde(flatten)]
+    pub load: GameServerLoadDto,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GameServerLoadDto {
+    #[serde(default)]
+    pub lobbies: usize,
+    #[serde(default)]
+    pub players: usize,
+    #[serde(default)]
+    pub connections: usize,
+    #[serde(default)]
+    pub draining: bool,
+}
+
+// Error envelope shared by every service; codes are listed in ERROR_CODES.md.
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    // Human-readable error string; clients branch on `code` instead.
+    pub message: String,
+    pub retryable: bool,
+    pub details: Option<serde_json::Value>,
+}
+
+// Stable error codes returned by head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    Unauthorized,
+    SessionExpired,
+    Forbidden,
+    NotFound,
+    Conflict,
+    RateLimited,
+    UpstreamUnavailable,
+    UpstreamError,
+}
+
+impl ErrorCode {
+    // Whether the same request may succeed when sent again after a short wait.
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::RateLimited | ErrorCode::UpstreamUnavailable
+        )
+    }
+}
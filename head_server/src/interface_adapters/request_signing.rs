@@ -0,0 +1,114 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// HMAC-SHA256 request signing shared with the game server for internal routes.
+//
+// The signed message is `{timestamp}\n{METHOD}\n{path}\n{body}`, where `path` includes
+// any `?query`; both headers must be present.
+
+pub const TIMESTAMP_HEADER: &str = "x-internal-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-internal-signature";
+
+// Requests older or newer than this are rejected to bound replay windows.
+pub const MAX_CLOCK_SKEW_SECS: u64 = 60;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    Missing,
+    Malformed,
+    Expired,
+    Mismatch,
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Returns the hex-encoded signature for a request.
+pub fn sign(secret: &[u8], timestamp: u64, method: &str, path: &str, body: &[u8]) -> String {
+    hex::encode(
+        mac(secret, timestamp, method, path, body)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+/// Checks a signature in constant time and rejects stale timestamps.
+pub fn verify(
+    secret: &[u8],
+    timestamp: &str,
+    signature: &str,
+    method: &str,
+    path: &str,
+    body: &[u8],
+    now: u64,
+) -> Result<(), SignatureError> {
+    let timestamp = timestamp
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| SignatureError::Malformed)?;
+    if now.abs_diff(timestamp) > MAX_CLOCK_SKEW_SECS {
+        return Err(SignatureError::Expired);
+    }
+    let signature = hex::decode(signature.trim()).map_err(|_| SignatureError::Malformed)?;
+
+    mac(secret, timestamp, method, path, body)
+        .verify_slice(&signature)
+        .map_err(|_| SignatureError::Mismatch)
+}
+
+fn mac(secret: &[u8], timestamp: u64, method: &str, path: &str, body: &[u8]) -> HmacSha256 {
+    // HMAC accepts keys of any length, so construction cannot fail.
+    let mut mac = HmacSha256::new_from_slice(secret).expect("hmac accepts any key length");
+    mac.update(format!("{timestamp}\n{method}\n{path}\n").as_bytes());
+    mac.update(body);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"internal-secret";
+    const BODY: &[u8] = br#"{"lobby_id":"match-1"}"#;
+
+    #[test]
+    fn verify_accepts_matching_signature() {
+        let signature = sign(SECRET, 1_000, "POST", "/lobbies", BODY);
+
+        assert_eq!(
+            verify(SECRET, "1000", &signature, "POST", "/lobbies", BODY, 1_030),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn verify_rejects_tampering_and_stale_requests() {
+        let signature = sign(SECRET, 1_000, "POST", "/lobbies", BODY);
+
+        assert_eq!(
+            verify(SECRET, "1000", &signature, "POST", "/lobbies", b"{}", 1_000),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify(
+                b"other", "1000", &signature, "POST", "/lobbies", BODY, 1_000
+            ),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify(SECRET, "1000", &signature, "POST", "/lobbies", BODY, 1_061),
+            Err(SignatureError::Expired)
+        );
+        assert_eq!(
+            verify(SECRET, "soon", &signature, "POST", "/lobbies", BODY, 1_000),
+            Err(SignatureError::Malformed)
+        );
+    }
+}
@@ -1,25 +1,43 @@
-use crate::interface_adapters::handlers::guest::{guest_init, guest_login};
+use crate::interface_adapters::handlers::guest::{guest_init, guest_login, guest_refresh};
 use crate::interface_adapters::handlers::health::health;
+use crate::interface_adapters::handlers::internal::{
+    game_server_heartbeat, lobby_aborted, register_game_server, require_internal_signature,
+};
 use crate::interface_adapters::handlers::matchmaking::{
     cancel_matchmaking, enter_matchmaking, poll_matchmaking,
 };
 use crate::interface_adapters::state::AppState;
 use axum::{
-    Router,
+    Router, middleware,
     routing::{get, post},
 };
 use std::sync::Arc;
 
 pub fn app(state: Arc<AppState>) -> Router {
+    // Game-server callbacks and registrations must carry a valid service signature.
+    let internal = Router::new()
+        .route("/internal/lobbies/{lobby_id}/aborted", post(lobby_aborted))
+        .route("/internal/game-servers", post(register_game_server))
+        .route(
+            "/internal/game-servers/heartbeat",
+            post(game_server_heartbeat),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_internal_signature,
+        ));
+
     // Wire the HTTP routes to their handlers.
     Router::new()
         .route("/health", get(health))
         .route("/guest/init", post(guest_init))
         .route("/guest/login", post(guest_login))
+        .route("/guest/refresh", post(guest_refresh))
         .route("/matchmaking/queue", post(enter_matchmaking))
         .route(
             "/matchmaking/queue/{ticket_id}",
             get(poll_matchmaking).delete(cancel_matchmaking),
         )
+        .merge(internal)
         .with_state(state)
 }
@@ -1,4 +1,4 @@
-use crate::use_cases::{GuestSessionService, MatchmakingService};
+use crate::use_cases::{GameServerRegistry, GuestSessionService, MatchmakingService};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -7,4 +7,8 @@ pub struct AppState {
     pub guest_sessions: Arc<GuestSessionService>,
     // Matchmaking stays behind a use-case service so handlers do not call upstream clients directly.
     pub matchmaking: Arc<MatchmakingService>,
+    // Game servers register and heartbeat here; it also backs match placement.
+    pub game_server_registry: Arc<dyn GameServerRegistry>,
+    // Shared secret game servers sign internal callbacks with.
+    pub internal_secret: Arc<str>,
 }
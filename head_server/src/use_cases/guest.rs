@@ -1,29 +1,53 @@
 use async_trait::async_trait;
 use std::fmt;
+use std::net::IpAddr;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GuestInit {
     pub display_name: String,
+    // Player's address, passed on so auth rate limits players rather than head.
+    pub client_ip: Option<IpAddr>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GuestInitResult {
     pub guest_id: u64,
+    pub guest_secret: String,
     pub session_token: String,
     pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GuestLogin {
     pub guest_id: u64,
+    pub guest_secret: String,
     pub display_name: String,
+    pub client_ip: Option<IpAddr>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GuestLoginResult {
     pub session_token: String,
     pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefreshSession {
+    pub refresh_token: String,
+    pub client_ip: Option<IpAddr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefreshSessionResult {
+    pub session_token: String,
+    pub expires_at: u64,
+    pub refresh_token: String,
+    pub refresh_expires_at: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -43,9 +67,12 @@ pub struct VerifySessionResult {
 pub enum AuthProviderError {
     BadRequest,
     Unauthorized,
+    // The session token was genuine but has expired; a refresh can replace it.
+    SessionExpired,
     Forbidden,
     NotFound,
     UnprocessableEntity,
+    RateLimited,
     UnexpectedClientError,
     UpstreamUnavailable,
     Unexpected,
@@ -56,9 +83,11 @@ impl fmt::Display for AuthProviderError {
         match self {
             AuthProviderError::BadRequest => write!(f, "bad request"),
             AuthProviderError::Unauthorized => write!(f, "unauthorized"),
+            AuthProviderError::SessionExpired => write!(f, "session expired"),
             AuthProviderError::Forbidden => write!(f, "forbidden"),
             AuthProviderError::NotFound => write!(f, "not found"),
             AuthProviderError::UnprocessableEntity => write!(f, "unprocessable entity"),
+            AuthProviderError::RateLimited => write!(f, "rate limited"),
             AuthProviderError::UnexpectedClientError => {
                 write!(f, "unexpected upstream client error")
             }
@@ -86,6 +115,11 @@ pub trait AuthProvider: Send + Sync {
         &self,
         req: VerifySession,
     ) -> Result<VerifySessionResult, AuthProviderError>;
+
+    async fn refresh_session(
+        &self,
+        req: RefreshSession,
+    ) -> Result<RefreshSessionResult, AuthProviderError>;
 }
 
 #[derive(Clone)]
@@ -111,6 +145,13 @@ impl GuestSessionService {
     ) -> Result<GuestLoginResult, AuthProviderError> {
         self.auth.create_guest_session(request).await
     }
+
+    pub async fn refresh_session(
+        &self,
+        request: RefreshSession,
+    ) -> Result<RefreshSessionResult, AuthProviderError> {
+        self.auth.refresh_session(request).await
+    }
 }
 
 #[cfg(test)]
@@ -123,9 +164,11 @@ mod tests {
         init_requests: Mutex<Vec<GuestInit>>,
         login_requests: Mutex<Vec<GuestLogin>>,
         verify_requests: Mutex<Vec<VerifySession>>,
+        refresh_requests: Mutex<Vec<RefreshSession>>,
         init_response: Mutex<Option<Result<GuestInitResult, AuthProviderError>>>,
         login_response: Mutex<Option<Result<GuestLoginResult, AuthProviderError>>>,
         verify_response: Mutex<Option<Result<VerifySessionResult, AuthProviderError>>>,
+        refresh_response: Mutex<Option<Result<RefreshSessionResult, AuthProviderError>>>,
     }
 
     #[async_trait]
@@ -165,6 +208,18 @@ mod tests {
                 .take()
                 .expect("verify response should be configured")
         }
+
+        async fn refresh_session(
+            &self,
+            req: RefreshSession,
+        ) -> Result<RefreshSessionResult, AuthProviderError> {
+            self.refresh_requests.lock().unwrap().push(req);
+            self.refresh_response
+                .lock()
+                .unwrap()
+                .take()
+                .expect("refresh response should be configured")
+        }
     }
 
     #[tokio::test]
@@ -172,8 +227,11 @@ mod tests {
         let auth = Arc::new(MockAuthProvider {
             init_response: Mutex::new(Some(Ok(GuestInitResult {
                 guest_id: 42,
+                guest_secret: "secret".into(),
                 session_token: "token".into(),
                 expires_at: 99,
+                refresh_token: "refresh".into(),
+                refresh_expires_at: 999,
             }))),
             ..Default::default()
         });
@@ -182,6 +240,7 @@ mod tests {
         let result = service
             .guest_init(GuestInit {
                 display_name: "Pilot".into(),
+                client_ip: None,
             })
             .await
             .expect("guest init should succeed");
@@ -190,14 +249,18 @@ mod tests {
             result,
             GuestInitResult {
                 guest_id: 42,
+                guest_secret: "secret".into(),
                 session_token: "token".into(),
                 expires_at: 99,
+                refresh_token: "refresh".into(),
+                refresh_expires_at: 999,
             }
         );
         assert_eq!(
             auth.init_requests.lock().unwrap().as_slice(),
             &[GuestInit {
                 display_name: "Pilot".into(),
+                client_ip: None,
             }]
         );
     }
@@ -208,6 +271,8 @@ mod tests {
             login_response: Mutex::new(Some(Ok(GuestLoginResult {
                 session_token: "token".into(),
                 expires_at: 123,
+                refresh_token: "refresh".into(),
+                refresh_expires_at: 1234,
             }))),
             ..Default::default()
         });
@@ -216,7 +281,9 @@ mod tests {
         let result = service
             .guest_login(GuestLogin {
                 guest_id: 7,
+                guest_secret: "secret".into(),
                 display_name: "Pilot".into(),
+                client_ip: None,
             })
             .await
             .expect("guest login should succeed");
@@ -226,13 +293,49 @@ mod tests {
             GuestLoginResult {
                 session_token: "token".into(),
                 expires_at: 123,
+                refresh_token: "refresh".into(),
+                refresh_expires_at: 1234,
             }
         );
         assert_eq!(
             auth.login_requests.lock().unwrap().as_slice(),
             &[GuestLogin {
                 guest_id: 7,
+                guest_secret: "secret".into(),
                 display_name: "Pilot".into(),
+                client_ip: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_session_delegates_to_auth_provider() {
+        let rotated = RefreshSessionResult {
+            session_token: "token-2".into(),
+            expires_at: 456,
+            refresh_token: "refresh-2".into(),
+            refresh_expires_at: 4567,
+        };
+        let auth = Arc::new(MockAuthProvider {
+            refresh_response: Mutex::new(Some(Ok(rotated.clone()))),
+            ..Default::default()
+        });
+        let service = GuestSessionService::new(auth.clone());
+
+        let result = service
+            .refresh_session(RefreshSession {
+                refresh_token: "refresh-1".into(),
+                client_ip: None,
+            })
+            .await
+            .expect("refresh should succeed");
+
+        assert_eq!(result, rotated);
+        assert_eq!(
+            auth.refresh_requests.lock().unwrap().as_slice(),
+            &[RefreshSession {
+                refresh_token: "refresh-1".into(),
+                client_ip: None,
             }]
         );
     }
@@ -96,6 +96,7 @@ impl std::error::Error for MatchmakingProviderError {}
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EnterMatchmakingError {
     Unauthorized,
+    SessionExpired,
     BadRequest,
     UnexpectedClientError,
     UpstreamUnavailable,
@@ -105,6 +106,7 @@ pub enum EnterMatchmakingError {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PollMatchmakingError {
     Unauthorized,
+    SessionExpired,
     BadRequest,
     NotFound,
     UnexpectedClientError,
@@ -115,6 +117,7 @@ pub enum PollMatchmakingError {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CancelMatchmakingError {
     Unauthorized,
+    SessionExpired,
     BadRequest,
     Conflict,
     NotFound,
@@ -127,6 +130,7 @@ impl fmt::Display for EnterMatchmakingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             EnterMatchmakingError::Unauthorized => write!(f, "unauthorized"),
+            EnterMatchmakingError::SessionExpired => write!(f, "session expired"),
             EnterMatchmakingError::BadRequest => write!(f, "bad request"),
             EnterMatchmakingError::UnexpectedClientError => {
                 write!(f, "unexpected upstream client error")
@@ -145,6 +149,7 @@ impl fmt::Display for PollMatchmakingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PollMatchmakingError::Unauthorized => write!(f, "unauthorized"),
+            PollMatchmakingError::SessionExpired => write!(f, "session expired"),
             PollMatchmakingError::BadRequest => write!(f, "bad request"),
             PollMatchmakingError::NotFound => write!(f, "not found"),
             PollMatchmakingError::UnexpectedClientError => {
@@ -162,6 +167,7 @@ impl fmt::Display for CancelMatchmakingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CancelMatchmakingError::Unauthorized => write!(f, "unauthorized"),
+            CancelMatchmakingError::SessionExpired => write!(f, "session expired"),
             CancelMatchmakingError::BadRequest => write!(f, "bad request"),
             CancelMatchmakingError::Conflict => write!(f, "conflict"),
             CancelMatchmakingError::NotFound => write!(f, "not found"),
@@ -184,11 +190,14 @@ impl From<AuthProviderError> for EnterMatchmakingError {
     fn from(error: AuthProviderError) -> Self {
         match error {
             AuthProviderError::Unauthorized => EnterMatchmakingError::Unauthorized,
+            AuthProviderError::SessionExpired => EnterMatchmakingError::SessionExpired,
             AuthProviderError::BadRequest => EnterMatchmakingError::BadRequest,
             AuthProviderError::UnexpectedClientError => {
                 EnterMatchmakingError::UnexpectedClientError
             }
-            AuthProviderError::UpstreamUnavailable => EnterMatchmakingError::UpstreamUnavailable,
+            AuthProviderError::UpstreamUnavailable | AuthProviderError::RateLimited => {
+                EnterMatchmakingError::UpstreamUnavailable
+            }
             AuthProviderError::Unexpected
             | AuthProviderError::Forbidden
             | AuthProviderError::NotFound
@@ -201,9 +210,12 @@ impl From<AuthProviderError> for PollMatchmakingError {
     fn from(error: AuthProviderError) -> Self {
         match error {
             AuthProviderError::Unauthorized => PollMatchmakingError::Unauthorized,
+            AuthProviderError::SessionExpired => PollMatchmakingError::SessionExpired,
             AuthProviderError::BadRequest => PollMatchmakingError::BadRequest,
             AuthProviderError::UnexpectedClientError => PollMatchmakingError::UnexpectedClientError,
-            AuthProviderError::UpstreamUnavailable => PollMatchmakingError::UpstreamUnavailable,
+            AuthProviderError::UpstreamUnavailable | AuthProviderError::RateLimited => {
+                PollMatchmakingError::UpstreamUnavailable
+            }
             AuthProviderError::Unexpected
             | AuthProviderError::NotFound
             | AuthProviderError::Forbidden
@@ -216,11 +228,14 @@ impl From<AuthProviderError> for CancelMatchmakingError {
     fn from(error: AuthProviderError) -> Self {
         match error {
             AuthProviderError::Unauthorized => CancelMatchmakingError::Unauthorized,
+            AuthProviderError::SessionExpired => CancelMatchmakingError::SessionExpired,
             AuthProviderError::BadRequest => CancelMatchmakingError::BadRequest,
             AuthProviderError::UnexpectedClientError => {
                 CancelMatchmakingError::UnexpectedClientError
             }
-            AuthProviderError::UpstreamUnavailable => CancelMatchmakingError::UpstreamUnavailable,
+            AuthProviderError::UpstreamUnavailable | AuthProviderError::RateLimited => {
+                CancelMatchmakingError::UpstreamUnavailable
+            }
             AuthProviderError::Unexpected
             | AuthProviderError::NotFound
             | AuthProviderError::Forbidden
@@ -310,15 +325,99 @@ pub enum CreateGameLobbyResult {
     AlreadyExists,
 }
 
+// Game-server report for a lobby that ended before its match could run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LobbyAborted {
+    pub lobby_id: String,
+    pub reason: LobbyAbortReason,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LobbyAbortReason {
+    // Fewer rostered players connected than the ready-check quorum requires.
+    RosterNoShow {
+        joined_players: Vec<u64>,
+        required: usize,
+    },
+    // An operator closed the lobby on the game server.
+    ClosedByAdmin,
+    // The game server started draining before the match began.
+    ServerDraining,
+    // The lobby's world task crashed on the game server.
+    WorldCrashed,
+    // No player connected before the lobby's join deadline.
+    Abandoned,
+}
+
+// Which tickets of an aborted lobby's match go back in the queue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TicketRequeue {
+    None,
+    // Every rostered player.
+    Roster,
+    // Only these players; the rest of the roster's tickets are canceled.
+    Players(Vec<u64>),
+}
+
+impl LobbyAbortReason {
+    // Server-side failures are not the players' fault, so their tickets go back in the queue.
+    // After a no-show only the players who joined are requeued: requeueing the absent ones
+    // would pair them into another lobby nobody joins.
+    pub fn ticket_requeue(&self) -> TicketRequeue {
+        match self {
+            LobbyAbortReason::ServerDraining | LobbyAbortReason::WorldCrashed => {
+                TicketRequeue::Roster
+            }
+            LobbyAbortReason::RosterNoShow { joined_players, .. } if !joined_players.is_empty() => {
+                TicketRequeue::Players(joined_players.clone())
+            }
+            LobbyAbortReason::RosterNoShow { .. }
+            | LobbyAbortReason::ClosedByAdmin
+            | LobbyAbortReason::Abandoned => TicketRequeue::None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GameServerError {
     BadRequest,
     UnexpectedClientError,
     UpstreamUnavailable,
     UnknownRegion { region: String },
+    // Every live game server in the region is at its lobby or connection cap.
+    NoCapacity { region: String },
     Unexpected,
 }
 
+// Occupancy a game server reports on registration and every heartbeat.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameServerLoad {
+    pub lobbies: usize,
+    pub players: usize,
+    pub connections: usize,
+    // Draining servers finish their matches but take no new ones.
+    pub draining: bool,
+}
+
+// A game server announcing itself to head so matches can be placed on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameServerRegistration {
+    pub server_id: String,
+    pub region: String,
+    pub server: ResolvedGameServer,
+    pub max_lobbies: usize,
+    pub max_connections: usize,
+    pub load: GameServerLoad,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameServerRegistryError {
+    // Registrations must target a region head already routes.
+    UnknownRegion { region: String },
+    // Heartbeat from a server head does not know (never registered or evicted).
+    UnknownServer,
+}
+
 #[async_trait]
 pub trait MatchmakingProvider: Send + Sync {
     async fn enqueue(
@@ -337,6 +436,14 @@ pub trait MatchmakingProvider: Send + Sync {
         player_id: u64,
         ticket_id: String,
     ) -> Result<MatchmakingLifecycleState, MatchmakingProviderError>;
+
+    // Returns the tickets of a match that was never played to the queue: those of
+    // `player_ids`, or every ticket when `None`.
+    async fn requeue_match(
+        &self,
+        match_id: String,
+        player_ids: Option<Vec<u64>>,
+    ) -> Result<(), MatchmakingProviderError>;
 }
 
 #[async_trait]
@@ -344,6 +451,21 @@ pub trait GameServerDirectory: Send + Sync {
     async fn resolve(&self, region: &str) -> Result<ResolvedGameServer, GameServerError>;
 }
 
+// Write side of the directory: game servers register and keep themselves alive.
+#[async_trait]
+pub trait GameServerRegistry: Send + Sync {
+    async fn register(
+        &self,
+        registration: GameServerRegistration,
+    ) -> Result<(), GameServerRegistryError>;
+
+    async fn heartbeat(
+        &self,
+        server_id: &str,
+        load: GameServerLoad,
+    ) -> Result<(), GameServerRegistryError>;
+}
+
 #[async_trait]
 pub trait GameServerProvisioner: Send + Sync {
     async fn create_lobby(
@@ -456,6 +578,58 @@ impl MatchmakingService {
         }
     }
 
+    // Lobby ids are match ids, so the report identifies the matchmaking tickets
+    // that were handed to the aborted lobby.
+    pub async fn record_lobby_aborted(&self, report: LobbyAborted) {
+        match &report.reason {
+            LobbyAbortReason::RosterNoShow {
+                joined_players,
+                required,
+            } => {
+                tracing::warn!(
+                    match_id = %report.lobby_id,
+                    joined = joined_players.len(),
+                    required,
+                    "game server aborted lobby: roster did not show up"
+                );
+            }
+            LobbyAbortReason::ClosedByAdmin => {
+                tracing::warn!(match_id = %report.lobby_id, "game server lobby closed by operator");
+            }
+            LobbyAbortReason::ServerDraining => {
+                tracing::warn!(match_id = %report.lobby_id, "game server drained before match start");
+            }
+            LobbyAbortReason::WorldCrashed => {
+                tracing::error!(match_id = %report.lobby_id, "game server lobby crashed");
+            }
+            LobbyAbortReason::Abandoned => {
+                tracing::warn!(match_id = %report.lobby_id, "game server lobby abandoned: nobody joined");
+            }
+        }
+
+        let player_ids = match report.reason.ticket_requeue() {
+            TicketRequeue::None => return,
+            TicketRequeue::Roster => None,
+            TicketRequeue::Players(player_ids) => Some(player_ids),
+        };
+        match self
+            .matchmaking
+            .requeue_match(report.lobby_id.clone(), player_ids)
+            .await
+        {
+            Ok(()) => {
+                tracing::info!(match_id = %report.lobby_id, "requeued tickets for aborted lobby");
+            }
+            Err(MatchmakingProviderError::NotFound) => {
+                // Lobbies head did not create (or already requeued) have no match to restore.
+                tracing::debug!(match_id = %report.lobby_id, "no match to requeue");
+            }
+            Err(error) => {
+                tracing::warn!(match_id = %report.lobby_id, %error, "failed to requeue tickets");
+            }
+        }
+    }
+
     async fn map_lifecycle_state(
         &self,
         state: MatchmakingLifecycleState,
@@ -513,6 +687,7 @@ fn map_game_server_error_to_enter(error: GameServerError) -> EnterMatchmakingErr
         GameServerError::UnexpectedClientError => EnterMatchmakingError::UnexpectedClientError,
         GameServerError::UpstreamUnavailable => EnterMatchmakingError::UpstreamUnavailable,
         GameServerError::UnknownRegion { .. } => EnterMatchmakingError::Unexpected,
+        GameServerError::NoCapacity { .. } => EnterMatchmakingError::UpstreamUnavailable,
         GameServerError::Unexpected => EnterMatchmakingError::Unexpected,
     }
 }
@@ -523,6 +698,7 @@ fn map_game_server_error_to_poll(error: GameServerError) -> PollMatchmakingError
         GameServerError::UnexpectedClientError => PollMatchmakingError::UnexpectedClientError,
         GameServerError::UpstreamUnavailable => PollMatchmakingError::UpstreamUnavailable,
         GameServerError::UnknownRegion { .. } => PollMatchmakingError::Unexpected,
+        GameServerError::NoCapacity { .. } => PollMatchmakingError::UpstreamUnavailable,
         GameServerError::Unexpected => PollMatchmakingError::Unexpected,
     }
 }
@@ -555,6 +731,13 @@ mod tests {
             panic!("guest login should not be called");
         }
 
+        async fn refresh_session(
+            &self,
+            _req: crate::use_cases::RefreshSession,
+        ) -> Result<crate::use_cases::RefreshSessionResult, AuthProviderError> {
+            panic!("refresh session should not be called");
+        }
+
         async fn verify_session(
             &self,
             req: VerifySession,
@@ -573,6 +756,7 @@ mod tests {
         enqueue_requests: Mutex<Vec<MatchmakingQueueRequest>>,
         poll_requests: Mutex<Vec<String>>,
         cancel_requests: Mutex<Vec<String>>,
+        requeue_requests: Mutex<Vec<(String, Option<Vec<u64>>)>>,
         enqueue_response:
             Mutex<Option<Result<MatchmakingLifecycleState, MatchmakingProviderError>>>,
         poll_response: Mutex<Option<Result<MatchmakingLifecycleState, MatchmakingProviderError>>>,
@@ -618,6 +802,18 @@ mod tests {
                 .take()
                 .expect("cancel response should be configured")
         }
+
+        async fn requeue_match(
+            &self,
+            match_id: String,
+            player_ids: Option<Vec<u64>>,
+        ) -> Result<(), MatchmakingProviderError> {
+            self.requeue_requests
+                .lock()
+                .unwrap()
+                .push((match_id, player_ids));
+            Ok(())
+        }
     }
 
     #[derive(Default)]
@@ -1014,4 +1210,50 @@ mod tests {
 
         assert_eq!(result, Err(PollMatchmakingError::Unexpected));
     }
+
+    #[tokio::test]
+    async fn record_lobby_aborted_requeues_server_failures_and_only_players_who_joined() {
+        let matchmaking = Arc::new(MockMatchmakingProvider::default());
+        let service = matchmaking_service(
+            Arc::new(MockAuthProvider::default()),
+            matchmaking.clone(),
+            Arc::new(MockGameServerDirectory::default()),
+            Arc::new(MockGameServerProvisioner::default()),
+        );
+
+        for (lobby_id, reason) in [
+            (
+                "match-no-show",
+                LobbyAbortReason::RosterNoShow {
+                    joined_players: Vec::new(),
+                    required: 1,
+                },
+            ),
+            (
+                "match-partial-show",
+                LobbyAbortReason::RosterNoShow {
+                    joined_players: vec![7],
+                    required: 2,
+                },
+            ),
+            ("match-abandoned", LobbyAbortReason::Abandoned),
+            ("match-closed", LobbyAbortReason::ClosedByAdmin),
+            ("match-crashed", LobbyAbortReason::WorldCrashed),
+        ] {
+            service
+                .record_lobby_aborted(LobbyAborted {
+                    lobby_id: lobby_id.into(),
+                    reason,
+                })
+                .await;
+        }
+
+        assert_eq!(
+            *matchmaking.requeue_requests.lock().unwrap(),
+            vec![
+                ("match-partial-show".to_string(), Some(vec![7])),
+                ("match-crashed".to_string(), None),
+            ]
+        );
+    }
 }
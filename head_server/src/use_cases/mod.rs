@@ -3,12 +3,13 @@ pub mod matchmaking;
 
 pub use guest::{
     AuthProvider, AuthProviderError, GuestInit, GuestInitResult, GuestLogin, GuestLoginResult,
-    GuestSessionService, VerifySession, VerifySessionResult,
+    GuestSessionService, RefreshSession, RefreshSessionResult, VerifySession, VerifySessionResult,
 };
 pub use matchmaking::{
     CancelMatchmaking, CancelMatchmakingError, CreateGameLobby, CreateGameLobbyResult,
-    EnterMatchmaking, EnterMatchmakingError, GameServerDirectory, GameServerError,
-    GameServerProvisioner, HeadMatchmakingResult, MatchmakingLifecycleState, MatchmakingProvider,
-    MatchmakingProviderError, MatchmakingQueueRequest, MatchmakingService, PollMatchmaking,
-    PollMatchmakingError, ResolvedGameServer,
+    EnterMatchmaking, EnterMatchmakingError, GameServerDirectory, GameServerError, GameServerLoad,
+    GameServerProvisioner, GameServerRegistration, GameServerRegistry, GameServerRegistryError,
+    HeadMatchmakingResult, LobbyAbortReason, LobbyAborted, MatchmakingLifecycleState,
+    MatchmakingProvider, MatchmakingProviderError, MatchmakingQueueRequest, MatchmakingService,
+    PollMatchmaking, PollMatchmakingError, ResolvedGameServer,
 };
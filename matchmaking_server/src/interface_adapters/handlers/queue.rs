@@ -1,15 +1,17 @@
 use crate::interface_adapters::protocol::{
-    ErrorResponse, QueueRequest, QueueResponse, QueueStatus, TicketOwnerQuery,
+    ErrorCode, ErrorResponse, QueueRequest, QueueResponse, QueueStatus, RequeueRequest,
+    RequeueResponse, TicketOwnerQuery,
 };
 use crate::interface_adapters::state::AppState;
 use crate::use_cases::matchmaker::{
-    CancelTicketError, EnqueuePlayer, TicketLookupError, TicketStatus,
+    CancelTicketError, EnqueuePlayer, RequeueMatchError, TicketLookupError, TicketStatus,
 };
 use axum::{
     Json,
     extract::{Path, Query, State, rejection::QueryRejection},
     http::StatusCode,
 };
+use serde_json::{Value, json};
 use std::sync::Arc;
 
 // Enqueue a player for matchmaking and attempt to match immediately.
@@ -59,17 +61,17 @@ pub async fn lookup_ticket(
 
     match outcome {
         Ok(status) => Ok(Json(map_ticket_status(status))),
-        Err(TicketLookupError::Unauthorized { ticket_id }) => Err((
+        Err(TicketLookupError::Unauthorized { ticket_id }) => Err(error_response(
             StatusCode::UNAUTHORIZED,
-            Json(ErrorResponse {
-                message: format!("ticket_id {ticket_id} is not owned by the caller"),
-            }),
+            ErrorCode::TicketNotOwned,
+            format!("ticket_id {ticket_id} is not owned by the caller"),
+            Some(json!({ "ticket_id": ticket_id })),
         )),
-        Err(TicketLookupError::NotFound { ticket_id }) => Err((
+        Err(TicketLookupError::NotFound { ticket_id }) => Err(error_response(
             StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                message: format!("ticket_id {ticket_id} was not found"),
-            }),
+            ErrorCode::TicketNotFound,
+            format!("ticket_id {ticket_id} was not found"),
+            Some(json!({ "ticket_id": ticket_id })),
         )),
     }
 }
@@ -90,23 +92,51 @@ pub async fn cancel_ticket(
 
     match outcome {
         Ok(status) => Ok(Json(map_ticket_status(status))),
-        Err(CancelTicketError::Unauthorized { ticket_id }) => Err((
+        Err(CancelTicketError::Unauthorized { ticket_id }) => Err(error_response(
             StatusCode::UNAUTHORIZED,
-            Json(ErrorResponse {
-                message: format!("ticket_id {ticket_id} is not owned by the caller"),
-            }),
+            ErrorCode::TicketNotOwned,
+            format!("ticket_id {ticket_id} is not owned by the caller"),
+            Some(json!({ "ticket_id": ticket_id })),
         )),
-        Err(CancelTicketError::NotFound { ticket_id }) => Err((
+        Err(CancelTicketError::NotFound { ticket_id }) => Err(error_response(
             StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                message: format!("ticket_id {ticket_id} was not found"),
-            }),
+            ErrorCode::TicketNotFound,
+            format!("ticket_id {ticket_id} was not found"),
+            Some(json!({ "ticket_id": ticket_id })),
         )),
-        Err(CancelTicketError::Matched { ticket_id }) => Err((
+        Err(CancelTicketError::Matched { ticket_id }) => Err(error_response(
             StatusCode::CONFLICT,
-            Json(ErrorResponse {
-                message: format!("ticket_id {ticket_id} is already matched"),
-            }),
+            ErrorCode::TicketMatched,
+            format!("ticket_id {ticket_id} is already matched"),
+            Some(json!({ "ticket_id": ticket_id })),
+        )),
+    }
+}
+
+// Return the tickets of a match whose lobby never got played to the queue.
+pub async fn requeue_match(
+    State(state): State<Arc<AppState>>,
+    Path(match_id): Path<String>,
+    Json(request): Json<RequeueRequest>,
+) -> Result<Json<RequeueResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if match_id.trim().is_empty() {
+        return Err(bad_request("match_id is required"));
+    }
+
+    let outcome = {
+        let mut matchmaker = state.matchmaker.lock().await;
+        matchmaker.requeue_match(match_id.as_str(), request.player_ids.as_deref())
+    };
+
+    match outcome {
+        Ok(tickets) => Ok(Json(RequeueResponse {
+            tickets: tickets.into_iter().map(map_ticket_status).collect(),
+        })),
+        Err(RequeueMatchError::NotFound { match_id }) => Err(error_response(
+            StatusCode::NOT_FOUND,
+            ErrorCode::MatchNotFound,
+            format!("match_id {match_id} was not found"),
+            Some(json!({ "match_id": match_id })),
         )),
     }
 }
@@ -143,10 +173,27 @@ fn map_ticket_status(status: TicketStatus) -> QueueResponse {
 }
 
 fn bad_request(message: impl Into<String>) -> (StatusCode, Json<ErrorResponse>) {
-    (
+    error_response(
         StatusCode::BAD_REQUEST,
+        ErrorCode::InvalidRequest,
+        message,
+        None,
+    )
+}
+
+fn error_response(
+    status: StatusCode,
+    code: ErrorCode,
+    message: impl Into<String>,
+    details: Option<Value>,
+) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        status,
         Json(ErrorResponse {
+            code,
             message: message.into(),
+            retryable: code.retryable(),
+            details,
         }),
     )
 }
@@ -336,7 +383,12 @@ mod tests {
             Ok(_) => panic!("missing ticket should not succeed"),
             Err((status, error)) => {
                 assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(error.0.code, ErrorCode::TicketNotFound);
                 assert_eq!(error.0.message, "ticket_id missing-ticket was not found");
+                assert_eq!(
+                    error.0.details,
+                    Some(json!({ "ticket_id": "missing-ticket" }))
+                );
             }
         }
     }
@@ -365,6 +417,7 @@ mod tests {
             Ok(_) => panic!("non-owner lookup should fail"),
             Err((status, error)) => {
                 assert_eq!(status, StatusCode::UNAUTHORIZED);
+                assert_eq!(error.0.code, ErrorCode::TicketNotOwned);
                 assert_eq!(
                     error.0.message,
                     format!("ticket_id {ticket_id} is not owned by the caller")
@@ -387,6 +440,7 @@ mod tests {
             Ok(_) => panic!("invalid query should fail"),
             Err((status, Json(error))) => {
                 assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(error.code, ErrorCode::InvalidRequest);
                 assert_eq!(error.message, "player_id query parameter is required");
             }
         }
@@ -497,7 +551,12 @@ mod tests {
             Ok(_) => panic!("missing ticket should not succeed"),
             Err((status, error)) => {
                 assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(error.0.code, ErrorCode::TicketNotFound);
                 assert_eq!(error.0.message, "ticket_id missing-ticket was not found");
+                assert_eq!(
+                    error.0.details,
+                    Some(json!({ "ticket_id": "missing-ticket" }))
+                );
             }
         }
     }
@@ -516,8 +575,63 @@ mod tests {
             Ok(_) => panic!("invalid query should fail"),
             Err((status, Json(error))) => {
                 assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(error.code, ErrorCode::InvalidRequest);
                 assert_eq!(error.message, "player_id query parameter is required");
             }
         }
     }
+
+    #[tokio::test]
+    async fn requeue_match_returns_the_players_who_showed_up_then_not_found() {
+        let state = app_state(matchmaker());
+        {
+            let mut matchmaker = state.matchmaker.lock().await;
+            matchmaker.enqueue(EnqueuePlayer {
+                player_id: 1,
+                player_skill: 1200,
+                region: "eu-west".into(),
+            });
+            matchmaker.enqueue(EnqueuePlayer {
+                player_id: 2,
+                player_skill: 1200,
+                region: "eu-west".into(),
+            });
+        }
+
+        let Json(response) = requeue_match(
+            State(state.clone()),
+            Path("match-test-2".to_string()),
+            Json(RequeueRequest {
+                player_ids: Some(vec![1]),
+            }),
+        )
+        .await
+        .expect("formed match should be requeued");
+        let statuses: Vec<_> = response
+            .tickets
+            .iter()
+            .map(|ticket| (ticket.ticket_id.as_str(), &ticket.status))
+            .collect();
+        assert!(matches!(
+            statuses.as_slice(),
+            [
+                ("ticket-test-1-1", QueueStatus::Waiting),
+                ("ticket-test-3-2", QueueStatus::Canceled),
+            ]
+        ));
+
+        match requeue_match(
+            State(state),
+            Path("match-test-2".to_string()),
+            Json(RequeueRequest::default()),
+        )
+        .await
+        {
+            Ok(_) => panic!("requeued match should be gone"),
+            Err((status, error)) => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(error.0.message, "match_id match-test-2 was not found");
+            }
+        }
+    }
 }
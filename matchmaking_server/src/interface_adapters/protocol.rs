@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 // Request payload for enqueueing a player into matchmaking.
 #[derive(Debug, Deserialize)]
@@ -26,6 +27,20 @@ pub struct QueueResponse {
     pub region: String,
 }
 
+// Request payload for returning a failed match's tickets to the queue.
+#[derive(Debug, Default, Deserialize)]
+pub struct RequeueRequest {
+    // Players who showed up for the match; absent requeues the whole roster.
+    #[serde(default)]
+    pub player_ids: Option<Vec<u64>>,
+}
+
+// Response payload listing the tickets a failed match put back in the queue.
+#[derive(Debug, Serialize)]
+pub struct RequeueResponse {
+    pub tickets: Vec<QueueResponse>,
+}
+
 // Outcome status for queue lifecycle responses.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -35,8 +50,30 @@ pub enum QueueStatus {
     Canceled,
 }
 
-// Simple error envelope for JSON responses.
+// Error envelope shared by every service; codes are listed in ERROR_CODES.md.
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
+    pub code: ErrorCode,
     pub message: String,
+    pub retryable: bool,
+    // The ticket or match the error is about, when there is one.
+    pub details: Option<Value>,
+}
+
+// Stable error codes clients branch on; messages are for humans and may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    TicketNotOwned,
+    TicketNotFound,
+    TicketMatched,
+    MatchNotFound,
+}
+
+impl ErrorCode {
+    // Whether the same request may succeed when sent again after a short wait.
+    pub fn retryable(self) -> bool {
+        false
+    }
 }
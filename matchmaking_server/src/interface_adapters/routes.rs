@@ -1,5 +1,7 @@
 use crate::interface_adapters::handlers::health::health;
-use crate::interface_adapters::handlers::queue::{cancel_ticket, enqueue, lookup_ticket};
+use crate::interface_adapters::handlers::queue::{
+    cancel_ticket, enqueue, lookup_ticket, requeue_match,
+};
 use crate::interface_adapters::state::AppState;
 use axum::{
     Router,
@@ -16,5 +18,9 @@ pub fn app(state: Arc<AppState>) -> Router {
             "/matchmaking/queue/{ticket_id}",
             get(lookup_ticket).delete(cancel_ticket),
         )
+        .route(
+            "/matchmaking/matches/{match_id}/requeue",
+            post(requeue_match),
+        )
         .with_state(state)
 }
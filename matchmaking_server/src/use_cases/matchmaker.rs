@@ -44,6 +44,12 @@ pub enum CancelTicketError {
     Matched { ticket_id: String },
 }
 
+// Errors that can occur while returning a failed match to the queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequeueMatchError {
+    NotFound { match_id: String },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct MatchRecord {
     match_id: String,
@@ -51,16 +57,29 @@ struct MatchRecord {
     region: String,
 }
 
+// Queue bookkeeping a ticket keeps through matches that never got played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QueueHistory {
+    // Enqueue order; a requeued ticket goes back to this place in the queue.
+    position: u64,
+    // Failed matches the ticket was in; tickets sharing one are not paired again.
+    failed_match_ids: Vec<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum TicketRecord {
     Waiting {
         player_id: u64,
         player_skill: u32,
         region: String,
+        history: QueueHistory,
     },
     Matched {
         player_id: u64,
+        // Kept so a failed match can put the ticket back in the queue unchanged.
+        player_skill: u32,
         match_id: String,
+        history: QueueHistory,
     },
     Canceled {
         player_id: u64,
@@ -80,6 +99,7 @@ pub struct Matchmaker {
     tickets_by_id: HashMap<String, TicketRecord>,
     active_ticket_by_player: HashMap<u64, String>,
     matches_by_id: HashMap<String, MatchRecord>,
+    next_position: u64,
 }
 
 impl Matchmaker {
@@ -91,6 +111,7 @@ impl Matchmaker {
             tickets_by_id: HashMap::new(),
             active_ticket_by_player: HashMap::new(),
             matches_by_id: HashMap::new(),
+            next_position: 0,
         }
     }
 
@@ -114,12 +135,14 @@ impl Matchmaker {
         }
 
         let ticket_id = self.ids.next_ticket_id(request.player_id);
+        let history = self.next_history();
         self.tickets_by_id.insert(
             ticket_id.clone(),
             TicketRecord::Waiting {
                 player_id: request.player_id,
                 player_skill: request.player_skill,
                 region: request.region.clone(),
+                history,
             },
         );
         self.queue.push_back(ticket_id.clone());
@@ -219,6 +242,203 @@ impl Matchmaker {
         }
     }
 
+    // Return the tickets of `player_ids` from a match that never got played to the queue,
+    // at the place they were first queued, then pair the region's waiting tickets.
+    // `None` requeues the whole roster; tickets of players left out are canceled.
+    //
+    // Ticket ids are preserved so polling clients see `waiting` again, or `matched`
+    // with a new match once pairing places them. Tickets from the failed match are
+    // never paired with each other again.
+    pub fn requeue_match(
+        &mut self,
+        match_id: &str,
+        player_ids: Option<&[u64]>,
+    ) -> Result<Vec<TicketStatus>, RequeueMatchError> {
+        let Some(record) = self.matches_by_id.remove(match_id) else {
+            return Err(RequeueMatchError::NotFound {
+                match_id: match_id.to_string(),
+            });
+        };
+
+        let mut ticket_ids: Vec<String> = self
+            .tickets_by_id
+            .iter()
+            .filter_map(|(ticket_id, ticket)| match ticket {
+                TicketRecord::Matched {
+                    match_id: ticket_match_id,
+                    ..
+                } if ticket_match_id == match_id => Some(ticket_id.clone()),
+                _ => None,
+            })
+            .collect();
+        ticket_ids.sort_unstable();
+
+        let mut requeued_ids = Vec::with_capacity(ticket_ids.len());
+        let mut canceled_ids = Vec::new();
+        for ticket_id in &ticket_ids {
+            let Some(TicketRecord::Matched {
+                player_id,
+                player_skill,
+                mut history,
+                ..
+            }) = self.tickets_by_id.get(ticket_id).cloned()
+            else {
+                continue;
+            };
+
+            if player_ids.is_some_and(|player_ids| !player_ids.contains(&player_id)) {
+                // Players who never showed up would only be paired into another dead lobby.
+                self.active_ticket_by_player.remove(&player_id);
+                self.tickets_by_id.insert(
+                    ticket_id.clone(),
+                    TicketRecord::Canceled {
+                        player_id,
+                        region: record.region.clone(),
+                    },
+                );
+                canceled_ids.push(ticket_id.clone());
+                continue;
+            }
+
+            history.failed_match_ids.push(match_id.to_string());
+            let position = history.position;
+            self.tickets_by_id.insert(
+                ticket_id.clone(),
+                TicketRecord::Waiting {
+                    player_id,
+                    player_skill,
+                    region: record.region.clone(),
+                    history,
+                },
+            );
+            let index = self
+                .queue
+                .iter()
+                .position(|queued_ticket_id| {
+                    self.tickets_by_id
+                        .get(queued_ticket_id)
+                        .and_then(TicketRecord::history)
+                        .is_some_and(|queued| queued.position > position)
+                })
+                .unwrap_or(self.queue.len());
+            self.queue.insert(index, ticket_id.clone());
+            requeued_ids.push(ticket_id.clone());
+        }
+
+        info!(
+            match_id = %match_id,
+            ticket_ids = ?requeued_ids,
+            canceled_ticket_ids = ?canceled_ids,
+            region = %record.region,
+            "requeued tickets from failed match"
+        );
+
+        // Pairing otherwise only runs on enqueue, so requeued players would wait for a newcomer.
+        self.pair_waiting_tickets(record.region.as_str());
+        let requeued = ticket_ids
+            .iter()
+            .filter_map(|ticket_id| self.status_for_ticket(ticket_id))
+            .collect();
+        Ok(requeued)
+    }
+
+    // Match waiting tickets in `region` two at a time, in queue order, skipping pairs
+    // that already shared a failed match.
+    fn pair_waiting_tickets(&mut self, region: &str) {
+        loop {
+            let waiting: Vec<(&String, &QueueHistory)> = self
+                .queue
+                .iter()
+                .filter_map(|ticket_id| match self.tickets_by_id.get(ticket_id) {
+                    Some(TicketRecord::Waiting {
+                        region: queued_region,
+                        history,
+                        ..
+                    }) if queued_region == region => Some((ticket_id, history)),
+                    _ => None,
+                })
+                .collect();
+            let pair = waiting.iter().enumerate().find_map(|(index, first)| {
+                waiting[index + 1..]
+                    .iter()
+                    .find(|second| first.1.can_pair_with(second.1))
+                    .map(|second| (first.0.clone(), second.0.clone()))
+            });
+            let Some((first_ticket_id, second_ticket_id)) = pair else {
+                return;
+            };
+            self.match_waiting_tickets(first_ticket_id, second_ticket_id);
+        }
+    }
+
+    // Like `create_match`, for two tickets that were both already waiting.
+    fn match_waiting_tickets(&mut self, first_ticket_id: String, second_ticket_id: String) {
+        self.queue.retain(|queued_ticket_id| {
+            queued_ticket_id != &first_ticket_id && queued_ticket_id != &second_ticket_id
+        });
+
+        let waiting = |ticket: Option<&TicketRecord>| match ticket.cloned() {
+            Some(TicketRecord::Waiting {
+                player_id,
+                player_skill,
+                region,
+                history,
+            }) => (player_id, player_skill, region, history),
+            _ => panic!("paired ticket should still be waiting"),
+        };
+        let (first_player_id, first_player_skill, region, first_history) =
+            waiting(self.tickets_by_id.get(&first_ticket_id));
+        let (second_player_id, second_player_skill, _, second_history) =
+            waiting(self.tickets_by_id.get(&second_ticket_id));
+
+        let match_id = self.ids.next_match_id(second_player_id, first_player_id);
+        let mut player_ids = vec![first_player_id, second_player_id];
+        player_ids.sort_unstable();
+        self.matches_by_id.insert(
+            match_id.clone(),
+            MatchRecord {
+                match_id: match_id.clone(),
+                player_ids: player_ids.clone(),
+                region: region.clone(),
+            },
+        );
+        for (ticket_id, player_id, player_skill, history) in [
+            (
+                &first_ticket_id,
+                first_player_id,
+                first_player_skill,
+                first_history,
+            ),
+            (
+                &second_ticket_id,
+                second_player_id,
+                second_player_skill,
+                second_history,
+            ),
+        ] {
+            self.tickets_by_id.insert(
+                ticket_id.clone(),
+                TicketRecord::Matched {
+                    player_id,
+                    player_skill,
+                    match_id: match_id.clone(),
+                    history,
+                },
+            );
+            self.active_ticket_by_player
+                .insert(player_id, ticket_id.clone());
+        }
+
+        info!(
+            match_id = %match_id,
+            ticket_id = %second_ticket_id,
+            opponent_ticket_id = %first_ticket_id,
+            player_ids = ?player_ids,
+            region = %region,
+            "formed matchmaking match"
+        );
+    }
+
     fn create_match(&mut self, opponent_ticket_id: String, request: EnqueuePlayer) -> TicketStatus {
         self.queue
             .retain(|queued_ticket_id| queued_ticket_id != &opponent_ticket_id);
@@ -231,8 +451,9 @@ impl Matchmaker {
 
         let TicketRecord::Waiting {
             player_id: opponent_player_id,
+            player_skill: opponent_player_skill,
             region,
-            ..
+            history: opponent_history,
         } = opponent_ticket
         else {
             panic!("queued ticket should still be waiting");
@@ -262,16 +483,21 @@ impl Matchmaker {
             opponent_ticket_id.clone(),
             TicketRecord::Matched {
                 player_id: opponent_player_id,
+                player_skill: opponent_player_skill,
                 match_id: match_id.clone(),
+                history: opponent_history,
             },
         );
 
         let ticket_id = self.ids.next_ticket_id(request.player_id);
+        let history = self.next_history();
         self.tickets_by_id.insert(
             ticket_id.clone(),
             TicketRecord::Matched {
                 player_id: request.player_id,
+                player_skill: request.player_skill,
                 match_id: match_id.clone(),
+                history,
             },
         );
 
@@ -299,6 +525,15 @@ impl Matchmaker {
         }
     }
 
+    fn next_history(&mut self) -> QueueHistory {
+        let position = self.next_position;
+        self.next_position += 1;
+        QueueHistory {
+            position,
+            failed_match_ids: Vec::new(),
+        }
+    }
+
     fn find_waiting_opponent_ticket(&self, region: &str) -> Option<String> {
         self.queue.iter().find_map(|ticket_id| {
             let ticket = self.tickets_by_id.get(ticket_id)?;
@@ -353,7 +588,25 @@ impl Matchmaker {
     }
 }
 
+impl QueueHistory {
+    fn can_pair_with(&self, other: &QueueHistory) -> bool {
+        !self
+            .failed_match_ids
+            .iter()
+            .any(|match_id| other.failed_match_ids.contains(match_id))
+    }
+}
+
 impl TicketRecord {
+    fn history(&self) -> Option<&QueueHistory> {
+        match self {
+            TicketRecord::Waiting { history, .. } | TicketRecord::Matched { history, .. } => {
+                Some(history)
+            }
+            TicketRecord::Canceled { .. } => None,
+        }
+    }
+
     fn belongs_to(&self, player_id: u64) -> bool {
         match self {
             TicketRecord::Waiting {
@@ -717,4 +970,112 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn requeue_match_returns_tickets_to_their_place_and_never_repairs_them() {
+        let mut matchmaker = matchmaker();
+        let TicketStatus::Waiting {
+            ticket_id: first_ticket,
+            ..
+        } = matchmaker.enqueue(queue_request(1, "eu-west"))
+        else {
+            panic!("first player should wait");
+        };
+        let TicketStatus::Matched {
+            ticket_id: second_ticket,
+            match_id,
+            ..
+        } = matchmaker.enqueue(queue_request(2, "eu-west"))
+        else {
+            panic!("second player should match");
+        };
+        let TicketStatus::Waiting {
+            ticket_id: third_ticket,
+            ..
+        } = matchmaker.enqueue(queue_request(3, "eu-west"))
+        else {
+            panic!("third player should wait");
+        };
+
+        matchmaker
+            .requeue_match(&match_id, None)
+            .expect("match should be requeued");
+
+        // Player 1 keeps its place ahead of player 3, but not next to player 2 again.
+        let Ok(TicketStatus::Matched {
+            match_id: rematch_id,
+            player_ids,
+            ..
+        }) = matchmaker.lookup_ticket(3, &third_ticket)
+        else {
+            panic!("waiting player should be matched with a requeued one");
+        };
+        assert_eq!(player_ids, vec![1, 3]);
+        assert_ne!(rematch_id, match_id);
+        assert!(matches!(
+            matchmaker.lookup_ticket(1, &first_ticket),
+            Ok(TicketStatus::Matched { match_id, .. }) if match_id == rematch_id
+        ));
+        assert_eq!(
+            matchmaker.lookup_ticket(2, &second_ticket),
+            Ok(TicketStatus::Waiting {
+                ticket_id: second_ticket.clone(),
+                region: "eu-west".into(),
+            })
+        );
+        assert_eq!(
+            matchmaker.requeue_match(&match_id, None),
+            Err(RequeueMatchError::NotFound {
+                match_id: match_id.clone(),
+            })
+        );
+    }
+
+    #[test]
+    fn requeue_match_cancels_tickets_of_players_who_did_not_show_up() {
+        let mut matchmaker = matchmaker();
+        let TicketStatus::Waiting {
+            ticket_id: first_ticket,
+            ..
+        } = matchmaker.enqueue(queue_request(1, "eu-west"))
+        else {
+            panic!("first player should wait");
+        };
+        let TicketStatus::Matched {
+            ticket_id: second_ticket,
+            match_id,
+            ..
+        } = matchmaker.enqueue(queue_request(2, "eu-west"))
+        else {
+            panic!("second player should match");
+        };
+
+        let requeued = matchmaker
+            .requeue_match(&match_id, Some(&[2]))
+            .expect("match should be requeued");
+
+        assert_eq!(
+            requeued,
+            vec![
+                TicketStatus::Canceled {
+                    ticket_id: first_ticket.clone(),
+                    region: "eu-west".into(),
+                },
+                TicketStatus::Waiting {
+                    ticket_id: second_ticket.clone(),
+                    region: "eu-west".into(),
+                },
+            ]
+        );
+
+        // The no-show can queue again; the player who showed up is waiting for them.
+        assert!(matches!(
+            matchmaker.enqueue(queue_request(3, "eu-west")),
+            TicketStatus::Matched { player_ids, .. } if player_ids == vec![2, 3]
+        ));
+        assert!(matches!(
+            matchmaker.enqueue(queue_request(1, "eu-west")),
+            TicketStatus::Waiting { ticket_id, .. } if ticket_id != first_ticket
+        ));
+    }
 }
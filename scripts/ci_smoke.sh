@@ -7,6 +7,8 @@ LOG_DIR="${ROOT_DIR}/.tmp/ci-smoke-logs"
 mkdir -p "${LOG_DIR}"
 
 POSTGRES_CONTAINER_NAME="jet-raiders-ci-smoke-postgres"
+# Head and the game server sign internal calls to each other with this secret.
+INTERNAL_API_SECRET="${INTERNAL_API_SECRET:-ci-smoke-internal-secret}"
 POSTGRES_STARTED_BY_SCRIPT=0
 
 AUTH_PID=""
@@ -116,6 +118,8 @@ start_services() {
     AUTH_SERVER_BIND_HOST=127.0.0.1 \
     BACKEND_PORTS_CONFIG_PATH=../config/backend_ports.toml \
     DATABASE_URL="${DATABASE_URL}" \
+    AUTH_TRUSTED_PROXIES=127.0.0.1 \
+    INTERNAL_API_SECRET="${INTERNAL_API_SECRET}" \
     cargo run
   ) >"${LOG_DIR}/auth_server.log" 2>&1 &
   AUTH_PID=$!
@@ -134,6 +138,7 @@ start_services() {
     GAME_SERVER_BIND_HOST=127.0.0.1 \
     GAME_SERVER_PORT=3001 \
     AUTH_SERVICE_URL=http://127.0.0.1:3002 \
+    INTERNAL_API_SECRET="${INTERNAL_API_SECRET}" \
     cargo run
   ) >"${LOG_DIR}/game_server.log" 2>&1 &
   GAME_PID=$!
@@ -152,6 +157,7 @@ start_head_service() {
     AUTH_SERVICE_URL=http://127.0.0.1:3002 \
     MATCHMAKING_SERVICE_URL=http://127.0.0.1:3003 \
     REGION_CONFIG_PATH=../config/regions.toml \
+    INTERNAL_API_SECRET="${INTERNAL_API_SECRET}" \
     cargo run
   ) >"${LOG_DIR}/head_server.log" 2>&1 &
   HEAD_PID=$!
@@ -0,0 +1,14 @@
+//! Local verification of auth's signed session tokens, shared by head and the game servers.
+//!
+//! Tokens use the JWS compact form base64url(header).base64url(claims).base64url(signature),
+//! signed with Ed25519 under a key auth publishes at `GET /auth/keys`. Verifiers also poll
+//! `GET /auth/revocations` and only decide locally while those polls keep succeeding.
+
+mod token;
+mod verifier;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+
+pub use token::{JsonWebKey, KeySet, KeySetResponse, Rejection, SessionClaims, SignedToken};
+pub use verifier::{KEY_SET_MAX_AGE, LocalVerifier};
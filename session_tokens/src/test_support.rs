@@ -0,0 +1,43 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ed25519_dalek::{Signer, SigningKey};
+
+/// Signs `claims` the way auth does, with the key derived from `seed`.
+pub fn sign_token(seed: u8, kid: &str, claims: serde_json::Value) -> String {
+    let header = serde_json::json!({ "alg": "EdDSA", "typ": "JWT", "kid": kid });
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature = SigningKey::from_bytes(&[seed; 32]).sign(signing_input.as_bytes());
+    format!(
+        "{signing_input}.{}",
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    )
+}
+
+/// The key set auth would publish for `seed`.
+pub fn key_set_json(seed: u8, kid: &str) -> serde_json::Value {
+    let public_key = SigningKey::from_bytes(&[seed; 32]).verifying_key();
+    serde_json::json!({
+        "keys": [{
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "alg": "EdDSA",
+            "use": "sig",
+            "kid": kid,
+            "x": URL_SAFE_NO_PAD.encode(public_key.to_bytes()),
+        }]
+    })
+}
+
+/// Claims for user 42's `session-1`, expiring at `exp`.
+pub fn claims(exp: u64) -> serde_json::Value {
+    serde_json::json!({
+        "user_id": 42,
+        "display_name": "Pilot",
+        "session_id": "session-1",
+        "exp": exp,
+    })
+}
@@ -0,0 +1,203 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const TOKEN_ALG: &str = "EdDSA";
+
+/// Key set published by auth, in JWK form.
+#[derive(Debug, Deserialize)]
+pub struct KeySetResponse {
+    pub keys: Vec<JsonWebKey>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JsonWebKey {
+    pub kty: String,
+    pub crv: String,
+    pub kid: String,
+    pub x: String,
+}
+
+/// Ed25519 verification keys by key id.
+#[derive(Debug, Default)]
+pub struct KeySet {
+    keys: HashMap<String, VerifyingKey>,
+}
+
+impl KeySet {
+    /// Keeps the Ed25519 keys of `response`; anything else is skipped.
+    pub fn from_response(response: KeySetResponse) -> Self {
+        let keys = response
+            .keys
+            .into_iter()
+            .filter(|key| key.kty == "OKP" && key.crv == "Ed25519")
+            .filter_map(|key| {
+                let bytes: [u8; 32] = URL_SAFE_NO_PAD.decode(&key.x).ok()?.try_into().ok()?;
+                Some((key.kid, VerifyingKey::from_bytes(&bytes).ok()?))
+            })
+            .collect();
+        Self { keys }
+    }
+}
+
+/// The session a verified token stands for.
+///
+/// `display_name` is the name the session was issued with; a rename shows up
+/// once the player refreshes or logs in again, at most one session TTL later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionClaims {
+    pub user_id: u64,
+    pub display_name: String,
+    pub session_id: String,
+    pub expires_at: u64,
+}
+
+/// Why a token was refused locally, so callers can answer as auth would.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// Forged, malformed, or naming a revoked session.
+    Invalid,
+    Expired,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenHeader {
+    alg: String,
+    kid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenClaims {
+    user_id: u64,
+    display_name: String,
+    session_id: String,
+    exp: u64,
+}
+
+/// A token shaped like one of auth's signed tokens; opaque tokens never parse.
+#[derive(Debug)]
+pub struct SignedToken<'a> {
+    kid: String,
+    signing_input: &'a str,
+    claims: &'a str,
+    signature: &'a str,
+}
+
+impl<'a> SignedToken<'a> {
+    pub fn parse(token: &'a str) -> Option<Self> {
+        let (signing_input, signature) = token.rsplit_once('.')?;
+        let (header, claims) = signing_input.split_once('.')?;
+        let header: TokenHeader =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+        if header.alg != TOKEN_ALG {
+            return None;
+        }
+        Some(Self {
+            kid: header.kid,
+            signing_input,
+            claims,
+            signature,
+        })
+    }
+
+    /// Checks the signature and expiry against `keys`; `None` when the key id is unknown.
+    pub fn verify(&self, keys: &KeySet, unix_now: u64) -> Option<Result<SessionClaims, Rejection>> {
+        let key = keys.keys.get(&self.kid)?;
+        Some(self.verify_with(key, unix_now))
+    }
+
+    fn verify_with(&self, key: &VerifyingKey, unix_now: u64) -> Result<SessionClaims, Rejection> {
+        let signature: [u8; 64] = URL_SAFE_NO_PAD
+            .decode(self.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(Rejection::Invalid)?;
+        key.verify_strict(
+            self.signing_input.as_bytes(),
+            &Signature::from_bytes(&signature),
+        )
+        .map_err(|_| Rejection::Invalid)?;
+
+        let claims: TokenClaims = URL_SAFE_NO_PAD
+            .decode(self.claims)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or(Rejection::Invalid)?;
+        if claims.exp <= unix_now {
+            return Err(Rejection::Expired);
+        }
+
+        Ok(SessionClaims {
+            user_id: claims.user_id,
+            display_name: claims.display_name,
+            session_id: claims.session_id,
+            expires_at: claims.exp,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claims, key_set_json, sign_token};
+
+    const UNIX_NOW: u64 = 1_700_000_000;
+
+    fn key_set(seed: u8, kid: &str) -> KeySet {
+        KeySet::from_response(serde_json::from_value(key_set_json(seed, kid)).unwrap())
+    }
+
+    #[test]
+    fn tokens_signed_by_a_published_key_verify_locally() {
+        let token = sign_token(1, "key-1", claims(UNIX_NOW + 60));
+
+        let session = SignedToken::parse(&token)
+            .unwrap()
+            .verify(&key_set(1, "key-1"), UNIX_NOW)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            session,
+            SessionClaims {
+                user_id: 42,
+                display_name: "Pilot".to_string(),
+                session_id: "session-1".to_string(),
+                expires_at: UNIX_NOW + 60,
+            }
+        );
+    }
+
+    #[test]
+    fn forged_and_expired_tokens_are_rejected() {
+        let keys = key_set(1, "key-1");
+        let forged = sign_token(9, "key-1", claims(UNIX_NOW + 60));
+        let expired = sign_token(1, "key-1", claims(UNIX_NOW));
+
+        assert_eq!(
+            SignedToken::parse(&forged).unwrap().verify(&keys, UNIX_NOW),
+            Some(Err(Rejection::Invalid))
+        );
+        assert_eq!(
+            SignedToken::parse(&expired)
+                .unwrap()
+                .verify(&keys, UNIX_NOW),
+            Some(Err(Rejection::Expired))
+        );
+    }
+
+    #[test]
+    fn unknown_key_ids_and_opaque_tokens_are_not_decided_locally() {
+        let token = sign_token(2, "key-2", claims(UNIX_NOW + 60));
+
+        assert!(
+            SignedToken::parse(&token)
+                .unwrap()
+                .verify(&key_set(1, "key-1"), UNIX_NOW)
+                .is_none()
+        );
+        assert!(SignedToken::parse("0b8e3c1a-6f0e-4a57-9d55-5d1c7f0a2b11").is_none());
+    }
+}
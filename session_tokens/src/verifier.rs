@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::token::{KeySet, KeySetResponse, Rejection, SessionClaims, SignedToken};
+
+// Minimum gap between key set fetches triggered by tokens naming an unknown key.
+const KEY_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// Key sets are refetched once this old, so keys auth retired stop verifying.
+pub const KEY_SET_MAX_AGE: Duration = Duration::from_secs(300);
+// Signed tokens are checked locally only while a revocation poll succeeded within
+// this many poll intervals; otherwise auth decides.
+const REVOCATION_STALE_POLLS: u32 = 3;
+
+/// State for verifying auth's signed tokens without calling auth.
+///
+/// Callers share one verifier between their token checks and their revocation
+/// poller, and fall back to auth whenever `verify` returns `None`.
+#[derive(Debug, Default)]
+pub struct LocalVerifier {
+    keys: Mutex<KeyState>,
+    revoked: Mutex<RevokedSessions>,
+    // Until when `revoked` counts as current; unset while revocations are not polled.
+    revocations_current_until: Mutex<Option<Instant>>,
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    keys: KeySet,
+    // Last fetch attempt, successful or not.
+    refreshed_at: Option<Instant>,
+    // Last successful fetch.
+    fetched_at: Option<Instant>,
+}
+
+impl KeyState {
+    fn is_stale(&self) -> bool {
+        self.fetched_at
+            .is_none_or(|fetched_at| fetched_at.elapsed() >= KEY_SET_MAX_AGE)
+    }
+}
+
+// Sessions auth revoked, kept until their tokens would have expired anyway.
+#[derive(Debug, Default)]
+struct RevokedSessions {
+    expires_at: HashMap<String, u64>,
+}
+
+impl RevokedSessions {
+    fn insert(&mut self, session_id: String, expires_at: u64, unix_now: u64) {
+        self.expires_at
+            .retain(|_, expires_at| *expires_at > unix_now);
+        self.expires_at.insert(session_id, expires_at);
+    }
+
+    fn contains(&self, session_id: &str) -> bool {
+        self.expires_at.contains_key(session_id)
+    }
+}
+
+// Plain data behind these locks stays valid across a panicking holder.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl LocalVerifier {
+    /// Records a successful revocation poll: `revocations` are `(session_id, expires_at)`
+    /// pairs, and local checks stay on for three `poll_interval`s unless another poll succeeds.
+    pub fn record_revocations(
+        &self,
+        revocations: impl IntoIterator<Item = (String, u64)>,
+        poll_interval: Duration,
+    ) {
+        let now = unix_now();
+        let mut revoked = lock(&self.revoked);
+        for (session_id, expires_at) in revocations {
+            revoked.insert(session_id, expires_at, now);
+        }
+        drop(revoked);
+        *lock(&self.revocations_current_until) =
+            Some(Instant::now() + poll_interval * REVOCATION_STALE_POLLS);
+    }
+
+    /// Verifies `token` against auth's keys and the polled revocations.
+    ///
+    /// `None` when revocations or keys are not current, or the token's key is unknown
+    /// even after a refresh; auth decides then. `fetch_keys` reads `GET /auth/keys`
+    /// and runs on first use, once the key set is `KEY_SET_MAX_AGE` old, and at most
+    /// every 30 seconds for tokens naming an unknown key. Keys missing from the new
+    /// set stop verifying.
+    pub async fn verify<F, Fut>(
+        &self,
+        token: &SignedToken<'_>,
+        fetch_keys: F,
+    ) -> Option<Result<SessionClaims, Rejection>>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Option<KeySetResponse>>,
+    {
+        let revocations_current =
+            lock(&self.revocations_current_until).is_some_and(|until| Instant::now() < until);
+        if !revocations_current {
+            return None;
+        }
+        let keys_stale = lock(&self.keys).is_stale();
+        if keys_stale && !self.refresh_keys(&fetch_keys).await {
+            return None;
+        }
+
+        let local = token.verify(&lock(&self.keys).keys, unix_now());
+        let result = match local {
+            Some(result) => result,
+            None => {
+                if !self.refresh_keys(&fetch_keys).await {
+                    return None;
+                }
+                token.verify(&lock(&self.keys).keys, unix_now())?
+            }
+        };
+
+        Some(result.and_then(|claims| {
+            if lock(&self.revoked).contains(&claims.session_id) {
+                Err(Rejection::Invalid)
+            } else {
+                Ok(claims)
+            }
+        }))
+    }
+
+    // Fetches the key set unless a fetch ran within `KEY_REFRESH_INTERVAL`; true when
+    // it was replaced.
+    async fn refresh_keys<F, Fut>(&self, fetch_keys: &F) -> bool
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Option<KeySetResponse>>,
+    {
+        {
+            let mut keys = lock(&self.keys);
+            if keys
+                .refreshed_at
+                .is_some_and(|refreshed_at| refreshed_at.elapsed() < KEY_REFRESH_INTERVAL)
+            {
+                return false;
+            }
+            keys.refreshed_at = Some(Instant::now());
+        }
+
+        let Some(response) = fetch_keys().await else {
+            return false;
+        };
+        let mut keys = lock(&self.keys);
+        keys.keys = KeySet::from_response(response);
+        keys.fetched_at = Some(Instant::now());
+        true
+    }
+
+    /// Installs `response` as if it had been fetched at `fetched_at`.
+    #[cfg(feature = "test-support")]
+    pub fn install_keys(&self, response: KeySetResponse, fetched_at: Instant) {
+        *lock(&self.keys) = KeyState {
+            keys: KeySet::from_response(response),
+            refreshed_at: Some(fetched_at),
+            fetched_at: Some(fetched_at),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIX_NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn revoked_sessions_are_forgotten_once_expired() {
+        let mut revoked = RevokedSessions::default();
+        revoked.insert("session-1".into(), UNIX_NOW + 10, UNIX_NOW);
+        assert!(revoked.contains("session-1"));
+
+        revoked.insert("session-2".into(), UNIX_NOW + 100, UNIX_NOW + 10);
+
+        assert!(!revoked.contains("session-1"));
+        assert!(revoked.contains("session-2"));
+    }
+}